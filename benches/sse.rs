@@ -0,0 +1,93 @@
+//! Throughput of SSE parsing on a synthetic high-token-rate stream.
+//!
+//! Run with `cargo bench --bench sse`. Prints MiB/s and events/s for the raw
+//! decoder and for full `MessageStream` accumulation at several network chunk
+//! sizes.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use uno_anthropic::messages::streaming::MessageStream;
+use uno_anthropic::streaming::sse::SseDecoder;
+
+const DELTAS: usize = 20_000;
+const ITERATIONS: u32 = 20;
+
+fn capture() -> String {
+    let mut body = String::new();
+    body.push_str("event: message_start\ndata: {\"type\":\"message_start\",\"message\":{\"id\":\"msg_1\",\"type\":\"message\",\"role\":\"assistant\",\"content\":[],\"model\":\"claude-opus-4-6\",\"stop_reason\":null,\"usage\":{\"input_tokens\":3,\"output_tokens\":0}}}\n\n");
+    body.push_str("event: content_block_start\ndata: {\"type\":\"content_block_start\",\"index\":0,\"content_block\":{\"type\":\"text\",\"text\":\"\"}}\n\n");
+    for i in 0..DELTAS {
+        body.push_str(&format!(
+            "event: content_block_delta\ndata: {{\"type\":\"content_block_delta\",\"index\":0,\"delta\":{{\"type\":\"text_delta\",\"text\":\"token {i} \"}}}}\n\n"
+        ));
+    }
+    body.push_str(
+        "event: content_block_stop\ndata: {\"type\":\"content_block_stop\",\"index\":0}\n\n",
+    );
+    body.push_str("event: message_delta\ndata: {\"type\":\"message_delta\",\"delta\":{\"stop_reason\":\"end_turn\",\"stop_sequence\":null},\"usage\":{\"output_tokens\":20000}}\n\n");
+    body.push_str("event: message_stop\ndata: {\"type\":\"message_stop\"}\n\n");
+    body
+}
+
+fn report(name: &str, bytes: usize, events: usize, elapsed: Duration) {
+    let secs = elapsed.as_secs_f64() / f64::from(ITERATIONS);
+    println!(
+        "{name:<32} {:>9.1} MiB/s {:>12.0} events/s",
+        bytes as f64 / secs / (1024.0 * 1024.0),
+        events as f64 / secs,
+    );
+}
+
+fn bench_decoder(body: &[u8], chunk_size: usize) {
+    let mut events = 0;
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        events = 0;
+        let mut decoder = SseDecoder::new();
+        for chunk in body.chunks(chunk_size) {
+            decoder.push(chunk);
+            while let Some(event) = decoder.next_event() {
+                black_box(event);
+                events += 1;
+            }
+        }
+        while let Some(event) = decoder.finish() {
+            black_box(event);
+            events += 1;
+        }
+    }
+    report(
+        &format!("decoder, {chunk_size} B chunks"),
+        body.len(),
+        events,
+        start.elapsed(),
+    );
+}
+
+fn bench_message_stream(runtime: &tokio::runtime::Runtime, body: &str) {
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        let message = runtime
+            .block_on(MessageStream::from_sse_text(body).accumulate())
+            .unwrap();
+        black_box(message);
+    }
+    report(
+        "MessageStream::accumulate",
+        body.len(),
+        DELTAS + 5,
+        start.elapsed(),
+    );
+}
+
+fn main() {
+    let body = capture();
+    for chunk_size in [64, 1024, 16 * 1024] {
+        bench_decoder(body.as_bytes(), chunk_size);
+    }
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+    bench_message_stream(&runtime, &body);
+}
@@ -1,9 +1,8 @@
-use futures::StreamExt;
+use std::io::Write;
 
 use uno_anthropic::Client;
-use uno_anthropic::messages::params::MessageCreateParams;
-use uno_anthropic::messages::streaming::{ContentBlockDelta, StreamEvent};
-use uno_anthropic::types::{MessageParam, Model};
+use uno_anthropic::examples_support::stream_text;
+use uno_anthropic::types::Model;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -13,32 +12,14 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("[user]: {}", content);
     print!("[assistant]: ");
 
-    let mut stream = client
-        .messages()
-        .create_stream(
-            MessageCreateParams::builder()
-                .model(Model::ClaudeSonnet4_5)
-                .max_tokens(1024)
-                .messages(vec![MessageParam::user(content)])
-                .build(),
-        )
-        .await?;
+    let message = stream_text(&client, Model::ClaudeSonnet4_5, content, |text| {
+        print!("{}", text);
+        let _ = std::io::stdout().flush();
+    })
+    .await?;
 
-    while let Some(event) = stream.next().await {
-        match event? {
-            StreamEvent::ContentBlockDelta {
-                delta: ContentBlockDelta::TextDelta { text },
-                ..
-            } => {
-                print!("{}", text);
-            }
-            StreamEvent::MessageDelta { delta, .. } => {
-                if let Some(ref stop_sequence) = delta.stop_sequence {
-                    print!("{}", stop_sequence);
-                }
-            }
-            _ => {}
-        }
+    if let Some(ref stop_sequence) = message.stop_sequence {
+        print!("{}", stop_sequence);
     }
 
     println!();
@@ -0,0 +1,260 @@
+//! Object-safe traits over the service surfaces.
+//!
+//! Application code can depend on `Arc<dyn MessagesApi>` instead of a concrete
+//! client, and tests can swap in a fake without running an HTTP server. The
+//! traits are implemented by the services and by `Client` itself, which is the
+//! convenient choice when an owned, `'static` value is needed.
+//!
+//! ```ignore
+//! use std::sync::Arc;
+//! use uno_anthropic::api::MessagesApi;
+//!
+//! struct Summarizer {
+//!     messages: Arc<dyn MessagesApi>,
+//! }
+//!
+//! let summarizer = Summarizer { messages: Arc::new(Client::new()) };
+//! ```
+
+use std::pin::Pin;
+
+use futures::stream::Stream;
+
+use crate::batches::{
+    BatchCreateParams, BatchListParams, BatchResult, BatchService, DeletedMessageBatch,
+    MessageBatch,
+};
+use crate::client::Client;
+use crate::error::Error;
+use crate::messages::params::{CountTokensParams, MessageCreateParams};
+use crate::messages::streaming::MessageStream;
+use crate::messages::{CountTokensResponse, MessageService};
+use crate::middleware::BoxFuture;
+use crate::types::message::Message;
+use crate::types::page::Page;
+
+/// A stream of parsed batch results, as returned by `BatchesApi::results`.
+pub type BatchResultStream = Pin<Box<dyn Stream<Item = Result<BatchResult, Error>> + Send>>;
+
+/// The Messages API.
+pub trait MessagesApi: Send + Sync {
+    /// Create a message. See `MessageService::create`.
+    fn create(&self, params: MessageCreateParams) -> BoxFuture<'_, Result<Message, Error>>;
+
+    /// Create a streaming message. See `MessageService::create_stream`.
+    fn create_stream(
+        &self,
+        params: MessageCreateParams,
+    ) -> BoxFuture<'_, Result<MessageStream, Error>>;
+
+    /// Count the input tokens of a request. See `MessageService::count_tokens`.
+    fn count_tokens(
+        &self,
+        params: CountTokensParams,
+    ) -> BoxFuture<'_, Result<CountTokensResponse, Error>>;
+}
+
+/// The Message Batches API.
+pub trait BatchesApi: Send + Sync {
+    /// Create a batch. See `BatchService::create`.
+    fn create(&self, params: BatchCreateParams) -> BoxFuture<'_, Result<MessageBatch, Error>>;
+
+    /// Get a batch by id.
+    fn get<'a>(&'a self, batch_id: &'a str) -> BoxFuture<'a, Result<MessageBatch, Error>>;
+
+    /// List batches.
+    fn list(&self, params: BatchListParams) -> BoxFuture<'_, Result<Page<MessageBatch>, Error>>;
+
+    /// Cancel a batch that is still processing.
+    fn cancel<'a>(&'a self, batch_id: &'a str) -> BoxFuture<'a, Result<MessageBatch, Error>>;
+
+    /// Delete a batch.
+    fn delete<'a>(&'a self, batch_id: &'a str)
+    -> BoxFuture<'a, Result<DeletedMessageBatch, Error>>;
+
+    /// Stream the results of an ended batch.
+    fn results<'a>(&'a self, batch_id: &'a str) -> BoxFuture<'a, Result<BatchResultStream, Error>>;
+}
+
+impl MessagesApi for MessageService<'_> {
+    fn create(&self, params: MessageCreateParams) -> BoxFuture<'_, Result<Message, Error>> {
+        Box::pin(MessageService::create(self, params))
+    }
+
+    fn create_stream(
+        &self,
+        params: MessageCreateParams,
+    ) -> BoxFuture<'_, Result<MessageStream, Error>> {
+        Box::pin(MessageService::create_stream(self, params))
+    }
+
+    fn count_tokens(
+        &self,
+        params: CountTokensParams,
+    ) -> BoxFuture<'_, Result<CountTokensResponse, Error>> {
+        Box::pin(MessageService::count_tokens(self, params))
+    }
+}
+
+impl MessagesApi for Client {
+    fn create(&self, params: MessageCreateParams) -> BoxFuture<'_, Result<Message, Error>> {
+        Box::pin(async move { self.messages().create(params).await })
+    }
+
+    fn create_stream(
+        &self,
+        params: MessageCreateParams,
+    ) -> BoxFuture<'_, Result<MessageStream, Error>> {
+        Box::pin(async move { self.messages().create_stream(params).await })
+    }
+
+    fn count_tokens(
+        &self,
+        params: CountTokensParams,
+    ) -> BoxFuture<'_, Result<CountTokensResponse, Error>> {
+        Box::pin(async move { self.messages().count_tokens(params).await })
+    }
+}
+
+impl BatchesApi for BatchService<'_> {
+    fn create(&self, params: BatchCreateParams) -> BoxFuture<'_, Result<MessageBatch, Error>> {
+        Box::pin(BatchService::create(self, params))
+    }
+
+    fn get<'a>(&'a self, batch_id: &'a str) -> BoxFuture<'a, Result<MessageBatch, Error>> {
+        Box::pin(BatchService::get(self, batch_id))
+    }
+
+    fn list(&self, params: BatchListParams) -> BoxFuture<'_, Result<Page<MessageBatch>, Error>> {
+        Box::pin(BatchService::list(self, params))
+    }
+
+    fn cancel<'a>(&'a self, batch_id: &'a str) -> BoxFuture<'a, Result<MessageBatch, Error>> {
+        Box::pin(BatchService::cancel(self, batch_id))
+    }
+
+    fn delete<'a>(
+        &'a self,
+        batch_id: &'a str,
+    ) -> BoxFuture<'a, Result<DeletedMessageBatch, Error>> {
+        Box::pin(BatchService::delete(self, batch_id))
+    }
+
+    fn results<'a>(&'a self, batch_id: &'a str) -> BoxFuture<'a, Result<BatchResultStream, Error>> {
+        Box::pin(BatchService::results(self, batch_id))
+    }
+}
+
+impl BatchesApi for Client {
+    fn create(&self, params: BatchCreateParams) -> BoxFuture<'_, Result<MessageBatch, Error>> {
+        Box::pin(async move { self.batches().create(params).await })
+    }
+
+    fn get<'a>(&'a self, batch_id: &'a str) -> BoxFuture<'a, Result<MessageBatch, Error>> {
+        Box::pin(async move { self.batches().get(batch_id).await })
+    }
+
+    fn list(&self, params: BatchListParams) -> BoxFuture<'_, Result<Page<MessageBatch>, Error>> {
+        Box::pin(async move { self.batches().list(params).await })
+    }
+
+    fn cancel<'a>(&'a self, batch_id: &'a str) -> BoxFuture<'a, Result<MessageBatch, Error>> {
+        Box::pin(async move { self.batches().cancel(batch_id).await })
+    }
+
+    fn delete<'a>(
+        &'a self,
+        batch_id: &'a str,
+    ) -> BoxFuture<'a, Result<DeletedMessageBatch, Error>> {
+        Box::pin(async move { self.batches().delete(batch_id).await })
+    }
+
+    fn results<'a>(&'a self, batch_id: &'a str) -> BoxFuture<'a, Result<BatchResultStream, Error>> {
+        Box::pin(async move { self.batches().results(batch_id).await })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+    use crate::types::content::ContentBlock;
+    use crate::types::message::MessageParam;
+    use crate::types::model::Model;
+
+    /// A fake that echoes the last user message back.
+    #[derive(Default)]
+    struct EchoMessages {
+        calls: Mutex<u32>,
+    }
+
+    impl MessagesApi for EchoMessages {
+        fn create(&self, params: MessageCreateParams) -> BoxFuture<'_, Result<Message, Error>> {
+            *self.calls.lock().unwrap() += 1;
+            let text = serde_json::to_value(&params.messages.last().unwrap().content).unwrap();
+            Box::pin(async move {
+                Ok(serde_json::from_value(serde_json::json!({
+                    "id": "msg_fake",
+                    "type": "message",
+                    "role": "assistant",
+                    "content": [{"type": "text", "text": text}],
+                    "model": "claude-opus-4-6",
+                    "stop_reason": "end_turn",
+                    "usage": {"input_tokens": 0, "output_tokens": 0}
+                }))?)
+            })
+        }
+
+        fn create_stream(
+            &self,
+            _params: MessageCreateParams,
+        ) -> BoxFuture<'_, Result<MessageStream, Error>> {
+            Box::pin(async { Ok(MessageStream::from_events(Vec::new())) })
+        }
+
+        fn count_tokens(
+            &self,
+            _params: CountTokensParams,
+        ) -> BoxFuture<'_, Result<CountTokensResponse, Error>> {
+            Box::pin(async { Ok(CountTokensResponse { input_tokens: 1 }) })
+        }
+    }
+
+    async fn ask(api: &dyn MessagesApi, question: &str) -> Result<String, Error> {
+        let message = api
+            .create(
+                MessageCreateParams::builder()
+                    .model(Model::ClaudeOpus4_6)
+                    .max_tokens(10)
+                    .messages(vec![MessageParam::user(question)])
+                    .build(),
+            )
+            .await?;
+        match &message.content[0] {
+            ContentBlock::Text(text) => Ok(text.text.clone()),
+            other => panic!("Expected text, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fake_messages_api() {
+        let fake = Arc::new(EchoMessages::default());
+        let api: Arc<dyn MessagesApi> = fake.clone();
+        assert_eq!(ask(api.as_ref(), "ping").await.unwrap(), "ping");
+        assert_eq!(*fake.calls.lock().unwrap(), 1);
+    }
+
+    #[test]
+    fn test_client_and_services_implement_traits() {
+        fn messages(_: &dyn MessagesApi) {}
+        fn batches(_: &dyn BatchesApi) {}
+
+        let client = Client::builder().api_key("test").build();
+        messages(&client);
+        messages(&client.messages());
+        batches(&client);
+        batches(&client.batches());
+        let _: Arc<dyn MessagesApi> = Arc::new(client);
+    }
+}
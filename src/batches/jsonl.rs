@@ -0,0 +1,160 @@
+//! JSONL files in the format the Batches API uses.
+//!
+//! Batch requests can be prepared offline and written one per line, then
+//! loaded and submitted later; downloaded results can be archived and read
+//! back as `BatchResult`s.
+//!
+//! ```ignore
+//! use uno_anthropic::batches::jsonl;
+//!
+//! params.save_jsonl("batch.jsonl")?;
+//! let batch = client.batches().create(BatchCreateParams::load_jsonl("batch.jsonl")?).await?;
+//!
+//! for result in jsonl::load_results("results.jsonl")? {
+//!     println!("{}", result?.custom_id);
+//! }
+//! ```
+
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+
+use super::types::{BatchCreateParams, BatchMessageRequest, BatchResult};
+
+impl BatchCreateParams {
+    /// Write one request per line.
+    ///
+    /// The `effort` and `skills` shorthands are expanded first, since they are
+    /// not part of the serialized params.
+    pub fn write_jsonl<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        for request in &self.requests {
+            let mut request = request.clone();
+            request.params.prepare();
+            serde_json::to_writer(&mut writer, &request)?;
+            writer.write_all(b"\n")?;
+        }
+        writer.flush()
+    }
+
+    /// Read requests written by `write_jsonl`, one per line. Blank lines are
+    /// skipped.
+    pub fn read_jsonl<R: BufRead>(reader: R) -> io::Result<Self> {
+        let requests = parse_lines::<_, BatchMessageRequest>(reader).collect::<io::Result<_>>()?;
+        Ok(Self { requests })
+    }
+
+    /// Write the requests to a JSONL file at `path`.
+    pub fn save_jsonl(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        self.write_jsonl(BufWriter::new(File::create(path)?))
+    }
+
+    /// Read requests from a JSONL file at `path`.
+    pub fn load_jsonl(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::read_jsonl(BufReader::new(File::open(path)?))
+    }
+}
+
+/// Read batch results, one per line, as the results endpoint returns them.
+///
+/// Results are parsed lazily, so large archives are not loaded into memory.
+pub fn read_results<R: BufRead>(reader: R) -> impl Iterator<Item = io::Result<BatchResult>> {
+    parse_lines(reader)
+}
+
+/// Read batch results from a JSONL file at `path`.
+pub fn load_results(
+    path: impl AsRef<Path>,
+) -> io::Result<impl Iterator<Item = io::Result<BatchResult>>> {
+    Ok(read_results(BufReader::new(File::open(path)?)))
+}
+
+/// Write batch results one per line, in the format `read_results` accepts.
+pub fn write_results<'a, W: Write>(
+    mut writer: W,
+    results: impl IntoIterator<Item = &'a BatchResult>,
+) -> io::Result<()> {
+    for result in results {
+        serde_json::to_writer(&mut writer, result)?;
+        writer.write_all(b"\n")?;
+    }
+    writer.flush()
+}
+
+/// Parse each non-blank line of `reader` as a `T`.
+fn parse_lines<R, T>(reader: R) -> impl Iterator<Item = io::Result<T>>
+where
+    R: BufRead,
+    T: serde::de::DeserializeOwned,
+{
+    reader.lines().filter_map(|line| match line {
+        Ok(line) if line.trim().is_empty() => None,
+        Ok(line) => Some(serde_json::from_str(&line).map_err(io::Error::from)),
+        Err(e) => Some(Err(e)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::batches::types::BatchResultBody;
+    use crate::messages::params::MessageCreateParams;
+    use crate::types::message::MessageParam;
+    use crate::types::model::Model;
+
+    #[test]
+    fn test_requests_round_trip() {
+        let request = |id: &str| BatchMessageRequest {
+            custom_id: id.to_string(),
+            params: MessageCreateParams::builder()
+                .model(Model::ClaudeOpus4_6)
+                .max_tokens(10)
+                .message(MessageParam::user("Hi"))
+                .build(),
+        };
+        let params = BatchCreateParams {
+            requests: vec![request("a"), request("b")],
+        };
+
+        let mut out = Vec::new();
+        params.write_jsonl(&mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert_eq!(text.lines().count(), 2);
+        assert!(text.starts_with(r#"{"custom_id":"a","params":{"#));
+
+        let read = BatchCreateParams::read_jsonl(text.as_bytes()).unwrap();
+        assert_eq!(read.requests.len(), 2);
+        assert_eq!(read.requests[1].custom_id, "b");
+    }
+
+    #[test]
+    fn test_results_round_trip() {
+        let text = concat!(
+            r#"{"custom_id":"a","result":{"type":"succeeded","message":{"id":"msg_1","type":"message","role":"assistant","content":[{"type":"text","text":"Hi"}],"model":"claude-opus-4-6","stop_reason":"end_turn","usage":{"input_tokens":1,"output_tokens":1}}}}"#,
+            "\n\n",
+            r#"{"custom_id":"b","result":{"type":"errored","error":{"type":"invalid_request_error","message":"bad"}}}"#,
+            "\n",
+            r#"{"custom_id":"c","result":{"type":"expired"}}"#,
+        );
+        let results: Vec<BatchResult> = read_results(text.as_bytes())
+            .collect::<io::Result<_>>()
+            .unwrap();
+        assert_eq!(results.len(), 3);
+        assert!(matches!(results[1].result, BatchResultBody::Errored { .. }));
+
+        let mut out = Vec::new();
+        write_results(&mut out, &results).unwrap();
+        let again: Vec<BatchResult> = read_results(out.as_slice())
+            .collect::<io::Result<_>>()
+            .unwrap();
+        assert_eq!(again.len(), 3);
+        assert_eq!(again[2].custom_id, "c");
+        assert!(matches!(again[2].result, BatchResultBody::Expired));
+    }
+
+    #[test]
+    fn test_invalid_line_is_an_error() {
+        let mut results = read_results("not json\n".as_bytes());
+        let err = results.next().unwrap().unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+}
@@ -1,32 +1,147 @@
+pub mod jsonl;
 pub mod types;
 
 use std::pin::Pin;
+use std::time::Duration;
 
 use futures::stream::Stream;
+use reqwest::header::HeaderMap;
+use tokio_util::sync::CancellationToken;
+use tracing::warn;
 
-use crate::client::Client;
+use crate::client::{Client, RequestOptions, before_deadline};
 use crate::error::Error;
+use crate::messages::build_headers;
 use crate::types::Page;
 
 pub use self::types::*;
 
+/// How `BatchService::wait` polls a batch.
+///
+/// The interval starts at `initial_interval` and grows by `multiplier` after
+/// each poll that finds the batch still processing, up to `max_interval`.
+#[derive(Debug, Clone)]
+pub struct PollConfig {
+    /// Delay after the first poll (default: 5s).
+    pub initial_interval: Duration,
+    /// Longest delay between polls (default: 60s).
+    pub max_interval: Duration,
+    /// Growth factor applied to the interval after each poll (default: 1.5).
+    /// Values below 1.0, including NaN, keep the interval fixed.
+    pub multiplier: f64,
+    /// Give up with `Error::Timeout` after this long. Unlimited by default.
+    pub timeout: Option<Duration>,
+    /// Stop waiting with `Error::Cancelled` when this token is cancelled.
+    pub cancellation: Option<CancellationToken>,
+}
+
+impl Default for PollConfig {
+    fn default() -> Self {
+        Self {
+            initial_interval: Duration::from_secs(5),
+            max_interval: Duration::from_secs(60),
+            multiplier: 1.5,
+            timeout: None,
+            cancellation: None,
+        }
+    }
+}
+
+impl PollConfig {
+    /// Poll every `interval`, without backoff.
+    pub fn fixed(interval: Duration) -> Self {
+        Self {
+            initial_interval: interval,
+            max_interval: interval,
+            multiplier: 1.0,
+            ..Self::default()
+        }
+    }
+
+    /// The interval after `interval`, grown by `multiplier` and capped at
+    /// `max_interval`.
+    fn next_interval(&self, interval: Duration) -> Duration {
+        let multiplier = if self.multiplier >= 1.0 {
+            self.multiplier
+        } else {
+            1.0
+        };
+        Duration::try_from_secs_f64(interval.as_secs_f64() * multiplier)
+            .map_or(self.max_interval, |next| next.min(self.max_interval))
+    }
+}
+
 /// Service for the Message Batches API.
 ///
 /// Access via `client.batches()`.
 pub struct BatchService<'a> {
     pub(crate) client: &'a Client,
+    pub(crate) extra_headers: Option<HeaderMap>,
+    pub(crate) betas: Vec<String>,
 }
 
 impl<'a> BatchService<'a> {
-    pub(crate) fn new(client: &'a Client) -> Self {
-        Self { client }
+    /// Create a service bound to `client`. Equivalent to `client.batches()`.
+    pub fn new(client: &'a Client) -> Self {
+        Self {
+            client,
+            extra_headers: None,
+            betas: Vec::new(),
+        }
+    }
+
+    /// Create a service that sends `headers` with every request.
+    pub fn with_extra_headers(client: &'a Client, headers: HeaderMap) -> Self {
+        Self {
+            client,
+            extra_headers: Some(headers),
+            betas: Vec::new(),
+        }
+    }
+
+    /// Send the headers in `options` with requests made through this service.
+    pub fn with_options(mut self, options: RequestOptions) -> Self {
+        self.extra_headers = options.merge_into(self.extra_headers.take());
+        self
+    }
+
+    /// Set the beta features to enable for requests through this service.
+    ///
+    /// Needed when the params inside a batch use beta features, such as
+    /// extended output or cache TTLs. They are sent in the `anthropic-beta`
+    /// header alongside any betas the batch's params enable themselves.
+    pub fn with_betas(mut self, betas: Vec<String>) -> Self {
+        self.betas = betas;
+        self
+    }
+
+    /// Extra headers plus the service's `anthropic-beta` header.
+    fn headers(&self) -> Option<HeaderMap> {
+        build_headers(self.extra_headers.as_ref(), Some(&self.betas))
     }
 
     /// Create a new message batch.
     ///
     /// Calls `POST /v1/messages/batches`.
-    pub async fn create(&self, params: BatchCreateParams) -> Result<MessageBatch, Error> {
-        self.client.post("messages/batches", &params, None).await
+    ///
+    /// The `effort` and `skills` shorthands on each request are expanded, and
+    /// the betas they need are enabled for the batch.
+    pub async fn create(&self, mut params: BatchCreateParams) -> Result<MessageBatch, Error> {
+        let mut betas = self.betas.clone();
+        for request in &mut params.requests {
+            let explicit = request.params.betas.take();
+            request.params.prepare();
+            for beta in request.params.betas.iter().flatten() {
+                if !betas.contains(beta) {
+                    betas.push(beta.clone());
+                }
+            }
+            request.params.betas = explicit;
+        }
+        let headers = build_headers(self.extra_headers.as_ref(), Some(&betas));
+        self.client
+            .post("messages/batches", &params, headers.as_ref())
+            .await
     }
 
     /// Get a message batch by ID.
@@ -34,7 +149,144 @@ impl<'a> BatchService<'a> {
     /// Calls `GET /v1/messages/batches/{batch_id}`.
     pub async fn get(&self, batch_id: &str) -> Result<MessageBatch, Error> {
         let path = format!("messages/batches/{}", batch_id);
-        self.client.get(&path, None).await
+        self.client.get(&path, self.headers().as_ref()).await
+    }
+
+    /// Poll a batch with `get` until its processing has ended.
+    ///
+    /// Polls every `interval`. When a poll fails with a retryable error (such as
+    /// a 429 that outlasted the client's own retries), the next poll waits for
+    /// the error's `retry_after` instead, falling back to `interval`. Other
+    /// errors are returned immediately.
+    pub async fn wait_until_ended(
+        &self,
+        batch_id: &str,
+        interval: Duration,
+    ) -> Result<MessageBatch, Error> {
+        self.wait(batch_id, PollConfig::fixed(interval)).await
+    }
+
+    /// Poll a batch with `get`, backing off as configured by `config`, until
+    /// its processing has ended.
+    ///
+    /// Retryable poll failures are handled as in `wait_until_ended`. Fails
+    /// with `Error::Timeout` once `config.timeout` has passed, or with
+    /// `Error::Cancelled` when `config.cancellation` is cancelled.
+    pub async fn wait(&self, batch_id: &str, config: PollConfig) -> Result<MessageBatch, Error> {
+        self.poll_until(batch_id, &config, |batch| {
+            matches!(batch.processing_status, BatchProcessingStatus::Ended)
+        })
+        .await
+    }
+
+    /// Poll a batch until `done` accepts it, within the limits of `config`.
+    async fn poll_until(
+        &self,
+        batch_id: &str,
+        config: &PollConfig,
+        done: impl Fn(&MessageBatch) -> bool,
+    ) -> Result<MessageBatch, Error> {
+        let poll = async {
+            let mut interval = config.initial_interval;
+            loop {
+                let delay = match self.get(batch_id).await {
+                    Ok(batch) if done(&batch) => return Ok(batch),
+                    Ok(_) => {
+                        let delay = interval;
+                        interval = config.next_interval(interval);
+                        delay
+                    }
+                    Err(e) if e.is_retryable() => {
+                        let delay = e.retry_after().unwrap_or(interval);
+                        warn!(
+                            batch_id,
+                            error = %e,
+                            delay_ms = delay.as_millis() as u64,
+                            "batch poll failed, retrying"
+                        );
+                        delay
+                    }
+                    Err(e) => return Err(e),
+                };
+                tokio::time::sleep(delay).await;
+            }
+        };
+        let deadline = config.timeout.map(|t| tokio::time::Instant::now() + t);
+        let poll = before_deadline(deadline, poll);
+        match &config.cancellation {
+            Some(token) => tokio::select! {
+                biased;
+                _ = token.cancelled() => Err(Error::Cancelled),
+                result = poll => result,
+            },
+            None => poll.await,
+        }
+    }
+
+    /// Watch a batch's progress.
+    ///
+    /// Polls the batch as configured by `config` and yields it whenever its
+    /// status or request counts change, starting with its current state. The
+    /// stream ends after yielding the batch once processing has ended. The
+    /// poll interval backs off while nothing changes and resets when progress
+    /// is seen.
+    ///
+    /// Retryable poll failures are retried as in `wait`; other errors, and
+    /// `Error::Timeout` once `config.timeout` has passed, are yielded and end
+    /// the stream. Cancelling `config.cancellation` ends the stream.
+    pub fn watch(
+        &self,
+        batch_id: &str,
+        config: PollConfig,
+    ) -> Pin<Box<dyn Stream<Item = Result<MessageBatch, Error>> + Send>> {
+        let state = WatchState {
+            client: self.client.clone(),
+            headers: self.headers(),
+            batch_id: batch_id.to_string(),
+            deadline: config.timeout.map(|t| tokio::time::Instant::now() + t),
+            interval: config.initial_interval,
+            next_delay: Duration::ZERO,
+            last: None,
+            done: false,
+            config,
+        };
+        Box::pin(futures::stream::unfold(state, |mut state| async move {
+            while !state.done {
+                // A cancelled poll ends the stream.
+                match state.poll().await? {
+                    Ok(batch) => {
+                        let ended = batch.processing_status == BatchProcessingStatus::Ended;
+                        let snapshot = (
+                            batch.processing_status.clone(),
+                            batch.request_counts.clone(),
+                        );
+                        if ended || state.last.as_ref() != Some(&snapshot) {
+                            state.last = Some(snapshot);
+                            state.interval = state.config.initial_interval;
+                            state.next_delay = state.interval;
+                            state.done = ended;
+                            return Some((Ok(batch), state));
+                        }
+                        state.next_delay = state.interval;
+                        state.interval = state.config.next_interval(state.interval);
+                    }
+                    Err(e) if e.is_retryable() && !state.timed_out() => {
+                        state.next_delay = e.retry_after().unwrap_or(state.interval);
+                        warn!(
+                            batch_id = state.batch_id,
+                            error = %e,
+                            delay_ms = state.next_delay.as_millis() as u64,
+                            "batch poll failed, retrying"
+                        );
+                    }
+                    Err(e) => {
+                        state.done = true;
+                        return Some((Err(e), state));
+                    }
+                }
+            }
+            None
+        }))
     }
 
     /// List message batches.
@@ -47,7 +299,24 @@ impl<'a> BatchService<'a> {
         } else {
             format!("messages/batches?{}", query)
         };
-        self.client.get(&path, None).await
+        self.client.get(&path, self.headers().as_ref()).await
+    }
+
+    /// Fetch the page after `page`, or `None` if it was the last one.
+    ///
+    /// The next page uses the API's default page size.
+    pub async fn next_page(
+        &self,
+        page: &Page<MessageBatch>,
+    ) -> Result<Option<Page<MessageBatch>>, Error> {
+        let Some(cursor) = page.next_cursor() else {
+            return Ok(None);
+        };
+        let params = BatchListParams {
+            after_id: Some(cursor.to_string()),
+            ..Default::default()
+        };
+        self.list(params).await.map(Some)
     }
 
     /// Cancel a message batch.
@@ -56,16 +325,37 @@ impl<'a> BatchService<'a> {
     pub async fn cancel(&self, batch_id: &str) -> Result<MessageBatch, Error> {
         let path = format!("messages/batches/{}/cancel", batch_id);
         self.client
-            .post::<MessageBatch>(&path, &serde_json::Value::Null, None)
+            .post::<MessageBatch>(&path, &serde_json::Value::Null, self.headers().as_ref())
             .await
     }
 
+    /// Cancel a batch and wait for the cancellation to finish.
+    ///
+    /// Requests still processing when the cancel arrives may complete before
+    /// it takes effect, so the batch is polled as configured by `config` until
+    /// it leaves the `canceling` status. The returned batch has the final
+    /// request counts.
+    pub async fn cancel_and_wait(
+        &self,
+        batch_id: &str,
+        config: PollConfig,
+    ) -> Result<MessageBatch, Error> {
+        let batch = self.cancel(batch_id).await?;
+        if batch.processing_status != BatchProcessingStatus::Canceling {
+            return Ok(batch);
+        }
+        self.poll_until(batch_id, &config, |batch| {
+            batch.processing_status != BatchProcessingStatus::Canceling
+        })
+        .await
+    }
+
     /// Delete a message batch.
     ///
     /// Calls `DELETE /v1/messages/batches/{batch_id}`.
     pub async fn delete(&self, batch_id: &str) -> Result<DeletedMessageBatch, Error> {
         let path = format!("messages/batches/{}", batch_id);
-        self.client.delete(&path, None).await
+        self.client.delete(&path, self.headers().as_ref()).await
     }
 
     /// Stream the results of a completed message batch as JSONL.
@@ -81,7 +371,7 @@ impl<'a> BatchService<'a> {
         // Execute a raw GET and get the response body as a byte stream
         let bytes = self
             .client
-            .execute_raw("GET", &path, None::<&()>, None)
+            .execute_raw("GET", &path, None::<&()>, self.headers().as_ref())
             .await?;
 
         // Parse JSONL: each line is a JSON object
@@ -99,6 +389,50 @@ impl<'a> BatchService<'a> {
     }
 }
 
+/// State of a `BatchService::watch` stream.
+struct WatchState {
+    client: Client,
+    /// Extra headers, including any betas set on the service.
+    headers: Option<HeaderMap>,
+    batch_id: String,
+    config: PollConfig,
+    deadline: Option<tokio::time::Instant>,
+    /// Backoff interval while the batch is unchanged.
+    interval: Duration,
+    /// Delay before the next poll.
+    next_delay: Duration,
+    last: Option<(BatchProcessingStatus, BatchRequestCounts)>,
+    done: bool,
+}
+
+impl WatchState {
+    /// Wait `next_delay`, then poll the batch. Returns `None` if cancelled.
+    async fn poll(&self) -> Option<Result<MessageBatch, Error>> {
+        let service = BatchService {
+            client: &self.client,
+            extra_headers: self.headers.clone(),
+            betas: Vec::new(),
+        };
+        let poll = before_deadline(self.deadline, async {
+            tokio::time::sleep(self.next_delay).await;
+            service.get(&self.batch_id).await
+        });
+        match &self.config.cancellation {
+            Some(token) => tokio::select! {
+                biased;
+                _ = token.cancelled() => None,
+                result = poll => Some(result),
+            },
+            None => Some(poll.await),
+        }
+    }
+
+    fn timed_out(&self) -> bool {
+        self.deadline
+            .is_some_and(|deadline| tokio::time::Instant::now() >= deadline)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -108,4 +442,261 @@ mod tests {
         let params = BatchListParams::default();
         assert_eq!(params.to_query_string(), "");
     }
+
+    #[test]
+    fn test_next_interval_tolerates_bad_multipliers() {
+        let second = Duration::from_secs(1);
+        let config = |multiplier| PollConfig {
+            multiplier,
+            ..PollConfig::default()
+        };
+        assert_eq!(config(2.0).next_interval(second), Duration::from_secs(2));
+        assert_eq!(config(1e300).next_interval(second), Duration::from_secs(60));
+        assert_eq!(
+            config(f64::INFINITY).next_interval(second),
+            Duration::from_secs(60)
+        );
+        assert_eq!(config(-2.0).next_interval(second), second);
+        assert_eq!(config(f64::NAN).next_interval(second), second);
+    }
+
+    fn batch_json(status: &str) -> serde_json::Value {
+        serde_json::json!({
+            "id": "msgbatch_1",
+            "type": "message_batch",
+            "processing_status": status,
+            "request_counts": {"processing": 0, "succeeded": 1, "errored": 0, "canceled": 0, "expired": 0},
+            "created_at": "2025-01-01T00:00:00Z"
+        })
+    }
+
+    #[tokio::test]
+    async fn test_wait_until_ended_honors_retry_after() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v1/messages/batches/msgbatch_1"))
+            .respond_with(
+                ResponseTemplate::new(429)
+                    .insert_header("retry-after-ms", "10")
+                    .set_body_json(serde_json::json!({
+                        "type": "error",
+                        "error": {"type": "rate_limit_error", "message": "slow down"}
+                    })),
+            )
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/v1/messages/batches/msgbatch_1"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(batch_json("ended")))
+            .mount(&server)
+            .await;
+
+        let client = Client::builder()
+            .api_key("test")
+            .base_url(server.uri())
+            .max_retries(0)
+            .build();
+        let start = std::time::Instant::now();
+        let batch = client
+            .batches()
+            .wait_until_ended("msgbatch_1", Duration::from_secs(60))
+            .await
+            .unwrap();
+        assert!(matches!(
+            batch.processing_status,
+            BatchProcessingStatus::Ended
+        ));
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+
+    #[tokio::test]
+    async fn test_wait_backs_off_until_timeout() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v1/messages/batches/msgbatch_1"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(batch_json("in_progress")))
+            .mount(&server)
+            .await;
+
+        let client = Client::builder()
+            .api_key("test")
+            .base_url(server.uri())
+            .build();
+        let config = PollConfig {
+            initial_interval: Duration::from_millis(10),
+            max_interval: Duration::from_millis(40),
+            multiplier: 2.0,
+            timeout: Some(Duration::from_millis(200)),
+            cancellation: None,
+        };
+        let err = client
+            .batches()
+            .wait("msgbatch_1", config)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::Timeout));
+        // Without request latency: polls at 0, 10, 30, 70, 110, 150 and 190ms.
+        let polls = server.received_requests().await.unwrap().len();
+        assert!((3..=7).contains(&polls), "{polls} polls");
+
+        let token = CancellationToken::new();
+        token.cancel();
+        let config = PollConfig {
+            cancellation: Some(token),
+            ..PollConfig::default()
+        };
+        let err = client
+            .batches()
+            .wait("msgbatch_1", config)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::Cancelled));
+    }
+
+    #[tokio::test]
+    async fn test_watch_emits_on_progress() {
+        use futures::StreamExt;
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let progress = |status: &str, processing: u32, succeeded: u32| {
+            let mut batch = batch_json(status);
+            batch["request_counts"]["processing"] = processing.into();
+            batch["request_counts"]["succeeded"] = succeeded.into();
+            ResponseTemplate::new(200).set_body_json(batch)
+        };
+        let server = MockServer::start().await;
+        for (times, response) in [
+            (2, progress("in_progress", 2, 0)),
+            (1, progress("in_progress", 1, 1)),
+            (2, progress("in_progress", 0, 2)),
+            (1, progress("ended", 0, 2)),
+        ] {
+            Mock::given(method("GET"))
+                .and(path("/v1/messages/batches/msgbatch_1"))
+                .respond_with(response)
+                .up_to_n_times(times)
+                .expect(times)
+                .mount(&server)
+                .await;
+        }
+
+        let client = Client::builder()
+            .api_key("test")
+            .base_url(server.uri())
+            .build();
+        let updates: Vec<MessageBatch> = client
+            .batches()
+            .watch("msgbatch_1", PollConfig::fixed(Duration::from_millis(1)))
+            .map(Result::unwrap)
+            .collect()
+            .await;
+        let succeeded: Vec<u32> = updates.iter().map(|b| b.request_counts.succeeded).collect();
+        assert_eq!(succeeded, [0, 1, 2, 2]);
+        assert_eq!(
+            updates.last().unwrap().processing_status,
+            BatchProcessingStatus::Ended
+        );
+    }
+
+    #[tokio::test]
+    async fn test_create_with_betas() {
+        use wiremock::matchers::{header, headers, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        use crate::messages::params::MessageCreateParams;
+        use crate::types::message::MessageParam;
+        use crate::types::metadata::ReasoningEffort;
+        use crate::types::model::Model;
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/messages/batches"))
+            .and(headers(
+                "anthropic-beta",
+                vec!["output-128k-2025-02-19", "effort-2025-11-24"],
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(batch_json("in_progress")))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/v1/messages/batches/msgbatch_1"))
+            .and(header("anthropic-beta", "output-128k-2025-02-19"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(batch_json("ended")))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = Client::builder()
+            .api_key("test")
+            .base_url(server.uri())
+            .build();
+        let batches = client
+            .batches()
+            .with_betas(vec!["output-128k-2025-02-19".to_string()]);
+        let mut params = MessageCreateParams::builder()
+            .model(Model::ClaudeOpus4_6)
+            .max_tokens(100_000)
+            .message(MessageParam::user("Hi"))
+            .build();
+        params.effort = Some(ReasoningEffort::High);
+        batches
+            .create(BatchCreateParams {
+                requests: vec![BatchMessageRequest {
+                    custom_id: "a".to_string(),
+                    params,
+                }],
+            })
+            .await
+            .unwrap();
+        batches.get("msgbatch_1").await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_cancel_and_wait() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/messages/batches/msgbatch_1/cancel"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(batch_json("canceling")))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/v1/messages/batches/msgbatch_1"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(batch_json("canceling")))
+            .up_to_n_times(2)
+            .mount(&server)
+            .await;
+        let mut ended = batch_json("ended");
+        ended["request_counts"]["canceled"] = 4.into();
+        Mock::given(method("GET"))
+            .and(path("/v1/messages/batches/msgbatch_1"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(ended))
+            .mount(&server)
+            .await;
+
+        let client = Client::builder()
+            .api_key("test")
+            .base_url(server.uri())
+            .build();
+        let batch = client
+            .batches()
+            .cancel_and_wait("msgbatch_1", PollConfig::fixed(Duration::from_millis(1)))
+            .await
+            .unwrap();
+        assert_eq!(batch.processing_status, BatchProcessingStatus::Ended);
+        assert_eq!(batch.request_counts.canceled, 4);
+        assert_eq!(server.received_requests().await.unwrap().len(), 4);
+    }
 }
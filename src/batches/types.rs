@@ -6,6 +6,7 @@ use crate::types::message::Message;
 
 /// A message batch returned by the Batches API.
 #[derive(Debug, Clone, Deserialize)]
+#[cfg_attr(feature = "strict-serde", serde(deny_unknown_fields))]
 pub struct MessageBatch {
     pub id: String,
     #[serde(rename = "type")]
@@ -24,17 +25,24 @@ pub struct MessageBatch {
 }
 
 /// Processing status of a message batch.
-#[derive(Debug, Clone, Deserialize)]
+///
+/// Unrecognized statuses deserialize to `BatchProcessingStatus::Other(String)`
+/// so that listing batches keeps working when the API adds a status.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
 #[non_exhaustive]
 #[serde(rename_all = "snake_case")]
 pub enum BatchProcessingStatus {
     InProgress,
     Canceling,
     Ended,
+    /// Any status not in the known variants.
+    #[serde(untagged)]
+    Other(String),
 }
 
 /// Counts of requests in a batch, categorized by status.
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[cfg_attr(feature = "strict-serde", serde(deny_unknown_fields))]
 pub struct BatchRequestCounts {
     pub processing: u32,
     pub succeeded: u32,
@@ -44,13 +52,13 @@ pub struct BatchRequestCounts {
 }
 
 /// Parameters for creating a message batch.
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BatchCreateParams {
     pub requests: Vec<BatchMessageRequest>,
 }
 
 /// A single request within a batch.
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BatchMessageRequest {
     pub custom_id: String,
     pub params: MessageCreateParams,
@@ -84,24 +92,36 @@ impl BatchListParams {
 }
 
 /// A single result line from the batch results JSONL file.
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BatchResult {
     pub custom_id: String,
     pub result: BatchResultBody,
 }
 
 /// The result body for a batch request.
-#[derive(Debug, Clone, Deserialize)]
+///
+/// Unrecognized result types deserialize to `BatchResultBody::Other` with the
+/// raw JSON preserved.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[non_exhaustive]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum BatchResultBody {
-    Succeeded { message: Message },
-    Errored { error: ApiErrorBody },
+    Succeeded {
+        message: Box<Message>,
+    },
+    Errored {
+        error: ApiErrorBody,
+    },
     Canceled,
     Expired,
+    /// Any result type not in the known variants.
+    #[serde(untagged)]
+    Other(serde_json::Value),
 }
 
 /// Response from deleting a message batch.
 #[derive(Debug, Clone, Deserialize)]
+#[cfg_attr(feature = "strict-serde", serde(deny_unknown_fields))]
 pub struct DeletedMessageBatch {
     pub id: String,
     #[serde(rename = "type")]
@@ -224,6 +244,15 @@ mod tests {
         assert_eq!(deleted.deleted_type, "message_batch_deleted");
     }
 
+    #[test]
+    fn test_batch_request_jsonl_round_trip() {
+        let line = r#"{"custom_id":"req_1","params":{"messages":[{"role":"user","content":"Hello"}],"model":"claude-opus-4-6","max_tokens":100}}"#;
+        let request: BatchMessageRequest = serde_json::from_str(line).unwrap();
+        assert_eq!(request.custom_id, "req_1");
+        assert_eq!(request.params.max_tokens, 100);
+        assert_eq!(serde_json::to_string(&request).unwrap(), line);
+    }
+
     #[test]
     fn test_batch_list_params_query_string() {
         let params = BatchListParams {
@@ -235,4 +264,27 @@ mod tests {
         assert!(qs.contains("limit=10"));
         assert!(qs.contains("after_id=batch_abc"));
     }
+
+    #[test]
+    fn test_unknown_status_and_result_type() {
+        let json = r#"{
+            "id": "msgbatch_123",
+            "type": "message_batch",
+            "processing_status": "archiving",
+            "request_counts": {"processing": 0, "succeeded": 0, "errored": 0, "canceled": 0, "expired": 0},
+            "created_at": "2025-01-01T00:00:00Z"
+        }"#;
+        let batch: MessageBatch = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            batch.processing_status,
+            BatchProcessingStatus::Other("archiving".to_string())
+        );
+
+        let json = r#"{"custom_id": "a", "result": {"type": "deferred", "until": "later"}}"#;
+        let result: BatchResult = serde_json::from_str(json).unwrap();
+        match result.result {
+            BatchResultBody::Other(value) => assert_eq!(value["until"], "later"),
+            other => panic!("Expected Other, got {other:?}"),
+        }
+    }
 }
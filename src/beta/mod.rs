@@ -32,6 +32,7 @@ pub const BETA_FAST_MODE_2026_02_01: &str = "fast-mode-2026-02-01";
 pub const BETA_ADAPTIVE_THINKING_2026_01_28: &str = "adaptive-thinking-2026-01-28";
 pub const BETA_CLAUDE_CODE_20250219: &str = "claude-code-20250219";
 pub const BETA_EFFORT_2025_11_24: &str = "effort-2025-11-24";
+pub const BETA_ADVANCED_TOOL_USE_2025_11_20: &str = "advanced-tool-use-2025-11-20";
 pub const BETA_OAUTH_2025_04_20: &str = "oauth-2025-04-20";
 pub const BETA_PROMPT_CACHING_SCOPE_2026_01_05: &str = "prompt-caching-scope-2026-01-05";
 pub const BETA_CODE_EXECUTION_2026_01_20: &str = "code-execution-20260120";
@@ -119,6 +120,19 @@ impl<'a> BetaMessageService<'a> {
         service.create_stream(params).await
     }
 
+    /// Create a streaming message with beta features enabled and accumulate
+    /// it into a final `Message`.
+    pub async fn create_stream_accumulated(
+        &self,
+        params: MessageCreateParams,
+    ) -> Result<Message, Error> {
+        let service = match self.beta_headers() {
+            Some(headers) => MessageService::with_extra_headers(self.client, headers),
+            None => MessageService::new(self.client),
+        };
+        service.create_stream_accumulated(params).await
+    }
+
     /// Count tokens with beta features enabled.
     pub async fn count_tokens(
         &self,
@@ -0,0 +1,243 @@
+//! Cumulative token budgets for cost control.
+
+use std::sync::{Arc, Mutex};
+
+use tracing::warn;
+
+use crate::error::Error;
+use crate::telemetry::UsageMetrics;
+use crate::types::usage::Usage;
+
+/// A cap on the tokens a client or conversation may use in total.
+///
+/// Usage from every response is added up, and once any configured limit has
+/// been reached further requests are rejected with `Error::BudgetExceeded`
+/// before they are sent, or only logged as a warning with `warn_only`. A
+/// request that starts under the budget runs to completion, so totals can end
+/// up above a limit by at most one response.
+///
+/// Clones share their totals, so one budget can cover several clients or
+/// conversations belonging to the same tenant.
+///
+/// ```ignore
+/// let budget = TokenBudget::new().max_input_tokens(1_000_000).max_output_tokens(200_000);
+/// let client = Client::builder().token_budget(budget.clone()).build();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct TokenBudget {
+    limits: Limits,
+    used: Arc<Mutex<TokenTotals>>,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct Limits {
+    input: Option<u64>,
+    output: Option<u64>,
+    total: Option<u64>,
+    warn_only: bool,
+}
+
+/// Tokens counted against a `TokenBudget` so far.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TokenTotals {
+    /// Input tokens, including tokens written to and read from the cache.
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+}
+
+impl TokenTotals {
+    /// Input and output tokens combined.
+    pub fn total(&self) -> u64 {
+        self.input_tokens + self.output_tokens
+    }
+}
+
+impl TokenBudget {
+    /// A budget with no limits, which only counts usage until limits are set.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Allow at most `limit` input tokens.
+    pub fn max_input_tokens(mut self, limit: u64) -> Self {
+        self.limits.input = Some(limit);
+        self
+    }
+
+    /// Allow at most `limit` output tokens.
+    pub fn max_output_tokens(mut self, limit: u64) -> Self {
+        self.limits.output = Some(limit);
+        self
+    }
+
+    /// Allow at most `limit` input and output tokens combined.
+    pub fn max_total_tokens(mut self, limit: u64) -> Self {
+        self.limits.total = Some(limit);
+        self
+    }
+
+    /// Log a warning instead of rejecting requests once the budget is spent.
+    pub fn warn_only(mut self, warn_only: bool) -> Self {
+        self.limits.warn_only = warn_only;
+        self
+    }
+
+    /// The tokens counted so far.
+    pub fn used(&self) -> TokenTotals {
+        *self.lock()
+    }
+
+    /// Start counting from zero again, keeping the limits.
+    pub fn reset(&self) {
+        *self.lock() = TokenTotals::default();
+    }
+
+    /// Add the usage of a response to the totals.
+    pub fn record(&self, usage: &Usage) {
+        self.add(
+            [
+                usage.input_tokens,
+                usage.cache_creation_input_tokens.unwrap_or(0),
+                usage.cache_read_input_tokens.unwrap_or(0),
+            ],
+            usage.output_tokens,
+        );
+    }
+
+    /// Add the usage reported to metrics hooks, which the client records for
+    /// every message request.
+    pub(crate) fn record_metrics(&self, usage: &UsageMetrics) {
+        self.add(
+            [
+                usage.input_tokens,
+                usage.cache_creation_input_tokens.unwrap_or(0),
+                usage.cache_read_input_tokens.unwrap_or(0),
+            ],
+            usage.output_tokens,
+        );
+    }
+
+    fn add(&self, input_tokens: [u32; 3], output_tokens: u32) {
+        let mut used = self.lock();
+        used.input_tokens += input_tokens.iter().copied().map(u64::from).sum::<u64>();
+        used.output_tokens += u64::from(output_tokens);
+    }
+
+    /// Check whether another request may be sent.
+    ///
+    /// Fails with `Error::BudgetExceeded` once a limit has been reached,
+    /// unless the budget is `warn_only`.
+    pub fn check(&self) -> Result<(), Error> {
+        let Some(reason) = self.exceeded() else {
+            return Ok(());
+        };
+        if self.limits.warn_only {
+            warn!("token budget exceeded: {reason}");
+            Ok(())
+        } else {
+            Err(Error::BudgetExceeded(reason))
+        }
+    }
+
+    /// Describe the first limit that has been reached, if any.
+    fn exceeded(&self) -> Option<String> {
+        let used = self.used();
+        [
+            ("input", self.limits.input, used.input_tokens),
+            ("output", self.limits.output, used.output_tokens),
+            ("total", self.limits.total, used.total()),
+        ]
+        .into_iter()
+        .find_map(|(kind, limit, used)| {
+            let limit = limit.filter(|limit| used >= *limit)?;
+            Some(format!("used {used} of {limit} {kind} tokens"))
+        })
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, TokenTotals> {
+        self.used.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn usage(input: u32, output: u32) -> Usage {
+        serde_json::from_value(serde_json::json!({
+            "input_tokens": input,
+            "output_tokens": output,
+            "cache_read_input_tokens": 5
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_rejects_once_limit_reached() {
+        let budget = TokenBudget::new().max_output_tokens(100);
+        budget.record(&usage(10, 60));
+        assert!(budget.check().is_ok());
+
+        budget.record(&usage(10, 40));
+        assert_eq!(
+            budget.used(),
+            TokenTotals {
+                input_tokens: 30,
+                output_tokens: 100
+            }
+        );
+        let err = budget.check().unwrap_err();
+        assert!(matches!(err, Error::BudgetExceeded(_)));
+        assert_eq!(err.code(), "budget_exceeded");
+
+        budget.reset();
+        assert!(budget.check().is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_client_budget() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        use crate::client::ClientBuilder;
+        use crate::messages::params::MessageCreateParams;
+        use crate::types::message::MessageParam;
+        use crate::types::model::Model;
+
+        let mut message = crate::mock::text_message("Hi");
+        message.usage.input_tokens = 80;
+        message.usage.output_tokens = 30;
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/messages"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(&message))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let budget = TokenBudget::new().max_input_tokens(50);
+        let client = ClientBuilder::new()
+            .api_key("test")
+            .base_url(server.uri())
+            .token_budget(budget.clone())
+            .build();
+        let params = MessageCreateParams::builder()
+            .model(Model::ClaudeOpus4_6)
+            .max_tokens(10)
+            .message(MessageParam::user("Hi"))
+            .build();
+        client.messages().create(params.clone()).await.unwrap();
+        assert_eq!(budget.used().input_tokens, 80);
+
+        let err = client.messages().create(params).await.unwrap_err();
+        assert!(matches!(err, Error::BudgetExceeded(_)));
+    }
+
+    #[test]
+    fn test_total_limit_and_warn_only() {
+        let budget = TokenBudget::new().max_total_tokens(50).warn_only(true);
+        budget.clone().record(&usage(45, 10));
+        assert_eq!(budget.used().total(), 60);
+        assert!(budget.check().is_ok());
+    }
+}
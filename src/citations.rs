@@ -0,0 +1,324 @@
+//! Rendering of the citations attached to a message's text.
+//!
+//! ```ignore
+//! use uno_anthropic::citations::AnnotatedText;
+//!
+//! let message = client.messages().create(params).await?;
+//! let annotated = AnnotatedText::from_message(&message);
+//! println!("{}", annotated.to_markdown());
+//! ```
+
+use std::borrow::Cow;
+use std::ops::Range;
+
+use crate::types::citation::TextCitation;
+use crate::types::content::{ContentBlock, DocumentBlockParam};
+use crate::types::document::DocumentSource;
+use crate::types::message::Message;
+
+/// A message's text with the spans its citations support.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AnnotatedText {
+    /// The concatenated text of every text block.
+    pub text: String,
+    /// The cited spans of `text`, in order.
+    pub spans: Vec<CitedSpan>,
+    /// Every distinct citation in the message, in order of first use.
+    pub sources: Vec<TextCitation>,
+}
+
+/// A span of `AnnotatedText::text` backed by one or more sources.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CitedSpan {
+    /// Byte range of the span in `AnnotatedText::text`.
+    pub range: Range<usize>,
+    /// Indices into `AnnotatedText::sources`.
+    pub sources: Vec<usize>,
+}
+
+impl AnnotatedText {
+    /// Collect the text blocks of `message` and their citations.
+    pub fn from_message(message: &Message) -> Self {
+        let mut annotated = AnnotatedText::default();
+        for block in &message.content {
+            let ContentBlock::Text(block) = block else {
+                continue;
+            };
+            let start = annotated.text.len();
+            annotated.text.push_str(&block.text);
+            let citations = block.citations.as_deref().unwrap_or_default();
+            if citations.is_empty() {
+                continue;
+            }
+            let sources = citations
+                .iter()
+                .map(|citation| source_index(&mut annotated.sources, citation))
+                .collect();
+            annotated.spans.push(CitedSpan {
+                range: start..annotated.text.len(),
+                sources,
+            });
+        }
+        annotated
+    }
+
+    /// Render the text with a Markdown footnote marker after each cited span,
+    /// followed by the footnotes quoting the cited text.
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::with_capacity(self.text.len());
+        let mut written = 0;
+        for span in &self.spans {
+            out.push_str(&self.text[written..span.range.start]);
+            push_cited(&mut out, &self.text[span.range.clone()], &span.sources);
+            written = span.range.end;
+        }
+        out.push_str(&self.text[written..]);
+        push_footnotes(&mut out, &self.sources);
+        out
+    }
+}
+
+/// The index of `citation` in `sources`, adding it if it's new.
+pub(crate) fn source_index(sources: &mut Vec<TextCitation>, citation: &TextCitation) -> usize {
+    match sources.iter().position(|source| source == citation) {
+        Some(index) => index,
+        None => {
+            sources.push(citation.clone());
+            sources.len() - 1
+        }
+    }
+}
+
+/// Append `text` followed by a footnote marker for each of `sources`.
+pub(crate) fn push_cited(out: &mut String, text: &str, sources: &[usize]) {
+    // Keep markers ahead of whitespace that ends the span.
+    let trimmed = text.trim_end();
+    out.push_str(trimmed);
+    for &source in sources {
+        out.push_str(&format!("[^{}]", source + 1));
+    }
+    out.push_str(&text[trimmed.len()..]);
+}
+
+/// Append a footnote definition for each of `sources` after a blank line.
+pub(crate) fn push_footnotes(out: &mut String, sources: &[TextCitation]) {
+    if sources.is_empty() {
+        return;
+    }
+    out.truncate(out.trim_end().len());
+    out.push('\n');
+    for (index, source) in sources.iter().enumerate() {
+        out.push_str(&format!("\n[^{}]: {}", index + 1, footnote(source)));
+    }
+    out.push('\n');
+}
+
+/// The footnote text for a citation: where it comes from and what it quotes.
+fn footnote(citation: &TextCitation) -> String {
+    let (label, cited_text) = match citation {
+        TextCitation::CharLocation(c) => (
+            document_label(c.document_title.as_deref(), c.document_index),
+            c.cited_text.as_str(),
+        ),
+        TextCitation::PageLocation(c) => {
+            let title = document_label(c.document_title.as_deref(), c.document_index);
+            let pages = if c.end_page_number <= c.start_page_number + 1 {
+                format!("p. {}", c.start_page_number)
+            } else {
+                format!("pp. {}–{}", c.start_page_number, c.end_page_number - 1)
+            };
+            (format!("{title}, {pages}"), c.cited_text.as_str())
+        }
+        TextCitation::ContentBlockLocation(c) => (
+            document_label(c.document_title.as_deref(), c.document_index),
+            c.cited_text.as_str(),
+        ),
+        TextCitation::WebSearchResultLocation(c) => {
+            let title = c
+                .title
+                .as_deref()
+                .or(c.url.as_deref())
+                .unwrap_or("Web result");
+            let label = match &c.url {
+                Some(url) => format!("[{title}]({url})"),
+                None => title.to_string(),
+            };
+            (label, c.cited_text.as_str())
+        }
+        TextCitation::SearchResultLocation(c) => {
+            (format!("{} ({})", c.title, c.source), c.cited_text.as_str())
+        }
+        TextCitation::Other(value) => (
+            "Source".to_string(),
+            value
+                .get("cited_text")
+                .and_then(|text| text.as_str())
+                .unwrap_or_default(),
+        ),
+    };
+    let quote = cited_text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if quote.is_empty() {
+        label
+    } else {
+        format!("{label}: \"{quote}\"")
+    }
+}
+
+fn document_label(title: Option<&str>, index: u32) -> String {
+    title.map_or_else(|| format!("Document {index}"), str::to_string)
+}
+
+/// Look up the text a citation points at in the documents sent with the request.
+///
+/// `documents` must be the document blocks of the request in the order they
+/// were sent, since that is what `document_index` counts. Character locations
+/// resolve against plain-text documents and block locations against
+/// custom-content documents; anything else, including PDF pages and search
+/// results, returns `None`.
+pub fn resolve<'a>(
+    citation: &TextCitation,
+    documents: &'a [DocumentBlockParam],
+) -> Option<Cow<'a, str>> {
+    match citation {
+        TextCitation::CharLocation(c) => {
+            let DocumentSource::Text(source) = &documents.get(c.document_index as usize)?.source
+            else {
+                return None;
+            };
+            let range = char_range(
+                &source.data,
+                c.start_char_index as usize..c.end_char_index as usize,
+            )?;
+            Some(Cow::Borrowed(&source.data[range]))
+        }
+        TextCitation::ContentBlockLocation(c) => {
+            let DocumentSource::Content(source) = &documents.get(c.document_index as usize)?.source
+            else {
+                return None;
+            };
+            let blocks = source
+                .content
+                .get(c.start_block_index as usize..c.end_block_index as usize)?;
+            match blocks {
+                [block] => Some(Cow::Borrowed(&block.text)),
+                _ => Some(Cow::Owned(
+                    blocks
+                        .iter()
+                        .map(|block| block.text.as_str())
+                        .collect::<Vec<_>>()
+                        .join("\n"),
+                )),
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Convert a range of character indices in `text` to a byte range.
+fn char_range(text: &str, chars: Range<usize>) -> Option<Range<usize>> {
+    if chars.start > chars.end {
+        return None;
+    }
+    let mut offsets = text
+        .char_indices()
+        .map(|(offset, _)| offset)
+        .chain(std::iter::once(text.len()));
+    let start = offsets.nth(chars.start)?;
+    let end = if chars.end == chars.start {
+        start
+    } else {
+        offsets.nth(chars.end - chars.start - 1)?
+    };
+    Some(start..end)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::text_message;
+    use crate::types::content::TextBlockParam;
+    use crate::types::document::{ContentBlockSource, PlainTextSource};
+
+    fn cited_message() -> Message {
+        Message {
+            content: serde_json::from_value(serde_json::json!([
+                {"type": "text", "text": "According to the report, "},
+                {"type": "text", "text": "grass is green. ", "citations": [{
+                    "type": "char_location",
+                    "cited_text": "The grass\nis green.",
+                    "document_index": 0,
+                    "document_title": "Report",
+                    "start_char_index": 4,
+                    "end_char_index": 23
+                }]},
+                {"type": "text", "text": "So is moss.", "citations": [
+                    {
+                        "type": "web_search_result_location",
+                        "cited_text": "Moss is green.",
+                        "encrypted_index": "abc",
+                        "title": "Moss",
+                        "url": "https://example.com/moss"
+                    },
+                    {
+                        "type": "char_location",
+                        "cited_text": "The grass\nis green.",
+                        "document_index": 0,
+                        "document_title": "Report",
+                        "start_char_index": 4,
+                        "end_char_index": 23
+                    }
+                ]}
+            ]))
+            .unwrap(),
+            ..text_message("")
+        }
+    }
+
+    #[test]
+    fn test_annotated_text() {
+        let annotated = AnnotatedText::from_message(&cited_message());
+        assert_eq!(
+            annotated.text,
+            "According to the report, grass is green. So is moss."
+        );
+        assert_eq!(annotated.sources.len(), 2);
+        assert_eq!(annotated.spans[0].range, 25..41);
+        assert_eq!(annotated.spans[1].sources, [1, 0]);
+        assert_eq!(
+            annotated.to_markdown(),
+            "According to the report, grass is green.[^1] So is moss.[^2][^1]\n\n\
+             [^1]: Report: \"The grass is green.\"\n\
+             [^2]: [Moss](https://example.com/moss): \"Moss is green.\"\n"
+        );
+    }
+
+    #[test]
+    fn test_resolve() {
+        let annotated = AnnotatedText::from_message(&cited_message());
+        let documents = [
+            DocumentBlockParam::new(DocumentSource::Text(PlainTextSource {
+                media_type: "text/plain".to_string(),
+                data: "Já: The grass\nis green.".to_string(),
+            })),
+            DocumentBlockParam::new(DocumentSource::Content(ContentBlockSource {
+                content: vec![TextBlockParam::new("a"), TextBlockParam::new("b")],
+            })),
+        ];
+        assert_eq!(
+            resolve(&annotated.sources[0], &documents).as_deref(),
+            Some("The grass\nis green.")
+        );
+        assert_eq!(resolve(&annotated.sources[1], &documents), None);
+
+        let blocks: TextCitation = serde_json::from_value(serde_json::json!({
+            "type": "content_block_location",
+            "cited_text": "a b",
+            "document_index": 1,
+            "document_title": null,
+            "start_block_index": 0,
+            "end_block_index": 2
+        }))
+        .unwrap();
+        assert_eq!(resolve(&blocks, &documents).as_deref(), Some("a\nb"));
+    }
+}
@@ -1,15 +1,80 @@
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
-use reqwest::header::HeaderMap;
+use futures::{StreamExt, TryStreamExt};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
 use serde::Serialize;
 use serde::de::DeserializeOwned;
-use tracing::{debug, warn};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tracing::{Instrument, debug, warn};
 
+use crate::budget::TokenBudget;
 use crate::config::ClientConfig;
-use crate::error::{ApiErrorResponse, Error, is_retryable_status};
-use crate::middleware::{BoxFuture, Middleware, execute_middleware_chain};
-use crate::retry::{RetryPolicy, check_should_retry_header, parse_retry_after};
+use crate::credentials::ApiKeyProvider;
+use crate::error::{ApiErrorResponse, BuildError, Error, is_retryable_status};
+use crate::middleware::{
+    BoxFuture, Extensions, Middleware, OperationInfo, ReplayableRequest, ResponseHook,
+    ResponseMiddleware, execute_middleware_chain_with_extensions, operation_name,
+};
+use crate::models::ModelCache;
+use crate::rate_limit::RateLimiter;
+use crate::response::ApiResponse;
+use crate::retry::{
+    BackoffProfile, CircuitBreaker, CircuitBreakerConfig, JitterStrategy, QuotaGate,
+    RetryClassifier, RetryDecision, RetryPolicy, StreamRetryPolicy, check_should_retry_header,
+    parse_retry_after,
+};
+use crate::telemetry::{MetricsHook, RequestMetrics, RequestOutcome, finish_request, http_span};
+use crate::timing::{RequestTiming, TimedConnectLayer, TimedResolver, send_timed};
+use crate::types::metadata::{Metadata, ServiceTier};
+
+/// Per-call options for [`Client::request`] and the services' `with_options`.
+#[derive(Debug, Clone, Default)]
+#[non_exhaustive]
+pub struct RequestOptions {
+    /// Headers added to this request, overriding the client's defaults.
+    pub headers: HeaderMap,
+}
+
+impl RequestOptions {
+    /// Add a header to the request. Like `ClientBuilder::default_header`, an
+    /// invalid name or value is ignored; use `try_header` to detect it.
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        if let Ok((name, value)) = parse_header(name, value) {
+            self.headers.insert(name, value);
+        }
+        self
+    }
+
+    /// Add a header to the request, failing with `Error::Validation` if
+    /// `name` or `value` is not a valid header.
+    pub fn try_header(mut self, name: &str, value: &str) -> Result<Self, Error> {
+        let (name, value) = parse_header(name, value)?;
+        self.headers.insert(name, value);
+        Ok(self)
+    }
+
+    /// Layer these headers over a service's existing extra headers.
+    pub(crate) fn merge_into(self, headers: Option<HeaderMap>) -> Option<HeaderMap> {
+        if self.headers.is_empty() {
+            return headers;
+        }
+        let mut merged = headers.unwrap_or_default();
+        merged.extend(self.headers);
+        Some(merged)
+    }
+}
+
+/// Parse a caller-supplied header.
+fn parse_header(name: &str, value: &str) -> Result<(HeaderName, HeaderValue), Error> {
+    let name = name
+        .parse::<HeaderName>()
+        .map_err(|e| Error::Validation(format!("invalid header name `{name}`: {e}")))?;
+    let value = value
+        .parse::<HeaderValue>()
+        .map_err(|e| Error::Validation(format!("invalid value for header `{name}`: {e}")))?;
+    Ok((name, value))
+}
 
 /// Shared inner state for the client.
 pub(crate) struct ClientInner {
@@ -17,6 +82,13 @@ pub(crate) struct ClientInner {
     pub(crate) config: ClientConfig,
     pub(crate) retry_policy: RetryPolicy,
     pub(crate) middlewares: Vec<Box<dyn Middleware>>,
+    pub(crate) api_key_provider: Option<Arc<dyn ApiKeyProvider>>,
+    pub(crate) metrics_hook: Option<Arc<dyn MetricsHook>>,
+    pub(crate) concurrency: Option<Arc<Semaphore>>,
+    pub(crate) quota_gate: QuotaGate,
+    pub(crate) circuit_breaker: Option<CircuitBreaker>,
+    pub(crate) model_cache: Option<ModelCache>,
+    pub(crate) token_budget: Option<TokenBudget>,
 }
 
 /// The Anthropic API client.
@@ -79,6 +151,67 @@ impl Client {
         crate::beta::BetaService::new(self)
     }
 
+    /// Build the default headers for a request, resolving the API key through the
+    /// configured `ApiKeyProvider` if there is one.
+    pub(crate) async fn request_headers(&self) -> Result<HeaderMap, Error> {
+        let inner = &self.inner;
+        let mut headers = inner.config.build_headers();
+        if let Some(provider) = &inner.api_key_provider
+            && inner.config.auth_token.is_none()
+        {
+            let key = provider.api_key().await?;
+            let mut value = reqwest::header::HeaderValue::from_str(&key).map_err(|_| {
+                Error::Validation("API key from provider is not a valid header value".to_string())
+            })?;
+            value.set_sensitive(true);
+            headers.insert("x-api-key", value);
+        }
+        Ok(headers)
+    }
+
+    /// Send a request to any API endpoint and deserialize the JSON response.
+    ///
+    /// An escape hatch for endpoints the SDK has no typed service for yet.
+    /// `path` is relative to `/v1/` and may include a query string. The request
+    /// gets the same authentication, default headers, middleware and retries as
+    /// the typed services.
+    ///
+    /// ```ignore
+    /// let usage: serde_json::Value = client
+    ///     .request(Method::GET, "organizations/usage_report?limit=10", None, RequestOptions::default())
+    ///     .await?;
+    /// ```
+    pub async fn request<T: DeserializeOwned>(
+        &self,
+        method: reqwest::Method,
+        path: &str,
+        body: Option<serde_json::Value>,
+        options: RequestOptions,
+    ) -> Result<T, Error> {
+        let bytes = self.request_bytes(method, path, body, options).await?;
+        decode(&bytes)
+    }
+
+    /// Like [`request`](Self::request), but returns the raw response body.
+    pub async fn request_bytes(
+        &self,
+        method: reqwest::Method,
+        path: &str,
+        body: Option<serde_json::Value>,
+        options: RequestOptions,
+    ) -> Result<bytes::Bytes, Error> {
+        let headers = (!options.headers.is_empty()).then_some(&options.headers);
+        self.execute_raw(method.as_str(), path, body.as_ref(), headers)
+            .await
+    }
+
+    /// Wait for a slot if `max_concurrent_requests` is set.
+    async fn acquire_slot(&self) -> Option<OwnedSemaphorePermit> {
+        let semaphore = self.inner.concurrency.clone()?;
+        // The semaphore is never closed, so acquiring cannot fail.
+        semaphore.acquire_owned().await.ok()
+    }
+
     /// Execute a POST request, deserializing the JSON response into `T`.
     ///
     /// Handles middleware chain execution, retry logic, and error parsing.
@@ -91,8 +224,37 @@ impl Client {
         let bytes = self
             .execute_raw("POST", path, Some(body), extra_headers)
             .await?;
-        let result = serde_json::from_slice(&bytes)?;
-        Ok(result)
+        decode(&bytes)
+    }
+
+    /// Like `post`, but also returns the response metadata.
+    pub(crate) async fn post_with_response<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        body: &impl Serialize,
+        extra_headers: Option<&HeaderMap>,
+    ) -> Result<(T, ApiResponse), Error> {
+        let (bytes, response) = self
+            .execute_raw_with_response("POST", path, Some(body), extra_headers)
+            .await?;
+        Ok((decode(&bytes)?, response))
+    }
+
+    /// POST a multipart form, deserializing the JSON response into `T`.
+    ///
+    /// The form is read into memory first, so it can be resent on retries
+    /// and passes through middleware like any other request.
+    pub(crate) async fn post_multipart<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        form: reqwest::multipart::Form,
+        extra_headers: Option<&HeaderMap>,
+    ) -> Result<T, Error> {
+        let body = RequestBody::multipart(form).await?;
+        let (bytes, _) = self
+            .execute_body_with_response("POST", path, Some(&body), extra_headers)
+            .await?;
+        decode(&bytes)
     }
 
     /// Execute a GET request, deserializing the JSON response into `T`.
@@ -104,8 +266,7 @@ impl Client {
         let bytes = self
             .execute_raw("GET", path, None::<&()>, extra_headers)
             .await?;
-        let result = serde_json::from_slice(&bytes)?;
-        Ok(result)
+        decode(&bytes)
     }
 
     /// Execute a DELETE request, deserializing the JSON response into `T`.
@@ -117,8 +278,7 @@ impl Client {
         let bytes = self
             .execute_raw("DELETE", path, None::<&()>, extra_headers)
             .await?;
-        let result = serde_json::from_slice(&bytes)?;
-        Ok(result)
+        decode(&bytes)
     }
 
     /// Execute a raw HTTP request with retry logic and middleware.
@@ -131,52 +291,133 @@ impl Client {
         body: Option<&B>,
         extra_headers: Option<&HeaderMap>,
     ) -> Result<bytes::Bytes, Error> {
+        self.execute_raw_with_response(method, path, body, extra_headers)
+            .await
+            .map(|(bytes, _)| bytes)
+    }
+
+    /// Like `execute_raw`, but also returns the response metadata.
+    pub(crate) async fn execute_raw_with_response<B: Serialize>(
+        &self,
+        method: &str,
+        path: &str,
+        body: Option<&B>,
+        extra_headers: Option<&HeaderMap>,
+    ) -> Result<(bytes::Bytes, ApiResponse), Error> {
+        let body = body.map(RequestBody::json).transpose()?;
+        self.execute_body_with_response(method, path, body.as_ref(), extra_headers)
+            .await
+    }
+
+    /// Like `execute_raw_with_response`, with an already encoded body.
+    async fn execute_body_with_response(
+        &self,
+        method: &str,
+        path: &str,
+        body: Option<&RequestBody>,
+        extra_headers: Option<&HeaderMap>,
+    ) -> Result<(bytes::Bytes, ApiResponse), Error> {
+        let span = http_span(method, path);
+        let start = Instant::now();
+        let _permit = self.acquire_slot().instrument(span.clone()).await;
+        let mut outcome = RequestOutcome::default();
+        let result = self
+            .execute_raw_attempts(method, path, body, extra_headers, &mut outcome)
+            .instrument(span.clone())
+            .await;
+        let metrics = RequestMetrics::new(
+            method,
+            path,
+            false,
+            &outcome,
+            start.elapsed(),
+            result.as_ref().err(),
+        );
+        finish_request(&span, self.inner.metrics_hook.as_ref(), metrics);
+        result
+    }
+
+    async fn execute_raw_attempts(
+        &self,
+        method: &str,
+        path: &str,
+        body: Option<&RequestBody>,
+        extra_headers: Option<&HeaderMap>,
+        outcome: &mut RequestOutcome,
+    ) -> Result<(bytes::Bytes, ApiResponse), Error> {
         let inner = &self.inner;
         let url = format!(
             "{}/v1/{}",
             inner.config.base_url.trim_end_matches('/'),
             path.trim_start_matches('/')
         );
-        let headers = inner.config.build_headers();
+        let headers = self.request_headers().await?;
+
+        let mut request = inner
+            .http
+            .request(method.parse().unwrap_or(reqwest::Method::GET), &url)
+            .headers(headers);
+        if let Some(extra) = extra_headers {
+            request = request.headers(extra.clone());
+        }
+        if let Some(body) = body {
+            request = request
+                .header(reqwest::header::CONTENT_TYPE, body.content_type.clone())
+                .body(body.data.clone());
+        }
+        let replayable = replayable(request)?;
+        let operation = operation_name(method, path);
 
         let max_retries = inner.retry_policy.max_retries;
+        let started = tokio::time::Instant::now();
+        let deadline = inner.retry_policy.deadline(started);
 
         for attempt in 0..=max_retries {
-            let mut request = inner
-                .http
-                .request(method.parse().unwrap_or(reqwest::Method::GET), &url);
-
-            request = request.headers(headers.clone());
-
-            if let Some(extra) = extra_headers {
-                request = request.headers(extra.clone());
-            }
-
-            if let Some(b) = body {
-                request = request.json(b);
-            }
-
-            let req = request.build().map_err(Error::Http)?;
+            let req = replayable.to_request();
 
+            inner.quota_gate.wait().await;
             debug!(attempt, url = %url, method, "executing request");
-
-            let result = if inner.middlewares.is_empty() {
-                inner.http.execute(req).await.map_err(Error::Http)
-            } else {
-                let http = &inner.http;
-                execute_middleware_chain(
-                    &inner.middlewares,
-                    req,
-                    move |r| -> BoxFuture<'_, Result<reqwest::Response, Error>> {
-                        Box::pin(async move { http.execute(r).await.map_err(Error::Http) })
-                    },
-                )
-                .await
+            let start = Instant::now();
+            outcome.attempts = attempt + 1;
+
+            let send = async {
+                if inner.middlewares.is_empty() {
+                    send_timed(&inner.http, req, attempt).await
+                } else {
+                    let http = &inner.http;
+                    let extensions = Extensions::default();
+                    extensions.insert(OperationInfo {
+                        name: operation.clone(),
+                        attempt,
+                        streaming: false,
+                    });
+                    execute_middleware_chain_with_extensions(
+                        &inner.middlewares,
+                        req,
+                        extensions,
+                        move |r| -> BoxFuture<'_, Result<reqwest::Response, Error>> {
+                            Box::pin(send_timed(http, r, attempt))
+                        },
+                    )
+                    .await
+                }
             };
+            if let Some(breaker) = &inner.circuit_breaker {
+                breaker.admit()?;
+            }
+            let result = before_deadline(deadline, send).await;
+            if let Some(breaker) = &inner.circuit_breaker {
+                breaker.record(&result);
+            }
 
             match result {
                 Ok(response) => {
                     let status = response.status().as_u16();
+                    outcome.status = Some(status);
+                    outcome.timing = response.extensions().get::<RequestTiming>().copied();
+                    if let Some(delay) = inner.retry_policy.quota_delay(response.headers()) {
+                        inner.quota_gate.hold(delay);
+                    }
 
                     if status >= 400 {
                         // Check x-should-retry header
@@ -193,8 +434,13 @@ impl Client {
                                 message: String::from_utf8_lossy(&body_bytes).to_string(),
                             });
 
-                        if retryable && attempt < max_retries {
-                            let delay = inner.retry_policy.delay_for_attempt(attempt, retry_after);
+                        let error = Error::from_api_response(status, error_body, retry_after);
+                        let retryable = inner.retry_policy.should_retry(&error, attempt, retryable);
+                        let delay = inner.retry_policy.delay_for_error(&error, attempt);
+                        if retryable
+                            && attempt < max_retries
+                            && inner.retry_policy.allows_retry(started, delay)
+                        {
                             warn!(
                                 attempt,
                                 status,
@@ -205,19 +451,45 @@ impl Client {
                             continue;
                         }
 
-                        return Err(Error::Api {
-                            status,
-                            body: error_body,
-                            retry_after,
-                        });
+                        return Err(error);
                     }
 
-                    let bytes = response.bytes().await.map_err(Error::Http)?;
-                    return Ok(bytes);
+                    let mut meta = ApiResponse::new(&response, Duration::ZERO);
+                    let body_start = Instant::now();
+                    let bytes = before_deadline(deadline, async {
+                        response.bytes().await.map_err(Error::Http)
+                    })
+                    .await?;
+                    meta.elapsed = started.elapsed();
+                    meta.timing = meta
+                        .timing
+                        .map(|timing| timing.with_body_read(body_start.elapsed()));
+                    outcome.timing = meta.timing;
+                    debug!(
+                        attempt,
+                        status,
+                        dns_ms = meta
+                            .timing
+                            .and_then(|t| t.dns)
+                            .map(|d| d.as_millis() as u64),
+                        connect_ms = meta
+                            .timing
+                            .and_then(|t| t.connect)
+                            .map(|d| d.as_millis() as u64),
+                        headers_ms = meta.timing.map(|t| t.time_to_headers.as_millis() as u64),
+                        total_ms = start.elapsed().as_millis() as u64,
+                        "request completed"
+                    );
+                    return Ok((bytes, meta));
                 }
                 Err(e) => {
-                    if e.is_retryable() && attempt < max_retries {
-                        let delay = inner.retry_policy.delay_for_attempt(attempt, None);
+                    let delay = inner.retry_policy.delay_for_attempt(attempt, None);
+                    if inner
+                        .retry_policy
+                        .should_retry(&e, attempt, e.is_retryable())
+                        && attempt < max_retries
+                        && inner.retry_policy.allows_retry(started, delay)
+                    {
                         warn!(
                             attempt,
                             error = %e,
@@ -243,6 +515,37 @@ impl Client {
         path: &str,
         body: &impl Serialize,
         extra_headers: Option<&HeaderMap>,
+    ) -> Result<reqwest::Response, Error> {
+        let span = http_span("POST", path);
+        let start = Instant::now();
+        let permit = self.acquire_slot().instrument(span.clone()).await;
+        let mut outcome = RequestOutcome::default();
+        let result = self
+            .execute_streaming_attempts(path, body, extra_headers, &mut outcome)
+            .instrument(span.clone())
+            .await
+            .map(|response| match permit {
+                Some(permit) => hold_permit(response, permit),
+                None => response,
+            });
+        let metrics = RequestMetrics::new(
+            "POST",
+            path,
+            true,
+            &outcome,
+            start.elapsed(),
+            result.as_ref().err(),
+        );
+        finish_request(&span, self.inner.metrics_hook.as_ref(), metrics);
+        result
+    }
+
+    async fn execute_streaming_attempts(
+        &self,
+        path: &str,
+        body: &impl Serialize,
+        extra_headers: Option<&HeaderMap>,
+        outcome: &mut RequestOutcome,
     ) -> Result<reqwest::Response, Error> {
         let inner = &self.inner;
         let url = format!(
@@ -250,7 +553,7 @@ impl Client {
             inner.config.base_url.trim_end_matches('/'),
             path.trim_start_matches('/')
         );
-        let headers = inner.config.build_headers();
+        let headers = self.request_headers().await?;
 
         // Serialize to Value and inject "stream": true
         let mut body_value = serde_json::to_value(body)?;
@@ -258,39 +561,68 @@ impl Client {
             obj.insert("stream".to_string(), serde_json::Value::Bool(true));
         }
 
-        let max_retries = inner.retry_policy.max_retries;
-
-        for attempt in 0..=max_retries {
-            let mut request = inner.http.request(reqwest::Method::POST, &url);
-            request = request.headers(headers.clone());
-
-            if let Some(extra) = extra_headers {
-                request = request.headers(extra.clone());
-            }
+        let mut request = inner
+            .http
+            .request(reqwest::Method::POST, &url)
+            .headers(headers);
+        if let Some(extra) = extra_headers {
+            request = request.headers(extra.clone());
+        }
+        let replayable = replayable(request.json(&body_value))?;
+        let operation = operation_name("POST", path);
 
-            request = request.json(&body_value);
+        let max_retries = match inner.retry_policy.stream {
+            StreamRetryPolicy::Never => 0,
+            StreamRetryPolicy::BeforeMessageStart => inner.retry_policy.max_retries,
+        };
+        let started = tokio::time::Instant::now();
+        let deadline = inner.retry_policy.deadline(started);
 
-            let req = request.build().map_err(Error::Http)?;
+        for attempt in 0..=max_retries {
+            let req = replayable.to_request();
 
+            inner.quota_gate.wait().await;
             debug!(attempt, url = %url, "executing streaming request");
-
-            let result = if inner.middlewares.is_empty() {
-                inner.http.execute(req).await.map_err(Error::Http)
-            } else {
-                let http = &inner.http;
-                execute_middleware_chain(
-                    &inner.middlewares,
-                    req,
-                    move |r| -> BoxFuture<'_, Result<reqwest::Response, Error>> {
-                        Box::pin(async move { http.execute(r).await.map_err(Error::Http) })
-                    },
-                )
-                .await
+            outcome.attempts = attempt + 1;
+
+            let send = async {
+                if inner.middlewares.is_empty() {
+                    send_timed(&inner.http, req, attempt).await
+                } else {
+                    let http = &inner.http;
+                    let extensions = Extensions::default();
+                    extensions.insert(OperationInfo {
+                        name: operation.clone(),
+                        attempt,
+                        streaming: true,
+                    });
+                    execute_middleware_chain_with_extensions(
+                        &inner.middlewares,
+                        req,
+                        extensions,
+                        move |r| -> BoxFuture<'_, Result<reqwest::Response, Error>> {
+                            Box::pin(send_timed(http, r, attempt))
+                        },
+                    )
+                    .await
+                }
             };
+            if let Some(breaker) = &inner.circuit_breaker {
+                breaker.admit()?;
+            }
+            let result = before_deadline(deadline, send).await;
+            if let Some(breaker) = &inner.circuit_breaker {
+                breaker.record(&result);
+            }
 
             match result {
                 Ok(response) => {
                     let status = response.status().as_u16();
+                    outcome.status = Some(status);
+                    outcome.timing = response.extensions().get::<RequestTiming>().copied();
+                    if let Some(delay) = inner.retry_policy.quota_delay(response.headers()) {
+                        inner.quota_gate.hold(delay);
+                    }
 
                     if status >= 400 {
                         let should_retry = check_should_retry_header(response.headers());
@@ -305,8 +637,13 @@ impl Client {
                                 message: String::from_utf8_lossy(&body_bytes).to_string(),
                             });
 
-                        if retryable && attempt < max_retries {
-                            let delay = inner.retry_policy.delay_for_attempt(attempt, retry_after);
+                        let error = Error::from_api_response(status, error_body, retry_after);
+                        let retryable = inner.retry_policy.should_retry(&error, attempt, retryable);
+                        let delay = inner.retry_policy.delay_for_error(&error, attempt);
+                        if retryable
+                            && attempt < max_retries
+                            && inner.retry_policy.allows_retry(started, delay)
+                        {
                             warn!(
                                 attempt,
                                 status,
@@ -317,18 +654,28 @@ impl Client {
                             continue;
                         }
 
-                        return Err(Error::Api {
-                            status,
-                            body: error_body,
-                            retry_after,
-                        });
+                        return Err(error);
                     }
 
+                    debug!(
+                        attempt,
+                        status,
+                        headers_ms = response
+                            .extensions()
+                            .get::<RequestTiming>()
+                            .map(|t| t.time_to_headers.as_millis() as u64),
+                        "streaming response started"
+                    );
                     return Ok(response);
                 }
                 Err(e) => {
-                    if e.is_retryable() && attempt < max_retries {
-                        let delay = inner.retry_policy.delay_for_attempt(attempt, None);
+                    let delay = inner.retry_policy.delay_for_attempt(attempt, None);
+                    if inner
+                        .retry_policy
+                        .should_retry(&e, attempt, e.is_retryable())
+                        && attempt < max_retries
+                        && inner.retry_policy.allows_retry(started, delay)
+                    {
                         warn!(
                             attempt,
                             error = %e,
@@ -347,6 +694,129 @@ impl Client {
     }
 }
 
+/// Build `request` once so every attempt sends the same headers and body.
+/// A request body encoded up front, so every attempt sends the same bytes.
+struct RequestBody {
+    content_type: HeaderValue,
+    data: bytes::Bytes,
+}
+
+impl RequestBody {
+    fn json(body: &impl Serialize) -> Result<Self, Error> {
+        Ok(Self {
+            content_type: HeaderValue::from_static("application/json"),
+            data: serde_json::to_vec(body)?.into(),
+        })
+    }
+
+    async fn multipart(form: reqwest::multipart::Form) -> Result<Self, Error> {
+        let content_type = HeaderValue::from_str(&format!(
+            "multipart/form-data; boundary={}",
+            form.boundary()
+        ))
+        .map_err(|e| Error::Validation(format!("invalid multipart boundary: {e}")))?;
+        let chunks: Vec<bytes::Bytes> = form
+            .into_stream()
+            .try_collect()
+            .await
+            .map_err(Error::Http)?;
+        Ok(Self {
+            content_type,
+            data: chunks.concat().into(),
+        })
+    }
+}
+
+fn replayable(request: reqwest::RequestBuilder) -> Result<ReplayableRequest, Error> {
+    let request = request.build().map_err(Error::Http)?;
+    ReplayableRequest::from_request(&request)
+        .ok_or_else(|| Error::Validation("request body cannot be replayed".to_string()))
+}
+
+/// Bytes of body kept on each side of a decode failure in `Error::Decode`.
+const DECODE_SNIPPET_CONTEXT: usize = 120;
+
+/// Deserialize a JSON response body into `T`.
+///
+/// Failures are reported as `Error::Decode` with the path to the offending
+/// value and the part of the body around it.
+pub(crate) fn decode<T: DeserializeOwned>(bytes: &[u8]) -> Result<T, Error> {
+    let mut deserializer = serde_json::Deserializer::from_slice(bytes);
+    let value = serde_path_to_error::deserialize(&mut deserializer).map_err(|err| {
+        let path = err.path().to_string();
+        decode_error(bytes, path, err.into_inner())
+    })?;
+    // Reject trailing data after the value, as `serde_json::from_slice` does.
+    deserializer
+        .end()
+        .map_err(|source| decode_error(bytes, ".".to_string(), source))?;
+    Ok(value)
+}
+
+fn decode_error(bytes: &[u8], path: String, source: serde_json::Error) -> Error {
+    Error::Decode {
+        body_snippet: body_snippet(bytes, &source),
+        source,
+        path,
+    }
+}
+
+/// The part of `bytes` around where `err` occurred, with secrets redacted.
+fn body_snippet(bytes: &[u8], err: &serde_json::Error) -> String {
+    let line_start: usize = bytes
+        .split_inclusive(|&b| b == b'\n')
+        .take(err.line().saturating_sub(1))
+        .map(<[u8]>::len)
+        .sum();
+    let offset = (line_start + err.column()).min(bytes.len());
+    let start = offset.saturating_sub(DECODE_SNIPPET_CONTEXT);
+    let end = (offset + DECODE_SNIPPET_CONTEXT).min(bytes.len());
+
+    let mut snippet = String::new();
+    if start > 0 {
+        snippet.push('…');
+    }
+    snippet.push_str(&String::from_utf8_lossy(&bytes[start..end]));
+    if end < bytes.len() {
+        snippet.push('…');
+    }
+    crate::redact::redact_secrets(&snippet).into_owned()
+}
+
+/// Run `fut`, failing with `Error::Timeout` if `deadline` passes first.
+pub(crate) async fn before_deadline<T>(
+    deadline: Option<tokio::time::Instant>,
+    fut: impl Future<Output = Result<T, Error>>,
+) -> Result<T, Error> {
+    match deadline {
+        Some(deadline) => tokio::time::timeout_at(deadline, fut)
+            .await
+            .unwrap_or(Err(Error::Timeout)),
+        None => fut.await,
+    }
+}
+
+/// Rebuild `response` so its body keeps `permit` until the body is dropped.
+fn hold_permit(response: reqwest::Response, permit: OwnedSemaphorePermit) -> reqwest::Response {
+    let mut builder = http::Response::builder()
+        .status(response.status())
+        .version(response.version());
+    if let Some(headers) = builder.headers_mut() {
+        *headers = response.headers().clone();
+    }
+    if let Some(timing) = response.extensions().get::<RequestTiming>() {
+        builder = builder.extension(*timing);
+    }
+    let body = response.bytes_stream().map(move |chunk| {
+        let _permit = &permit;
+        chunk
+    });
+    let response = builder
+        .body(reqwest::Body::wrap_stream(body))
+        .expect("parts are copied from a valid response");
+    reqwest::Response::from(response)
+}
+
 impl Default for Client {
     fn default() -> Self {
         Self::new()
@@ -359,28 +829,78 @@ pub struct ClientBuilder {
     retry_policy: RetryPolicy,
     http_client: Option<reqwest::Client>,
     middlewares: Vec<Box<dyn Middleware>>,
+    api_key_provider: Option<Arc<dyn ApiKeyProvider>>,
+    metrics_hook: Option<Arc<dyn MetricsHook>>,
+    rate_limiter: Option<RateLimiter>,
+    max_concurrent_requests: Option<usize>,
     proxy_url: Option<String>,
     accept_invalid_certs: bool,
+    model_cache_ttl: Option<Duration>,
+    token_budget: Option<TokenBudget>,
+    /// Whether `config.auth_token` came from `ANTHROPIC_AUTH_TOKEN`, so an
+    /// explicit API key replaces it.
+    auth_token_from_env: bool,
 }
 
 impl ClientBuilder {
     pub fn new() -> Self {
+        let config = ClientConfig::from_env();
         Self {
-            config: ClientConfig::from_env(),
+            auth_token_from_env: config.auth_token.is_some(),
+            config,
             retry_policy: RetryPolicy::default(),
             http_client: None,
             middlewares: Vec::new(),
+            api_key_provider: None,
+            metrics_hook: None,
+            rate_limiter: None,
+            max_concurrent_requests: None,
             proxy_url: None,
             accept_invalid_certs: false,
+            model_cache_ttl: None,
+            token_budget: None,
         }
     }
 
     /// Set the API key.
+    ///
+    /// Replaces a token read from `ANTHROPIC_AUTH_TOKEN`, but not one set with
+    /// `auth_token`.
     pub fn api_key(mut self, key: impl Into<String>) -> Self {
         self.config.api_key = key.into();
+        self.clear_env_auth_token();
+        self
+    }
+
+    /// Resolve the API key through `provider` on every request.
+    ///
+    /// Takes precedence over `api_key` and replaces a token read from
+    /// `ANTHROPIC_AUTH_TOKEN`; an `auth_token` set explicitly still takes
+    /// precedence over both.
+    pub fn api_key_provider(mut self, provider: impl ApiKeyProvider + 'static) -> Self {
+        self.api_key_provider = Some(Arc::new(provider));
+        self.clear_env_auth_token();
+        self
+    }
+
+    /// Authenticate with `Authorization: Bearer <token>` instead of `x-api-key`.
+    ///
+    /// For gateways and OAuth access tokens. When set, the API key is not sent.
+    pub fn auth_token(mut self, token: impl Into<String>) -> Self {
+        self.config.auth_token = Some(token.into());
+        self.auth_token_from_env = false;
         self
     }
 
+    /// Drop an auth token read from the environment, so an explicitly
+    /// configured API key is the one sent.
+    fn clear_env_auth_token(&mut self) {
+        if self.auth_token_from_env {
+            self.config.auth_token = None;
+            self.auth_token_from_env = false;
+        }
+    }
+
     /// Set the base URL.
     pub fn base_url(mut self, url: impl Into<String>) -> Self {
         self.config.base_url = url.into();
@@ -393,6 +913,72 @@ impl ClientBuilder {
         self
     }
 
+    /// Delay upcoming requests until a rate limit quota resets once a response
+    /// shows `threshold` or less of it remaining (e.g. `0.05` for 5%).
+    ///
+    /// Uses the `anthropic-ratelimit-*` headers of every response, so the client
+    /// slows down before it starts receiving 429s.
+    pub fn proactive_backoff(mut self, threshold: f64) -> Self {
+        self.retry_policy.proactive_backoff_threshold = Some(threshold);
+        self
+    }
+
+    /// Cap the total time spent on a request, including retries and backoff.
+    ///
+    /// Retries stop once the next backoff would overrun `budget`, and an
+    /// attempt still in flight when it runs out fails with `Error::Timeout`.
+    pub fn max_elapsed(mut self, budget: Duration) -> Self {
+        self.retry_policy.max_elapsed = Some(budget);
+        self
+    }
+
+    /// Decide which failures are retried with `classifier`, which receives the
+    /// error and the zero-based attempt number.
+    ///
+    /// Return `RetryDecision::Default` to keep the built-in behavior for an
+    /// error. Retries remain bounded by `max_retries` and `max_elapsed`.
+    pub fn retry_classifier(
+        mut self,
+        classifier: impl Fn(&Error, u32) -> RetryDecision + Send + Sync + 'static,
+    ) -> Self {
+        self.retry_policy.classifier = Some(RetryClassifier::new(classifier));
+        self
+    }
+
+    /// Set the backoff for 529 `overloaded_error` responses.
+    ///
+    /// Defaults to 2s doubling up to 30s, longer than for other failures so
+    /// an overloaded API gets time to recover.
+    pub fn overloaded_backoff(mut self, initial_delay: Duration, max_delay: Duration) -> Self {
+        self.retry_policy.overloaded_backoff = BackoffProfile {
+            initial_delay,
+            max_delay,
+        };
+        self
+    }
+
+    /// Set how retry backoff delays are randomized.
+    pub fn jitter(mut self, jitter: JitterStrategy) -> Self {
+        self.retry_policy.jitter = jitter;
+        self
+    }
+
+    /// Fail fast with `Error::CircuitOpen` after repeated server failures.
+    ///
+    /// See [`CircuitBreakerConfig`] for how the circuit opens and recovers.
+    pub fn circuit_breaker(mut self, config: CircuitBreakerConfig) -> Self {
+        self.retry_policy.circuit_breaker = Some(config);
+        self
+    }
+
+    /// Set which streaming failures are retried.
+    ///
+    /// Defaults to `StreamRetryPolicy::BeforeMessageStart`.
+    pub fn stream_retry_policy(mut self, policy: StreamRetryPolicy) -> Self {
+        self.retry_policy.stream = policy;
+        self
+    }
+
     /// Set the request timeout.
     pub fn timeout(mut self, timeout: Duration) -> Self {
         self.config.timeout = timeout;
@@ -417,6 +1003,41 @@ impl ClientBuilder {
         self
     }
 
+    /// Set the service tier used by message requests that don't specify one.
+    pub fn default_service_tier(mut self, tier: ServiceTier) -> Self {
+        self.config.default_service_tier = Some(tier);
+        self
+    }
+
+    /// Set the metadata used by message requests that don't specify any.
+    ///
+    /// Handy for attributing all traffic from a deployment to one `user_id`.
+    pub fn default_metadata(mut self, metadata: Metadata) -> Self {
+        self.config.default_metadata = Some(metadata);
+        self
+    }
+
+    /// Strip Markdown code fences and language tags wrapping the text blocks of
+    /// `messages().create` responses. Off by default.
+    ///
+    /// Useful when extracting JSON or code from answers, and applied before
+    /// `messages().create_typed` parses the text.
+    pub fn strip_code_fences(mut self, strip: bool) -> Self {
+        self.config.strip_code_fences = strip;
+        self
+    }
+
+    /// Stream `messages().create` requests that are too large to finish
+    /// within the request timeout, and return the accumulated message. Off by
+    /// default, in which case such requests are only logged as a warning.
+    ///
+    /// Generation time is estimated from `max_tokens`, at one hour for
+    /// 128,000 tokens.
+    pub fn auto_stream_large_requests(mut self, enabled: bool) -> Self {
+        self.config.auto_stream_large_requests = enabled;
+        self
+    }
+
     /// Add a default header.
     pub fn default_header(mut self, name: &str, value: &str) -> Self {
         if let (Ok(name), Ok(value)) = (
@@ -434,6 +1055,55 @@ impl ClientBuilder {
         self
     }
 
+    /// Add a hook that can inspect or rewrite responses.
+    ///
+    /// The hook runs in the middleware chain at the position it is added.
+    pub fn response_hook(self, hook: impl ResponseHook + 'static) -> Self {
+        self.middleware(ResponseMiddleware(hook))
+    }
+
+    /// Throttle requests through `limiter` before they are sent.
+    ///
+    /// The limiter runs after any other middleware, so it sees the final request.
+    pub fn rate_limiter(mut self, limiter: RateLimiter) -> Self {
+        self.rate_limiter = Some(limiter);
+        self
+    }
+
+    /// Allow at most `n` requests in flight at once across all clones of the
+    /// client. Further requests wait for a slot.
+    ///
+    /// A request holds its slot through any retries, and a streaming request
+    /// holds it until the stream is dropped.
+    pub fn max_concurrent_requests(mut self, n: usize) -> Self {
+        self.max_concurrent_requests = Some(n);
+        self
+    }
+
+    /// Cache the model list for `ttl`, shared by all clones of the client.
+    ///
+    /// `models().list_all()` and `models().get()` answer from the cache while
+    /// it is fresh instead of calling the API.
+    pub fn model_cache_ttl(mut self, ttl: Duration) -> Self {
+        self.model_cache_ttl = Some(ttl);
+        self
+    }
+
+    /// Count the usage of every message request against `budget`, and reject
+    /// requests once it is spent.
+    ///
+    /// Pass a clone of the same budget to several clients to share it.
+    pub fn token_budget(mut self, budget: TokenBudget) -> Self {
+        self.token_budget = Some(budget);
+        self
+    }
+
+    /// Report request latency and token usage to `hook`.
+    pub fn metrics_hook(mut self, hook: impl MetricsHook + 'static) -> Self {
+        self.metrics_hook = Some(Arc::new(hook));
+        self
+    }
+
     /// Route all requests through the given proxy URL.
     ///
     /// Ignored if a custom `http_client` is provided.
@@ -452,30 +1122,105 @@ impl ClientBuilder {
     }
 
     /// Build the `Client`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the proxy URL is invalid or the HTTP client cannot be built.
+    /// Prefer `try_build`, which also validates the API key and base URL.
     pub fn build(self) -> Client {
-        let http = self.http_client.unwrap_or_else(|| {
-            let mut builder = reqwest::Client::builder()
-                .timeout(self.config.timeout)
-                .tcp_keepalive(std::time::Duration::from_secs(60));
+        self.finish()
+            .unwrap_or_else(|e| panic!("failed to build client: {e}"))
+    }
+
+    /// Validate the configuration and build the `Client`.
+    ///
+    /// Checks that an API key (or auth token) is set and usable as a header
+    /// value, that the base URL is an absolute `http`/`https` URL, and that the
+    /// proxy URL (if any) is valid. No credential is required when a middleware
+    /// is installed, since integrations such as Bedrock and Vertex authenticate
+    /// through middleware.
+    pub fn try_build(self) -> Result<Client, BuildError> {
+        let credential = self
+            .config
+            .auth_token
+            .as_deref()
+            .unwrap_or(&self.config.api_key);
+        if credential.is_empty() && self.api_key_provider.is_none() {
+            if self.middlewares.is_empty() {
+                return Err(BuildError::MissingApiKey);
+            }
+        } else if credential.trim() != credential
+            || reqwest::header::HeaderValue::from_str(credential).is_err()
+        {
+            return Err(BuildError::InvalidApiKey(
+                "contains whitespace or characters not allowed in a header".to_string(),
+            ));
+        }
 
-            if let Some(ref proxy_url) = self.proxy_url {
-                builder = builder.proxy(reqwest::Proxy::all(proxy_url).expect("invalid proxy URL"));
+        let base_url = &self.config.base_url;
+        match reqwest::Url::parse(base_url) {
+            Ok(url) if matches!(url.scheme(), "http" | "https") => {}
+            Ok(url) => {
+                return Err(BuildError::InvalidBaseUrl {
+                    url: base_url.clone(),
+                    reason: format!("unsupported scheme '{}'", url.scheme()),
+                });
             }
-            if self.accept_invalid_certs {
-                builder = builder.danger_accept_invalid_certs(true);
+            Err(e) => {
+                return Err(BuildError::InvalidBaseUrl {
+                    url: base_url.clone(),
+                    reason: e.to_string(),
+                });
             }
+        }
+
+        self.finish()
+    }
+
+    fn finish(mut self) -> Result<Client, BuildError> {
+        if let Some(limiter) = self.rate_limiter.take() {
+            self.middlewares.push(Box::new(limiter));
+        }
+
+        let http = match self.http_client {
+            Some(http) => http,
+            None => {
+                let mut builder = reqwest::Client::builder()
+                    .timeout(self.config.timeout)
+                    .tcp_keepalive(std::time::Duration::from_secs(60))
+                    .dns_resolver(Arc::new(TimedResolver))
+                    .connector_layer(TimedConnectLayer);
+
+                if let Some(ref proxy_url) = self.proxy_url {
+                    builder = builder
+                        .proxy(reqwest::Proxy::all(proxy_url).map_err(BuildError::InvalidProxy)?);
+                }
+                if self.accept_invalid_certs {
+                    builder = builder.danger_accept_invalid_certs(true);
+                }
 
-            builder.build().expect("failed to build reqwest client")
-        });
+                builder.build().map_err(BuildError::HttpClient)?
+            }
+        };
 
-        Client {
+        let circuit_breaker = self.retry_policy.circuit_breaker.map(CircuitBreaker::new);
+        Ok(Client {
             inner: Arc::new(ClientInner {
                 http,
                 config: self.config,
                 retry_policy: self.retry_policy,
                 middlewares: self.middlewares,
+                api_key_provider: self.api_key_provider,
+                metrics_hook: self.metrics_hook,
+                concurrency: self
+                    .max_concurrent_requests
+                    .map(|n| Arc::new(Semaphore::new(n.max(1)))),
+                quota_gate: QuotaGate::default(),
+                circuit_breaker,
+                model_cache: self.model_cache_ttl.map(ModelCache::new),
+                token_budget: self.token_budget,
             }),
-        }
+        })
     }
 }
 
@@ -489,6 +1234,29 @@ impl Default for ClientBuilder {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_request_options_headers() {
+        let options = RequestOptions::default()
+            .header("x-tenant", "a")
+            .header("bad name", "b")
+            .header("x-bad-value", "c\nd");
+        assert_eq!(options.headers.len(), 1);
+        assert_eq!(options.headers["x-tenant"], "a");
+
+        let options = RequestOptions::default()
+            .try_header("x-tenant", "a")
+            .unwrap();
+        assert_eq!(options.headers["x-tenant"], "a");
+        assert!(matches!(
+            RequestOptions::default().try_header("bad name", "b"),
+            Err(Error::Validation(_))
+        ));
+        assert!(matches!(
+            RequestOptions::default().try_header("x-bad-value", "c\nd"),
+            Err(Error::Validation(_))
+        ));
+    }
+
     #[test]
     fn test_client_builder_defaults() {
         let client = ClientBuilder::new().api_key("test-key").build();
@@ -544,4 +1312,492 @@ mod tests {
         let debug = format!("{:?}", client);
         assert!(debug.contains("https://api.example.com"));
     }
+
+    #[tokio::test]
+    async fn test_response_carries_request_timing() {
+        use std::sync::Mutex;
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        use crate::middleware::Next;
+
+        struct CaptureTiming(Arc<Mutex<Option<RequestTiming>>>);
+
+        impl Middleware for CaptureTiming {
+            fn handle<'a>(
+                &'a self,
+                request: reqwest::Request,
+                next: Next<'a>,
+            ) -> BoxFuture<'a, Result<reqwest::Response, Error>> {
+                Box::pin(async move {
+                    let response = next.run(request).await?;
+                    *self.0.lock().unwrap() = response.extensions().get::<RequestTiming>().copied();
+                    Ok(response)
+                })
+            }
+        }
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v1/models/claude-opus-4-6"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({})))
+            .mount(&server)
+            .await;
+
+        let captured = Arc::new(Mutex::new(None));
+        let client = ClientBuilder::new()
+            .api_key("test")
+            .base_url(server.uri())
+            .middleware(CaptureTiming(captured.clone()))
+            .build();
+        let _: serde_json::Value = client.get("models/claude-opus-4-6", None).await.unwrap();
+
+        let timing = captured.lock().unwrap().expect("timing should be attached");
+        assert_eq!(timing.attempt, 0);
+    }
+
+    #[test]
+    fn test_try_build_validates_config() {
+        let err = ClientBuilder::new().api_key("").try_build().unwrap_err();
+        assert!(matches!(err, BuildError::MissingApiKey));
+
+        let err = ClientBuilder::new()
+            .api_key("bad key\n")
+            .try_build()
+            .unwrap_err();
+        assert!(matches!(err, BuildError::InvalidApiKey(_)));
+
+        let err = ClientBuilder::new()
+            .api_key("key")
+            .base_url("not a url")
+            .try_build()
+            .unwrap_err();
+        assert!(matches!(err, BuildError::InvalidBaseUrl { .. }));
+
+        let err = ClientBuilder::new()
+            .api_key("key")
+            .base_url("ftp://example.com")
+            .try_build()
+            .unwrap_err();
+        assert!(matches!(err, BuildError::InvalidBaseUrl { .. }));
+
+        let err = ClientBuilder::new()
+            .api_key("key")
+            .base_url("https://api.example.com")
+            .proxy_url("http://[::1")
+            .try_build()
+            .unwrap_err();
+        assert!(matches!(err, BuildError::InvalidProxy(_)));
+
+        let client = ClientBuilder::new()
+            .api_key("key")
+            .base_url("https://api.example.com")
+            .try_build()
+            .unwrap();
+        assert_eq!(client.inner.config.api_key, "key");
+    }
+
+    #[test]
+    fn test_client_builder_auth_token() {
+        let client = ClientBuilder::new()
+            .api_key("")
+            .auth_token("token")
+            .base_url("https://api.example.com")
+            .try_build()
+            .unwrap();
+        let headers = client.inner.config.build_headers();
+        assert_eq!(headers.get("authorization").unwrap(), "Bearer token");
+        assert!(headers.get("x-api-key").is_none());
+    }
+
+    #[test]
+    fn test_explicit_api_key_replaces_env_auth_token() {
+        let mut builder = ClientBuilder::new();
+        builder.config.auth_token = Some("env-token".to_string());
+        builder.auth_token_from_env = true;
+        let client = builder
+            .api_key("key")
+            .base_url("https://api.example.com")
+            .try_build()
+            .unwrap();
+        let headers = client.inner.config.build_headers();
+        assert_eq!(headers.get("x-api-key").unwrap(), "key");
+        assert!(headers.get("authorization").is_none());
+
+        let client = ClientBuilder::new()
+            .auth_token("token")
+            .api_key("key")
+            .base_url("https://api.example.com")
+            .try_build()
+            .unwrap();
+        let headers = client.inner.config.build_headers();
+        assert_eq!(headers.get("authorization").unwrap(), "Bearer token");
+    }
+
+    #[tokio::test]
+    async fn test_api_key_provider_rotates_key() {
+        use std::sync::atomic::{AtomicU32, Ordering};
+
+        let calls = Arc::new(AtomicU32::new(0));
+        let counter = calls.clone();
+        let client = ClientBuilder::new()
+            .api_key("")
+            .base_url("https://api.example.com")
+            .api_key_provider(move || {
+                let n = counter.fetch_add(1, Ordering::SeqCst);
+                async move { Ok(format!("key-{n}")) }
+            })
+            .try_build()
+            .unwrap();
+
+        let first = client.request_headers().await.unwrap();
+        let second = client.request_headers().await.unwrap();
+        assert_eq!(first.get("x-api-key").unwrap(), "key-0");
+        assert_eq!(second.get("x-api-key").unwrap(), "key-1");
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_max_concurrent_requests() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v1/models/m"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({}))
+                    .set_delay(Duration::from_millis(200)),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/v1/messages"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("content-type", "text/event-stream")
+                    .set_body_string("event: ping\ndata: {}\n\n"),
+            )
+            .mount(&server)
+            .await;
+
+        let client = ClientBuilder::new()
+            .api_key("test")
+            .base_url(server.uri())
+            .max_concurrent_requests(1)
+            .build();
+
+        let start = Instant::now();
+        let (a, b) = tokio::join!(
+            client.get::<serde_json::Value>("models/m", None),
+            client.get::<serde_json::Value>("models/m", None),
+        );
+        a.unwrap();
+        b.unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(400));
+
+        // An open stream keeps its slot until it is dropped.
+        let response = client
+            .execute_streaming("messages", &serde_json::json!({}), None)
+            .await
+            .unwrap();
+        let blocked = tokio::time::timeout(
+            Duration::from_millis(100),
+            client.get::<serde_json::Value>("models/m", None),
+        )
+        .await;
+        assert!(blocked.is_err());
+        drop(response);
+        client
+            .get::<serde_json::Value>("models/m", None)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_max_elapsed_limits_retries() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v1/models/m"))
+            .respond_with(ResponseTemplate::new(529).insert_header("retry-after", "2"))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/v1/models/slow"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({}))
+                    .set_delay(Duration::from_secs(5)),
+            )
+            .mount(&server)
+            .await;
+
+        let client = ClientBuilder::new()
+            .api_key("test")
+            .base_url(server.uri())
+            .max_retries(5)
+            .max_elapsed(Duration::from_millis(300))
+            .build();
+
+        // A 2s retry-after does not fit in the budget, so the 529 is returned.
+        let err = client
+            .get::<serde_json::Value>("models/m", None)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::Overloaded { .. }));
+
+        let start = Instant::now();
+        let err = client
+            .get::<serde_json::Value>("models/slow", None)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::Timeout));
+        assert!(start.elapsed() < Duration::from_secs(1));
+    }
+
+    #[tokio::test]
+    async fn test_overloaded_uses_overloaded_backoff() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v1/models/m"))
+            .respond_with(ResponseTemplate::new(529).set_body_json(serde_json::json!({
+                "type": "error",
+                "error": {"type": "overloaded_error", "message": "Overloaded"}
+            })))
+            .up_to_n_times(2)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/v1/models/m"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({})))
+            .mount(&server)
+            .await;
+
+        let client = ClientBuilder::new()
+            .api_key("test")
+            .base_url(server.uri())
+            .jitter(JitterStrategy::None)
+            .overloaded_backoff(Duration::from_millis(150), Duration::from_secs(1))
+            .build();
+
+        // Two retries wait 150ms then 300ms, instead of the default 500ms and 1s.
+        let start = Instant::now();
+        client
+            .get::<serde_json::Value>("models/m", None)
+            .await
+            .unwrap();
+        let elapsed = start.elapsed();
+        assert!(elapsed >= Duration::from_millis(450), "{elapsed:?}");
+        assert!(elapsed < Duration::from_millis(1500), "{elapsed:?}");
+    }
+
+    #[tokio::test]
+    async fn test_timing_records_connection_phases() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/jobs"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({})))
+            .mount(&server)
+            .await;
+
+        let client = ClientBuilder::new()
+            .api_key("test")
+            .base_url(format!("http://localhost:{}", server.address().port()))
+            .build();
+        let body = serde_json::json!({});
+        let post = || client.post_with_response::<serde_json::Value>("jobs", &body, None);
+
+        let (_, response) = post().await.unwrap();
+        let timing = response.timing.unwrap();
+        assert!(timing.dns.is_some());
+        let connect = timing.connect.unwrap();
+        assert!(timing.dns.unwrap() + connect <= timing.time_to_headers);
+
+        // The pooled connection is reused, so there is nothing to set up.
+        let (_, response) = post().await.unwrap();
+        let timing = response.timing.unwrap();
+        assert_eq!(timing.dns, None);
+        assert_eq!(timing.connect, None);
+    }
+
+    #[tokio::test]
+    async fn test_retry_classifier() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v1/models/m"))
+            .respond_with(ResponseTemplate::new(400).set_body_json(serde_json::json!({
+                "type": "error",
+                "error": {"type": "invalid_request_error", "message": "Try again"}
+            })))
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/v1/models/m"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({})))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/v1/jobs"))
+            .respond_with(ResponseTemplate::new(529))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = ClientBuilder::new()
+            .api_key("test")
+            .base_url(server.uri())
+            .retry_classifier(|err, _attempt| match err {
+                Error::Api { status: 400, .. } => RetryDecision::Retry,
+                Error::Overloaded { .. } => RetryDecision::DoNotRetry,
+                _ => RetryDecision::Default,
+            })
+            .build();
+
+        client
+            .get::<serde_json::Value>("models/m", None)
+            .await
+            .unwrap();
+        let err = client
+            .post::<serde_json::Value>("jobs", &serde_json::json!({}), None)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::Overloaded { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_circuit_breaker_fails_fast() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v1/models/m"))
+            .respond_with(ResponseTemplate::new(529))
+            .expect(2)
+            .mount(&server)
+            .await;
+
+        let client = ClientBuilder::new()
+            .api_key("test")
+            .base_url(server.uri())
+            .max_retries(0)
+            .circuit_breaker(CircuitBreakerConfig {
+                failure_threshold: 2,
+                cool_down: Duration::from_secs(60),
+            })
+            .build();
+
+        for _ in 0..2 {
+            let err = client
+                .get::<serde_json::Value>("models/m", None)
+                .await
+                .unwrap_err();
+            assert!(matches!(err, Error::Overloaded { .. }));
+        }
+        let err = client
+            .get::<serde_json::Value>("models/m", None)
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), "circuit_open");
+    }
+
+    #[tokio::test]
+    async fn test_request_escape_hatch() {
+        use wiremock::matchers::{body_json, header, method, path, query_param};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/experimental/things"))
+            .and(query_param("dry_run", "true"))
+            .and(header("x-api-key", "test"))
+            .and(header("anthropic-beta", "things-2026-01-01"))
+            .and(body_json(serde_json::json!({"name": "a"})))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({"id": "t_1"})),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/v1/experimental/things/t_1/content"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("raw"))
+            .mount(&server)
+            .await;
+
+        let client = ClientBuilder::new()
+            .api_key("test")
+            .base_url(server.uri())
+            .build();
+        let created: serde_json::Value = client
+            .request(
+                reqwest::Method::POST,
+                "experimental/things?dry_run=true",
+                Some(serde_json::json!({"name": "a"})),
+                RequestOptions::default().header("anthropic-beta", "things-2026-01-01"),
+            )
+            .await
+            .unwrap();
+        assert_eq!(created["id"], "t_1");
+
+        let bytes = client
+            .request_bytes(
+                reqwest::Method::GET,
+                "experimental/things/t_1/content",
+                None,
+                RequestOptions::default(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(&bytes[..], b"raw");
+    }
+
+    #[test]
+    fn test_decode_error_reports_path_and_snippet() {
+        #[derive(Debug, serde::Deserialize)]
+        struct Reply {
+            #[allow(dead_code)]
+            items: Vec<u32>,
+        }
+
+        let body = format!(
+            r#"{{"key": "sk-ant-api03-secret", "padding": "{}", "items": [1, "two"]}}"#,
+            "x".repeat(300)
+        );
+        match decode::<Reply>(body.as_bytes()).unwrap_err() {
+            Error::Decode {
+                path, body_snippet, ..
+            } => {
+                assert_eq!(path, "items[1]");
+                assert!(body_snippet.starts_with('…'));
+                assert!(body_snippet.ends_with(r#""items": [1, "two"]}"#));
+            }
+            other => panic!("Expected Decode error, got {other:?}"),
+        }
+
+        let body = br#"{"key": "sk-ant-api03-secret", "items": 3}"#;
+        let err = decode::<Reply>(body).unwrap_err();
+        assert_eq!(err.code(), "decode");
+        let message = err.to_string();
+        assert!(message.contains("at `items`"), "{message}");
+        assert!(message.contains("[REDACTED]"), "{message}");
+        assert!(!message.contains("secret"), "{message}");
+
+        let err = decode::<Reply>(br#"{"items": []} trailing"#).unwrap_err();
+        assert!(matches!(err, Error::Decode { path, .. } if path == "."));
+    }
 }
@@ -0,0 +1,194 @@
+//! Summarization of old conversation turns once a conversation grows too long.
+//!
+//! ```ignore
+//! use uno_anthropic::compaction::Compactor;
+//!
+//! let compactor = Compactor::new(Model::ClaudeHaiku4_5, 100_000).keep_recent(6);
+//! params.messages = compactor.compact(&client, &params).await?;
+//! let message = client.messages().create(params).await?;
+//! ```
+
+use crate::client::Client;
+use crate::context_window::{
+    SUMMARY_MAX_TOKENS, TokenCounter, count_tokens, is_clean_start, summarize_before,
+};
+use crate::error::Error;
+use crate::messages::params::MessageCreateParams;
+use crate::types::message::MessageParam;
+use crate::types::model::Model;
+
+/// Messages kept verbatim by default when compacting.
+const DEFAULT_KEEP_RECENT: usize = 4;
+
+/// Replaces the oldest turns of a conversation with a summary generated by a
+/// cheap model once the request exceeds a token threshold.
+///
+/// Unlike `ContextWindow`, which trims until a budget is met, a compactor
+/// summarizes in one pass and keeps the most recent messages verbatim. The
+/// kept messages always start at a user message without tool results, so the
+/// conversation stays valid.
+#[derive(Debug, Clone)]
+pub struct Compactor {
+    model: Model,
+    threshold: u32,
+    keep_recent: usize,
+    max_summary_tokens: u32,
+    counter: TokenCounter,
+}
+
+impl Compactor {
+    /// Summarize with `model` once a request's input exceeds `threshold` tokens.
+    pub fn new(model: Model, threshold: u32) -> Self {
+        Self {
+            model,
+            threshold,
+            keep_recent: DEFAULT_KEEP_RECENT,
+            max_summary_tokens: SUMMARY_MAX_TOKENS,
+            counter: TokenCounter::default(),
+        }
+    }
+
+    /// Keep at least the last `count` messages verbatim (default: 4).
+    pub fn keep_recent(mut self, count: usize) -> Self {
+        self.keep_recent = count;
+        self
+    }
+
+    /// Set the output token limit for the summary (default: 1024).
+    pub fn max_summary_tokens(mut self, max_tokens: u32) -> Self {
+        self.max_summary_tokens = max_tokens;
+        self
+    }
+
+    /// Set how tokens are counted (default: `TokenCounter::Api`).
+    pub fn counter(mut self, counter: TokenCounter) -> Self {
+        self.counter = counter;
+        self
+    }
+
+    /// Return the messages of `params`, with the oldest replaced by a summary
+    /// if the request exceeds the threshold.
+    ///
+    /// The messages are returned unchanged when the request is within the
+    /// threshold or there are no older turns that can be summarized.
+    pub async fn compact(
+        &self,
+        client: &Client,
+        params: &MessageCreateParams,
+    ) -> Result<Vec<MessageParam>, Error> {
+        let mut messages = params.messages.clone();
+        if count_tokens(client, self.counter, params).await? <= self.threshold {
+            return Ok(messages);
+        }
+        let from = messages.len().saturating_sub(self.keep_recent);
+        let Some(cut) = compaction_cut(&messages, from) else {
+            return Ok(messages);
+        };
+        summarize_before(
+            client,
+            &self.model,
+            self.max_summary_tokens,
+            &mut messages,
+            cut,
+        )
+        .await?;
+        Ok(messages)
+    }
+}
+
+/// Where the kept messages start: the last clean start at or before `from`,
+/// so at least the requested number of messages survive.
+fn compaction_cut(messages: &[MessageParam], from: usize) -> Option<usize> {
+    (1..=from.min(messages.len().saturating_sub(1)))
+        .rev()
+        .find(|&i| is_clean_start(&messages[i]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::ClientBuilder;
+    use crate::mock::text_message;
+    use crate::types::content::{ContentBlockParam, ToolResultBlockParam};
+    use crate::types::message::MessageContent;
+
+    #[test]
+    fn test_compaction_cut_skips_tool_results() {
+        let tool_result =
+            MessageParam::user_blocks(vec![ContentBlockParam::ToolResult(ToolResultBlockParam {
+                tool_use_id: "toolu_1".to_string(),
+                content: None,
+                is_error: None,
+                cache_control: None,
+            })]);
+        let messages = vec![
+            MessageParam::user("a"),
+            MessageParam::assistant("b"),
+            MessageParam::user("c"),
+            MessageParam::assistant("d"),
+            tool_result,
+            MessageParam::assistant("e"),
+        ];
+        assert_eq!(compaction_cut(&messages, 4), Some(2));
+        assert_eq!(compaction_cut(&messages, 1), None);
+    }
+
+    #[tokio::test]
+    async fn test_compact() {
+        use wiremock::matchers::{body_partial_json, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/messages/count_tokens"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({"input_tokens": 500})),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/v1/messages"))
+            .and(body_partial_json(serde_json::json!({
+                "model": "claude-haiku-4-5",
+                "max_tokens": 256
+            })))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(text_message("They planned a trip.")),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = ClientBuilder::new()
+            .api_key("test")
+            .base_url(server.uri())
+            .build();
+        let params = MessageCreateParams::builder()
+            .model(Model::ClaudeOpus4_6)
+            .messages(vec![
+                MessageParam::user("plan a trip"),
+                MessageParam::assistant("where to?"),
+                MessageParam::user("Lisbon"),
+                MessageParam::assistant("booked"),
+                MessageParam::user("thanks"),
+            ])
+            .build();
+
+        let compactor = Compactor::new(Model::ClaudeHaiku4_5, 100)
+            .keep_recent(1)
+            .max_summary_tokens(256);
+        let messages = compactor.compact(&client, &params).await.unwrap();
+        assert_eq!(messages.len(), 2);
+        match &messages[0].content {
+            MessageContent::Text(t) => assert!(t.contains("They planned a trip.")),
+            _ => panic!("Expected text content"),
+        }
+        assert_eq!(messages[1], MessageParam::user("thanks"));
+
+        let unchanged = Compactor::new(Model::ClaudeHaiku4_5, 1_000)
+            .compact(&client, &params)
+            .await
+            .unwrap();
+        assert_eq!(unchanged, params.messages);
+    }
+}
@@ -1,6 +1,10 @@
 use reqwest::header::{HeaderMap, HeaderValue};
+use std::fmt;
 use std::time::Duration;
 
+use crate::redact::{REDACTED, RedactedHeaders};
+use crate::types::metadata::{Metadata, ServiceTier};
+
 const DEFAULT_BASE_URL: &str = "https://api.anthropic.com";
 const DEFAULT_ANTHROPIC_VERSION: &str = "2023-06-01";
 const DEFAULT_TIMEOUT_SECS: u64 = 600;
@@ -8,35 +12,58 @@ const DEFAULT_MAX_RETRIES: u32 = 2;
 pub const DEFAULT_USER_AGENT: &str = "Anthropic/Rust 0.1.0";
 
 /// Configuration for the Anthropic API client.
-#[derive(Debug, Clone)]
+///
+/// The `Debug` output redacts `api_key`, `auth_token` and credential headers.
+#[derive(Clone)]
 pub struct ClientConfig {
     pub api_key: String,
+    /// Bearer token sent as `Authorization: Bearer <token>`. When set, the
+    /// `x-api-key` header is not sent.
+    pub auth_token: Option<String>,
     pub base_url: String,
     pub max_retries: u32,
     pub timeout: Duration,
     pub default_headers: HeaderMap,
     pub user_agent: String,
     pub beta_features: Vec<String>,
+    /// Service tier applied to message requests that don't set one.
+    pub default_service_tier: Option<ServiceTier>,
+    /// Metadata applied to message requests that don't set any.
+    pub default_metadata: Option<Metadata>,
+    /// Strip Markdown code fences from text returned by `MessageService::create`.
+    pub strip_code_fences: bool,
+    /// Send `MessageService::create` requests whose `max_tokens` could take
+    /// longer than `timeout` to generate as streams, accumulating the result.
+    pub auto_stream_large_requests: bool,
 }
 
 impl ClientConfig {
     /// Create a new ClientConfig from environment variables and defaults.
     ///
-    /// Reads `ANTHROPIC_API_KEY` and `ANTHROPIC_BASE_URL` from the environment.
-    /// Falls back to the default base URL if `ANTHROPIC_BASE_URL` is not set.
+    /// Reads `ANTHROPIC_API_KEY`, `ANTHROPIC_AUTH_TOKEN` and `ANTHROPIC_BASE_URL`
+    /// from the environment. Falls back to the default base URL if
+    /// `ANTHROPIC_BASE_URL` is not set.
     pub fn from_env() -> Self {
         let api_key = std::env::var("ANTHROPIC_API_KEY").unwrap_or_default();
+        let auth_token = std::env::var("ANTHROPIC_AUTH_TOKEN")
+            .ok()
+            .filter(|t| !t.is_empty());
         let base_url =
             std::env::var("ANTHROPIC_BASE_URL").unwrap_or_else(|_| DEFAULT_BASE_URL.to_string());
 
         Self {
             api_key,
+            auth_token,
             base_url,
             max_retries: DEFAULT_MAX_RETRIES,
             timeout: Duration::from_secs(DEFAULT_TIMEOUT_SECS),
             default_headers: HeaderMap::new(),
             user_agent: DEFAULT_USER_AGENT.to_string(),
             beta_features: Vec::new(),
+            default_service_tier: None,
+            default_metadata: None,
+            strip_code_fences: false,
+            auto_stream_large_requests: false,
         }
     }
 
@@ -60,9 +87,15 @@ impl ClientConfig {
             headers.insert(reqwest::header::USER_AGENT, val);
         }
 
-        if !self.api_key.is_empty()
-            && let Ok(val) = HeaderValue::from_str(&self.api_key)
+        if let Some(token) = &self.auth_token {
+            if let Ok(mut val) = HeaderValue::from_str(&format!("Bearer {token}")) {
+                val.set_sensitive(true);
+                headers.insert(reqwest::header::AUTHORIZATION, val);
+            }
+        } else if !self.api_key.is_empty()
+            && let Ok(mut val) = HeaderValue::from_str(&self.api_key)
         {
+            val.set_sensitive(true);
             headers.insert("x-api-key", val);
         }
 
@@ -82,6 +115,33 @@ impl ClientConfig {
     }
 }
 
+impl fmt::Debug for ClientConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let api_key = if self.api_key.is_empty() {
+            ""
+        } else {
+            REDACTED
+        };
+        f.debug_struct("ClientConfig")
+            .field("api_key", &api_key)
+            .field("auth_token", &self.auth_token.as_ref().map(|_| REDACTED))
+            .field("base_url", &self.base_url)
+            .field("max_retries", &self.max_retries)
+            .field("timeout", &self.timeout)
+            .field("default_headers", &RedactedHeaders(&self.default_headers))
+            .field("user_agent", &self.user_agent)
+            .field("beta_features", &self.beta_features)
+            .field("default_service_tier", &self.default_service_tier)
+            .field("default_metadata", &self.default_metadata)
+            .field("strip_code_fences", &self.strip_code_fences)
+            .field(
+                "auto_stream_large_requests",
+                &self.auto_stream_large_requests,
+            )
+            .finish()
+    }
+}
+
 impl Default for ClientConfig {
     fn default() -> Self {
         Self::from_env()
@@ -96,12 +156,17 @@ mod tests {
     fn test_default_config() {
         let config = ClientConfig {
             api_key: String::new(),
+            auth_token: None,
             base_url: DEFAULT_BASE_URL.to_string(),
             max_retries: DEFAULT_MAX_RETRIES,
             timeout: Duration::from_secs(DEFAULT_TIMEOUT_SECS),
             default_headers: HeaderMap::new(),
             user_agent: DEFAULT_USER_AGENT.to_string(),
             beta_features: Vec::new(),
+            default_service_tier: None,
+            default_metadata: None,
+            strip_code_fences: false,
+            auto_stream_large_requests: false,
         };
         assert_eq!(config.base_url, "https://api.anthropic.com");
         assert_eq!(config.max_retries, 2);
@@ -112,12 +177,17 @@ mod tests {
     fn test_build_headers_without_api_key() {
         let config = ClientConfig {
             api_key: String::new(),
+            auth_token: None,
             base_url: DEFAULT_BASE_URL.to_string(),
             max_retries: DEFAULT_MAX_RETRIES,
             timeout: Duration::from_secs(DEFAULT_TIMEOUT_SECS),
             default_headers: HeaderMap::new(),
             user_agent: DEFAULT_USER_AGENT.to_string(),
             beta_features: Vec::new(),
+            default_service_tier: None,
+            default_metadata: None,
+            strip_code_fences: false,
+            auto_stream_large_requests: false,
         };
         let headers = config.build_headers();
         assert_eq!(headers.get("anthropic-version").unwrap(), "2023-06-01");
@@ -130,12 +200,17 @@ mod tests {
     fn test_build_headers_with_api_key() {
         let config = ClientConfig {
             api_key: "sk-ant-test-key".to_string(),
+            auth_token: None,
             base_url: DEFAULT_BASE_URL.to_string(),
             max_retries: DEFAULT_MAX_RETRIES,
             timeout: Duration::from_secs(DEFAULT_TIMEOUT_SECS),
             default_headers: HeaderMap::new(),
             user_agent: DEFAULT_USER_AGENT.to_string(),
             beta_features: Vec::new(),
+            default_service_tier: None,
+            default_metadata: None,
+            strip_code_fences: false,
+            auto_stream_large_requests: false,
         };
         let headers = config.build_headers();
         assert_eq!(headers.get("x-api-key").unwrap(), "sk-ant-test-key");
@@ -148,14 +223,55 @@ mod tests {
 
         let config = ClientConfig {
             api_key: String::new(),
+            auth_token: None,
             base_url: DEFAULT_BASE_URL.to_string(),
             max_retries: DEFAULT_MAX_RETRIES,
             timeout: Duration::from_secs(DEFAULT_TIMEOUT_SECS),
             default_headers: custom,
             user_agent: DEFAULT_USER_AGENT.to_string(),
             beta_features: Vec::new(),
+            default_service_tier: None,
+            default_metadata: None,
+            strip_code_fences: false,
+            auto_stream_large_requests: false,
         };
         let headers = config.build_headers();
         assert_eq!(headers.get("anthropic-version").unwrap(), "2024-01-01");
     }
+
+    #[test]
+    fn test_build_headers_with_auth_token() {
+        let config = ClientConfig {
+            api_key: "sk-ant-test-key".to_string(),
+            auth_token: Some("gateway-token".to_string()),
+            ..ClientConfig::from_env()
+        };
+        let headers = config.build_headers();
+        assert_eq!(
+            headers.get("authorization").unwrap(),
+            "Bearer gateway-token"
+        );
+        assert!(headers.get("x-api-key").is_none());
+    }
+
+    #[test]
+    fn test_debug_redacts_credentials() {
+        let mut default_headers = HeaderMap::new();
+        default_headers.insert("x-api-key", HeaderValue::from_static("sk-ant-header"));
+        let config = ClientConfig {
+            api_key: "sk-ant-test-key".to_string(),
+            auth_token: Some("gateway-token".to_string()),
+            default_headers,
+            ..ClientConfig::from_env()
+        };
+        let debug = format!("{config:?}");
+        assert!(!debug.contains("sk-ant-test-key"));
+        assert!(!debug.contains("gateway-token"));
+        assert!(!debug.contains("sk-ant-header"));
+        assert!(debug.contains("api_key: \"[REDACTED]\""));
+        assert!(debug.contains("base_url"));
+
+        let headers = config.build_headers();
+        assert!(headers.get("authorization").unwrap().is_sensitive());
+    }
 }
@@ -0,0 +1,549 @@
+//! Automatic trimming of conversation history to fit a token budget.
+//!
+//! ```ignore
+//! use uno_anthropic::context_window::{ContextWindow, TrimStrategy};
+//!
+//! let window = ContextWindow::new(150_000).strategy(TrimStrategy::DropOldest);
+//! window.fit(&client, &mut params).await?;
+//! let message = client.messages().create(params).await?;
+//! ```
+
+use crate::client::Client;
+use crate::error::Error;
+use crate::messages::params::MessageCreateParams;
+use crate::types::common::Role;
+use crate::types::content::ContentBlockParam;
+use crate::types::message::{MessageContent, MessageParam, SystemContent};
+use crate::types::model::Model;
+
+/// Text shorter than this (in characters) is not truncated further.
+const MIN_TRUNCATE_CHARS: usize = 200;
+
+/// Output token limit for summaries generated by `TrimStrategy::Summarize`.
+pub(crate) const SUMMARY_MAX_TOKENS: u32 = 1024;
+
+/// Marker appended to text shortened by `TrimStrategy::TruncateOldest`.
+const TRUNCATION_MARKER: &str = "… [truncated]";
+
+/// How `ContextWindow` reduces a conversation that exceeds its budget.
+#[derive(Debug, Clone)]
+pub enum TrimStrategy {
+    /// Drop the oldest messages.
+    DropOldest,
+    /// Halve the longest text of the oldest messages, dropping a message once
+    /// nothing in it is long enough to shorten.
+    TruncateOldest,
+    /// Replace the oldest half of the conversation with a summary generated by
+    /// `model` (typically a fast model such as Haiku).
+    Summarize { model: Model },
+}
+
+/// How `ContextWindow` measures the size of a request.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TokenCounter {
+    /// Call the `count_tokens` endpoint. Exact, but costs a round-trip per check.
+    #[default]
+    Api,
+    /// Use the offline estimator from the `tokenizer` feature.
+    #[cfg(feature = "tokenizer")]
+    Estimate,
+}
+
+/// Keeps a request's input within a token budget by trimming its oldest messages.
+///
+/// The most recent message is never removed, and trimming always leaves the
+/// conversation starting with a user message that does not contain tool results,
+/// so the request stays valid.
+#[derive(Debug, Clone)]
+pub struct ContextWindow {
+    budget: u32,
+    strategy: TrimStrategy,
+    counter: TokenCounter,
+}
+
+impl ContextWindow {
+    /// Create a context window that keeps input tokens at or below `budget`.
+    pub fn new(budget: u32) -> Self {
+        Self {
+            budget,
+            strategy: TrimStrategy::DropOldest,
+            counter: TokenCounter::default(),
+        }
+    }
+
+    /// Set the trim strategy (default: `TrimStrategy::DropOldest`).
+    pub fn strategy(mut self, strategy: TrimStrategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    /// Set how tokens are counted (default: `TokenCounter::Api`).
+    pub fn counter(mut self, counter: TokenCounter) -> Self {
+        self.counter = counter;
+        self
+    }
+
+    /// The input token budget.
+    pub fn budget(&self) -> u32 {
+        self.budget
+    }
+
+    /// Trim `params.messages` until the request fits within the budget.
+    ///
+    /// Returns the final token count. This is best-effort: if only the most
+    /// recent message is left and it still exceeds the budget, the count is
+    /// returned as-is.
+    pub async fn fit(
+        &self,
+        client: &Client,
+        params: &mut MessageCreateParams,
+    ) -> Result<u32, Error> {
+        loop {
+            let tokens = self.count(client, params).await?;
+            if tokens <= self.budget || params.messages.len() <= 1 {
+                return Ok(tokens);
+            }
+
+            let trimmed = match &self.strategy {
+                TrimStrategy::DropOldest => drop_oldest(&mut params.messages),
+                TrimStrategy::TruncateOldest => {
+                    truncate_oldest(&mut params.messages) || drop_oldest(&mut params.messages)
+                }
+                TrimStrategy::Summarize { model } => {
+                    summarize_oldest(client, model, &mut params.messages).await?
+                        || drop_oldest(&mut params.messages)
+                }
+            };
+
+            if !trimmed {
+                return Ok(tokens);
+            }
+        }
+    }
+
+    async fn count(&self, client: &Client, params: &MessageCreateParams) -> Result<u32, Error> {
+        count_tokens(client, self.counter, params).await
+    }
+}
+
+/// Measure the input tokens of `params` with `counter`.
+pub(crate) async fn count_tokens(
+    client: &Client,
+    counter: TokenCounter,
+    params: &MessageCreateParams,
+) -> Result<u32, Error> {
+    match counter {
+        TokenCounter::Api => Ok(client
+            .messages()
+            .count_tokens_for(params)
+            .await?
+            .input_tokens),
+        #[cfg(feature = "tokenizer")]
+        TokenCounter::Estimate => Ok(crate::tokenizer::estimate_tokens(params)),
+    }
+}
+
+/// Whether a conversation may start at this message.
+pub(crate) fn is_clean_start(message: &MessageParam) -> bool {
+    message.role == Role::User
+        && match &message.content {
+            MessageContent::Text(_) => true,
+            MessageContent::Blocks(blocks) => !blocks
+                .iter()
+                .any(|b| matches!(b, ContentBlockParam::ToolResult(_))),
+        }
+}
+
+/// Index of the first message at or after `from` that the conversation may start at.
+fn next_clean_start(messages: &[MessageParam], from: usize) -> Option<usize> {
+    (from..messages.len().saturating_sub(1))
+        .find(|&i| is_clean_start(&messages[i]))
+        .or_else(|| {
+            // The last message may be the only valid start left.
+            let last = messages.len().checked_sub(1)?;
+            (last >= from && is_clean_start(&messages[last])).then_some(last)
+        })
+}
+
+/// Drop the oldest message along with any messages that would leave the
+/// conversation in an invalid state.
+fn drop_oldest(messages: &mut Vec<MessageParam>) -> bool {
+    if messages.len() <= 1 {
+        return false;
+    }
+    let cut = next_clean_start(messages, 1).unwrap_or(messages.len() - 1);
+    messages.drain(..cut);
+    true
+}
+
+/// Halve the first sufficiently long text in the oldest messages.
+fn truncate_oldest(messages: &mut [MessageParam]) -> bool {
+    let last = messages.len().saturating_sub(1);
+    for message in &mut messages[..last] {
+        let texts: Vec<&mut String> = match &mut message.content {
+            MessageContent::Text(text) => vec![text],
+            MessageContent::Blocks(blocks) => blocks
+                .iter_mut()
+                .filter_map(|b| match b {
+                    ContentBlockParam::Text(t) => Some(&mut t.text),
+                    _ => None,
+                })
+                .collect(),
+        };
+        if let Some(text) = texts
+            .into_iter()
+            .filter(|t| t.chars().count() >= MIN_TRUNCATE_CHARS)
+            .max_by_key(|t| t.len())
+        {
+            let keep = text.chars().count() / 2;
+            let byte_index = text.char_indices().nth(keep).map_or(text.len(), |(i, _)| i);
+            text.truncate(byte_index);
+            text.push_str(TRUNCATION_MARKER);
+            return true;
+        }
+    }
+    false
+}
+
+/// Replace the oldest half of the conversation with a generated summary.
+///
+/// Returns `false` without summarizing unless at least two messages would be
+/// replaced, since swapping one message for a summary would not shorten the
+/// history.
+async fn summarize_oldest(
+    client: &Client,
+    model: &Model,
+    messages: &mut Vec<MessageParam>,
+) -> Result<bool, Error> {
+    let Some(cut) = next_clean_start(messages, messages.len() / 2) else {
+        return Ok(false);
+    };
+    if cut < 2 {
+        return Ok(false);
+    }
+    summarize_before(client, model, SUMMARY_MAX_TOKENS, messages, cut).await?;
+    Ok(true)
+}
+
+/// Replace `messages[..cut]` with a single user message holding a summary of
+/// them generated by `model`.
+pub(crate) async fn summarize_before(
+    client: &Client,
+    model: &Model,
+    max_tokens: u32,
+    messages: &mut Vec<MessageParam>,
+    cut: usize,
+) -> Result<(), Error> {
+    let transcript = messages[..cut]
+        .iter()
+        .map(render_message)
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    let request = MessageCreateParams::builder()
+        .model(model.clone())
+        .max_tokens(max_tokens)
+        .system(SystemContent::from(
+            "Summarize the following conversation so it can replace the original \
+             messages as context. Keep facts, decisions, open questions and any \
+             details the assistant will need to continue. Reply with the summary only.",
+        ))
+        .messages(vec![MessageParam::user(transcript)])
+        .build();
+    let response = client.messages().create(request).await?;
+    let summary = response
+        .content
+        .iter()
+        .filter_map(|block| match block {
+            crate::types::content::ContentBlock::Text(t) => Some(t.text.as_str()),
+            _ => None,
+        })
+        .collect::<String>();
+
+    messages.splice(
+        ..cut,
+        [MessageParam::user(format!(
+            "Summary of the earlier conversation:\n{summary}"
+        ))],
+    );
+    Ok(())
+}
+
+/// Render a message as plain text for summarization.
+fn render_message(message: &MessageParam) -> String {
+    let role = match message.role {
+        Role::User => "User",
+        Role::Assistant => "Assistant",
+    };
+    let body = match &message.content {
+        MessageContent::Text(text) => text.clone(),
+        MessageContent::Blocks(blocks) => blocks
+            .iter()
+            .map(|block| match block {
+                ContentBlockParam::Text(t) => t.text.clone(),
+                ContentBlockParam::ToolUse(t) => format!("[tool call {}: {}]", t.name, t.input),
+                other => {
+                    let kind = serde_json::to_value(other)
+                        .ok()
+                        .and_then(|v| v.get("type").and_then(|t| t.as_str()).map(String::from))
+                        .unwrap_or_default();
+                    format!("[{kind}]")
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+    };
+    format!("{role}: {body}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::ClientBuilder;
+    use crate::mock::text_message;
+    use crate::types::content::{ToolResultBlockParam, ToolUseBlockParam};
+
+    fn tool_use_turn() -> (MessageParam, MessageParam) {
+        let call =
+            MessageParam::assistant_blocks(vec![ContentBlockParam::ToolUse(ToolUseBlockParam {
+                id: "toolu_1".to_string(),
+                name: "lookup".to_string(),
+                input: serde_json::json!({}),
+                cache_control: None,
+                caller: None,
+            })]);
+        let result =
+            MessageParam::user_blocks(vec![ContentBlockParam::ToolResult(ToolResultBlockParam {
+                tool_use_id: "toolu_1".to_string(),
+                content: None,
+                is_error: None,
+                cache_control: None,
+            })]);
+        (call, result)
+    }
+
+    #[test]
+    fn test_drop_oldest_keeps_valid_start() {
+        let (call, result) = tool_use_turn();
+        let mut messages = vec![
+            MessageParam::user("first"),
+            call,
+            result,
+            MessageParam::assistant("done"),
+            MessageParam::user("second"),
+        ];
+        assert!(drop_oldest(&mut messages));
+        assert_eq!(messages.len(), 1);
+        assert!(is_clean_start(&messages[0]));
+    }
+
+    #[test]
+    fn test_drop_oldest_keeps_last_message() {
+        let mut messages = vec![MessageParam::user("only")];
+        assert!(!drop_oldest(&mut messages));
+        assert_eq!(messages.len(), 1);
+    }
+
+    #[test]
+    fn test_truncate_oldest() {
+        let mut messages = vec![
+            MessageParam::user("a".repeat(400)),
+            MessageParam::assistant("ok"),
+            MessageParam::user("latest"),
+        ];
+        assert!(truncate_oldest(&mut messages));
+        match &messages[0].content {
+            MessageContent::Text(t) => {
+                assert!(t.starts_with(&"a".repeat(200)));
+                assert!(t.ends_with(TRUNCATION_MARKER));
+                assert_eq!(t.chars().count(), 200 + TRUNCATION_MARKER.chars().count());
+            }
+            _ => panic!("Expected text content"),
+        }
+
+        let mut short = vec![MessageParam::user("hi"), MessageParam::user("there")];
+        assert!(!truncate_oldest(&mut short));
+    }
+
+    #[tokio::test]
+    async fn test_fit_with_api_counter() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/messages/count_tokens"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({"input_tokens": 500})),
+            )
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/v1/messages/count_tokens"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({"input_tokens": 50})),
+            )
+            .mount(&server)
+            .await;
+
+        let client = ClientBuilder::new()
+            .api_key("test")
+            .base_url(server.uri())
+            .build();
+        let mut params = MessageCreateParams::builder()
+            .model(Model::ClaudeOpus4_6)
+            .max_tokens(1024)
+            .messages(vec![
+                MessageParam::user("old"),
+                MessageParam::assistant("reply"),
+                MessageParam::user("new"),
+            ])
+            .build();
+
+        let tokens = ContextWindow::new(100)
+            .fit(&client, &mut params)
+            .await
+            .unwrap();
+        assert_eq!(tokens, 50);
+        assert_eq!(params.messages.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_api_counter_sends_betas() {
+        use wiremock::matchers::{header, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/messages/count_tokens"))
+            .and(header("anthropic-beta", "context-1m-2025-08-07"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({"input_tokens": 50})),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = ClientBuilder::new()
+            .api_key("test")
+            .base_url(server.uri())
+            .build();
+        let mut params = MessageCreateParams::builder()
+            .model(Model::ClaudeOpus4_6)
+            .max_tokens(1024)
+            .message(MessageParam::user("hello"))
+            .beta("context-1m-2025-08-07")
+            .build();
+
+        let tokens = ContextWindow::new(100)
+            .fit(&client, &mut params)
+            .await
+            .unwrap();
+        assert_eq!(tokens, 50);
+    }
+
+    #[tokio::test]
+    async fn test_fit_with_summarize() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/messages/count_tokens"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({"input_tokens": 500})),
+            )
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/v1/messages/count_tokens"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({"input_tokens": 50})),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/v1/messages"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(text_message("They said hello.")),
+            )
+            .mount(&server)
+            .await;
+
+        let client = ClientBuilder::new()
+            .api_key("test")
+            .base_url(server.uri())
+            .build();
+        let mut params = MessageCreateParams::builder()
+            .model(Model::ClaudeOpus4_6)
+            .max_tokens(1024)
+            .messages(vec![
+                MessageParam::user("hello"),
+                MessageParam::assistant("hi"),
+                MessageParam::user("next"),
+                MessageParam::assistant("sure"),
+                MessageParam::user("latest"),
+            ])
+            .build();
+
+        ContextWindow::new(100)
+            .strategy(TrimStrategy::Summarize {
+                model: Model::ClaudeHaiku4_5,
+            })
+            .fit(&client, &mut params)
+            .await
+            .unwrap();
+
+        assert_eq!(params.messages.len(), 4);
+        match &params.messages[0].content {
+            MessageContent::Text(t) => assert!(t.contains("They said hello.")),
+            _ => panic!("Expected text content"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_summarize_falls_back_to_drop_for_single_message() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/messages/count_tokens"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({"input_tokens": 500})),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/v1/messages"))
+            .respond_with(ResponseTemplate::new(500))
+            .expect(0)
+            .mount(&server)
+            .await;
+
+        let client = ClientBuilder::new()
+            .api_key("test")
+            .base_url(server.uri())
+            .build();
+        let mut params = MessageCreateParams::builder()
+            .model(Model::ClaudeOpus4_6)
+            .max_tokens(1024)
+            .messages(vec![
+                MessageParam::user("Summary of the earlier conversation:\nhello"),
+                MessageParam::user("x".repeat(1000)),
+            ])
+            .build();
+
+        let tokens = ContextWindow::new(100)
+            .strategy(TrimStrategy::Summarize {
+                model: Model::ClaudeHaiku4_5,
+            })
+            .fit(&client, &mut params)
+            .await
+            .unwrap();
+
+        assert_eq!(tokens, 500);
+        assert_eq!(params.messages.len(), 1);
+    }
+}
@@ -0,0 +1,33 @@
+use std::future::Future;
+
+use crate::error::Error;
+use crate::middleware::BoxFuture;
+
+/// Supplies the API key for each request.
+///
+/// Register with `ClientBuilder::api_key_provider` to fetch keys from a secret
+/// store (Vault, SSM, ...) and rotate them without rebuilding the client. The
+/// provider is called once per request, so implementations that hit a remote
+/// store should cache the key themselves.
+///
+/// Any async closure returning `Result<String, Error>` is a provider:
+///
+/// ```ignore
+/// let client = Client::builder()
+///     .api_key_provider(|| async { Ok(fetch_key_from_vault().await?) })
+///     .build();
+/// ```
+pub trait ApiKeyProvider: Send + Sync {
+    /// Return the API key to use for the next request.
+    fn api_key(&self) -> BoxFuture<'_, Result<String, Error>>;
+}
+
+impl<F, Fut> ApiKeyProvider for F
+where
+    F: Fn() -> Fut + Send + Sync,
+    Fut: Future<Output = Result<String, Error>> + Send + 'static,
+{
+    fn api_key(&self) -> BoxFuture<'_, Result<String, Error>> {
+        Box::pin(self())
+    }
+}
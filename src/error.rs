@@ -1,4 +1,6 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+
+use crate::retry::RetryClass;
 
 /// Errors returned by the Anthropic SDK.
 #[derive(Debug, thiserror::Error)]
@@ -16,17 +18,78 @@ pub enum Error {
         retry_after: Option<std::time::Duration>,
     },
 
+    /// The API is temporarily overloaded (status 529). Retried with
+    /// `RetryPolicy::overloaded_backoff`.
+    #[error("API overloaded (status 529): {body}")]
+    Overloaded {
+        body: ApiErrorBody,
+        /// The `retry-after` duration from the response headers, if present.
+        retry_after: Option<std::time::Duration>,
+    },
+
     #[error("Serialization error: {0}")]
     Serialization(#[from] serde_json::Error),
 
+    /// A response body did not match the expected type.
+    #[error("Decode error at `{path}`: {source} (body: {body_snippet})")]
+    Decode {
+        #[source]
+        source: serde_json::Error,
+        /// The part of the body around the failure, with secrets redacted.
+        body_snippet: String,
+        /// Where in the body decoding failed, e.g. `content[0].citations`.
+        path: String,
+    },
+
     #[error("Stream error: {0}")]
     StreamError(String),
 
     #[error("Request timed out")]
     Timeout,
 
+    /// The request was cancelled through a `CancellationToken`.
+    #[error("Request cancelled")]
+    Cancelled,
+
     #[error("OAuth error: {0}")]
     OAuth(String),
+
+    /// A request was rejected locally before being sent.
+    #[error("Validation error: {0}")]
+    Validation(String),
+
+    /// A `TokenBudget` has been spent, so the request was not sent.
+    #[error("Token budget exceeded: {0}")]
+    BudgetExceeded(String),
+
+    /// The circuit breaker is open after repeated server failures, so the
+    /// request was not sent.
+    #[error("Circuit breaker open")]
+    CircuitOpen {
+        /// Time until a probe request will be let through, or `None` while a
+        /// probe is already in flight.
+        retry_after: Option<std::time::Duration>,
+    },
+}
+
+/// Errors returned by `ClientBuilder::try_build`.
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum BuildError {
+    #[error("missing API key: set ANTHROPIC_API_KEY or call ClientBuilder::api_key")]
+    MissingApiKey,
+
+    #[error("invalid API key: {0}")]
+    InvalidApiKey(String),
+
+    #[error("invalid base URL '{url}': {reason}")]
+    InvalidBaseUrl { url: String, reason: String },
+
+    #[error("invalid proxy URL: {0}")]
+    InvalidProxy(#[source] reqwest::Error),
+
+    #[error("failed to build HTTP client: {0}")]
+    HttpClient(#[source] reqwest::Error),
 }
 
 /// Wrapper for the `error` field in API error JSON responses.
@@ -36,7 +99,7 @@ pub struct ApiErrorResponse {
 }
 
 /// The error detail returned in the `error` field of API error responses.
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ApiErrorBody {
     #[serde(rename = "type")]
     pub error_type: String,
@@ -50,11 +113,30 @@ impl std::fmt::Display for ApiErrorBody {
 }
 
 impl Error {
+    /// Build the error for an API response with an error `status`, mapping 529
+    /// to `Error::Overloaded`.
+    pub(crate) fn from_api_response(
+        status: u16,
+        body: ApiErrorBody,
+        retry_after: Option<std::time::Duration>,
+    ) -> Self {
+        match status {
+            529 => Error::Overloaded { body, retry_after },
+            _ => Error::Api {
+                status,
+                body,
+                retry_after,
+            },
+        }
+    }
+
     /// Returns the `retry-after` duration from the response headers, if this
     /// is an API error that included one.
     pub fn retry_after(&self) -> Option<std::time::Duration> {
         match self {
-            Error::Api { retry_after, .. } => *retry_after,
+            Error::Api { retry_after, .. }
+            | Error::Overloaded { retry_after, .. }
+            | Error::CircuitOpen { retry_after } => *retry_after,
             _ => None,
         }
     }
@@ -64,6 +146,7 @@ impl Error {
     pub fn is_retryable(&self) -> bool {
         match self {
             Error::Api { status, .. } => is_retryable_status(*status),
+            Error::Overloaded { .. } => true,
             Error::Http(e) => {
                 if e.is_timeout() {
                     return true;
@@ -79,6 +162,86 @@ impl Error {
             _ => false,
         }
     }
+
+    /// Returns the backoff profile a retry of this error uses.
+    pub fn retry_class(&self) -> RetryClass {
+        match self {
+            Error::Overloaded { .. } => RetryClass::Overloaded,
+            _ => RetryClass::Standard,
+        }
+    }
+
+    /// Returns a stable, machine-readable code for this error.
+    ///
+    /// Codes are stable across releases, so they are safe to use as metric
+    /// labels or to map into an application's own error taxonomy. API errors are
+    /// classified by HTTP status:
+    ///
+    /// | Code | Cause |
+    /// |------|-------|
+    /// | `invalid_request` | API status 400 |
+    /// | `authentication` | API status 401 |
+    /// | `permission_denied` | API status 403 |
+    /// | `not_found` | API status 404 |
+    /// | `request_too_large` | API status 413 |
+    /// | `rate_limited` | API status 429 |
+    /// | `overloaded` | API overloaded (status 529) |
+    /// | `server_error` | Other API status >= 500 |
+    /// | `api_error` | Any other API status |
+    /// | `timeout` | Request timed out |
+    /// | `cancelled` | Cancelled by the caller |
+    /// | `connection` | Could not connect |
+    /// | `http` | Other transport error |
+    /// | `serialization` | JSON (de)serialization failed |
+    /// | `decode` | Response body did not match the expected type |
+    /// | `stream` | Streaming or response parsing failed |
+    /// | `oauth` | OAuth flow failed |
+    /// | `validation` | Request rejected locally before sending |
+    /// | `circuit_open` | Circuit breaker open, request not sent |
+    /// | `budget_exceeded` | Token budget spent, request not sent |
+    pub fn code(&self) -> &'static str {
+        match self {
+            Error::Api { status, .. } => match status {
+                400 => "invalid_request",
+                401 => "authentication",
+                403 => "permission_denied",
+                404 => "not_found",
+                413 => "request_too_large",
+                429 => "rate_limited",
+                500.. => "server_error",
+                _ => "api_error",
+            },
+            Error::Overloaded { .. } => "overloaded",
+            Error::Http(e) if e.is_timeout() => "timeout",
+            Error::Http(e) if e.is_connect() => "connection",
+            Error::Http(_) => "http",
+            Error::Timeout => "timeout",
+            Error::Cancelled => "cancelled",
+            Error::Serialization(_) => "serialization",
+            Error::Decode { .. } => "decode",
+            Error::StreamError(_) => "stream",
+            Error::OAuth(_) => "oauth",
+            Error::Validation(_) => "validation",
+            Error::CircuitOpen { .. } => "circuit_open",
+            Error::BudgetExceeded(_) => "budget_exceeded",
+        }
+    }
+}
+
+impl BuildError {
+    /// Returns a stable, machine-readable code for this error.
+    ///
+    /// All codes are prefixed with `config_` to keep them distinct from
+    /// `Error::code` values.
+    pub fn code(&self) -> &'static str {
+        match self {
+            BuildError::MissingApiKey => "config_missing_api_key",
+            BuildError::InvalidApiKey(_) => "config_invalid_api_key",
+            BuildError::InvalidBaseUrl { .. } => "config_invalid_base_url",
+            BuildError::InvalidProxy(_) => "config_invalid_proxy",
+            BuildError::HttpClient(_) => "config_http_client",
+        }
+    }
 }
 
 /// Check if an HTTP status code is retryable.
@@ -86,6 +249,14 @@ pub fn is_retryable_status(status: u16) -> bool {
     matches!(status, 408 | 409 | 429) || status >= 500
 }
 
+/// Check if an error type from an SSE `error` event is retryable.
+pub fn is_retryable_error_type(error_type: &str) -> bool {
+    matches!(
+        error_type,
+        "overloaded_error" | "api_error" | "rate_limit_error"
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -117,6 +288,41 @@ mod tests {
         assert!(!is_retryable_status(200));
     }
 
+    #[test]
+    fn test_error_codes() {
+        let api = |status| {
+            Error::from_api_response(
+                status,
+                ApiErrorBody {
+                    error_type: "error".to_string(),
+                    message: String::new(),
+                },
+                None,
+            )
+        };
+        assert_eq!(api(400).code(), "invalid_request");
+        assert_eq!(api(429).code(), "rate_limited");
+        assert_eq!(api(529).code(), "overloaded");
+        assert_eq!(api(503).code(), "server_error");
+        assert_eq!(api(418).code(), "api_error");
+        assert_eq!(Error::Timeout.code(), "timeout");
+        assert_eq!(Error::Cancelled.code(), "cancelled");
+        assert_eq!(Error::StreamError(String::new()).code(), "stream");
+        assert_eq!(Error::Validation(String::new()).code(), "validation");
+        assert_eq!(
+            Error::BudgetExceeded(String::new()).code(),
+            "budget_exceeded"
+        );
+        assert_eq!(BuildError::MissingApiKey.code(), "config_missing_api_key");
+    }
+
+    #[test]
+    fn test_is_retryable_error_type() {
+        assert!(is_retryable_error_type("overloaded_error"));
+        assert!(is_retryable_error_type("api_error"));
+        assert!(!is_retryable_error_type("invalid_request_error"));
+    }
+
     #[test]
     fn test_api_error_is_retryable() {
         let err = Error::Api {
@@ -142,6 +348,22 @@ mod tests {
         assert_eq!(err.retry_after(), None);
     }
 
+    #[test]
+    fn test_overloaded_classification() {
+        let body = ApiErrorBody {
+            error_type: "overloaded_error".to_string(),
+            message: "Overloaded".to_string(),
+        };
+        let err = Error::from_api_response(529, body.clone(), None);
+        assert!(matches!(err, Error::Overloaded { .. }));
+        assert!(err.is_retryable());
+        assert_eq!(err.retry_class(), RetryClass::Overloaded);
+
+        let err = Error::from_api_response(503, body, None);
+        assert!(matches!(err, Error::Api { status: 503, .. }));
+        assert_eq!(err.retry_class(), RetryClass::Standard);
+    }
+
     #[test]
     fn test_timeout_is_retryable() {
         let err = Error::Timeout;
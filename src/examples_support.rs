@@ -0,0 +1,247 @@
+//! Reusable flows from the crate's examples, for smoke-testing an environment.
+//!
+//! ```ignore
+//! use uno_anthropic::{Client, examples_support};
+//!
+//! // Exercises tool use and streaming end to end with the configured API key.
+//! examples_support::smoke_test(&Client::new()).await?;
+//! ```
+
+use crate::client::Client;
+use crate::error::Error;
+use crate::messages::params::MessageCreateParams;
+use crate::messages::streaming::{ContentBlockDelta, StreamEvent};
+use crate::types::common::{Role, StopReason};
+use crate::types::content::{
+    ContentBlock, ContentBlockParam, ToolResultBlockParam, ToolResultContent, ToolUseBlock,
+};
+use crate::types::message::{Message, MessageContent, MessageParam};
+use crate::types::model::Model;
+use crate::types::tool::{Tool, ToolDefinition, ToolInputSchema};
+
+/// Model used by `smoke_test`.
+pub const SMOKE_TEST_MODEL: Model = Model::ClaudeSonnet4_5;
+
+/// Upper bound on request/tool-result round trips in `run_tool_conversation`.
+const MAX_TOOL_ROUNDS: usize = 5;
+
+/// The `get_weather` tool from the tools example.
+pub fn weather_tool() -> ToolDefinition {
+    ToolDefinition::Custom(Tool {
+        name: "get_weather".to_string(),
+        description: Some("Get the current weather for a location.".to_string()),
+        input_schema: ToolInputSchema::object()
+            .property(
+                "location",
+                serde_json::json!({
+                    "type": "string",
+                    "description": "The city and state, e.g. San Francisco, CA"
+                }),
+            )
+            .required("location"),
+        ..Default::default()
+    })
+}
+
+/// Simulated execution of the tools returned by [`weather_tool`].
+pub fn run_tool(tool_use: &ToolUseBlock) -> ToolResultBlockParam {
+    let (content, is_error) = match tool_use.name.as_str() {
+        "get_weather" => {
+            let location = tool_use
+                .input
+                .get("location")
+                .and_then(|l| l.as_str())
+                .unwrap_or("San Francisco, CA");
+            let result = serde_json::json!({
+                "temperature": "72",
+                "unit": "fahrenheit",
+                "conditions": "Partly cloudy",
+                "location": location
+            });
+            (result.to_string(), None)
+        }
+        other => (format!("Unknown tool: {other}"), Some(true)),
+    };
+    ToolResultBlockParam {
+        tool_use_id: tool_use.id.clone(),
+        content: Some(ToolResultContent::Text(content)),
+        is_error,
+        cache_control: None,
+    }
+}
+
+/// Run a conversation with `tools`, answering each tool call with `run_tool`
+/// until the model stops asking for tools.
+///
+/// Returns the full conversation and the final assistant message.
+pub async fn run_tool_conversation(
+    client: &Client,
+    model: Model,
+    prompt: &str,
+    tools: Vec<ToolDefinition>,
+) -> Result<(Vec<MessageParam>, Message), Error> {
+    let mut messages = vec![MessageParam::user(prompt)];
+    for _ in 0..MAX_TOOL_ROUNDS {
+        let message = client
+            .messages()
+            .create(
+                MessageCreateParams::builder()
+                    .model(model.clone())
+                    .max_tokens(1024)
+                    .messages(messages.clone())
+                    .tools(tools.clone())
+                    .build(),
+            )
+            .await?;
+        messages.push(message.to_param());
+
+        let results: Vec<ContentBlockParam> = message
+            .content
+            .iter()
+            .filter_map(|block| match block {
+                ContentBlock::ToolUse(tool_use) => {
+                    Some(ContentBlockParam::ToolResult(run_tool(tool_use)))
+                }
+                _ => None,
+            })
+            .collect();
+        if results.is_empty() {
+            return Ok((messages, message));
+        }
+        messages.push(MessageParam {
+            role: Role::User,
+            content: MessageContent::Blocks(results),
+        });
+    }
+    Err(Error::Validation(format!(
+        "model was still calling tools after {MAX_TOOL_ROUNDS} rounds"
+    )))
+}
+
+/// Stream a reply to `prompt`, passing each text delta to `on_text`.
+pub async fn stream_text(
+    client: &Client,
+    model: Model,
+    prompt: &str,
+    mut on_text: impl FnMut(&str),
+) -> Result<Message, Error> {
+    client
+        .messages()
+        .create_stream(
+            MessageCreateParams::builder()
+                .model(model)
+                .max_tokens(1024)
+                .messages(vec![MessageParam::user(prompt)])
+                .build(),
+        )
+        .await?
+        .accumulate_with(|event| {
+            if let StreamEvent::ContentBlockDelta {
+                delta: ContentBlockDelta::TextDelta { text },
+                ..
+            } = event
+            {
+                on_text(text);
+            }
+        })
+        .await
+}
+
+/// Check that `client` can complete a tool-use round trip and a streaming
+/// request.
+///
+/// Fails with `Error::Validation` if the model never calls the weather tool or
+/// the stream produces no text.
+pub async fn smoke_test(client: &Client) -> Result<(), Error> {
+    let (messages, _) = run_tool_conversation(
+        client,
+        SMOKE_TEST_MODEL,
+        "What is the weather like in San Francisco? Use the get_weather tool.",
+        vec![weather_tool()],
+    )
+    .await?;
+    let called_tool = messages.iter().any(|m| {
+        matches!(&m.content, MessageContent::Blocks(blocks)
+            if blocks.iter().any(|b| matches!(b, ContentBlockParam::ToolResult(_))))
+    });
+    if !called_tool {
+        return Err(Error::Validation(
+            "smoke test: model did not call get_weather".to_string(),
+        ));
+    }
+
+    let mut streamed = String::new();
+    let message = stream_text(client, SMOKE_TEST_MODEL, "Say hello.", |text| {
+        streamed.push_str(text)
+    })
+    .await?;
+    if streamed.is_empty() || message.stop_reason != Some(StopReason::EndTurn) {
+        return Err(Error::Validation(
+            "smoke test: stream did not produce a complete text reply".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tool_use_response() -> serde_json::Value {
+        serde_json::json!({
+            "id": "msg_1",
+            "type": "message",
+            "role": "assistant",
+            "content": [{"type": "tool_use", "id": "toolu_1", "name": "get_weather", "input": {"location": "Paris"}}],
+            "model": "claude-sonnet-4-5",
+            "stop_reason": "tool_use",
+            "usage": {"input_tokens": 10, "output_tokens": 5}
+        })
+    }
+
+    #[tokio::test]
+    async fn test_run_tool_conversation() {
+        use wiremock::matchers::{body_string_contains, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/messages"))
+            .and(body_string_contains("tool_result"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(crate::mock::text_message("It is 72F in Paris.")),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/v1/messages"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(tool_use_response()))
+            .mount(&server)
+            .await;
+
+        let client = Client::builder()
+            .api_key("test")
+            .base_url(server.uri())
+            .build();
+        let (messages, last) =
+            run_tool_conversation(&client, SMOKE_TEST_MODEL, "Weather?", vec![weather_tool()])
+                .await
+                .unwrap();
+        assert_eq!(messages.len(), 4);
+        assert_eq!(last.stop_reason, Some(StopReason::EndTurn));
+    }
+
+    #[test]
+    fn test_run_tool_unknown() {
+        let tool_use = ToolUseBlock {
+            id: "toolu_1".to_string(),
+            name: "launch".to_string(),
+            input: serde_json::json!({}),
+            partial_json: None,
+            extra: Default::default(),
+        };
+        let result = run_tool(&tool_use);
+        assert_eq!(result.is_error, Some(true));
+    }
+}
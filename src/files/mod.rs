@@ -3,7 +3,7 @@ pub mod types;
 use reqwest::header::{HeaderMap, HeaderValue};
 use reqwest::multipart;
 
-use crate::client::Client;
+use crate::client::{Client, RequestOptions};
 use crate::error::Error;
 use crate::types::Page;
 
@@ -16,17 +16,38 @@ pub use self::types::*;
 /// This API requires the `files-api-2025-04-14` beta header.
 pub struct FileService<'a> {
     pub(crate) client: &'a Client,
+    pub(crate) extra_headers: Option<HeaderMap>,
 }
 
 impl<'a> FileService<'a> {
-    pub(crate) fn new(client: &'a Client) -> Self {
-        Self { client }
+    /// Create a service bound to `client`. Equivalent to `client.files()`.
+    pub fn new(client: &'a Client) -> Self {
+        Self {
+            client,
+            extra_headers: None,
+        }
+    }
+
+    /// Create a service that sends `headers` with every request.
+    ///
+    /// The required beta flag is sent alongside any `anthropic-beta` value in `headers`.
+    pub fn with_extra_headers(client: &'a Client, headers: HeaderMap) -> Self {
+        Self {
+            client,
+            extra_headers: Some(headers),
+        }
     }
 
-    /// Build the beta header for files API requests.
-    fn beta_headers() -> HeaderMap {
-        let mut headers = HeaderMap::new();
-        headers.insert(
+    /// Send the headers in `options` with requests made through this service.
+    pub fn with_options(mut self, options: RequestOptions) -> Self {
+        self.extra_headers = options.merge_into(self.extra_headers.take());
+        self
+    }
+
+    /// Build the headers for files API requests, including the beta header.
+    fn beta_headers(&self) -> HeaderMap {
+        let mut headers = self.extra_headers.clone().unwrap_or_default();
+        headers.append(
             "anthropic-beta",
             HeaderValue::from_static("files-api-2025-04-14"),
         );
@@ -42,43 +63,15 @@ impl<'a> FileService<'a> {
         filename: &str,
         mime_type: &str,
     ) -> Result<FileMetadata, Error> {
-        let inner = &self.client.inner;
-        let url = format!("{}/v1/files", inner.config.base_url.trim_end_matches('/'));
-        let headers = inner.config.build_headers();
-        let beta_headers = Self::beta_headers();
-
         let part = multipart::Part::bytes(file_data)
             .file_name(filename.to_string())
             .mime_str(mime_type)
             .map_err(|e| Error::StreamError(format!("Invalid MIME type: {}", e)))?;
-
         let form = multipart::Form::new().part("file", part);
 
-        let mut request = inner.http.post(&url).headers(headers).headers(beta_headers);
-        request = request.multipart(form);
-
-        let req = request.build().map_err(Error::Http)?;
-        let response = inner.http.execute(req).await.map_err(Error::Http)?;
-
-        let status = response.status().as_u16();
-        if status >= 400 {
-            let body_bytes = response.bytes().await.map_err(Error::Http)?;
-            let error_body = serde_json::from_slice::<crate::error::ApiErrorResponse>(&body_bytes)
-                .map(|r| r.error)
-                .unwrap_or_else(|_| crate::error::ApiErrorBody {
-                    error_type: "unknown_error".to_string(),
-                    message: String::from_utf8_lossy(&body_bytes).to_string(),
-                });
-            return Err(Error::Api {
-                status,
-                body: error_body,
-                retry_after: None,
-            });
-        }
-
-        let bytes = response.bytes().await.map_err(Error::Http)?;
-        let result = serde_json::from_slice(&bytes)?;
-        Ok(result)
+        self.client
+            .post_multipart("files", form, Some(&self.beta_headers()))
+            .await
     }
 
     /// Get file metadata.
@@ -86,7 +79,7 @@ impl<'a> FileService<'a> {
     /// Calls `GET /v1/files/{file_id}`.
     pub async fn get_metadata(&self, file_id: &str) -> Result<FileMetadata, Error> {
         let path = format!("files/{}", file_id);
-        let headers = Self::beta_headers();
+        let headers = self.beta_headers();
         self.client.get(&path, Some(&headers)).await
     }
 
@@ -95,7 +88,7 @@ impl<'a> FileService<'a> {
     /// Calls `GET /v1/files/{file_id}/content`.
     pub async fn download(&self, file_id: &str) -> Result<bytes::Bytes, Error> {
         let path = format!("files/{}/content", file_id);
-        let headers = Self::beta_headers();
+        let headers = self.beta_headers();
         self.client
             .execute_raw("GET", &path, None::<&()>, Some(&headers))
             .await
@@ -111,7 +104,7 @@ impl<'a> FileService<'a> {
         } else {
             format!("files?{}", query)
         };
-        let headers = Self::beta_headers();
+        let headers = self.beta_headers();
         self.client.get(&path, Some(&headers)).await
     }
 
@@ -120,7 +113,7 @@ impl<'a> FileService<'a> {
     /// Calls `DELETE /v1/files/{file_id}`.
     pub async fn delete(&self, file_id: &str) -> Result<DeletedFile, Error> {
         let path = format!("files/{}", file_id);
-        let headers = Self::beta_headers();
+        let headers = self.beta_headers();
         self.client.delete(&path, Some(&headers)).await
     }
 }
@@ -134,4 +127,68 @@ mod tests {
         let params = FileListParams::default();
         assert_eq!(params.to_query_string(), "");
     }
+
+    #[tokio::test]
+    async fn test_upload_retries_through_middleware() {
+        use wiremock::matchers::{body_string_contains, header, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        use crate::middleware::{AsyncMiddleware, Next};
+
+        struct Tag;
+
+        impl AsyncMiddleware for Tag {
+            async fn handle(
+                &self,
+                mut request: reqwest::Request,
+                next: Next<'_>,
+            ) -> Result<reqwest::Response, Error> {
+                request
+                    .headers_mut()
+                    .insert("x-upload", HeaderValue::from_static("yes"));
+                next.run(request).await
+            }
+        }
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/files"))
+            .respond_with(ResponseTemplate::new(529))
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/v1/files"))
+            .and(header("anthropic-beta", "files-api-2025-04-14"))
+            .and(header("x-upload", "yes"))
+            .and(body_string_contains("filename=\"notes.txt\""))
+            .and(body_string_contains("hello"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "id": "file_1",
+                "type": "file",
+                "filename": "notes.txt",
+                "mime_type": "text/plain",
+                "size_bytes": 5,
+                "created_at": "2025-04-14T12:00:00Z"
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = Client::builder()
+            .api_key("test")
+            .base_url(server.uri())
+            .overloaded_backoff(
+                std::time::Duration::from_millis(1),
+                std::time::Duration::from_millis(1),
+            )
+            .middleware(Tag)
+            .build();
+        let file = client
+            .files()
+            .upload(b"hello".to_vec(), "notes.txt", "text/plain")
+            .await
+            .unwrap();
+        assert_eq!(file.id, "file_1");
+    }
 }
@@ -2,6 +2,7 @@ use serde::{Deserialize, Serialize};
 
 /// Metadata about an uploaded file.
 #[derive(Debug, Clone, Deserialize)]
+#[cfg_attr(feature = "strict-serde", serde(deny_unknown_fields))]
 pub struct FileMetadata {
     pub id: String,
     #[serde(rename = "type")]
@@ -16,6 +17,7 @@ pub struct FileMetadata {
 
 /// Response when a file is deleted.
 #[derive(Debug, Clone, Deserialize)]
+#[cfg_attr(feature = "strict-serde", serde(deny_unknown_fields))]
 pub struct DeletedFile {
     pub id: String,
     #[serde(rename = "type")]
@@ -0,0 +1,299 @@
+//! Loading images into request content blocks.
+//!
+//! Enabled with the `image` feature. The media type is detected from the file
+//! contents, and the `*_within` variants downscale images that exceed the API's
+//! limits before they are encoded, instead of letting the request fail
+//! server-side.
+//!
+//! ```ignore
+//! use uno_anthropic::images::{self, ImageLimits};
+//!
+//! let block = images::load_within("photo.jpg", &ImageLimits::recommended())?;
+//! let message = MessageParam::user_blocks(vec![ContentBlockParam::Image(block)]);
+//! ```
+
+use std::io::Cursor;
+use std::path::Path;
+
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageFormat};
+
+use crate::types::content::ImageBlockParam;
+use crate::types::image::{ImageSource, MediaType};
+
+/// Largest width or height the API accepts.
+pub const MAX_DIMENSION: u32 = 8000;
+
+/// Largest long edge the API processes without resizing the image itself.
+pub const RECOMMENDED_MAX_DIMENSION: u32 = 1568;
+
+/// Largest base64-encoded image the API accepts, in bytes.
+pub const MAX_ENCODED_BYTES: usize = 5 * 1024 * 1024;
+
+/// Smallest long edge tried when shrinking an image to fit `max_encoded_bytes`.
+const MIN_DIMENSION: u32 = 64;
+
+/// JPEG quality used when an image has to be re-encoded to fit the size limit.
+const JPEG_QUALITY: u8 = 85;
+
+/// Errors returned while loading an image.
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum ImageError {
+    #[error("failed to read image: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("unsupported image format: expected JPEG, PNG, GIF or WebP")]
+    UnsupportedFormat,
+
+    #[error("failed to process image: {0}")]
+    Image(#[from] image::ImageError),
+
+    #[error("image is {size} bytes encoded, limit is {limit}")]
+    TooLarge { size: usize, limit: usize },
+}
+
+/// Size limits an image is downscaled to fit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ImageLimits {
+    /// Largest allowed width or height, in pixels.
+    pub max_dimension: u32,
+    /// Largest allowed base64-encoded size, in bytes.
+    pub max_encoded_bytes: usize,
+}
+
+impl Default for ImageLimits {
+    /// The hard limits enforced by the API.
+    fn default() -> Self {
+        Self {
+            max_dimension: MAX_DIMENSION,
+            max_encoded_bytes: MAX_ENCODED_BYTES,
+        }
+    }
+}
+
+impl ImageLimits {
+    /// Limits that avoid the API's own resizing, which lowers latency without
+    /// affecting quality.
+    pub fn recommended() -> Self {
+        Self {
+            max_dimension: RECOMMENDED_MAX_DIMENSION,
+            ..Self::default()
+        }
+    }
+}
+
+/// Detect a supported image type from the leading bytes of `data`.
+pub fn detect_media_type(data: &[u8]) -> Option<MediaType> {
+    if data.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        Some(MediaType::Jpeg)
+    } else if data.starts_with(b"\x89PNG\r\n\x1a\n") {
+        Some(MediaType::Png)
+    } else if data.starts_with(b"GIF87a") || data.starts_with(b"GIF89a") {
+        Some(MediaType::Gif)
+    } else if data.len() >= 12 && &data[..4] == b"RIFF" && &data[8..12] == b"WEBP" {
+        Some(MediaType::Webp)
+    } else {
+        None
+    }
+}
+
+/// Build an image block from encoded image bytes, unchanged.
+pub fn from_bytes(data: impl AsRef<[u8]>) -> Result<ImageBlockParam, ImageError> {
+    let data = data.as_ref();
+    let media_type = detect_media_type(data).ok_or(ImageError::UnsupportedFormat)?;
+    Ok(ImageBlockParam::new(ImageSource::base64(data, media_type)))
+}
+
+/// Read an image file into an image block, unchanged.
+pub fn load(path: impl AsRef<Path>) -> Result<ImageBlockParam, ImageError> {
+    from_bytes(std::fs::read(path)?)
+}
+
+/// Build an image block from encoded image bytes, downscaling the image if it
+/// exceeds `limits`.
+///
+/// Images already within the limits are sent as-is. Larger images are resized
+/// to fit `max_dimension`, keeping their aspect ratio, and re-encoded; if the
+/// result is still over `max_encoded_bytes` it is converted to JPEG and shrunk
+/// further until it fits.
+pub fn from_bytes_within(
+    data: impl AsRef<[u8]>,
+    limits: &ImageLimits,
+) -> Result<ImageBlockParam, ImageError> {
+    let data = data.as_ref();
+    let media_type = detect_media_type(data).ok_or(ImageError::UnsupportedFormat)?;
+    let (width, height) = image::ImageReader::with_format(Cursor::new(data), format(&media_type))
+        .into_dimensions()?;
+    if width.max(height) <= limits.max_dimension
+        && encoded_len(data.len()) <= limits.max_encoded_bytes
+    {
+        return Ok(ImageBlockParam::new(ImageSource::base64(data, media_type)));
+    }
+
+    let mut image = image::load_from_memory_with_format(data, format(&media_type))?;
+    if width.max(height) > limits.max_dimension {
+        image = image.resize(
+            limits.max_dimension,
+            limits.max_dimension,
+            FilterType::Lanczos3,
+        );
+    }
+    // GIF and WebP are re-encoded as PNG, which the encoder supports losslessly.
+    let media_type = match media_type {
+        MediaType::Jpeg => MediaType::Jpeg,
+        _ => MediaType::Png,
+    };
+    let mut encoded = encode(&image, &media_type)?;
+    if encoded_len(encoded.len()) <= limits.max_encoded_bytes {
+        return Ok(base64_block(&encoded, media_type));
+    }
+
+    loop {
+        encoded = encode(&image, &MediaType::Jpeg)?;
+        if encoded_len(encoded.len()) <= limits.max_encoded_bytes {
+            return Ok(base64_block(&encoded, MediaType::Jpeg));
+        }
+        let long_edge = image.width().max(image.height()) * 3 / 4;
+        if long_edge < MIN_DIMENSION {
+            return Err(ImageError::TooLarge {
+                size: encoded_len(encoded.len()),
+                limit: limits.max_encoded_bytes,
+            });
+        }
+        image = image.resize(long_edge, long_edge, FilterType::Lanczos3);
+    }
+}
+
+/// Read an image file into an image block, downscaling the image if it exceeds
+/// `limits`. See [`from_bytes_within`].
+pub fn load_within(
+    path: impl AsRef<Path>,
+    limits: &ImageLimits,
+) -> Result<ImageBlockParam, ImageError> {
+    from_bytes_within(std::fs::read(path)?, limits)
+}
+
+fn format(media_type: &MediaType) -> ImageFormat {
+    match media_type {
+        MediaType::Jpeg => ImageFormat::Jpeg,
+        MediaType::Png => ImageFormat::Png,
+        MediaType::Gif => ImageFormat::Gif,
+        MediaType::Webp => ImageFormat::WebP,
+    }
+}
+
+fn base64_block(data: &[u8], media_type: MediaType) -> ImageBlockParam {
+    ImageBlockParam::new(ImageSource::base64(data, media_type))
+}
+
+fn encode(image: &DynamicImage, media_type: &MediaType) -> Result<Vec<u8>, ImageError> {
+    let mut out = Cursor::new(Vec::new());
+    match media_type {
+        MediaType::Jpeg => {
+            let encoder =
+                image::codecs::jpeg::JpegEncoder::new_with_quality(&mut out, JPEG_QUALITY);
+            DynamicImage::ImageRgb8(image.to_rgb8()).write_with_encoder(encoder)?;
+        }
+        other => image.write_to(&mut out, format(other))?,
+    }
+    Ok(out.into_inner())
+}
+
+/// Length of `len` bytes once base64-encoded with padding.
+fn encoded_len(len: usize) -> usize {
+    len.div_ceil(3) * 4
+}
+
+#[cfg(test)]
+mod tests {
+    use base64::Engine;
+
+    use super::*;
+
+    fn png(width: u32, height: u32) -> Vec<u8> {
+        let image = DynamicImage::ImageRgb8(image::RgbImage::from_fn(width, height, |x, y| {
+            image::Rgb([(x % 256) as u8, (y % 256) as u8, 128])
+        }));
+        encode(&image, &MediaType::Png).unwrap()
+    }
+
+    fn decoded(block: &ImageBlockParam) -> (MediaType, DynamicImage) {
+        let ImageSource::Base64(source) = &block.source else {
+            panic!("Expected Base64 source");
+        };
+        let bytes = base64::engine::general_purpose::STANDARD
+            .decode(&source.data)
+            .unwrap();
+        (
+            source.media_type.clone(),
+            image::load_from_memory(&bytes).unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_detect_media_type() {
+        assert_eq!(detect_media_type(&png(1, 1)), Some(MediaType::Png));
+        assert_eq!(
+            detect_media_type(&[0xFF, 0xD8, 0xFF, 0xE0]),
+            Some(MediaType::Jpeg)
+        );
+        assert_eq!(detect_media_type(b"GIF89a..."), Some(MediaType::Gif));
+        assert_eq!(
+            detect_media_type(b"RIFF\0\0\0\0WEBPVP8 "),
+            Some(MediaType::Webp)
+        );
+        assert_eq!(detect_media_type(b"%PDF-1.7"), None);
+        assert!(matches!(
+            from_bytes(b"%PDF-1.7"),
+            Err(ImageError::UnsupportedFormat)
+        ));
+    }
+
+    #[test]
+    fn test_within_limits_is_unchanged() {
+        let data = png(40, 20);
+        let block = from_bytes_within(&data, &ImageLimits::recommended()).unwrap();
+        let ImageSource::Base64(source) = &block.source else {
+            panic!("Expected Base64 source");
+        };
+        assert_eq!(
+            source.data,
+            base64::engine::general_purpose::STANDARD.encode(&data)
+        );
+    }
+
+    #[test]
+    fn test_downscales_to_max_dimension() {
+        let limits = ImageLimits {
+            max_dimension: 50,
+            ..ImageLimits::default()
+        };
+        let (media_type, image) = decoded(&from_bytes_within(png(200, 100), &limits).unwrap());
+        assert_eq!(media_type, MediaType::Png);
+        assert_eq!((image.width(), image.height()), (50, 25));
+    }
+
+    #[test]
+    fn test_shrinks_to_max_encoded_bytes() {
+        let limits = ImageLimits {
+            max_encoded_bytes: 4_000,
+            ..ImageLimits::default()
+        };
+        let block = from_bytes_within(png(400, 400), &limits).unwrap();
+        let ImageSource::Base64(source) = &block.source else {
+            panic!("Expected Base64 source");
+        };
+        assert!(source.data.len() <= 4_000);
+        assert_eq!(source.media_type, MediaType::Jpeg);
+
+        let limits = ImageLimits {
+            max_encoded_bytes: 10,
+            ..ImageLimits::default()
+        };
+        assert!(matches!(
+            from_bytes_within(png(400, 400), &limits),
+            Err(ImageError::TooLarge { limit: 10, .. })
+        ));
+    }
+}
@@ -19,12 +19,40 @@
 //! ).await?;
 //! ```
 
+pub mod api;
+pub mod budget;
+pub mod citations;
 pub mod client;
+pub mod compaction;
 pub mod config;
+pub mod context_window;
+pub mod credentials;
 pub mod error;
+#[cfg(feature = "examples-support")]
+pub mod examples_support;
+#[cfg(feature = "image")]
+pub mod images;
+pub mod markdown;
 pub mod middleware;
+#[cfg(any(test, feature = "test-util"))]
+pub mod mock;
+#[cfg(feature = "openai")]
+pub mod openai;
+pub mod pricing;
+pub mod rate_limit;
+pub mod redact;
+pub mod response;
+pub mod response_cache;
 pub mod retry;
+pub mod telemetry;
+mod time;
+pub mod timing;
+#[cfg(feature = "tokenizer")]
+pub mod tokenizer;
+pub mod tools;
 pub mod types;
+#[cfg(feature = "test-util")]
+pub mod vcr;
 
 pub mod messages;
 pub mod streaming;
@@ -46,7 +74,7 @@ pub mod vertex;
 
 // Re-export key types at crate root for ergonomic imports.
 pub use client::{Client, ClientBuilder};
-pub use error::Error;
+pub use error::{BuildError, Error};
 pub use messages::params::{CountTokensParams, MessageCreateParams};
 pub use oauth::{OAuthConfig, OAuthTokens};
 pub use types::*;
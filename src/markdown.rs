@@ -0,0 +1,335 @@
+//! Markdown rendering of message responses for logs and chat transcripts.
+//!
+//! ```ignore
+//! use uno_anthropic::markdown::MarkdownOptions;
+//!
+//! let message = client.messages().create(params).await?;
+//! println!("{}", message.to_markdown(&MarkdownOptions::default().thinking(true)));
+//! ```
+
+use crate::citations::{push_cited, push_footnotes, source_index};
+use crate::types::content::{
+    BashCodeExecutionContent, CodeExecutionContent, ContentBlock, McpToolResultContent,
+    McpToolResultContentBlock, TextEditorCodeExecutionContent, ToolSearchToolResultContent,
+    WebFetchToolResultContent, WebSearchToolResultContent,
+};
+use crate::types::message::Message;
+
+/// Controls which parts of a message `Message::to_markdown` renders.
+///
+/// By default text, tool use summaries, server tool results and citation
+/// footnotes are rendered, and thinking is left out.
+#[derive(Debug, Clone)]
+pub struct MarkdownOptions {
+    thinking: bool,
+    tool_use: bool,
+    tool_results: bool,
+    citations: bool,
+}
+
+impl Default for MarkdownOptions {
+    fn default() -> Self {
+        Self {
+            thinking: false,
+            tool_use: true,
+            tool_results: true,
+            citations: true,
+        }
+    }
+}
+
+impl MarkdownOptions {
+    /// Render thinking blocks as block quotes.
+    pub fn thinking(mut self, render: bool) -> Self {
+        self.thinking = render;
+        self
+    }
+
+    /// Render each tool call with its name and input.
+    pub fn tool_use(mut self, render: bool) -> Self {
+        self.tool_use = render;
+        self
+    }
+
+    /// Render the results of server tools, such as code execution output and
+    /// web search hits.
+    pub fn tool_results(mut self, render: bool) -> Self {
+        self.tool_results = render;
+        self
+    }
+
+    /// Mark cited text with footnotes listing the sources.
+    pub fn citations(mut self, render: bool) -> Self {
+        self.citations = render;
+        self
+    }
+}
+
+/// Render `message` as Markdown. Consecutive text blocks form one section;
+/// other blocks get a section each.
+pub(crate) fn render(message: &Message, options: &MarkdownOptions) -> String {
+    let mut sections: Vec<String> = Vec::new();
+    let mut sources = Vec::new();
+    let mut in_text = false;
+    for block in &message.content {
+        if let ContentBlock::Text(text) = block {
+            if !in_text {
+                sections.push(String::new());
+                in_text = true;
+            }
+            let section = sections.last_mut().expect("text section was just pushed");
+            match text.citations.as_deref() {
+                Some(citations) if options.citations && !citations.is_empty() => {
+                    let indices: Vec<usize> = citations
+                        .iter()
+                        .map(|citation| source_index(&mut sources, citation))
+                        .collect();
+                    push_cited(section, &text.text, &indices);
+                }
+                _ => section.push_str(&text.text),
+            }
+            continue;
+        }
+        in_text = false;
+        if let Some(section) = render_block(block, options) {
+            sections.push(section);
+        }
+    }
+    let mut out = sections
+        .iter()
+        .map(|section| section.trim())
+        .filter(|section| !section.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    push_footnotes(&mut out, &sources);
+    out
+}
+
+fn render_block(block: &ContentBlock, options: &MarkdownOptions) -> Option<String> {
+    match block {
+        ContentBlock::Thinking(t) if options.thinking => Some(quote(&t.thinking)),
+        ContentBlock::RedactedThinking(_) if options.thinking => {
+            Some("> *[redacted thinking]*".to_string())
+        }
+        ContentBlock::ToolUse(t) if options.tool_use => Some(tool_call(&t.name, &t.input)),
+        ContentBlock::ServerToolUse(t) if options.tool_use => Some(tool_call(&t.name, &t.input)),
+        ContentBlock::McpToolUse(t) if options.tool_use => Some(tool_call(
+            &format!("{}/{}", t.server_label, t.name),
+            &t.input,
+        )),
+        _ if options.tool_results => render_result(block),
+        _ => None,
+    }
+}
+
+fn render_result(block: &ContentBlock) -> Option<String> {
+    match block {
+        ContentBlock::WebSearchToolResult(w) => Some(match &w.content {
+            WebSearchToolResultContent::Results(results) if results.is_empty() => {
+                "**Web search:** no results".to_string()
+            }
+            WebSearchToolResultContent::Results(results) => {
+                let mut out = "**Web search results:**\n".to_string();
+                for result in results {
+                    out.push_str(&format!("\n- [{}]({})", result.title, result.url));
+                }
+                out
+            }
+            WebSearchToolResultContent::Error(e) => {
+                format!("**Web search failed:** `{}`", code_name(&e.error_code))
+            }
+        }),
+        ContentBlock::WebFetchToolResult(w) => Some(match &w.content {
+            WebFetchToolResultContent::WebFetchResult(fetch) => match &fetch.content.title {
+                Some(title) => format!("**Fetched:** [{title}]({})", fetch.url),
+                None => format!("**Fetched:** <{}>", fetch.url),
+            },
+            WebFetchToolResultContent::WebFetchToolResultError(e) => {
+                format!("**Web fetch failed:** `{}`", code_name(&e.error_code))
+            }
+        }),
+        ContentBlock::ToolSearchToolResult(t) => Some(match &t.content {
+            ToolSearchToolResultContent::ToolSearchToolSearchResult(result) => {
+                let names: Vec<String> = result
+                    .tool_references
+                    .iter()
+                    .map(|tool| format!("`{}`", tool.tool_name))
+                    .collect();
+                format!("**Tools found:** {}", names.join(", "))
+            }
+            ToolSearchToolResultContent::ToolSearchToolResultError(e) => {
+                format!("**Tool search failed:** {}", e.error_message)
+            }
+        }),
+        ContentBlock::McpToolResult(m) => {
+            let text = match &m.content {
+                McpToolResultContent::Text(text) => text.clone(),
+                McpToolResultContent::Blocks(blocks) => blocks
+                    .iter()
+                    .map(|McpToolResultContentBlock::Text(block)| block.text.as_str())
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            };
+            let label = if m.is_error == Some(true) {
+                "Tool error"
+            } else {
+                "Tool result"
+            };
+            Some(format!("**{label}:**\n\n{}", fence("", &text)))
+        }
+        ContentBlock::CodeExecutionToolResult(c) => {
+            let mut parts = Vec::new();
+            for content in &c.content {
+                match content {
+                    CodeExecutionContent::CodeExecutionOutput(o) => {
+                        if !o.output.is_empty() {
+                            parts.push(fence("", &o.output));
+                        }
+                        if let Some(file_id) = &o.file_id {
+                            parts.push(format!("**Output file:** `{file_id}`"));
+                        }
+                    }
+                    CodeExecutionContent::CodeExecutionResult(r) => {
+                        parts.push(format!("**Result:** `{}`", r.return_value));
+                    }
+                    CodeExecutionContent::CodeExecutionError(e) => {
+                        parts.push(format!("**Error:** {}", e.error_message));
+                    }
+                }
+            }
+            Some(parts.join("\n\n"))
+        }
+        ContentBlock::BashCodeExecutionToolResult(b) => {
+            let mut parts = Vec::new();
+            for content in &b.content {
+                match content {
+                    BashCodeExecutionContent::BashCodeExecutionOutput(o) => {
+                        for stream in [&o.stdout, &o.stderr].into_iter().flatten() {
+                            if !stream.is_empty() {
+                                parts.push(fence("", stream));
+                            }
+                        }
+                        if let Some(file_id) = &o.file_id {
+                            parts.push(format!("**Output file:** `{file_id}`"));
+                        }
+                    }
+                    BashCodeExecutionContent::BashCodeExecutionResult(r) => {
+                        parts.push(format!("**Exit code:** {}", r.return_code));
+                    }
+                }
+            }
+            Some(parts.join("\n\n"))
+        }
+        ContentBlock::TextEditorCodeExecutionToolResult(t) => Some(
+            t.content
+                .iter()
+                .map(
+                    |TextEditorCodeExecutionContent::TextEditorCodeExecutionOutput(o)| {
+                        fence("", &o.output)
+                    },
+                )
+                .collect::<Vec<_>>()
+                .join("\n\n"),
+        ),
+        ContentBlock::ContainerUpload(c) => Some(format!("**Uploaded file:** `{}`", c.file_id)),
+        _ => None,
+    }
+}
+
+/// A one-line summary of a tool call followed by its input.
+fn tool_call(name: &str, input: &serde_json::Value) -> String {
+    let input = serde_json::to_string_pretty(input).unwrap_or_default();
+    format!("**Tool call:** `{name}`\n\n{}", fence("json", &input))
+}
+
+/// The wire name of an error code enum.
+fn code_name(code: &impl serde::Serialize) -> String {
+    match serde_json::to_value(code) {
+        Ok(serde_json::Value::String(name)) => name,
+        _ => "unknown".to_string(),
+    }
+}
+
+/// Wrap `text` in a code fence long enough not to be closed by any backtick
+/// run inside it.
+fn fence(lang: &str, text: &str) -> String {
+    let longest = text.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+    let ticks = "`".repeat(longest.max(2) + 1);
+    format!("{ticks}{lang}\n{}\n{ticks}", text.trim_end_matches('\n'))
+}
+
+/// Prefix every line of `text` with `> `.
+fn quote(text: &str) -> String {
+    text.trim()
+        .lines()
+        .map(|line| {
+            if line.is_empty() {
+                ">".to_string()
+            } else {
+                format!("> {line}")
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::text_message;
+
+    fn message() -> Message {
+        Message {
+            content: serde_json::from_value(serde_json::json!([
+                {"type": "thinking", "thinking": "Check the docs.\n\nThen run code.", "signature": "sig"},
+                {"type": "text", "text": "The sky is blue. ", "citations": [{
+                    "type": "web_search_result_location",
+                    "cited_text": "The sky is blue.",
+                    "encrypted_index": "abc",
+                    "title": "Sky",
+                    "url": "https://example.com/sky"
+                }]},
+                {"type": "text", "text": "Let me check."},
+                {"type": "server_tool_use", "id": "srvtoolu_1", "name": "bash_code_execution", "input": {"command": "echo hi"}},
+                {"type": "bash_code_execution_tool_result", "tool_use_id": "srvtoolu_1", "content": [
+                    {"type": "bash_code_execution_output", "stdout": "hi\n", "stderr": ""},
+                    {"type": "bash_code_execution_result", "return_code": 0}
+                ]},
+                {"type": "text", "text": "Done."}
+            ]))
+            .unwrap(),
+            ..text_message("")
+        }
+    }
+
+    #[test]
+    fn test_to_markdown() {
+        let markdown = message().to_markdown(&MarkdownOptions::default().thinking(true));
+        assert_eq!(
+            markdown,
+            "> Check the docs.\n>\n> Then run code.\n\n\
+             The sky is blue.[^1] Let me check.\n\n\
+             **Tool call:** `bash_code_execution`\n\n```json\n{\n  \"command\": \"echo hi\"\n}\n```\n\n\
+             ```\nhi\n```\n\n**Exit code:** 0\n\n\
+             Done.\n\n\
+             [^1]: [Sky](https://example.com/sky): \"The sky is blue.\"\n"
+        );
+    }
+
+    #[test]
+    fn test_to_markdown_text_only() {
+        let options = MarkdownOptions::default()
+            .tool_use(false)
+            .tool_results(false)
+            .citations(false);
+        assert_eq!(
+            message().to_markdown(&options),
+            "The sky is blue. Let me check.\n\nDone."
+        );
+    }
+
+    #[test]
+    fn test_fence_outlasts_backticks() {
+        assert_eq!(fence("", "a ``` b"), "````\na ``` b\n````");
+    }
+}
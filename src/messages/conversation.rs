@@ -0,0 +1,255 @@
+//! A multi-turn conversation that keeps its own history.
+//!
+//! ```ignore
+//! use uno_anthropic::messages::conversation::Conversation;
+//!
+//! let mut chat = Conversation::new(Model::ClaudeSonnet4_5, 1024).system("Be brief.");
+//! let reply = chat.send(&client, "Hi!").await?;
+//! let reply = chat.send(&client, "What did I just say?").await?;
+//!
+//! // Conversations are plain serde values, so they can be saved and resumed.
+//! let saved = serde_json::to_string(&chat)?;
+//! let chat: Conversation = serde_json::from_str(&saved)?;
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+use crate::budget::TokenBudget;
+use crate::client::Client;
+use crate::error::Error;
+use crate::messages::params::MessageCreateParams;
+use crate::messages::streaming::StreamEvent;
+use crate::types::message::{Message, MessageParam, SystemContent};
+use crate::types::model::Model;
+use crate::types::tool::ToolDefinition;
+
+/// Conversation state: the model, system prompt, tools and message history.
+///
+/// Each `send` appends the new user turn and the assistant's reply to
+/// `messages`. If the request fails the user turn is removed again, so the
+/// history stays valid and the call can be retried.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Conversation {
+    pub model: Model,
+    pub max_tokens: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub system: Option<SystemContent>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tools: Vec<ToolDefinition>,
+    #[serde(default)]
+    pub messages: Vec<MessageParam>,
+    /// Budget the replies are counted against. Not saved with the
+    /// conversation; set it again after deserializing.
+    #[serde(skip)]
+    pub budget: Option<TokenBudget>,
+}
+
+impl Conversation {
+    /// Start an empty conversation.
+    pub fn new(model: Model, max_tokens: u32) -> Self {
+        Self {
+            model,
+            max_tokens,
+            system: None,
+            tools: Vec::new(),
+            messages: Vec::new(),
+            budget: None,
+        }
+    }
+
+    /// Set the system prompt.
+    pub fn system(mut self, system: impl Into<SystemContent>) -> Self {
+        self.system = Some(system.into());
+        self
+    }
+
+    /// Count the usage of each reply against `budget`, and fail sends with
+    /// `Error::BudgetExceeded` once it is spent.
+    pub fn budget(mut self, budget: TokenBudget) -> Self {
+        self.budget = Some(budget);
+        self
+    }
+
+    /// Add a tool the model may call.
+    pub fn tool(mut self, tool: impl Into<ToolDefinition>) -> Self {
+        self.tools.push(tool.into());
+        self
+    }
+
+    /// Build request params for the current history.
+    ///
+    /// Useful for setting options the conversation does not track, then
+    /// sending the request and calling `push_reply` with the result.
+    pub fn params(&self) -> MessageCreateParams {
+        let mut params = MessageCreateParams::builder()
+            .model(self.model.clone())
+            .max_tokens(self.max_tokens)
+            .messages(self.messages.clone())
+            .maybe_system(self.system.clone())
+            .build();
+        if !self.tools.is_empty() {
+            params.tools = Some(self.tools.clone());
+        }
+        params
+    }
+
+    /// Send a user text message and record the reply.
+    pub async fn send(
+        &mut self,
+        client: &Client,
+        text: impl Into<String>,
+    ) -> Result<Message, Error> {
+        self.send_message(client, MessageParam::user(text)).await
+    }
+
+    /// Send any user message, such as tool results, and record the reply.
+    pub async fn send_message(
+        &mut self,
+        client: &Client,
+        message: MessageParam,
+    ) -> Result<Message, Error> {
+        self.check_budget()?;
+        self.messages.push(message);
+        let result = client.messages().create(self.params()).await;
+        self.finish_turn(result)
+    }
+
+    /// Send a user text message as a streaming request, calling `on_event` for
+    /// each event as it arrives, and record the reply.
+    pub async fn send_stream(
+        &mut self,
+        client: &Client,
+        text: impl Into<String>,
+        on_event: impl FnMut(&StreamEvent),
+    ) -> Result<Message, Error> {
+        self.check_budget()?;
+        self.messages.push(MessageParam::user(text));
+        let result = match client.messages().create_stream(self.params()).await {
+            Ok(stream) => stream.accumulate_with(on_event).await,
+            Err(err) => Err(err),
+        };
+        self.finish_turn(result)
+    }
+
+    /// Append an assistant reply obtained outside of `send`.
+    pub fn push_reply(&mut self, message: &Message) {
+        self.messages.push(message.to_param());
+    }
+
+    fn check_budget(&self) -> Result<(), Error> {
+        self.budget.as_ref().map_or(Ok(()), TokenBudget::check)
+    }
+
+    fn finish_turn(&mut self, result: Result<Message, Error>) -> Result<Message, Error> {
+        match result {
+            Ok(message) => {
+                if let Some(budget) = &self.budget {
+                    budget.record(&message.usage);
+                }
+                self.push_reply(&message);
+                Ok(message)
+            }
+            Err(err) => {
+                self.messages.pop();
+                Err(err)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use wiremock::matchers::{body_partial_json, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use super::*;
+    use crate::client::ClientBuilder;
+    use crate::mock::text_message as reply;
+    use crate::types::common::Role;
+
+    #[tokio::test]
+    async fn test_send_appends_history() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/messages"))
+            .and(body_partial_json(serde_json::json!({
+                "system": "Be brief.",
+                "messages": [
+                    {"role": "user", "content": "Hi"},
+                    {"role": "assistant", "content": [{"type": "text", "text": "Hello"}]},
+                    {"role": "user", "content": "Again"}
+                ]
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(reply("Hello again")))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/v1/messages"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(reply("Hello")))
+            .mount(&server)
+            .await;
+
+        let client = ClientBuilder::new()
+            .api_key("test")
+            .base_url(server.uri())
+            .build();
+        let mut chat = Conversation::new(Model::ClaudeSonnet4_5, 100).system("Be brief.");
+        chat.send(&client, "Hi").await.unwrap();
+        chat.send(&client, "Again").await.unwrap();
+        assert_eq!(chat.messages.len(), 4);
+        assert_eq!(chat.messages[3].role, Role::Assistant);
+
+        let saved = serde_json::to_string(&chat).unwrap();
+        let restored: Conversation = serde_json::from_str(&saved).unwrap();
+        assert_eq!(restored.messages.len(), 4);
+        assert_eq!(restored.model, Model::ClaudeSonnet4_5);
+    }
+
+    #[tokio::test]
+    async fn test_budget_stops_sends() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/messages"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(reply("Hello")))
+            .expect(2)
+            .mount(&server)
+            .await;
+
+        let client = ClientBuilder::new()
+            .api_key("test")
+            .base_url(server.uri())
+            .build();
+        let budget = TokenBudget::new().max_output_tokens(2);
+        let mut chat = Conversation::new(Model::ClaudeSonnet4_5, 100).budget(budget.clone());
+        chat.send(&client, "Hi").await.unwrap();
+        chat.send(&client, "Again").await.unwrap();
+        let err = chat.send(&client, "Once more").await.unwrap_err();
+        assert!(matches!(err, Error::BudgetExceeded(_)));
+        assert_eq!(chat.messages.len(), 4);
+        assert_eq!(budget.used().output_tokens, 2);
+    }
+
+    #[tokio::test]
+    async fn test_failed_send_leaves_history_unchanged() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/messages"))
+            .respond_with(ResponseTemplate::new(400).set_body_json(serde_json::json!({
+                "type": "error",
+                "error": {"type": "invalid_request_error", "message": "bad"}
+            })))
+            .mount(&server)
+            .await;
+
+        let client = ClientBuilder::new()
+            .api_key("test")
+            .base_url(server.uri())
+            .build();
+        let mut chat = Conversation::new(Model::ClaudeSonnet4_5, 100);
+        assert!(chat.send(&client, "Hi").await.is_err());
+        assert!(chat.messages.is_empty());
+        assert!(chat.send_stream(&client, "Hi", |_| {}).await.is_err());
+        assert!(chat.messages.is_empty());
+    }
+}
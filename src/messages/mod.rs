@@ -1,15 +1,33 @@
+pub mod conversation;
 pub mod params;
 pub mod streaming;
+pub mod transcript;
 
-use reqwest::header::HeaderMap;
+use std::time::Duration;
+
+use futures::StreamExt;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
 use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use tokio_util::sync::CancellationToken;
+use tracing::{Instrument, warn};
 
-use crate::client::Client;
-use crate::error::Error;
+use crate::client::{Client, RequestOptions};
+use crate::error::{Error, is_retryable_error_type};
+use crate::response::ApiResponse;
+use crate::retry::{RetryClass, StreamRetryPolicy};
+use crate::telemetry::{UsageRecorder, chat_span};
+use crate::types::common::StopReason;
+use crate::types::container::{ContainerConfig, ContainerParam};
 use crate::types::message::Message;
+#[cfg(feature = "schemars")]
+use crate::types::metadata::{OutputConfig, OutputFormat};
 
 use self::params::{CountTokensParams, MessageCreateParams};
-use self::streaming::MessageStream;
+use self::streaming::{MessageStream, StreamEvent};
+
+/// Default cap on the number of requests made by `MessageService::create_until_done`.
+pub const DEFAULT_MAX_CONTINUATIONS: u32 = 10;
 
 /// Response from the count_tokens endpoint.
 #[derive(Debug, Clone, Deserialize)]
@@ -21,7 +39,10 @@ pub struct CountTokensResponse {
 ///
 /// The `anthropic-beta` header is set to a comma-joined list of beta feature flags
 /// when `betas` is non-empty. Returns `None` when both inputs are `None`/empty.
-fn build_headers(base: Option<&HeaderMap>, betas: Option<&Vec<String>>) -> Option<HeaderMap> {
+pub(crate) fn build_headers(
+    base: Option<&HeaderMap>,
+    betas: Option<&Vec<String>>,
+) -> Option<HeaderMap> {
     match (base, betas.filter(|b| !b.is_empty())) {
         (None, None) => None,
         (base, beta_list) => {
@@ -40,6 +61,15 @@ fn build_headers(base: Option<&HeaderMap>, betas: Option<&Vec<String>>) -> Optio
     }
 }
 
+/// Roughly how long the API may take to generate `max_tokens` tokens.
+///
+/// Uses the same estimate as the official SDKs, one hour per 128,000 tokens.
+/// `MessageService::create` compares it with the request timeout to decide
+/// whether a request should be streamed instead.
+pub fn expected_generation_time(max_tokens: u32) -> Duration {
+    Duration::from_secs(60 * 60) * max_tokens / 128_000
+}
+
 /// Service for the Messages API.
 pub struct MessageService<'a> {
     pub(crate) client: &'a Client,
@@ -47,25 +77,119 @@ pub struct MessageService<'a> {
 }
 
 impl<'a> MessageService<'a> {
-    pub(crate) fn new(client: &'a Client) -> Self {
+    /// Create a service bound to `client`. Equivalent to `client.messages()`.
+    pub fn new(client: &'a Client) -> Self {
         Self {
             client,
             extra_headers: None,
         }
     }
 
-    pub(crate) fn with_extra_headers(client: &'a Client, headers: HeaderMap) -> Self {
+    /// Create a service that sends `headers` with every request.
+    ///
+    /// Useful for wrappers that need to attach tracing, routing or beta headers
+    /// to every call without going through the client's default headers. Any
+    /// `betas` on the request params are merged into these headers.
+    pub fn with_extra_headers(client: &'a Client, headers: HeaderMap) -> Self {
         Self {
             client,
             extra_headers: Some(headers),
         }
     }
 
+    /// Send the headers in `options` with requests made through this service,
+    /// such as a tracing header for a single call:
+    ///
+    /// ```ignore
+    /// let options = RequestOptions::default().header("baggage", "session=abc");
+    /// let message = client.messages().with_options(options).create(params).await?;
+    /// ```
+    ///
+    /// They override headers of the same name set earlier, except that any
+    /// `betas` on the params still determine `anthropic-beta`.
+    pub fn with_options(mut self, options: RequestOptions) -> Self {
+        self.extra_headers = options.merge_into(self.extra_headers.take());
+        self
+    }
+
+    /// Fill in the client's default service tier and metadata where `params` has
+    /// none, expand the `effort` and `skills` shorthands, and check the result
+    /// and the client's token budget.
+    fn apply_defaults(&self, params: &mut MessageCreateParams) -> Result<(), Error> {
+        params.prepare();
+        params.validate()?;
+        if let Some(budget) = &self.client.inner.token_budget {
+            budget.check()?;
+        }
+        let config = &self.client.inner.config;
+        if params.service_tier.is_none() {
+            params.service_tier = config.default_service_tier.clone();
+        }
+        if params.metadata.is_none() {
+            params.metadata = config.default_metadata.clone();
+        }
+        Ok(())
+    }
+
+    /// Start a `chat` span for `params` and collect its response attributes.
+    fn usage_recorder(&self, params: &MessageCreateParams) -> UsageRecorder {
+        UsageRecorder::new(
+            chat_span(&params.model.to_string()),
+            self.client.inner.metrics_hook.clone(),
+        )
+        .with_budget(self.client.inner.token_budget.clone())
+    }
+
     /// Create a message (non-streaming).
     ///
     /// Sends a POST request to `/v1/messages` with `"stream": false` injected.
     /// Any `betas` set on `params` are merged into the `anthropic-beta` header.
     pub async fn create(&self, params: MessageCreateParams) -> Result<Message, Error> {
+        Ok(self.create_with_response(params).await?.0)
+    }
+
+    /// Like `create`, but fails with `Error::Cancelled` as soon as `token` is
+    /// cancelled, dropping the in-flight request.
+    ///
+    /// To keep the partial output of a cancelled generation, stream it and use
+    /// `MessageStream::accumulate_until_cancelled` instead.
+    pub async fn create_cancellable(
+        &self,
+        params: MessageCreateParams,
+        token: CancellationToken,
+    ) -> Result<Message, Error> {
+        tokio::select! {
+            biased;
+            _ = token.cancelled() => Err(Error::Cancelled),
+            result = self.create(params) => result,
+        }
+    }
+
+    /// Like `create`, but also returns the status, headers, request id and
+    /// timing of the HTTP response.
+    pub async fn create_with_response(
+        &self,
+        mut params: MessageCreateParams,
+    ) -> Result<(Message, ApiResponse), Error> {
+        self.apply_defaults(&mut params)?;
+        let config = &self.client.inner.config;
+        let expected = expected_generation_time(params.max_tokens);
+        if expected > config.timeout {
+            if config.auto_stream_large_requests {
+                let (stream, response) = self.stream_prepared(&params).await?;
+                let mut message = stream.accumulate().await?;
+                if config.strip_code_fences {
+                    message.strip_code_fences();
+                }
+                return Ok((message, response));
+            }
+            warn!(
+                max_tokens = params.max_tokens,
+                expected_secs = expected.as_secs(),
+                timeout_secs = config.timeout.as_secs(),
+                "non-streaming request may outlast the request timeout; consider streaming"
+            );
+        }
         let has_betas = params.betas.as_ref().is_some_and(|b| !b.is_empty())
             || !self.client.inner.config.beta_features.is_empty();
         let path = if has_betas {
@@ -78,7 +202,118 @@ impl<'a> MessageService<'a> {
         if let Some(obj) = body.as_object_mut() {
             obj.insert("stream".to_string(), serde_json::Value::Bool(false));
         }
-        self.client.post(path, &body, headers.as_ref()).await
+        let recorder = self.usage_recorder(&params);
+        let (mut message, response): (Message, _) = self
+            .client
+            .post_with_response(path, &body, headers.as_ref())
+            .instrument(recorder.span().clone())
+            .await?;
+        recorder
+            .with_request_id(response.request_id.clone())
+            .record_message(&message);
+        if self.client.inner.config.strip_code_fences {
+            message.strip_code_fences();
+        }
+        Ok((message, response))
+    }
+
+    /// Create a message and parse its text as JSON into `T`.
+    ///
+    /// The text blocks of the response are concatenated before parsing. Enable
+    /// `ClientBuilder::strip_code_fences` if the model tends to wrap its answer in
+    /// a Markdown code block, or use [`Message::parse_json`] for more lenient
+    /// parsing.
+    pub async fn create_typed<T: DeserializeOwned>(
+        &self,
+        params: MessageCreateParams,
+    ) -> Result<T, Error> {
+        let message = self.create(params).await?;
+        Ok(serde_json::from_str(&message.text())?)
+    }
+
+    /// Create a message constrained to the JSON schema of `T` and parse it.
+    ///
+    /// Sets `output_config.format` to the strict schema from
+    /// [`OutputFormat::from_schema`], replacing any format already set, then
+    /// parses the response like `create_typed`.
+    ///
+    /// ```ignore
+    /// #[derive(Deserialize, JsonSchema)]
+    /// struct Sentiment { label: String, score: f64 }
+    ///
+    /// let sentiment: Sentiment = client.messages().create_structured(params).await?;
+    /// ```
+    #[cfg(feature = "schemars")]
+    pub async fn create_structured<T: schemars::JsonSchema + DeserializeOwned>(
+        &self,
+        mut params: MessageCreateParams,
+    ) -> Result<T, Error> {
+        let format = OutputFormat::from_schema::<T>();
+        params
+            .output_config
+            .get_or_insert_with(OutputConfig::default)
+            .format = Some(format);
+        self.create_typed(params).await
+    }
+
+    /// Create a message, transparently continuing any `pause_turn` responses.
+    ///
+    /// Long-running server tool turns (web search, code execution) may stop with
+    /// `StopReason::PauseTurn`. This resends the conversation with the partial
+    /// assistant turn appended until the model finishes, making at most
+    /// [`DEFAULT_MAX_CONTINUATIONS`] requests.
+    pub async fn create_until_done(&self, params: MessageCreateParams) -> Result<Message, Error> {
+        self.create_until_done_with_limit(params, DEFAULT_MAX_CONTINUATIONS)
+            .await
+    }
+
+    /// Like `create_until_done`, but with an explicit cap on the number of requests.
+    ///
+    /// Each continuation runs in the container the previous response reported,
+    /// so code execution state carries over. The returned message's `content`
+    /// holds the blocks from every request in order, and its `usage` counts are
+    /// summed across requests. If the cap is reached while still paused, the
+    /// message is returned with `stop_reason == Some(StopReason::PauseTurn)`.
+    pub async fn create_until_done_with_limit(
+        &self,
+        params: MessageCreateParams,
+        max_requests: u32,
+    ) -> Result<Message, Error> {
+        let base_messages = params.messages.clone();
+        let mut result = self.create(params.clone()).await?;
+
+        for _ in 1..max_requests.max(1) {
+            if result.stop_reason != Some(StopReason::PauseTurn) {
+                break;
+            }
+
+            let mut messages = base_messages.clone();
+            messages.push(result.to_param());
+            let mut next_params = params.clone();
+            next_params.messages = messages;
+            if let Some(container) = &result.container {
+                next_params.container = Some(match next_params.container {
+                    Some(ContainerParam::Config(config)) => {
+                        ContainerParam::Config(ContainerConfig {
+                            id: Some(container.id.clone()),
+                            ..config
+                        })
+                    }
+                    _ => ContainerParam::Id(container.id.clone()),
+                });
+            }
+
+            let next = self.create(next_params).await?;
+            result.content.extend(next.content);
+            result.usage.add(&next.usage);
+            result.stop_reason = next.stop_reason;
+            result.stop_sequence = next.stop_sequence;
+            if next.container.is_some() {
+                result.container = next.container;
+            }
+        }
+
+        Ok(result)
     }
 
     /// Create a streaming message.
@@ -87,6 +322,24 @@ impl<'a> MessageService<'a> {
     /// Returns a `MessageStream` that yields `StreamEvent` items.
     /// Any `betas` set on `params` are merged into the `anthropic-beta` header.
     pub async fn create_stream(&self, params: MessageCreateParams) -> Result<MessageStream, Error> {
+        Ok(self.create_stream_with_response(params).await?.0)
+    }
+
+    /// Like `create_stream`, but also returns the status, headers, request id
+    /// and timing of the HTTP response the stream is read from.
+    pub async fn create_stream_with_response(
+        &self,
+        mut params: MessageCreateParams,
+    ) -> Result<(MessageStream, ApiResponse), Error> {
+        self.apply_defaults(&mut params)?;
+        self.stream_prepared(&params).await
+    }
+
+    /// Open a stream for `params` that `apply_defaults` has already been run on.
+    async fn stream_prepared(
+        &self,
+        params: &MessageCreateParams,
+    ) -> Result<(MessageStream, ApiResponse), Error> {
         let has_betas = params.betas.as_ref().is_some_and(|b| !b.is_empty())
             || !self.client.inner.config.beta_features.is_empty();
         let path = if has_betas {
@@ -95,12 +348,108 @@ impl<'a> MessageService<'a> {
             "messages"
         };
         let headers = build_headers(self.extra_headers.as_ref(), params.betas.as_ref());
-        let response = self
-            .client
-            .execute_streaming(path, &params, headers.as_ref())
+        let recorder = self.usage_recorder(params);
+        let (stream, response) = self
+            .open_stream(path, params, headers.as_ref())
+            .instrument(recorder.span().clone())
             .await?;
+        let recorder = recorder.with_request_id(response.request_id.clone());
+        Ok((recorder.wrap(stream), response))
+    }
+
+    /// Start a stream, retrying failures that happen before `message_start`
+    /// unless the client's `StreamRetryPolicy` forbids it.
+    ///
+    /// A retry waits for the interval from an SSE `retry:` field when the
+    /// server sent one, and reconnects with `Last-Event-ID` set to the last
+    /// SSE `id:` received.
+    async fn open_stream(
+        &self,
+        path: &str,
+        params: &MessageCreateParams,
+        headers: Option<&HeaderMap>,
+    ) -> Result<(MessageStream, ApiResponse), Error> {
+        let policy = &self.client.inner.retry_policy;
+        let started = tokio::time::Instant::now();
+
+        let mut attempt = 0;
+        let mut resume_headers: Option<HeaderMap> = None;
+        loop {
+            let response = self
+                .client
+                .execute_streaming(path, params, resume_headers.as_ref().or(headers))
+                .await?;
+            let meta = ApiResponse::new(&response, started.elapsed());
+            let mut stream = MessageStream::new(response);
+            if policy.stream == StreamRetryPolicy::Never {
+                return Ok((stream, meta));
+            }
 
-        Ok(MessageStream::new(response))
+            // Failures before `message_start` have not generated anything yet, so
+            // they are safe to retry. Peek at the first event to find out.
+            let first = stream.next().await;
+            let retryable = match &first {
+                Some(Ok(StreamEvent::Error { error })) => policy.should_retry(
+                    &Error::StreamError(format!(
+                        "Stream error: {}: {}",
+                        error.error_type, error.message
+                    )),
+                    attempt,
+                    is_retryable_error_type(&error.error_type),
+                ),
+                Some(Err(e)) => policy.should_retry(e, attempt, e.is_retryable()),
+                _ => false,
+            };
+
+            let class = match &first {
+                Some(Ok(StreamEvent::Error { error }))
+                    if error.error_type == "overloaded_error" =>
+                {
+                    RetryClass::Overloaded
+                }
+                Some(Err(e)) => e.retry_class(),
+                _ => RetryClass::Standard,
+            };
+            let delay = policy.delay_for_class(class, attempt, stream.server_retry());
+            if retryable && attempt < policy.max_retries && policy.allows_retry(started, delay) {
+                warn!(
+                    attempt,
+                    delay_ms = delay.as_millis() as u64,
+                    "retrying stream that failed before message_start"
+                );
+                if let Some(id) = stream
+                    .last_event_id()
+                    .and_then(|id| HeaderValue::from_str(&id).ok())
+                {
+                    let mut map = headers.cloned().unwrap_or_default();
+                    map.insert(HeaderName::from_static("last-event-id"), id);
+                    resume_headers = Some(map);
+                }
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+                continue;
+            }
+
+            let stream = match first {
+                Some(event) => {
+                    stream.rewrap(|stream| futures::stream::once(async { event }).chain(stream))
+                }
+                None => stream,
+            };
+            return Ok((stream, meta));
+        }
+    }
+
+    /// Create a streaming message and accumulate it into a final `Message`.
+    ///
+    /// Equivalent to `create_stream(params).await?.accumulate().await`. Streaming
+    /// avoids the HTTP timeouts that long non-streaming requests can hit, while
+    /// still returning a complete `Message`.
+    pub async fn create_stream_accumulated(
+        &self,
+        params: MessageCreateParams,
+    ) -> Result<Message, Error> {
+        self.create_stream(params).await?.accumulate().await
     }
 
     /// Count the tokens in a set of messages.
@@ -118,11 +467,32 @@ impl<'a> MessageService<'a> {
             )
             .await
     }
+
+    /// Count the input tokens of the request `params` would make.
+    ///
+    /// Builds `CountTokensParams` from `params`, so a request can be checked
+    /// before it is sent. Any `betas` on `params` are sent as well.
+    pub async fn count_tokens_for(
+        &self,
+        params: &MessageCreateParams,
+    ) -> Result<CountTokensResponse, Error> {
+        let headers = build_headers(self.extra_headers.as_ref(), params.betas.as_ref());
+        self.client
+            .post(
+                "messages/count_tokens",
+                &CountTokensParams::from(params),
+                headers.as_ref(),
+            )
+            .await
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::time::Duration;
+
     use crate::client::ClientBuilder;
+    use crate::messages::expected_generation_time;
     use crate::messages::params::MessageCreateParams;
     use crate::types::message::MessageParam;
     use crate::types::model::Model;
@@ -180,4 +550,630 @@ mod tests {
         let params = base_params();
         assert_eq!(resolve_create_path(&params, &client), "messages");
     }
+
+    const SSE_BODY: &str = concat!(
+        "event: message_start\n",
+        "data: {\"message\":{\"id\":\"msg_1\",\"type\":\"message\",\"role\":\"assistant\",\"content\":[],\"model\":\"claude-opus-4-6\",\"stop_reason\":null,\"usage\":{\"input_tokens\":3,\"output_tokens\":0}}}\n\n",
+        "event: content_block_start\n",
+        "data: {\"index\":0,\"content_block\":{\"type\":\"text\",\"text\":\"\"}}\n\n",
+        "event: content_block_delta\n",
+        "data: {\"index\":0,\"delta\":{\"type\":\"text_delta\",\"text\":\"Hello\"}}\n\n",
+        "event: content_block_stop\n",
+        "data: {\"index\":0}\n\n",
+        "event: message_delta\n",
+        "data: {\"delta\":{\"stop_reason\":\"end_turn\",\"stop_sequence\":null},\"usage\":{\"output_tokens\":1}}\n\n",
+        "event: message_stop\n",
+        "data: {}\n\n",
+    );
+
+    const OVERLOADED_SSE: &str = "event: error\ndata: {\"error\":{\"type\":\"overloaded_error\",\"message\":\"Overloaded\"}}\n\n";
+
+    #[tokio::test]
+    async fn test_create_stream_accumulated() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/messages"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(SSE_BODY, "text/event-stream"))
+            .mount(&server)
+            .await;
+
+        let client = ClientBuilder::new()
+            .api_key("test")
+            .base_url(server.uri())
+            .build();
+        let message = client
+            .messages()
+            .create_stream_accumulated(base_params())
+            .await
+            .unwrap();
+
+        assert_eq!(message.id, "msg_1");
+        assert_eq!(message.usage.output_tokens, 1);
+        match &message.content[0] {
+            crate::types::content::ContentBlock::Text(t) => assert_eq!(t.text, "Hello"),
+            _ => panic!("Expected Text block"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_stream_with_response() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/messages"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("request-id", "req_stream")
+                    .set_body_raw(SSE_BODY, "text/event-stream"),
+            )
+            .mount(&server)
+            .await;
+
+        let client = ClientBuilder::new()
+            .api_key("test")
+            .base_url(server.uri())
+            .build();
+        let (stream, response) = client
+            .messages()
+            .create_stream_with_response(base_params())
+            .await
+            .unwrap();
+        assert_eq!(response.status, 200);
+        assert_eq!(response.request_id.as_deref(), Some("req_stream"));
+
+        let message = stream.accumulate().await.unwrap();
+        assert_eq!(message.id, "msg_1");
+    }
+
+    #[test]
+    fn test_expected_generation_time() {
+        assert_eq!(expected_generation_time(128_000), Duration::from_secs(3600));
+        assert_eq!(
+            expected_generation_time(1_024),
+            Duration::from_millis(28_800)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_create_streams_large_requests() {
+        use wiremock::matchers::{body_partial_json, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/messages"))
+            .and(body_partial_json(serde_json::json!({"stream": true})))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(SSE_BODY, "text/event-stream"))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        // 1024 tokens are expected to take longer than the 5 second timeout.
+        let client = ClientBuilder::new()
+            .api_key("test")
+            .base_url(server.uri())
+            .timeout(Duration::from_secs(5))
+            .auto_stream_large_requests(true)
+            .build();
+        let params = MessageCreateParams {
+            max_tokens: 1_024,
+            ..base_params()
+        };
+        let message = client.messages().create(params).await.unwrap();
+        assert_eq!(message.id, "msg_1");
+        assert_eq!(message.usage.output_tokens, 1);
+    }
+
+    fn response_json(text: &str, stop_reason: &str) -> crate::types::message::Message {
+        let mut message = crate::mock::text_message(text);
+        message.stop_reason = serde_json::from_value(stop_reason.into()).unwrap();
+        message
+    }
+
+    #[tokio::test]
+    async fn test_create_typed_strips_code_fences() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/messages"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(response_json("```json\n{\"answer\": 42}\n```", "end_turn")),
+            )
+            .mount(&server)
+            .await;
+
+        let plain = ClientBuilder::new()
+            .api_key("test")
+            .base_url(server.uri())
+            .build();
+        let result: Result<serde_json::Value, _> =
+            plain.messages().create_typed(base_params()).await;
+        assert!(matches!(result, Err(crate::error::Error::Serialization(_))));
+
+        let client = ClientBuilder::new()
+            .api_key("test")
+            .base_url(server.uri())
+            .strip_code_fences(true)
+            .build();
+        let value: serde_json::Value = client.messages().create_typed(base_params()).await.unwrap();
+        assert_eq!(value["answer"], 42);
+    }
+
+    #[cfg(feature = "schemars")]
+    #[tokio::test]
+    async fn test_create_structured_sends_schema() {
+        use wiremock::matchers::{body_partial_json, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        #[derive(serde::Deserialize, schemars::JsonSchema)]
+        struct Sentiment {
+            label: String,
+            score: f64,
+        }
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/messages"))
+            .and(body_partial_json(serde_json::json!({
+                "output_config": {"format": {"type": "json_schema", "name": "Sentiment", "strict": true}}
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(response_json(
+                "{\"label\": \"positive\", \"score\": 0.8}",
+                "end_turn",
+            )))
+            .mount(&server)
+            .await;
+
+        let client = ClientBuilder::new()
+            .api_key("test")
+            .base_url(server.uri())
+            .build();
+        let sentiment: Sentiment = client
+            .messages()
+            .create_structured(base_params())
+            .await
+            .unwrap();
+        assert_eq!(sentiment.label, "positive");
+        assert_eq!(sentiment.score, 0.8);
+    }
+
+    #[tokio::test]
+    async fn test_count_tokens_for() {
+        use wiremock::matchers::{body_json, header, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/messages/count_tokens"))
+            .and(header("anthropic-beta", "beta-a"))
+            .and(body_json(serde_json::json!({
+                "model": "claude-opus-4-6",
+                "messages": [{"role": "user", "content": "hi"}]
+            })))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({"input_tokens": 8})),
+            )
+            .mount(&server)
+            .await;
+
+        let client = ClientBuilder::new()
+            .api_key("test")
+            .base_url(server.uri())
+            .build();
+        let count = client
+            .messages()
+            .count_tokens_for(&params_with_betas(vec!["beta-a".to_string()]))
+            .await
+            .unwrap();
+        assert_eq!(count.input_tokens, 8);
+    }
+
+    #[tokio::test]
+    async fn test_create_sends_extra_fields() {
+        use wiremock::matchers::{body_partial_json, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/messages"))
+            .and(body_partial_json(serde_json::json!({
+                "max_tokens": 10,
+                "stream": false,
+                "unreleased_option": "on"
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(response_json("Hi", "end_turn")))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = ClientBuilder::new()
+            .api_key("test")
+            .base_url(server.uri())
+            .build();
+        let mut params = base_params();
+        params.extra = Some(
+            serde_json::json!({"unreleased_option": "on", "stream": true})
+                .as_object()
+                .cloned()
+                .unwrap(),
+        );
+        client.messages().create(params).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_create_with_options_headers() {
+        use wiremock::matchers::{header, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        use super::MessageService;
+        use crate::client::RequestOptions;
+        use reqwest::header::HeaderMap;
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/messages"))
+            .and(header("baggage", "session=abc"))
+            .and(header("x-tenant", "beta-corp"))
+            .and(header("x-route", "eu"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(response_json("Hi", "end_turn")))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = ClientBuilder::new()
+            .api_key("test")
+            .base_url(server.uri())
+            .build();
+        let mut headers = HeaderMap::new();
+        headers.insert("x-tenant", "acme".parse().unwrap());
+        headers.insert("x-route", "eu".parse().unwrap());
+        let options = RequestOptions::default()
+            .header("baggage", "session=abc")
+            .header("x-tenant", "beta-corp");
+        MessageService::with_extra_headers(&client, headers)
+            .with_options(options)
+            .create(base_params())
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_create_cancellable() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        use crate::error::Error;
+        use tokio_util::sync::CancellationToken;
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/messages"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(response_json("Hi", "end_turn"))
+                    .set_delay(std::time::Duration::from_secs(30)),
+            )
+            .mount(&server)
+            .await;
+
+        let client = ClientBuilder::new()
+            .api_key("test")
+            .base_url(server.uri())
+            .build();
+        let token = CancellationToken::new();
+        let stop = token.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+            stop.cancel();
+        });
+        let err = client
+            .messages()
+            .create_cancellable(base_params(), token)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::Cancelled));
+    }
+
+    #[tokio::test]
+    async fn test_create_with_response() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/messages"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("request-id", "req_abc")
+                    .set_body_json(response_json("Hi", "end_turn")),
+            )
+            .mount(&server)
+            .await;
+
+        let client = ClientBuilder::new()
+            .api_key("test")
+            .base_url(server.uri())
+            .build();
+        let (message, response) = client
+            .messages()
+            .create_with_response(base_params())
+            .await
+            .unwrap();
+        assert_eq!(message.id, "msg_mock");
+        assert_eq!(response.status, 200);
+        assert_eq!(response.request_id.as_deref(), Some("req_abc"));
+        assert_eq!(response.timing.map(|t| t.attempt), Some(0));
+    }
+
+    #[tokio::test]
+    async fn test_create_until_done_continues_pause_turn() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/messages"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(response_json("Part 1", "pause_turn")),
+            )
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/v1/messages"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(response_json("Part 2", "end_turn")),
+            )
+            .mount(&server)
+            .await;
+
+        let client = ClientBuilder::new()
+            .api_key("test")
+            .base_url(server.uri())
+            .build();
+        let message = client
+            .messages()
+            .create_until_done(base_params())
+            .await
+            .unwrap();
+
+        assert_eq!(message.stop_reason, Some(crate::types::StopReason::EndTurn));
+        assert_eq!(message.content.len(), 2);
+        assert_eq!(message.usage.output_tokens, 4);
+
+        let requests = server.received_requests().await.unwrap();
+        assert_eq!(requests.len(), 2);
+        let body: serde_json::Value = serde_json::from_slice(&requests[1].body).unwrap();
+        let messages = body["messages"].as_array().unwrap();
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[1]["role"], "assistant");
+        assert_eq!(messages[1]["content"][0]["text"], "Part 1");
+        assert!(body.get("container").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_create_until_done_reuses_container() {
+        use wiremock::matchers::{body_partial_json, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let mut paused = response_json("Running...", "pause_turn");
+        paused.container = Some(crate::types::message::ContainerInfo {
+            id: "container_1".to_string(),
+            expires_at: None,
+        });
+        paused.usage.cache_read_input_tokens = Some(100);
+        let mut done = response_json("Done.", "end_turn");
+        done.usage.cache_read_input_tokens = Some(50);
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/messages"))
+            .and(body_partial_json(
+                serde_json::json!({"container": "container_1"}),
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(&done))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/v1/messages"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(&paused))
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+
+        let client = ClientBuilder::new()
+            .api_key("test")
+            .base_url(server.uri())
+            .build();
+        let message = client
+            .messages()
+            .create_until_done(base_params())
+            .await
+            .unwrap();
+
+        assert_eq!(message.stop_reason, Some(crate::types::StopReason::EndTurn));
+        assert_eq!(message.usage.cache_read_input_tokens, Some(150));
+        assert_eq!(
+            message.container.map(|c| c.id).as_deref(),
+            Some("container_1")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_create_until_done_respects_limit() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/messages"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(response_json("...", "pause_turn")),
+            )
+            .expect(3)
+            .mount(&server)
+            .await;
+
+        let client = ClientBuilder::new()
+            .api_key("test")
+            .base_url(server.uri())
+            .build();
+        let message = client
+            .messages()
+            .create_until_done_with_limit(base_params(), 3)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            message.stop_reason,
+            Some(crate::types::StopReason::PauseTurn)
+        );
+        assert_eq!(message.content.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_create_stream_retries_error_before_message_start() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/messages"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_raw(OVERLOADED_SSE, "text/event-stream"),
+            )
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/v1/messages"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(SSE_BODY, "text/event-stream"))
+            .mount(&server)
+            .await;
+
+        let client = ClientBuilder::new()
+            .api_key("test")
+            .base_url(server.uri())
+            .build();
+        let message = client
+            .messages()
+            .create_stream_accumulated(base_params())
+            .await
+            .unwrap();
+
+        assert_eq!(message.id, "msg_1");
+        assert_eq!(server.received_requests().await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_create_stream_retry_honors_sse_id_and_retry() {
+        use wiremock::matchers::{header, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        let overloaded = format!("retry: 5\nid: evt_7\n{OVERLOADED_SSE}");
+        Mock::given(method("POST"))
+            .and(path("/v1/messages"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(overloaded, "text/event-stream"))
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/v1/messages"))
+            .and(header("last-event-id", "evt_7"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(SSE_BODY, "text/event-stream"))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = ClientBuilder::new()
+            .api_key("test")
+            .base_url(server.uri())
+            .build();
+        let started = std::time::Instant::now();
+        let message = client
+            .messages()
+            .create_stream_accumulated(base_params())
+            .await
+            .unwrap();
+
+        assert_eq!(message.id, "msg_1");
+        // The server's 5ms retry interval replaces the default backoff.
+        assert!(started.elapsed() < std::time::Duration::from_millis(300));
+    }
+
+    #[tokio::test]
+    async fn test_create_stream_never_retry_policy() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        use crate::retry::StreamRetryPolicy;
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/messages"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_raw(OVERLOADED_SSE, "text/event-stream"),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = ClientBuilder::new()
+            .api_key("test")
+            .base_url(server.uri())
+            .stream_retry_policy(StreamRetryPolicy::Never)
+            .build();
+        let result = client
+            .messages()
+            .create_stream_accumulated(base_params())
+            .await;
+
+        assert!(matches!(result, Err(crate::error::Error::StreamError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_client_defaults_applied_unless_overridden() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        use crate::types::metadata::{Metadata, ServiceTier};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/messages"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(response_json("ok", "end_turn")))
+            .mount(&server)
+            .await;
+
+        let client = ClientBuilder::new()
+            .api_key("test")
+            .base_url(server.uri())
+            .default_service_tier(ServiceTier::StandardOnly)
+            .default_metadata(Metadata {
+                user_id: Some("deploy-1".to_string()),
+            })
+            .build();
+
+        client.messages().create(base_params()).await.unwrap();
+        let mut params = base_params();
+        params.service_tier = Some(ServiceTier::Auto);
+        client.messages().create(params).await.unwrap();
+
+        let requests = server.received_requests().await.unwrap();
+        let first: serde_json::Value = serde_json::from_slice(&requests[0].body).unwrap();
+        assert_eq!(first["service_tier"], "standard_only");
+        assert_eq!(first["metadata"]["user_id"], "deploy-1");
+        let second: serde_json::Value = serde_json::from_slice(&requests[1].body).unwrap();
+        assert_eq!(second["service_tier"], "auto");
+    }
 }
@@ -1,14 +1,25 @@
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
+use crate::beta::{
+    BETA_CONTEXT_1M_2025_08_07, BETA_EFFORT_2025_11_24, BETA_FILES_API_2025_04_14,
+    BETA_INTERLEAVED_THINKING_2025_05_14, BETA_SKILLS_2025_10_02,
+};
+use crate::error::Error;
+use crate::types::common::ExtraFields;
+use crate::types::container::{ContainerConfig, ContainerParam, SkillReference};
 use crate::types::message::{MessageParam, SystemContent};
 use crate::types::metadata::{
     CacheControl, ContextManagementConfig, InferenceGeo, Metadata, OutputConfig, ReasoningEffort,
     ServiceTier,
 };
-use crate::types::model::Model;
+use crate::types::model::{Model, ModelSpec};
 use crate::types::thinking::ThinkingConfig;
 use crate::types::tool::{ToolChoice, ToolDefinition};
 
+/// The `max_tokens` used when the builder is given none and the model's
+/// output limit is unknown.
+pub const FALLBACK_MAX_TOKENS: u32 = 4_096;
+
 /// Parameters for creating a message.
 ///
 /// Use the builder pattern via `MessageCreateParams::builder()`:
@@ -20,18 +31,32 @@ use crate::types::tool::{ToolChoice, ToolDefinition};
 ///     .build();
 /// ```
 ///
+/// Messages and tools can also be added one at a time with `.message(...)` and
+/// `.tool(...)`.
+///
 /// The `stream` field is not exposed; it is injected internally by
-/// `create()` (false) and `create_stream()` (true).
-#[derive(Debug, Clone, Serialize, bon::Builder)]
+/// `create()` (false) and `create_stream()` (true). When deserializing a saved
+/// request body, a `stream` key is ignored.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, bon::Builder)]
 pub struct MessageCreateParams {
-    pub model: Model,
+    #[builder(field)]
     pub messages: Vec<MessageParam>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub system: Option<SystemContent>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(field)]
     pub tools: Option<Vec<ToolDefinition>>,
+    /// Beta feature flags sent as the `anthropic-beta` header.
+    /// Not serialized into the JSON body — extracted by the MessageService.
+    #[serde(skip)]
+    #[builder(field)]
+    pub betas: Option<Vec<String>>,
+    pub model: Model,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system: Option<SystemContent>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub metadata: Option<Metadata>,
+    /// Defaults in the builder to the model's `max_output_tokens`, or
+    /// [`FALLBACK_MAX_TOKENS`] for models without a known limit.
+    #[builder(default = model.max_output_tokens().unwrap_or(FALLBACK_MAX_TOKENS))]
     pub max_tokens: u32,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub thinking: Option<ThinkingConfig>,
@@ -49,23 +74,194 @@ pub struct MessageCreateParams {
     pub top_k: Option<u32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub top_p: Option<f64>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Shorthand for `output_config.effort`. It is moved there, and the
+    /// `effort-2025-11-24` beta enabled, when the request is sent.
+    #[serde(skip)]
     pub effort: Option<ReasoningEffort>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub inference_geo: Option<InferenceGeo>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub context_management: Option<ContextManagementConfig>,
-    /// Container identifier for code execution reuse across requests.
+    /// Container to reuse across requests, optionally with skills to load.
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub container: Option<String>,
+    #[builder(into)]
+    pub container: Option<ContainerParam>,
+    /// Shorthand for `container.skills`. The skills are moved there, and the
+    /// skills beta enabled, when the request is sent.
+    #[serde(skip)]
+    pub skills: Option<Vec<SkillReference>>,
     /// Top-level cache control that automatically applies a cache_control marker
     /// to the last cacheable block in the request.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub cache_control: Option<CacheControl>,
-    /// Beta feature flags sent as the `anthropic-beta` header.
-    /// Not serialized into the JSON body — extracted by the MessageService.
-    #[serde(skip)]
-    pub betas: Option<Vec<String>>,
+    /// Additional top-level body fields, for API parameters this type does
+    /// not model yet. Keys should not repeat a typed field.
+    #[serde(flatten, default, deserialize_with = "deserialize_extra")]
+    pub extra: Option<ExtraFields>,
+}
+
+/// Collect unrecognized keys into `extra`, dropping the `stream` flag that the
+/// service sets itself.
+fn deserialize_extra<'de, D>(deserializer: D) -> Result<Option<ExtraFields>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let mut extra = ExtraFields::deserialize(deserializer)?;
+    extra.remove("stream");
+    Ok((!extra.is_empty()).then_some(extra))
+}
+
+impl<S: message_create_params_builder::State> MessageCreateParamsBuilder<S> {
+    /// Set the conversation, replacing any messages added so far.
+    pub fn messages(mut self, messages: Vec<MessageParam>) -> Self {
+        self.messages = messages;
+        self
+    }
+
+    /// Append a message to the conversation.
+    pub fn message(mut self, message: MessageParam) -> Self {
+        self.messages.push(message);
+        self
+    }
+
+    /// Set the tools, replacing any tools added so far.
+    pub fn tools(mut self, tools: Vec<ToolDefinition>) -> Self {
+        self.tools = Some(tools);
+        self
+    }
+
+    /// Append a tool definition.
+    pub fn tool(mut self, tool: impl Into<ToolDefinition>) -> Self {
+        self.tools.get_or_insert_with(Vec::new).push(tool.into());
+        self
+    }
+
+    /// Set the beta flags, replacing any added so far.
+    pub fn betas(mut self, betas: Vec<String>) -> Self {
+        self.betas = Some(betas);
+        self
+    }
+
+    /// Enable a beta flag, unless it is already enabled.
+    pub fn beta(mut self, beta: impl Into<String>) -> Self {
+        let beta = beta.into();
+        let betas = self.betas.get_or_insert_with(Vec::new);
+        if !betas.contains(&beta) {
+            betas.push(beta);
+        }
+        self
+    }
+
+    /// Set the model from a parsed `ModelSpec`, enabling the 1M-token context
+    /// beta when the spec has the `[1m]` suffix.
+    ///
+    /// Sending the request fails with `Error::Validation` if the model does
+    /// not support the extended context window.
+    pub fn model_spec(
+        self,
+        spec: ModelSpec,
+    ) -> MessageCreateParamsBuilder<message_create_params_builder::SetModel<S>>
+    where
+        S::Model: message_create_params_builder::IsUnset,
+    {
+        let builder = if spec.extended_context {
+            self.beta(BETA_CONTEXT_1M_2025_08_07)
+        } else {
+            self
+        };
+        builder.model(spec.model)
+    }
+
+    /// Enable extended thinking with `budget_tokens`, interleaved between tool
+    /// calls, and the `interleaved-thinking-2025-05-14` beta it requires.
+    pub fn interleaved_thinking(
+        self,
+        budget_tokens: u32,
+    ) -> MessageCreateParamsBuilder<message_create_params_builder::SetThinking<S>>
+    where
+        S::Thinking: message_create_params_builder::IsUnset,
+    {
+        self.beta(BETA_INTERLEAVED_THINKING_2025_05_14)
+            .thinking(ThinkingConfig::enabled(budget_tokens))
+    }
+}
+
+impl MessageCreateParams {
+    /// Move the `effort` and `skills` shorthands to where the API expects them
+    /// and enable the betas they need, including the Files API beta when a
+    /// message references an uploaded file.
+    ///
+    /// A thinking config is dropped for models that don't support extended
+    /// thinking, which would otherwise reject the request.
+    pub(crate) fn prepare(&mut self) {
+        if self.thinking.is_some() && !self.model.supports_extended_thinking() {
+            tracing::debug!(model = %self.model, "dropping thinking config unsupported by model");
+            self.thinking = None;
+        }
+
+        if let Some(effort) = self.effort.take() {
+            self.output_config
+                .get_or_insert_with(OutputConfig::default)
+                .effort = Some(effort);
+        }
+        if self
+            .output_config
+            .as_ref()
+            .is_some_and(|c| c.effort.is_some())
+        {
+            self.add_beta(BETA_EFFORT_2025_11_24);
+        }
+
+        if let Some(skills) = self.skills.take() {
+            let mut config = match self.container.take() {
+                Some(ContainerParam::Config(config)) => config,
+                Some(ContainerParam::Id(id)) => ContainerConfig {
+                    id: Some(id),
+                    ..Default::default()
+                },
+                None => ContainerConfig::default(),
+            };
+            config.skills.get_or_insert_with(Vec::new).extend(skills);
+            self.container = Some(ContainerParam::Config(config));
+        }
+        if matches!(&self.container, Some(ContainerParam::Config(c)) if c.skills.is_some()) {
+            self.add_beta(BETA_SKILLS_2025_10_02);
+        }
+
+        if self.messages.iter().any(MessageParam::references_file) {
+            self.add_beta(BETA_FILES_API_2025_04_14);
+        }
+    }
+
+    /// Reject combinations the API is known to refuse, before sending them.
+    ///
+    /// The 1M-token context beta is only accepted by models that support it;
+    /// unknown models are let through. Each tool is checked with
+    /// `ToolDefinition::validate`.
+    pub(crate) fn validate(&self) -> Result<(), Error> {
+        for tool in self.tools.iter().flatten() {
+            tool.validate()?;
+        }
+        let wants_1m = self
+            .betas
+            .iter()
+            .flatten()
+            .any(|b| b == BETA_CONTEXT_1M_2025_08_07);
+        if wants_1m && !matches!(self.model, Model::Other(_)) && !self.model.supports_1m_context() {
+            return Err(Error::Validation(format!(
+                "{} does not support the 1M-token context window",
+                self.model
+            )));
+        }
+        Ok(())
+    }
+
+    fn add_beta(&mut self, beta: &str) {
+        let betas = self.betas.get_or_insert_with(Vec::new);
+        if !betas.iter().any(|b| b == beta) {
+            betas.push(beta.to_string());
+        }
+    }
 }
 
 /// Parameters for counting tokens.
@@ -77,7 +273,7 @@ pub struct MessageCreateParams {
 ///     .messages(vec![MessageParam::user("Hello")])
 ///     .build();
 /// ```
-#[derive(Debug, Clone, Serialize, bon::Builder)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, bon::Builder)]
 pub struct CountTokensParams {
     pub model: Model,
     pub messages: Vec<MessageParam>,
@@ -91,6 +287,20 @@ pub struct CountTokensParams {
     pub thinking: Option<ThinkingConfig>,
 }
 
+impl From<&MessageCreateParams> for CountTokensParams {
+    /// Copy the fields that affect the input token count.
+    fn from(params: &MessageCreateParams) -> Self {
+        Self {
+            model: params.model.clone(),
+            messages: params.messages.clone(),
+            system: params.system.clone(),
+            tools: params.tools.clone(),
+            tool_choice: params.tool_choice.clone(),
+            thinking: params.thinking.clone(),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -118,6 +328,21 @@ mod tests {
         assert!(!json.contains("stream"));
     }
 
+    #[test]
+    fn test_max_tokens_defaults_to_model_limit() {
+        let params = MessageCreateParams::builder()
+            .model(Model::ClaudeOpus4_6)
+            .message(MessageParam::user("Hello"))
+            .build();
+        assert_eq!(params.max_tokens, 128_000);
+
+        let params = MessageCreateParams::builder()
+            .model(Model::Other("claude-future".to_string()))
+            .message(MessageParam::user("Hello"))
+            .build();
+        assert_eq!(params.max_tokens, FALLBACK_MAX_TOKENS);
+    }
+
     #[test]
     fn test_message_create_params_with_optionals() {
         let params = MessageCreateParams::builder()
@@ -145,6 +370,201 @@ mod tests {
         assert!(json.contains(r#""service_tier":"auto""#));
     }
 
+    #[test]
+    fn test_prepare_moves_effort_into_output_config() {
+        let mut params = MessageCreateParams::builder()
+            .model(Model::ClaudeOpus4_5)
+            .max_tokens(100)
+            .messages(vec![MessageParam::user("Hi")])
+            .effort(ReasoningEffort::Low)
+            .betas(vec!["other-beta".to_string()])
+            .build();
+        assert!(!serde_json::to_string(&params).unwrap().contains("effort"));
+
+        params.prepare();
+        params.prepare();
+        let json = serde_json::to_string(&params).unwrap();
+        assert!(json.contains(r#""output_config":{"effort":"low"}"#));
+        assert_eq!(
+            params.betas.unwrap(),
+            vec!["other-beta".to_string(), BETA_EFFORT_2025_11_24.to_string()]
+        );
+    }
+
+    #[test]
+    fn test_prepare_strips_thinking_for_unsupported_models() {
+        let params = |model| {
+            MessageCreateParams::builder()
+                .model(model)
+                .max_tokens(2048)
+                .messages(vec![MessageParam::user("Hi")])
+                .thinking(ThinkingConfig::Enabled {
+                    budget_tokens: 1024,
+                    display: None,
+                })
+                .build()
+        };
+
+        let mut haiku = params(Model::Claude3_5HaikuLatest);
+        haiku.prepare();
+        assert!(haiku.thinking.is_none());
+        assert!(!serde_json::to_string(&haiku).unwrap().contains("thinking"));
+
+        let mut opus = params(Model::ClaudeOpus4_6);
+        opus.prepare();
+        assert!(opus.thinking.is_some());
+    }
+
+    #[test]
+    fn test_interleaved_thinking_enables_beta() {
+        let params = MessageCreateParams::builder()
+            .model(Model::ClaudeSonnet4_5)
+            .max_tokens(4096)
+            .message(MessageParam::user("Hi"))
+            .beta(BETA_INTERLEAVED_THINKING_2025_05_14)
+            .interleaved_thinking(2048)
+            .build();
+        assert_eq!(
+            params.betas.unwrap(),
+            vec![BETA_INTERLEAVED_THINKING_2025_05_14.to_string()]
+        );
+        assert!(matches!(
+            params.thinking,
+            Some(ThinkingConfig::Enabled {
+                budget_tokens: 2048,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_model_spec_enables_1m_context() {
+        let params = MessageCreateParams::builder()
+            .model_spec(ModelSpec::parse("sonnet[1m]"))
+            .message(MessageParam::user("Hi"))
+            .build();
+        assert_eq!(params.model, Model::ClaudeSonnet4_6);
+        assert_eq!(
+            params.betas.as_deref(),
+            Some(&[BETA_CONTEXT_1M_2025_08_07.to_string()][..])
+        );
+        assert!(params.validate().is_ok());
+
+        let params = MessageCreateParams::builder()
+            .model_spec(ModelSpec::parse("haiku[1m]"))
+            .message(MessageParam::user("Hi"))
+            .build();
+        assert!(matches!(params.validate(), Err(Error::Validation(_))));
+
+        let params = MessageCreateParams::builder()
+            .model_spec(ModelSpec::parse("haiku"))
+            .message(MessageParam::user("Hi"))
+            .build();
+        assert!(params.betas.is_none());
+        assert!(params.validate().is_ok());
+    }
+
+    #[test]
+    fn test_prepare_moves_skills_into_container() {
+        let mut params = MessageCreateParams::builder()
+            .model(Model::ClaudeOpus4_5)
+            .max_tokens(100)
+            .messages(vec![MessageParam::user("Hi")])
+            .container("container_1")
+            .skills(vec![SkillReference::anthropic("pptx")])
+            .build();
+        params.prepare();
+        let json = serde_json::to_value(&params).unwrap();
+        assert_eq!(
+            json["container"],
+            serde_json::json!({"id": "container_1", "skills": [{"type": "anthropic", "skill_id": "pptx"}]})
+        );
+        assert!(json.get("skills").is_none());
+        assert_eq!(
+            params.betas.unwrap(),
+            vec![BETA_SKILLS_2025_10_02.to_string()]
+        );
+    }
+
+    #[test]
+    fn test_prepare_enables_files_beta_for_file_sources() {
+        use crate::types::content::{
+            ContentBlockParam, ImageBlockParam, ToolResultBlockParam, ToolResultContent,
+            ToolResultContentBlock,
+        };
+        use crate::types::image::ImageSource;
+
+        let mut params = MessageCreateParams::builder()
+            .model(Model::ClaudeOpus4_6)
+            .max_tokens(100)
+            .message(MessageParam::user_document_file("file_doc"))
+            .build();
+        params.prepare();
+        assert_eq!(
+            params.betas.unwrap(),
+            vec![BETA_FILES_API_2025_04_14.to_string()]
+        );
+
+        let mut params = MessageCreateParams::builder()
+            .model(Model::ClaudeOpus4_6)
+            .max_tokens(100)
+            .message(MessageParam::user_blocks(vec![
+                ContentBlockParam::ToolResult(ToolResultBlockParam {
+                    tool_use_id: "toolu_1".to_string(),
+                    content: Some(ToolResultContent::Blocks(vec![
+                        ToolResultContentBlock::Image(ImageBlockParam::new(ImageSource::file(
+                            "file_img",
+                        ))),
+                    ])),
+                    is_error: None,
+                    cache_control: None,
+                }),
+            ]))
+            .build();
+        params.prepare();
+        assert!(params.betas.is_some());
+
+        let mut params = MessageCreateParams::builder()
+            .model(Model::ClaudeOpus4_6)
+            .max_tokens(100)
+            .message(MessageParam::user_image_url("https://example.com/a.png"))
+            .build();
+        params.prepare();
+        assert!(params.betas.is_none());
+    }
+
+    #[test]
+    fn test_builder_appends_messages_and_tools() {
+        use crate::types::tool::Tool;
+
+        let params = MessageCreateParams::builder()
+            .model(Model::ClaudeOpus4_6)
+            .max_tokens(100)
+            .message(MessageParam::user("Hi"))
+            .message(MessageParam::assistant("Hello"))
+            .tool(Tool {
+                name: "a".to_string(),
+                ..Default::default()
+            })
+            .tool(ToolDefinition::Custom(Tool {
+                name: "b".to_string(),
+                ..Default::default()
+            }))
+            .message(MessageParam::user("Bye"))
+            .build();
+        assert_eq!(params.messages.len(), 3);
+        assert_eq!(params.tools.as_ref().map(Vec::len), Some(2));
+
+        let replaced = MessageCreateParams::builder()
+            .message(MessageParam::user("dropped"))
+            .messages(vec![MessageParam::user("kept")])
+            .model(Model::ClaudeOpus4_6)
+            .max_tokens(100)
+            .build();
+        assert_eq!(replaced.messages.len(), 1);
+        assert!(replaced.tools.is_none());
+    }
+
     #[test]
     fn test_message_create_params_no_stream_field() {
         let params = MessageCreateParams::builder()
@@ -156,6 +576,84 @@ mod tests {
         assert!(!json.contains("stream"));
     }
 
+    #[test]
+    fn test_message_create_params_round_trip() {
+        let json = serde_json::json!({
+            "model": "claude-sonnet-4-5",
+            "max_tokens": 512,
+            "stream": true,
+            "messages": [
+                {"role": "user", "content": "What's the weather?"},
+                {"role": "assistant", "content": [
+                    {"type": "tool_use", "id": "toolu_1", "name": "get_weather", "input": {"city": "Paris"}}
+                ]},
+                {"role": "user", "content": [
+                    {"type": "tool_result", "tool_use_id": "toolu_1", "content": "Sunny"}
+                ]}
+            ],
+            "system": "Be brief.",
+            "tools": [{
+                "name": "get_weather",
+                "input_schema": {"type": "object", "properties": {"city": {"type": "string"}}}
+            }],
+            "tool_choice": {"type": "auto"},
+            "temperature": 0.5
+        });
+        let params: MessageCreateParams = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(params.model, Model::ClaudeSonnet4_5);
+        assert_eq!(params.messages.len(), 3);
+        assert!(params.betas.is_none());
+        assert!(params.extra.is_none());
+
+        let mut expected = json;
+        expected.as_object_mut().unwrap().remove("stream");
+        assert_eq!(serde_json::to_value(&params).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_extra_fields_flattened_into_body() {
+        let mut extra = ExtraFields::new();
+        extra.insert(
+            "new_param".to_string(),
+            serde_json::json!({"enabled": true}),
+        );
+        let params = MessageCreateParams::builder()
+            .model(Model::ClaudeOpus4_6)
+            .max_tokens(100)
+            .messages(vec![MessageParam::user("Hi")])
+            .extra(extra)
+            .build();
+        let json = serde_json::to_value(&params).unwrap();
+        assert_eq!(json["new_param"], serde_json::json!({"enabled": true}));
+        assert!(json.get("extra").is_none());
+
+        let parsed: MessageCreateParams = serde_json::from_value(json).unwrap();
+        assert_eq!(
+            parsed.extra.unwrap()["new_param"],
+            serde_json::json!({"enabled": true})
+        );
+    }
+
+    #[test]
+    fn test_count_tokens_params_from_create_params() {
+        let create = MessageCreateParams::builder()
+            .model(Model::ClaudeSonnet4_5)
+            .max_tokens(1024)
+            .messages(vec![MessageParam::user("Hello")])
+            .system(SystemContent::from("Be brief."))
+            .temperature(0.2)
+            .build();
+        let json = serde_json::to_value(CountTokensParams::from(&create)).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "model": "claude-sonnet-4-5",
+                "messages": [{"role": "user", "content": "Hello"}],
+                "system": "Be brief."
+            })
+        );
+    }
+
     #[test]
     fn test_count_tokens_params_minimal() {
         let params = CountTokensParams::builder()
@@ -171,6 +669,17 @@ mod tests {
         assert!(!json.contains("thinking"));
     }
 
+    #[test]
+    fn test_count_tokens_params_deserialize() {
+        let params: CountTokensParams = serde_json::from_str(
+            r#"{"model": "claude-opus-4-6", "messages": [{"role": "user", "content": "Hi"}]}"#,
+        )
+        .unwrap();
+        assert_eq!(params.model, Model::ClaudeOpus4_6);
+        assert_eq!(params.messages.len(), 1);
+        assert!(params.system.is_none());
+    }
+
     #[test]
     fn test_count_tokens_params_with_system() {
         let params = CountTokensParams::builder()
@@ -1,20 +1,28 @@
 use std::pin::Pin;
+use std::sync::{Arc, Mutex};
 use std::task::{Context, Poll};
+use std::time::Duration;
 
 use futures::StreamExt;
 use futures::stream::Stream;
 use pin_project_lite::pin_project;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use tokio::io::AsyncRead;
+use tokio_util::sync::CancellationToken;
 
 use crate::error::Error;
-use crate::streaming::sse::{RawSseEvent, parse_sse_stream};
+use crate::streaming::sse::{RawSseEvent, parse_sse_reader, parse_sse_stream};
+use crate::streaming::text::TextStream;
 use crate::types::common::StopReason;
 use crate::types::content::ContentBlock;
 use crate::types::message::Message;
 use crate::types::usage::MessageDeltaUsage;
 
 /// SSE event deserialized from the stream. Dispatched by `event:` field name.
-#[derive(Debug, Clone, Deserialize)]
+///
+/// Serializes to the JSON carried in the event's `data:` line, including the
+/// `type` tag.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[non_exhaustive]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum StreamEvent {
@@ -44,7 +52,7 @@ pub enum StreamEvent {
 }
 
 /// Delta types for streaming content blocks.
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[non_exhaustive]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum ContentBlockDelta {
@@ -69,7 +77,8 @@ pub enum ContentBlockDelta {
 }
 
 /// Delta information in a `message_delta` streaming event.
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "strict-serde", serde(deny_unknown_fields))]
 pub struct MessageDelta {
     pub stop_reason: Option<StopReason>,
     pub stop_sequence: Option<String>,
@@ -109,20 +118,70 @@ pin_project! {
     pub struct MessageStream {
         #[pin]
         inner: Pin<Box<dyn Stream<Item = Result<StreamEvent, Error>> + Send>>,
+        resume: Arc<Mutex<ResumeState>>,
+    }
+}
+
+/// The SSE `id` and `retry` fields seen so far on a stream.
+#[derive(Debug, Default)]
+struct ResumeState {
+    last_event_id: Option<String>,
+    retry: Option<Duration>,
+}
+
+impl ResumeState {
+    fn observe(&mut self, raw: &RawSseEvent) {
+        if let Some(id) = &raw.id {
+            // Per the SSE spec an empty `id:` resets the last event id.
+            self.last_event_id = Some(id.clone()).filter(|id| !id.is_empty());
+        }
+        if let Some(ms) = raw.retry {
+            self.retry = Some(Duration::from_millis(ms));
+        }
     }
 }
 
 impl MessageStream {
     /// Create a new `MessageStream` from a raw reqwest Response.
     pub fn new(response: reqwest::Response) -> Self {
-        let sse_stream = parse_sse_stream(response);
-        let event_stream = sse_stream.map(|result| match result {
-            Ok(raw) => parse_stream_event(raw),
+        Self::from_raw(parse_sse_stream(response))
+    }
+
+    /// Create a `MessageStream` by parsing captured SSE text, such as the body
+    /// of a recorded streaming response.
+    ///
+    /// The text goes through the same parser as a live response, so a capture
+    /// replays byte-for-byte.
+    pub fn from_sse_text(text: impl Into<String>) -> Self {
+        Self::from_sse_reader(std::io::Cursor::new(text.into().into_bytes()))
+    }
+
+    /// Create a `MessageStream` by parsing SSE text from `reader`, such as a
+    /// file of captured events.
+    pub fn from_sse_reader<R>(reader: R) -> Self
+    where
+        R: AsyncRead + Send + Unpin + 'static,
+    {
+        Self::from_raw(parse_sse_reader(reader))
+    }
+
+    fn from_raw<S>(sse_stream: S) -> Self
+    where
+        S: Stream<Item = Result<RawSseEvent, Error>> + Send + 'static,
+    {
+        let resume = Arc::new(Mutex::new(ResumeState::default()));
+        let state = resume.clone();
+        let event_stream = sse_stream.map(move |result| match result {
+            Ok(raw) => {
+                state.lock().unwrap().observe(&raw);
+                parse_stream_event(raw)
+            }
             Err(e) => Err(e),
         });
 
         Self {
             inner: Box::pin(event_stream),
+            resume,
         }
     }
 
@@ -136,9 +195,35 @@ impl MessageStream {
     {
         Self {
             inner: Box::pin(stream),
+            resume: Arc::default(),
         }
     }
 
+    /// Replace the event stream with `f(self)`, keeping track of the SSE
+    /// fields seen by the original stream.
+    pub(crate) fn rewrap<S>(self, f: impl FnOnce(Self) -> S) -> Self
+    where
+        S: Stream<Item = Result<StreamEvent, Error>> + Send + 'static,
+    {
+        let resume = self.resume.clone();
+        Self {
+            inner: Box::pin(f(self)),
+            resume,
+        }
+    }
+
+    /// The most recent SSE `id:` field received, to send as `Last-Event-ID`
+    /// when reconnecting.
+    pub fn last_event_id(&self) -> Option<String> {
+        self.resume.lock().unwrap().last_event_id.clone()
+    }
+
+    /// The reconnection delay requested by the server with an SSE `retry:`
+    /// field.
+    pub fn server_retry(&self) -> Option<Duration> {
+        self.resume.lock().unwrap().retry
+    }
+
     /// Create a `MessageStream` from a pre-built list of events.
     ///
     /// Convenience wrapper around `from_stream` that converts a `Vec<StreamEvent>`
@@ -147,6 +232,42 @@ impl MessageStream {
         Self::from_stream(futures::stream::iter(events.into_iter().map(Ok)))
     }
 
+    /// Fail with `Error::Timeout` if no event arrives for `idle`.
+    ///
+    /// The window restarts with every event, including pings, so long
+    /// generations are unaffected while a silently dropped connection is
+    /// detected quickly. The connection is closed when the timeout fires and
+    /// the stream ends after yielding the error.
+    pub fn with_idle_timeout(self, idle: Duration) -> Self {
+        self.rewrap(|stream| {
+            futures::stream::unfold(Some(stream), move |state| async move {
+                let mut stream = state?;
+                match tokio::time::timeout(idle, stream.next()).await {
+                    Ok(Some(item)) => Some((item, Some(stream))),
+                    Ok(None) => None,
+                    Err(_) => Some((Err(Error::Timeout), None)),
+                }
+            })
+        })
+    }
+
+    /// Yield only the text deltas, in order.
+    ///
+    /// Thinking, tool input and other events are skipped. Use the adapters on
+    /// `TextStream` to re-segment the text into sentences, words or chunks.
+    pub fn text_stream(self) -> TextStream {
+        TextStream::from_stream(self.filter_map(|event| async move {
+            match event {
+                Ok(StreamEvent::ContentBlockDelta {
+                    delta: ContentBlockDelta::TextDelta { text },
+                    ..
+                }) => Some(Ok(text)),
+                Ok(_) => None,
+                Err(err) => Some(Err(err)),
+            }
+        }))
+    }
+
     /// Consume the stream and accumulate events into a final `Message`.
     ///
     /// This processes all stream events, building up the complete message
@@ -161,76 +282,204 @@ impl MessageStream {
         mut self,
         mut callback: impl FnMut(&StreamEvent),
     ) -> Result<Message, Error> {
-        let mut message: Option<Message> = None;
-        let mut content_blocks: Vec<ContentBlock> = Vec::new();
-        // Track partial JSON for tool_use blocks (keyed by index)
-        let mut partial_json_bufs: std::collections::HashMap<usize, String> =
-            std::collections::HashMap::new();
-
+        let mut accumulator = Accumulator::default();
         while let Some(event_result) = self.next().await {
             let event = event_result?;
             callback(&event);
+            accumulator.apply(&event)?;
+        }
+        accumulator.finish()
+    }
 
-            match &event {
-                StreamEvent::MessageStart { message: msg } => {
-                    message = Some(msg.clone());
-                }
-                StreamEvent::ContentBlockStart {
-                    index,
-                    content_block,
-                } => {
-                    let idx = *index as usize;
-                    // Ensure the vec is large enough
-                    while content_blocks.len() <= idx {
-                        content_blocks.push(ContentBlock::Text(crate::types::content::TextBlock {
-                            text: String::new(),
-                            citations: None,
-                        }));
-                    }
-                    content_blocks[idx] = content_block.clone();
-                }
-                StreamEvent::ContentBlockDelta { index, delta } => {
-                    let idx = *index as usize;
-                    if idx < content_blocks.len() {
-                        apply_delta(&mut content_blocks[idx], delta, &mut partial_json_bufs, idx);
+    /// Consume the stream and accumulate events into a final `Message`, also
+    /// returning every event in the order it arrived.
+    pub async fn accumulate_with_events(self) -> Result<(Message, Vec<StreamEvent>), Error> {
+        let mut events = Vec::new();
+        let message = self
+            .accumulate_with(|event| events.push(event.clone()))
+            .await?;
+        Ok((message, events))
+    }
+
+    /// Accumulate the stream until it completes or `deadline` passes.
+    ///
+    /// If the deadline passes mid-stream the request is aborted and the content
+    /// received so far is returned with `incomplete` set; its `stop_reason` is
+    /// `None` and a tool_use block still streaming keeps its initial input.
+    /// Returns `Error::Timeout` if the deadline passes before `message_start`.
+    pub async fn accumulate_with_deadline(
+        self,
+        deadline: tokio::time::Instant,
+    ) -> Result<PartialMessage, Error> {
+        self.accumulate_until(tokio::time::sleep_until(deadline), Error::Timeout)
+            .await
+    }
+
+    /// Accumulate the stream until it completes or `token` is cancelled.
+    ///
+    /// Intended for "stop" buttons: cancelling the token closes the connection
+    /// and returns what was generated so far, with the same `incomplete`
+    /// semantics as `accumulate_with_deadline`. Returns `Error::Cancelled` if
+    /// the token is cancelled before `message_start`.
+    pub async fn accumulate_until_cancelled(
+        self,
+        token: CancellationToken,
+    ) -> Result<PartialMessage, Error> {
+        self.accumulate_until(token.cancelled_owned(), Error::Cancelled)
+            .await
+    }
+
+    /// End the stream with `Error::Cancelled` once `token` is cancelled.
+    ///
+    /// The connection is closed as soon as the token fires, even while the
+    /// stream is waiting for the next event.
+    pub fn with_cancellation(self, token: CancellationToken) -> Self {
+        self.rewrap(|stream| {
+            futures::stream::unfold(Some(stream), move |state| {
+                let token = token.clone();
+                async move {
+                    let mut stream = state?;
+                    tokio::select! {
+                        biased;
+                        item = stream.next() => item.map(|item| (item, Some(stream))),
+                        _ = token.cancelled() => Some((Err(Error::Cancelled), None)),
                     }
                 }
-                StreamEvent::ContentBlockStop { index } => {
-                    let idx = *index as usize;
-                    // Finalize tool_use blocks: parse accumulated partial JSON into input
-                    if let Some(json_str) = partial_json_bufs.remove(&idx)
-                        && idx < content_blocks.len()
-                        && let ContentBlock::ToolUse(ref mut tool_use) = content_blocks[idx]
-                        && let Ok(parsed) = serde_json::from_str::<serde_json::Value>(&json_str)
-                    {
-                        tool_use.input = parsed;
+            })
+        })
+    }
+
+    async fn accumulate_until(
+        mut self,
+        stop: impl Future<Output = ()>,
+        stopped_early: Error,
+    ) -> Result<PartialMessage, Error> {
+        let mut accumulator = Accumulator::default();
+        let mut stop = std::pin::pin!(stop);
+        loop {
+            tokio::select! {
+                biased;
+                event = self.next() => match event {
+                    Some(event_result) => accumulator.apply(&event_result?)?,
+                    None => {
+                        return Ok(PartialMessage {
+                            message: accumulator.finish()?,
+                            incomplete: false,
+                        });
                     }
+                },
+                _ = &mut stop => {
+                    // Dropping the stream closes the connection.
+                    drop(self);
+                    return match accumulator.message {
+                        Some(_) => Ok(PartialMessage {
+                            message: accumulator.finish()?,
+                            incomplete: true,
+                        }),
+                        None => Err(stopped_early),
+                    };
                 }
-                StreamEvent::MessageDelta { delta, usage } => {
-                    if let Some(ref mut msg) = message {
-                        msg.stop_reason = delta.stop_reason.clone();
-                        msg.stop_sequence = delta.stop_sequence.clone();
-                        msg.usage.output_tokens = usage.output_tokens;
-                    }
+            }
+        }
+    }
+}
+
+/// A message accumulated by `MessageStream::accumulate_with_deadline` or
+/// `MessageStream::accumulate_until_cancelled`.
+#[derive(Debug, Clone)]
+pub struct PartialMessage {
+    pub message: Message,
+    /// The deadline passed or the token was cancelled before the stream
+    /// finished.
+    pub incomplete: bool,
+}
+
+/// Builds a `Message` from stream events.
+#[derive(Default)]
+pub(crate) struct Accumulator {
+    message: Option<Message>,
+    content_blocks: Vec<ContentBlock>,
+    // Track partial JSON for tool_use blocks (keyed by index)
+    partial_json_bufs: std::collections::HashMap<usize, String>,
+}
+
+impl Accumulator {
+    pub(crate) fn apply(&mut self, event: &StreamEvent) -> Result<(), Error> {
+        match event {
+            StreamEvent::MessageStart { message: msg } => {
+                self.message = Some(msg.clone());
+            }
+            StreamEvent::ContentBlockStart {
+                index,
+                content_block,
+            } => {
+                let idx = *index as usize;
+                // Server tool results (web search hits, fetched pages, code
+                // execution output) arrive whole here and get no deltas.
+                // Ensure the vec is large enough
+                while self.content_blocks.len() <= idx {
+                    self.content_blocks.push(ContentBlock::Text(
+                        crate::types::content::TextBlock {
+                            text: String::new(),
+                            citations: None,
+                            extra: Default::default(),
+                        },
+                    ));
                 }
-                StreamEvent::MessageStop => {
-                    // Final event
+                self.content_blocks[idx] = content_block.clone();
+            }
+            StreamEvent::ContentBlockDelta { index, delta } => {
+                let idx = *index as usize;
+                if idx < self.content_blocks.len() {
+                    apply_delta(
+                        &mut self.content_blocks[idx],
+                        delta,
+                        &mut self.partial_json_bufs,
+                        idx,
+                    );
                 }
-                StreamEvent::Ping => {
-                    // Keep-alive, ignore
+            }
+            StreamEvent::ContentBlockStop { index } => {
+                let idx = *index as usize;
+                if let Some(json_str) = self.partial_json_bufs.remove(&idx)
+                    && let Some(block) = self.content_blocks.get_mut(idx)
+                {
+                    finish_input(block, &json_str);
                 }
-                StreamEvent::Error { error } => {
-                    return Err(Error::StreamError(format!(
-                        "Stream error: {}: {}",
-                        error.error_type, error.message
-                    )));
+            }
+            StreamEvent::MessageDelta { delta, usage } => {
+                if let Some(ref mut msg) = self.message {
+                    msg.stop_reason = delta.stop_reason.clone();
+                    msg.stop_sequence = delta.stop_sequence.clone();
+                    msg.usage.apply_delta(usage);
                 }
             }
+            StreamEvent::MessageStop => {
+                // Final event
+            }
+            StreamEvent::Ping => {
+                // Keep-alive, ignore
+            }
+            StreamEvent::Error { error } => {
+                return Err(Error::StreamError(format!(
+                    "Stream error: {}: {}",
+                    error.error_type, error.message
+                )));
+            }
         }
+        Ok(())
+    }
+
+    /// The content block at `index` as accumulated so far. A tool use block's
+    /// input is filled in once its `content_block_stop` has been applied.
+    pub(crate) fn block(&self, index: usize) -> Option<&ContentBlock> {
+        self.content_blocks.get(index)
+    }
 
-        match message {
+    pub(crate) fn finish(self) -> Result<Message, Error> {
+        match self.message {
             Some(mut msg) => {
-                msg.content = content_blocks;
+                msg.content = self.content_blocks;
                 Ok(msg)
             }
             None => Err(Error::StreamError(
@@ -272,12 +521,23 @@ fn apply_delta(
         ) => {
             thinking_block.signature.push_str(signature);
         }
-        (ContentBlock::ToolUse(_), ContentBlockDelta::InputJsonDelta { partial_json }) => {
+        (
+            ContentBlock::ToolUse(_) | ContentBlock::ServerToolUse(_),
+            ContentBlockDelta::InputJsonDelta { partial_json },
+        ) => {
             partial_json_bufs
                 .entry(index)
                 .or_default()
                 .push_str(partial_json);
         }
+        (ContentBlock::Text(text_block), ContentBlockDelta::CitationsDelta { citation }) => {
+            if let Ok(citation) = serde_json::from_value(citation.clone()) {
+                text_block
+                    .citations
+                    .get_or_insert_with(Vec::new)
+                    .push(citation);
+            }
+        }
         (
             ContentBlock::Compaction(compaction_block),
             ContentBlockDelta::CompactionDelta { compacted },
@@ -285,11 +545,25 @@ fn apply_delta(
             compaction_block.compacted.push_str(compacted);
         }
         _ => {
-            // Other combinations (citation deltas, etc.)
+            // Deltas that don't match the block's type.
         }
     }
 }
 
+/// Set the input of a finished tool use or server tool use block from the
+/// JSON accumulated from its `input_json_delta`s. Input that doesn't parse is
+/// left as it was.
+fn finish_input(block: &mut ContentBlock, json: &str) {
+    let input = match block {
+        ContentBlock::ToolUse(tool_use) => &mut tool_use.input,
+        ContentBlock::ServerToolUse(server_tool_use) => &mut server_tool_use.input,
+        _ => return,
+    };
+    if let Ok(parsed) = serde_json::from_str(json) {
+        *input = parsed;
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -372,6 +646,7 @@ mod tests {
         let mut block = ContentBlock::Text(crate::types::content::TextBlock {
             text: "Hello".to_string(),
             citations: None,
+            extra: Default::default(),
         });
         let mut bufs = std::collections::HashMap::new();
         apply_delta(
@@ -395,6 +670,7 @@ mod tests {
             name: "get_weather".to_string(),
             input: serde_json::Value::Object(serde_json::Map::new()),
             partial_json: None,
+            extra: Default::default(),
         });
         let mut bufs = std::collections::HashMap::new();
         apply_delta(
@@ -420,6 +696,7 @@ mod tests {
     fn test_apply_delta_compaction() {
         let mut block = ContentBlock::Compaction(crate::types::content::CompactionBlock {
             compacted: "Part 1".to_string(),
+            extra: Default::default(),
         });
         let mut bufs = std::collections::HashMap::new();
         apply_delta(
@@ -461,4 +738,313 @@ mod tests {
             _ => panic!("Expected ContentBlockDelta"),
         }
     }
+
+    fn partial_events() -> Vec<Result<StreamEvent, Error>> {
+        let events: Vec<StreamEvent> = serde_json::from_value(serde_json::json!([
+            {"type": "message_start", "message": {"id": "msg_1", "type": "message", "role": "assistant", "content": [], "model": "claude-opus-4-6", "stop_reason": null, "usage": {"input_tokens": 3, "output_tokens": 0}}},
+            {"type": "content_block_start", "index": 0, "content_block": {"type": "text", "text": ""}},
+            {"type": "content_block_delta", "index": 0, "delta": {"type": "text_delta", "text": "Partial"}}
+        ]))
+        .unwrap();
+        events.into_iter().map(Ok).collect()
+    }
+
+    #[tokio::test]
+    async fn test_accumulate_server_tool_stream() {
+        let events: Vec<StreamEvent> = serde_json::from_value(serde_json::json!([
+            {"type": "message_start", "message": {"id": "msg_1", "type": "message", "role": "assistant", "content": [], "model": "claude-sonnet-4-5", "stop_reason": null, "usage": {"input_tokens": 10, "output_tokens": 1}}},
+            {"type": "content_block_start", "index": 0, "content_block": {"type": "server_tool_use", "id": "srvtoolu_1", "name": "web_search", "input": {}}},
+            {"type": "content_block_delta", "index": 0, "delta": {"type": "input_json_delta", "partial_json": "{\"query\": "}},
+            {"type": "content_block_delta", "index": 0, "delta": {"type": "input_json_delta", "partial_json": "\"rust sdk\"}"}},
+            {"type": "content_block_stop", "index": 0},
+            {"type": "content_block_start", "index": 1, "content_block": {"type": "web_search_tool_result", "tool_use_id": "srvtoolu_1", "content": [
+                {"type": "web_search_result", "url": "https://example.com", "title": "Example", "encrypted_content": "enc"}
+            ]}},
+            {"type": "content_block_stop", "index": 1},
+            {"type": "content_block_start", "index": 2, "content_block": {"type": "text", "text": ""}},
+            {"type": "content_block_delta", "index": 2, "delta": {"type": "citations_delta", "citation": {
+                "type": "web_search_result_location", "cited_text": "Example text", "encrypted_index": "idx", "title": "Example", "url": "https://example.com"
+            }}},
+            {"type": "content_block_delta", "index": 2, "delta": {"type": "text_delta", "text": "It says so."}},
+            {"type": "content_block_stop", "index": 2},
+            {"type": "message_delta", "delta": {"stop_reason": "end_turn", "stop_sequence": null}, "usage": {
+                "input_tokens": 900, "output_tokens": 40, "server_tool_use": {"web_search_requests": 1}
+            }},
+            {"type": "message_stop"}
+        ]))
+        .unwrap();
+        let message = MessageStream::from_events(events)
+            .accumulate()
+            .await
+            .unwrap();
+
+        let ContentBlock::ServerToolUse(search) = &message.content[0] else {
+            panic!("Expected ServerToolUse block");
+        };
+        assert_eq!(search.input, serde_json::json!({"query": "rust sdk"}));
+        assert!(matches!(
+            &message.content[1],
+            ContentBlock::WebSearchToolResult(r) if r.tool_use_id == "srvtoolu_1"
+        ));
+        let ContentBlock::Text(text) = &message.content[2] else {
+            panic!("Expected Text block");
+        };
+        assert_eq!(text.text, "It says so.");
+        assert!(matches!(
+            text.citations.as_deref(),
+            Some([crate::types::citation::TextCitation::WebSearchResultLocation(_)])
+        ));
+        assert_eq!(message.usage.input_tokens, 900);
+        assert_eq!(message.usage.output_tokens, 40);
+        assert_eq!(
+            message
+                .usage
+                .server_tool_use
+                .and_then(|u| u.web_search_requests),
+            Some(1)
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_accumulate_with_deadline_returns_partial() {
+        let stream = MessageStream::from_stream(
+            futures::stream::iter(partial_events()).chain(futures::stream::pending()),
+        );
+        let deadline = tokio::time::Instant::now() + std::time::Duration::from_secs(1);
+        let partial = stream.accumulate_with_deadline(deadline).await.unwrap();
+        assert!(partial.incomplete);
+        assert!(partial.message.stop_reason.is_none());
+        match &partial.message.content[0] {
+            ContentBlock::Text(t) => assert_eq!(t.text, "Partial"),
+            _ => panic!("Expected Text block"),
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_accumulate_with_deadline_before_message_start() {
+        let stream = MessageStream::from_stream(futures::stream::pending());
+        let deadline = tokio::time::Instant::now() + std::time::Duration::from_secs(1);
+        let result = stream.accumulate_with_deadline(deadline).await;
+        assert!(matches!(result, Err(Error::Timeout)));
+    }
+
+    #[tokio::test]
+    async fn test_accumulate_with_deadline_complete() {
+        let mut events = partial_events();
+        events.push(Ok(StreamEvent::ContentBlockStop { index: 0 }));
+        events.push(Ok(StreamEvent::MessageStop));
+        let deadline = tokio::time::Instant::now() + std::time::Duration::from_secs(60);
+        let partial = MessageStream::from_stream(futures::stream::iter(events))
+            .accumulate_with_deadline(deadline)
+            .await
+            .unwrap();
+        assert!(!partial.incomplete);
+        assert_eq!(partial.message.content.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_accumulate_until_cancelled_returns_partial() {
+        let token = CancellationToken::new();
+        let stream = MessageStream::from_stream(
+            futures::stream::iter(partial_events()).chain(futures::stream::pending()),
+        );
+        let stop = token.clone();
+        tokio::spawn(async move {
+            tokio::task::yield_now().await;
+            stop.cancel();
+        });
+        let partial = stream.accumulate_until_cancelled(token).await.unwrap();
+        assert!(partial.incomplete);
+        match &partial.message.content[0] {
+            ContentBlock::Text(t) => assert_eq!(t.text, "Partial"),
+            _ => panic!("Expected Text block"),
+        }
+
+        let token = CancellationToken::new();
+        token.cancel();
+        let result = MessageStream::from_stream(futures::stream::pending())
+            .accumulate_until_cancelled(token)
+            .await;
+        assert!(matches!(result, Err(Error::Cancelled)));
+    }
+
+    #[tokio::test]
+    async fn test_with_cancellation() {
+        let token = CancellationToken::new();
+        let mut stream = MessageStream::from_stream(
+            futures::stream::iter(partial_events()).chain(futures::stream::pending()),
+        )
+        .with_cancellation(token.clone());
+        for _ in 0..partial_events().len() {
+            assert!(stream.next().await.unwrap().is_ok());
+        }
+        token.cancel();
+        assert!(matches!(stream.next().await, Some(Err(Error::Cancelled))));
+        assert!(stream.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_text_stream_sentences() {
+        let events: Vec<StreamEvent> = serde_json::from_value(serde_json::json!([
+            {"type": "content_block_start", "index": 0, "content_block": {"type": "thinking", "thinking": "", "signature": ""}},
+            {"type": "content_block_delta", "index": 0, "delta": {"type": "thinking_delta", "thinking": "Hmm. Ok."}},
+            {"type": "content_block_start", "index": 1, "content_block": {"type": "text", "text": ""}},
+            {"type": "content_block_delta", "index": 1, "delta": {"type": "text_delta", "text": "Hi. How"}},
+            {"type": "content_block_delta", "index": 1, "delta": {"type": "text_delta", "text": " are you?"}},
+            {"type": "message_stop"}
+        ]))
+        .unwrap();
+        let sentences: Vec<String> = MessageStream::from_events(events)
+            .text_stream()
+            .sentences()
+            .map(Result::unwrap)
+            .collect()
+            .await;
+        assert_eq!(sentences, ["Hi. ", "How are you?"]);
+    }
+
+    #[tokio::test]
+    async fn test_tracks_sse_id_and_retry() {
+        let mut stream = MessageStream::from_sse_text(concat!(
+            "retry: 2500\nid: 1\nevent: ping\ndata: {}\n\n",
+            "id: 2\nevent: ping\ndata: {}\n\n",
+            "id:\nevent: ping\ndata: {}\n\n",
+        ))
+        .with_idle_timeout(Duration::from_secs(30));
+        assert!(stream.last_event_id().is_none());
+        stream.next().await.unwrap().unwrap();
+        assert_eq!(stream.last_event_id().as_deref(), Some("1"));
+        assert_eq!(stream.server_retry(), Some(Duration::from_millis(2500)));
+        stream.next().await.unwrap().unwrap();
+        assert_eq!(stream.last_event_id().as_deref(), Some("2"));
+        stream.next().await.unwrap().unwrap();
+        assert!(stream.last_event_id().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_from_sse_text_replays_capture() {
+        let capture = concat!(
+            ": captured from production\n",
+            "event: message_start\n",
+            "data: {\"type\":\"message_start\",\"message\":{\"id\":\"msg_1\",\"type\":\"message\",\"role\":\"assistant\",\"content\":[],\"model\":\"claude-opus-4-6\",\"stop_reason\":null,\"usage\":{\"input_tokens\":3,\"output_tokens\":0}}}\n\n",
+            "event: content_block_start\n",
+            "data: {\"type\":\"content_block_start\",\"index\":0,\"content_block\":{\"type\":\"text\",\"text\":\"\"}}\n\n",
+            "event: ping\n",
+            "data: {\"type\":\"ping\"}\n\n",
+            "event: content_block_delta\n",
+            "data: {\"type\":\"content_block_delta\",\"index\":0,\"delta\":{\"type\":\"text_delta\",\"text\":\"Hel\"}}\n\n",
+            "event: content_block_delta\n",
+            "data: {\"type\":\"content_block_delta\",\"index\":0,\"delta\":{\"type\":\"text_delta\",\"text\":\"lo\"}}\n\n",
+            "event: content_block_stop\n",
+            "data: {\"type\":\"content_block_stop\",\"index\":0}\n\n",
+            "event: message_delta\n",
+            "data: {\"type\":\"message_delta\",\"delta\":{\"stop_reason\":\"end_turn\",\"stop_sequence\":null},\"usage\":{\"output_tokens\":2}}\n\n",
+            "event: message_stop\n",
+            "data: {\"type\":\"message_stop\"}\n",
+        );
+        let message = MessageStream::from_sse_text(capture)
+            .accumulate()
+            .await
+            .unwrap();
+        assert_eq!(message.id, "msg_1");
+        match &message.content[0] {
+            ContentBlock::Text(text) => assert_eq!(text.text, "Hello"),
+            other => panic!("Expected text block, got {other:?}"),
+        }
+        assert_eq!(message.usage.output_tokens, 2);
+
+        let events: Vec<_> = MessageStream::from_sse_reader(capture.as_bytes())
+            .collect()
+            .await;
+        assert_eq!(events.len(), 8);
+        assert!(matches!(events[2], Ok(StreamEvent::Ping)));
+    }
+
+    #[tokio::test]
+    async fn test_accumulate_with_events() {
+        let capture = concat!(
+            "event: message_start\n",
+            "data: {\"type\":\"message_start\",\"message\":{\"id\":\"msg_1\",\"type\":\"message\",\"role\":\"assistant\",\"content\":[],\"model\":\"claude-opus-4-6\",\"stop_reason\":null,\"usage\":{\"input_tokens\":3,\"output_tokens\":0}}}\n\n",
+            "event: ping\n",
+            "data: {\"type\":\"ping\"}\n\n",
+            "event: message_delta\n",
+            "data: {\"type\":\"message_delta\",\"delta\":{\"stop_reason\":\"end_turn\",\"stop_sequence\":null},\"usage\":{\"output_tokens\":2}}\n\n",
+            "event: message_stop\n",
+            "data: {\"type\":\"message_stop\"}\n",
+        );
+        let (message, events) = MessageStream::from_sse_text(capture)
+            .accumulate_with_events()
+            .await
+            .unwrap();
+        assert_eq!(message.id, "msg_1");
+        assert_eq!(message.usage.output_tokens, 2);
+        assert_eq!(events.len(), 4);
+        assert!(matches!(events[1], StreamEvent::Ping));
+        assert!(matches!(events[3], StreamEvent::MessageStop));
+    }
+
+    #[test]
+    fn test_stream_event_serializes_to_wire_format() {
+        let start: StreamEvent = serde_json::from_value(serde_json::json!({
+            "type": "message_start", "message": {
+                "id": "msg_1", "type": "message", "role": "assistant", "content": [],
+                "model": "claude-opus-4-6", "stop_reason": null, "stop_sequence": null,
+                "usage": {"input_tokens": 3, "output_tokens": 0}
+            }
+        }))
+        .unwrap();
+        let start = serde_json::to_value(&start).unwrap();
+        assert_eq!(start["type"], "message_start");
+        assert_eq!(start["message"]["id"], "msg_1");
+        assert_eq!(start["message"]["usage"]["input_tokens"], 3);
+
+        let payloads = [
+            serde_json::json!({"type": "content_block_start", "index": 0,
+                "content_block": {"type": "text", "text": ""}}),
+            serde_json::json!({"type": "content_block_delta", "index": 0,
+                "delta": {"type": "text_delta", "text": "Hi"}}),
+            serde_json::json!({"type": "content_block_delta", "index": 1,
+                "delta": {"type": "input_json_delta", "partial_json": "{\"a\":"}}),
+            serde_json::json!({"type": "content_block_stop", "index": 0}),
+            serde_json::json!({"type": "message_delta",
+                "delta": {"stop_reason": "end_turn", "stop_sequence": null},
+                "usage": {"output_tokens": 2}}),
+            serde_json::json!({"type": "message_stop"}),
+            serde_json::json!({"type": "ping"}),
+            serde_json::json!({"type": "error",
+                "error": {"type": "overloaded_error", "message": "Overloaded"}}),
+        ];
+        for payload in payloads {
+            let event: StreamEvent = serde_json::from_value(payload.clone()).unwrap();
+            assert_eq!(serde_json::to_value(&event).unwrap(), payload);
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_with_idle_timeout() {
+        let stalled = futures::stream::iter(partial_events()).chain(futures::stream::pending());
+        let mut stream =
+            MessageStream::from_stream(stalled).with_idle_timeout(Duration::from_secs(30));
+        let mut received = 0;
+        let err = loop {
+            match stream.next().await {
+                Some(Ok(_)) => received += 1,
+                Some(Err(err)) => break err,
+                None => panic!("stream ended without timing out"),
+            }
+        };
+        assert_eq!(received, partial_events().len());
+        assert!(matches!(err, Error::Timeout));
+        assert!(stream.next().await.is_none());
+
+        let mut events = partial_events();
+        events.push(Ok(StreamEvent::ContentBlockStop { index: 0 }));
+        events.push(Ok(StreamEvent::MessageStop));
+        let message = MessageStream::from_stream(futures::stream::iter(events))
+            .with_idle_timeout(Duration::from_secs(30))
+            .accumulate()
+            .await
+            .unwrap();
+        assert_eq!(message.content.len(), 1);
+    }
 }
@@ -0,0 +1,305 @@
+//! Human-readable session transcripts written while a response streams.
+//!
+//! ```ignore
+//! use uno_anthropic::messages::transcript::TranscriptWriter;
+//!
+//! let file = tokio::fs::File::create("session.log").await?;
+//! let mut transcript = TranscriptWriter::new(file).omit_thinking(true);
+//! for message in &params.messages {
+//!     transcript.write_message(message).await?;
+//! }
+//! let stream = client.messages().create_stream(params).await?;
+//! let message = transcript.record(stream).await?;
+//! ```
+
+use std::time::SystemTime;
+
+use futures::StreamExt;
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+
+use crate::error::Error;
+use crate::messages::streaming::{Accumulator, MessageStream, StreamEvent};
+use crate::redact::RedactionPolicy;
+use crate::time::format_rfc3339;
+use crate::types::common::Role;
+use crate::types::content::{ContentBlock, ContentBlockParam};
+use crate::types::message::{Message, MessageContent, MessageParam};
+
+/// Writes a timestamped, redacted transcript of a conversation to an `AsyncWrite`.
+///
+/// Each content block is written as one entry when it completes, so redaction
+/// sees the whole block rather than individual deltas and a secret split across
+/// deltas is still caught.
+pub struct TranscriptWriter<W> {
+    writer: W,
+    policy: RedactionPolicy,
+    omit_thinking: bool,
+    accumulator: Accumulator,
+}
+
+impl<W: AsyncWrite + Unpin> TranscriptWriter<W> {
+    /// Create a writer using the default `RedactionPolicy`.
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            policy: RedactionPolicy::default(),
+            omit_thinking: false,
+            accumulator: Accumulator::default(),
+        }
+    }
+
+    /// Set the redaction policy applied to every entry.
+    pub fn redaction(mut self, policy: RedactionPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Replace thinking content with a placeholder instead of writing it.
+    pub fn omit_thinking(mut self, omit: bool) -> Self {
+        self.omit_thinking = omit;
+        self
+    }
+
+    /// Write a request-side message, such as the user's prompt or a tool result.
+    pub async fn write_message(&mut self, message: &MessageParam) -> std::io::Result<()> {
+        let role = role_name(&message.role);
+        match &message.content {
+            MessageContent::Text(text) => self.write_entry(role, text).await,
+            MessageContent::Blocks(blocks) => {
+                for block in blocks {
+                    let (label, body) = self.render_param_block(role, block);
+                    self.write_entry(&label, &body).await?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Write the transcript entries for a single stream event.
+    pub async fn write_event(&mut self, event: &StreamEvent) -> std::io::Result<()> {
+        // An error event is written as an entry; only `record` returns it.
+        let _ = self.apply(event);
+        self.write_entries(event).await
+    }
+
+    /// Consume `stream`, writing each event to the transcript, and return the
+    /// accumulated `Message`.
+    pub async fn record(&mut self, mut stream: MessageStream) -> Result<Message, Error> {
+        let write_failed =
+            |e: std::io::Error| Error::StreamError(format!("Failed to write transcript: {e}"));
+        while let Some(event) = stream.next().await {
+            let event = event?;
+            let applied = self.apply(&event);
+            self.write_entries(&event).await.map_err(write_failed)?;
+            applied?;
+        }
+        self.writer.flush().await.map_err(write_failed)?;
+        std::mem::take(&mut self.accumulator).finish()
+    }
+
+    /// Flush and return the underlying writer.
+    pub async fn into_inner(mut self) -> std::io::Result<W> {
+        self.writer.flush().await?;
+        Ok(self.writer)
+    }
+
+    fn apply(&mut self, event: &StreamEvent) -> Result<(), Error> {
+        if matches!(event, StreamEvent::MessageStart { .. }) {
+            self.accumulator = Accumulator::default();
+        }
+        self.accumulator.apply(event)
+    }
+
+    async fn write_entries(&mut self, event: &StreamEvent) -> std::io::Result<()> {
+        match event {
+            StreamEvent::MessageStart { message } => {
+                let body = format!("message {} (model {})", message.id, message.model);
+                self.write_entry("assistant", &body).await
+            }
+            StreamEvent::ContentBlockStop { index } => {
+                let Some(block) = self.accumulator.block(*index as usize) else {
+                    return Ok(());
+                };
+                let (label, body) = self.render_block(block);
+                self.write_entry(&label, &body).await
+            }
+            StreamEvent::MessageDelta { delta, usage } => {
+                let reason = delta
+                    .stop_reason
+                    .as_ref()
+                    .and_then(|r| serde_json::to_value(r).ok())
+                    .and_then(|v| v.as_str().map(String::from))
+                    .unwrap_or_else(|| "none".to_string());
+                let body = format!("{reason} (output tokens {})", usage.output_tokens);
+                self.write_entry("stop", &body).await
+            }
+            StreamEvent::Error { error } => {
+                let body = format!("{}: {}", error.error_type, error.message);
+                self.write_entry("error", &body).await
+            }
+            StreamEvent::ContentBlockStart { .. }
+            | StreamEvent::ContentBlockDelta { .. }
+            | StreamEvent::MessageStop
+            | StreamEvent::Ping => Ok(()),
+        }
+    }
+
+    async fn write_entry(&mut self, label: &str, body: &str) -> std::io::Result<()> {
+        let line = format!("[{}] {label}: {}\n", timestamp(), self.policy.apply(body));
+        self.writer.write_all(line.as_bytes()).await
+    }
+
+    fn render_block(&self, block: &ContentBlock) -> (String, String) {
+        match block {
+            ContentBlock::Text(t) => ("assistant".to_string(), t.text.clone()),
+            ContentBlock::Thinking(_) if self.omit_thinking => {
+                ("assistant thinking".to_string(), "[omitted]".to_string())
+            }
+            ContentBlock::Thinking(t) => ("assistant thinking".to_string(), t.thinking.clone()),
+            ContentBlock::RedactedThinking(_) => (
+                "assistant thinking".to_string(),
+                "[redacted by API]".to_string(),
+            ),
+            ContentBlock::ToolUse(t) => (
+                format!("assistant tool_use {} ({})", t.name, t.id),
+                t.input.to_string(),
+            ),
+            ContentBlock::ServerToolUse(t) => (
+                format!("assistant server_tool_use {} ({})", t.name, t.id),
+                t.input.to_string(),
+            ),
+            other => {
+                let value = serde_json::to_value(other).unwrap_or_default();
+                (
+                    format!("assistant {}", block_type(&value)),
+                    value.to_string(),
+                )
+            }
+        }
+    }
+
+    fn render_param_block(&self, role: &str, block: &ContentBlockParam) -> (String, String) {
+        match block {
+            ContentBlockParam::Text(t) => (role.to_string(), t.text.clone()),
+            ContentBlockParam::Thinking(_) if self.omit_thinking => {
+                (format!("{role} thinking"), "[omitted]".to_string())
+            }
+            ContentBlockParam::Thinking(t) => (format!("{role} thinking"), t.thinking.clone()),
+            ContentBlockParam::ToolUse(t) => (
+                format!("{role} tool_use {} ({})", t.name, t.id),
+                t.input.to_string(),
+            ),
+            ContentBlockParam::ToolResult(t) => {
+                let body = match &t.content {
+                    Some(content) => match serde_json::to_value(content).unwrap_or_default() {
+                        serde_json::Value::String(s) => s,
+                        value => value.to_string(),
+                    },
+                    None => String::new(),
+                };
+                let label = if t.is_error == Some(true) {
+                    format!("{role} tool_result {} (error)", t.tool_use_id)
+                } else {
+                    format!("{role} tool_result {}", t.tool_use_id)
+                };
+                (label, body)
+            }
+            ContentBlockParam::Image(_) | ContentBlockParam::Document(_) => {
+                let value = serde_json::to_value(block).unwrap_or_default();
+                (role.to_string(), format!("[{}]", block_type(&value)))
+            }
+            other => {
+                let value = serde_json::to_value(other).unwrap_or_default();
+                (format!("{role} {}", block_type(&value)), value.to_string())
+            }
+        }
+    }
+}
+
+fn role_name(role: &Role) -> &'static str {
+    match role {
+        Role::User => "user",
+        Role::Assistant => "assistant",
+    }
+}
+
+fn block_type(value: &serde_json::Value) -> &str {
+    value
+        .get("type")
+        .and_then(|t| t.as_str())
+        .unwrap_or("block")
+}
+
+/// Current UTC time formatted as RFC 3339 with millisecond precision.
+fn timestamp() -> String {
+    format_rfc3339(SystemTime::now())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn events() -> Vec<StreamEvent> {
+        let raw = serde_json::json!([
+            {"type": "message_start", "message": {"id": "msg_1", "type": "message", "role": "assistant", "content": [], "model": "claude-opus-4-6", "stop_reason": null, "usage": {"input_tokens": 3, "output_tokens": 0}}},
+            {"type": "content_block_start", "index": 0, "content_block": {"type": "thinking", "thinking": "", "signature": ""}},
+            {"type": "content_block_delta", "index": 0, "delta": {"type": "thinking_delta", "thinking": "secret plan"}},
+            {"type": "content_block_stop", "index": 0},
+            {"type": "content_block_start", "index": 1, "content_block": {"type": "text", "text": ""}},
+            {"type": "content_block_delta", "index": 1, "delta": {"type": "text_delta", "text": "Your key is sk-ant-"}},
+            {"type": "content_block_delta", "index": 1, "delta": {"type": "text_delta", "text": "abc123"}},
+            {"type": "content_block_stop", "index": 1},
+            {"type": "content_block_start", "index": 2, "content_block": {"type": "tool_use", "id": "toolu_1", "name": "lookup", "input": {}}},
+            {"type": "content_block_delta", "index": 2, "delta": {"type": "input_json_delta", "partial_json": "{\"q\":"}},
+            {"type": "content_block_delta", "index": 2, "delta": {"type": "input_json_delta", "partial_json": "\"x\"}"}},
+            {"type": "content_block_stop", "index": 2},
+            {"type": "message_delta", "delta": {"stop_reason": "tool_use", "stop_sequence": null}, "usage": {"output_tokens": 9}},
+            {"type": "message_stop"}
+        ]);
+        serde_json::from_value(raw).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_record_writes_redacted_transcript() {
+        let mut transcript = TranscriptWriter::new(Vec::new()).omit_thinking(true);
+        transcript
+            .write_message(&MessageParam::user("Hello"))
+            .await
+            .unwrap();
+        let message = transcript
+            .record(MessageStream::from_events(events()))
+            .await
+            .unwrap();
+        assert_eq!(message.content.len(), 3);
+
+        let output = String::from_utf8(transcript.into_inner().await.unwrap()).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert!(lines[0].ends_with("] user: Hello"));
+        assert!(lines[1].ends_with("] assistant: message msg_1 (model claude-opus-4-6)"));
+        assert!(lines[2].ends_with("] assistant thinking: [omitted]"));
+        assert!(lines[3].ends_with("] assistant: Your key is [REDACTED]"));
+        assert!(lines[4].ends_with(r#"] assistant tool_use lookup (toolu_1): {"q":"x"}"#));
+        assert!(lines[5].ends_with("] stop: tool_use (output tokens 9)"));
+        assert!(!output.contains("secret plan"));
+    }
+
+    #[tokio::test]
+    async fn test_record_writes_then_returns_stream_error() {
+        let mut events = events();
+        events.truncate(4);
+        events.push(
+            serde_json::from_value(serde_json::json!({
+                "type": "error",
+                "error": {"type": "overloaded_error", "message": "Overloaded"}
+            }))
+            .unwrap(),
+        );
+        let mut transcript = TranscriptWriter::new(Vec::new());
+        let result = transcript.record(MessageStream::from_events(events)).await;
+        assert!(matches!(result, Err(Error::StreamError(_))));
+
+        let output = String::from_utf8(transcript.into_inner().await.unwrap()).unwrap();
+        assert!(output.contains("] assistant thinking: secret plan\n"));
+        assert!(output.ends_with("] error: overloaded_error: Overloaded\n"));
+    }
+}
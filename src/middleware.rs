@@ -1,10 +1,93 @@
 use std::future::Future;
 use std::pin::Pin;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use reqwest::header::HeaderMap;
 
 /// A boxed future that is Send, used for middleware return types.
 pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
 
+/// Typed values shared by the middlewares handling one attempt.
+///
+/// Every `Next` in a chain carries the same map, so a middleware can leave
+/// state for the ones after it (or read what they left once `next.run`
+/// returns) without smuggling it through request headers. Values are cloned
+/// out, so no lock is held across an `.await`.
+#[derive(Debug, Clone, Default)]
+pub struct Extensions(Arc<Mutex<http::Extensions>>);
+
+impl Extensions {
+    /// Insert `value`, returning the previous value of the same type.
+    pub fn insert<T: Clone + Send + Sync + 'static>(&self, value: T) -> Option<T> {
+        self.0.lock().unwrap().insert(value)
+    }
+
+    /// A copy of the value of type `T`, if one has been inserted.
+    pub fn get<T: Clone + Send + Sync + 'static>(&self) -> Option<T> {
+        self.0.lock().unwrap().get::<T>().cloned()
+    }
+
+    /// Remove and return the value of type `T`.
+    pub fn remove<T: Clone + Send + Sync + 'static>(&self) -> Option<T> {
+        self.0.lock().unwrap().remove::<T>()
+    }
+}
+
+/// Path segments that name an action on the resource before them rather than
+/// a resource ID.
+const OPERATION_ACTIONS: &[&str] = &["cancel", "content", "count_tokens", "results"];
+
+/// Path segments that name a collection even where an ID could appear.
+const OPERATION_COLLECTIONS: &[&str] = &["batches", "versions"];
+
+/// Describes the API call an attempt belongs to.
+///
+/// The client inserts one into the chain's `Extensions` for every attempt, so
+/// middleware can read it with `next.extensions().get::<OperationInfo>()`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OperationInfo {
+    /// Dotted operation name, e.g. `messages.create`, `messages.batches.list`
+    /// or `files.content`.
+    pub name: String,
+    /// Zero-based retry attempt.
+    pub attempt: u32,
+    /// Whether the response is streamed as server-sent events.
+    pub streaming: bool,
+}
+
+/// Name the operation for `method` on `path` (relative to `/v1/`).
+///
+/// Collections end in `list` or `create`, single resources in `retrieve`,
+/// `update` or `delete`, and action paths take the action's name.
+pub(crate) fn operation_name(method: &str, path: &str) -> String {
+    let path = path.split('?').next().unwrap_or_default();
+    let mut parts: Vec<&str> = Vec::new();
+    let mut on_item = false;
+    for segment in path.split('/').filter(|segment| !segment.is_empty()) {
+        if OPERATION_ACTIONS.contains(&segment) {
+            parts.push(segment);
+            return parts.join(".");
+        }
+        if !parts.is_empty() && !on_item && !OPERATION_COLLECTIONS.contains(&segment) {
+            on_item = true;
+            continue;
+        }
+        parts.push(segment);
+        on_item = false;
+    }
+    let action = match (method, on_item) {
+        ("GET", false) => "list",
+        ("GET", true) => "retrieve",
+        ("POST", false) => "create",
+        ("POST", true) => "update",
+        ("DELETE", _) => "delete",
+        _ => return format!("{}.{}", parts.join("."), method.to_ascii_lowercase()),
+    };
+    parts.push(action);
+    parts.join(".")
+}
+
 /// Represents the next handler in the middleware chain.
 #[derive(Clone)]
 pub struct Next<'a> {
@@ -14,6 +97,7 @@ pub struct Next<'a> {
             + Sync
             + 'a,
     >,
+    extensions: Extensions,
 }
 
 impl<'a> Next<'a> {
@@ -23,7 +107,26 @@ impl<'a> Next<'a> {
         + Sync
         + 'a,
     ) -> Self {
-        Self { inner: Arc::new(f) }
+        Self::with_extensions(f, Extensions::default())
+    }
+
+    /// Like `new`, sharing `extensions` with the rest of the chain.
+    pub fn with_extensions(
+        f: impl Fn(reqwest::Request) -> BoxFuture<'a, Result<reqwest::Response, crate::error::Error>>
+        + Send
+        + Sync
+        + 'a,
+        extensions: Extensions,
+    ) -> Self {
+        Self {
+            inner: Arc::new(f),
+            extensions,
+        }
+    }
+
+    /// The values shared by the middlewares handling this attempt.
+    pub fn extensions(&self) -> &Extensions {
+        &self.extensions
     }
 
     pub fn run(
@@ -38,6 +141,12 @@ impl<'a> Next<'a> {
 ///
 /// Used by Bedrock and Vertex integrations to rewrite requests
 /// (e.g., sign with SigV4, inject OAuth tokens, rewrite URLs).
+///
+/// A middleware may also change the response `next.run` returns before
+/// passing it back; implement `ResponseHook` when only the response matters.
+///
+/// Implementing `AsyncMiddleware` instead avoids the boxing: every
+/// `AsyncMiddleware` is also a `Middleware`.
 pub trait Middleware: Send + Sync {
     fn handle<'a>(
         &'a self,
@@ -46,6 +155,166 @@ pub trait Middleware: Send + Sync {
     ) -> BoxFuture<'a, Result<reqwest::Response, crate::error::Error>>;
 }
 
+/// Middleware written as an `async fn`.
+///
+/// ```ignore
+/// struct Tag;
+///
+/// impl AsyncMiddleware for Tag {
+///     async fn handle(&self, mut request: reqwest::Request, next: Next<'_>) -> Result<reqwest::Response, Error> {
+///         request.headers_mut().insert("x-team", HeaderValue::from_static("search"));
+///         next.run(request).await
+///     }
+/// }
+///
+/// let client = Client::builder().middleware(Tag).build();
+/// ```
+pub trait AsyncMiddleware: Send + Sync {
+    fn handle(
+        &self,
+        request: reqwest::Request,
+        next: Next<'_>,
+    ) -> impl Future<Output = Result<reqwest::Response, crate::error::Error>> + Send;
+}
+
+impl<M: AsyncMiddleware> Middleware for M {
+    fn handle<'a>(
+        &'a self,
+        request: reqwest::Request,
+        next: Next<'a>,
+    ) -> BoxFuture<'a, Result<reqwest::Response, crate::error::Error>> {
+        Box::pin(AsyncMiddleware::handle(self, request, next))
+    }
+}
+
+/// The method, URL and headers of the request a response answers.
+#[derive(Debug, Clone)]
+pub struct RequestHead {
+    pub method: reqwest::Method,
+    pub url: reqwest::Url,
+    pub headers: HeaderMap,
+    /// The values shared by the middlewares handling this attempt.
+    pub extensions: Extensions,
+}
+
+/// A hook that inspects or rewrites responses on their way back through the
+/// middleware chain.
+///
+/// The response is split into an `http::Response`, so its status and headers
+/// can be changed in place and its body replaced, e.g. to translate a
+/// provider's streaming format into SSE or to serve a cached body. Add a hook
+/// with `ClientBuilder::response_hook`; it sees the response after every
+/// middleware added later has handled it.
+pub trait ResponseHook: Send + Sync {
+    fn on_response<'a>(
+        &'a self,
+        request: &'a RequestHead,
+        response: http::Response<reqwest::Body>,
+    ) -> BoxFuture<'a, Result<http::Response<reqwest::Body>, crate::error::Error>>;
+}
+
+/// Runs a `ResponseHook` as a middleware.
+pub struct ResponseMiddleware<H>(pub H);
+
+impl<H: ResponseHook> Middleware for ResponseMiddleware<H> {
+    fn handle<'a>(
+        &'a self,
+        request: reqwest::Request,
+        next: Next<'a>,
+    ) -> BoxFuture<'a, Result<reqwest::Response, crate::error::Error>> {
+        Box::pin(async move {
+            let head = RequestHead {
+                method: request.method().clone(),
+                url: request.url().clone(),
+                headers: request.headers().clone(),
+                extensions: next.extensions().clone(),
+            };
+            let response = next.run(request).await?;
+            let url = response.url().clone();
+            let (parts, body) = self
+                .0
+                .on_response(&head, response.into())
+                .await?
+                .into_parts();
+
+            // Rebuild through the builder so the response keeps its URL.
+            let mut builder = http::Response::builder()
+                .status(parts.status)
+                .version(parts.version);
+            if let Some(extensions) = builder.extensions_mut() {
+                *extensions = parts.extensions;
+            }
+            if let Some(headers) = builder.headers_mut() {
+                *headers = parts.headers;
+            }
+            let response = reqwest::ResponseBuilderExt::url(builder, url)
+                .body(body)
+                .expect("parts are copied from a valid response");
+            Ok(reqwest::Response::from(response))
+        })
+    }
+}
+
+/// A request that can be rebuilt identically for every retry attempt.
+///
+/// The client captures each request once and sends a fresh copy per attempt,
+/// so whatever middleware changes (signatures, rewritten URLs or bodies)
+/// applies to that attempt only and is redone from the original on the next.
+#[derive(Debug, Clone)]
+pub struct ReplayableRequest {
+    method: reqwest::Method,
+    url: reqwest::Url,
+    headers: HeaderMap,
+    body: Option<bytes::Bytes>,
+    timeout: Option<Duration>,
+    version: reqwest::Version,
+}
+
+impl ReplayableRequest {
+    /// Capture `request`, or return `None` if its body is a stream that
+    /// cannot be replayed.
+    pub fn from_request(request: &reqwest::Request) -> Option<Self> {
+        let body = match request.body() {
+            None => None,
+            Some(body) => Some(bytes::Bytes::copy_from_slice(body.as_bytes()?)),
+        };
+        Some(Self {
+            method: request.method().clone(),
+            url: request.url().clone(),
+            headers: request.headers().clone(),
+            body,
+            timeout: request.timeout().copied(),
+            version: request.version(),
+        })
+    }
+
+    pub fn method(&self) -> &reqwest::Method {
+        &self.method
+    }
+
+    pub fn url(&self) -> &reqwest::Url {
+        &self.url
+    }
+
+    pub fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+
+    pub fn body(&self) -> Option<&[u8]> {
+        self.body.as_deref()
+    }
+
+    /// Build a fresh copy of the original request for one attempt.
+    pub fn to_request(&self) -> reqwest::Request {
+        let mut request = reqwest::Request::new(self.method.clone(), self.url.clone());
+        *request.headers_mut() = self.headers.clone();
+        *request.body_mut() = self.body.clone().map(reqwest::Body::from);
+        *request.timeout_mut() = self.timeout;
+        *request.version_mut() = self.version;
+        request
+    }
+}
+
 /// Execute a request through a middleware chain, calling the final handler at the end.
 pub fn execute_middleware_chain<'a>(
     middlewares: &'a [Box<dyn Middleware>],
@@ -54,34 +323,52 @@ pub fn execute_middleware_chain<'a>(
     + Send
     + Sync
     + 'a,
+) -> BoxFuture<'a, Result<reqwest::Response, crate::error::Error>> {
+    execute_middleware_chain_with_extensions(middlewares, request, Extensions::default(), handler)
+}
+
+/// Like `execute_middleware_chain`, sharing `extensions` with every
+/// middleware in the chain.
+pub fn execute_middleware_chain_with_extensions<'a>(
+    middlewares: &'a [Box<dyn Middleware>],
+    request: reqwest::Request,
+    extensions: Extensions,
+    handler: impl Fn(reqwest::Request) -> BoxFuture<'a, Result<reqwest::Response, crate::error::Error>>
+    + Send
+    + Sync
+    + 'a,
 ) -> BoxFuture<'a, Result<reqwest::Response, crate::error::Error>> {
     if middlewares.is_empty() {
         return handler(request);
     }
 
-    let next = build_chain(middlewares, handler);
+    let next = build_chain(middlewares, extensions, handler);
     next.run(request)
 }
 
 /// Recursively build the middleware chain from the inside out.
 fn build_chain<'a>(
     middlewares: &'a [Box<dyn Middleware>],
+    extensions: Extensions,
     handler: impl Fn(reqwest::Request) -> BoxFuture<'a, Result<reqwest::Response, crate::error::Error>>
     + Send
     + Sync
     + 'a,
 ) -> Next<'a> {
     if middlewares.is_empty() {
-        return Next::new(handler);
+        return Next::with_extensions(handler, extensions);
     }
 
     let (first, rest) = middlewares.split_first().unwrap();
-    let inner_next = build_chain(rest, handler);
+    let inner_next = build_chain(rest, extensions.clone(), handler);
 
-    Next::new(move |req| {
-        let inner_clone = inner_next.clone();
-        first.handle(req, inner_clone)
-    })
+    Next::with_extensions(
+        move |req| {
+            let inner_clone = inner_next.clone();
+            first.handle(req, inner_clone)
+        },
+        extensions,
+    )
 }
 
 #[cfg(test)]
@@ -157,4 +444,354 @@ mod tests {
             .unwrap();
         assert_eq!(resp.status(), 200);
     }
+
+    #[test]
+    fn test_replayable_request_round_trip() {
+        let mut request = reqwest::Request::new(
+            reqwest::Method::POST,
+            "https://example.com".parse().unwrap(),
+        );
+        request
+            .headers_mut()
+            .insert("x-test", "hello".parse().unwrap());
+        *request.body_mut() = Some(reqwest::Body::from("{\"a\":1}"));
+
+        let replayable = ReplayableRequest::from_request(&request).unwrap();
+        let mut first = replayable.to_request();
+        first
+            .headers_mut()
+            .insert("x-test", "changed".parse().unwrap());
+        *first.body_mut() = Some(reqwest::Body::from("{}"));
+
+        let second = replayable.to_request();
+        assert_eq!(second.method(), reqwest::Method::POST);
+        assert_eq!(second.headers().get("x-test").unwrap(), "hello");
+        assert_eq!(
+            second.body().and_then(|b| b.as_bytes()),
+            Some(&b"{\"a\":1}"[..])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_retries_replay_original_request() {
+        use std::sync::Mutex;
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        /// Signs each request and rewrites its body, recording what it saw.
+        struct Signing(Arc<Mutex<Vec<Vec<u8>>>>);
+
+        impl Middleware for Signing {
+            fn handle<'a>(
+                &'a self,
+                mut request: reqwest::Request,
+                next: Next<'a>,
+            ) -> BoxFuture<'a, Result<reqwest::Response, crate::error::Error>> {
+                Box::pin(async move {
+                    let body = request.body().and_then(|b| b.as_bytes()).unwrap().to_vec();
+                    assert!(!request.headers().contains_key("x-signature"));
+                    self.0.lock().unwrap().push(body.clone());
+
+                    request
+                        .headers_mut()
+                        .append("x-signature", "sig".parse().unwrap());
+                    let mut value: serde_json::Value = serde_json::from_slice(&body).unwrap();
+                    value["signed"] = serde_json::Value::Bool(true);
+                    *request.body_mut() = Some(serde_json::to_vec(&value).unwrap().into());
+                    next.run(request).await
+                })
+            }
+        }
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/jobs"))
+            .respond_with(ResponseTemplate::new(500))
+            .up_to_n_times(2)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/v1/jobs"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({})))
+            .mount(&server)
+            .await;
+
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let client = crate::client::ClientBuilder::new()
+            .api_key("test")
+            .base_url(server.uri())
+            .middleware(Signing(seen.clone()))
+            .build();
+        let _: serde_json::Value = client
+            .post("jobs", &serde_json::json!({"job": 1}), None)
+            .await
+            .unwrap();
+
+        let bodies = seen.lock().unwrap().clone();
+        assert_eq!(bodies.len(), 3);
+        for body in bodies {
+            assert_eq!(body, br#"{"job":1}"#);
+        }
+        for request in server.received_requests().await.unwrap() {
+            assert_eq!(request.headers.get_all("x-signature").iter().count(), 1);
+            assert_eq!(
+                request.body_json::<serde_json::Value>().unwrap(),
+                serde_json::json!({"job": 1, "signed": true})
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_response_hook_rewrites_response() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        /// Doubles the `value` field and tags the response.
+        struct Double;
+
+        impl ResponseHook for Double {
+            fn on_response<'a>(
+                &'a self,
+                request: &'a RequestHead,
+                response: http::Response<reqwest::Body>,
+            ) -> BoxFuture<'a, Result<http::Response<reqwest::Body>, crate::error::Error>>
+            {
+                Box::pin(async move {
+                    assert_eq!(request.url.path(), "/v1/values/a");
+                    let (mut parts, body) = response.into_parts();
+                    let bytes = reqwest::Response::from(http::Response::new(body))
+                        .bytes()
+                        .await?;
+                    let mut value: serde_json::Value = serde_json::from_slice(&bytes)?;
+                    value["value"] = serde_json::json!(value["value"].as_i64().unwrap() * 2);
+                    parts.headers.insert("x-doubled", "true".parse().unwrap());
+                    parts.headers.remove(reqwest::header::CONTENT_LENGTH);
+                    let body = reqwest::Body::from(serde_json::to_vec(&value)?);
+                    Ok(http::Response::from_parts(parts, body))
+                })
+            }
+        }
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/values/a"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({"value": 21})),
+            )
+            .mount(&server)
+            .await;
+
+        let client = crate::client::ClientBuilder::new()
+            .api_key("test")
+            .base_url(server.uri())
+            .response_hook(Double)
+            .build();
+        let (value, meta) = client
+            .post_with_response::<serde_json::Value>("values/a", &serde_json::json!({}), None)
+            .await
+            .unwrap();
+        assert_eq!(value, serde_json::json!({"value": 42}));
+        assert_eq!(meta.headers.get("x-doubled").unwrap(), "true");
+    }
+
+    #[tokio::test]
+    async fn test_extensions_shared_through_chain() {
+        #[derive(Clone, Debug, PartialEq)]
+        struct Tenant(&'static str);
+
+        struct SetTenant;
+
+        impl Middleware for SetTenant {
+            fn handle<'a>(
+                &'a self,
+                request: reqwest::Request,
+                next: Next<'a>,
+            ) -> BoxFuture<'a, Result<reqwest::Response, crate::error::Error>> {
+                Box::pin(async move {
+                    next.extensions().insert(Tenant("acme"));
+                    let response = next.run(request).await?;
+                    assert_eq!(next.extensions().get::<u16>(), Some(201));
+                    Ok(response)
+                })
+            }
+        }
+
+        struct CheckTenant;
+
+        impl Middleware for CheckTenant {
+            fn handle<'a>(
+                &'a self,
+                request: reqwest::Request,
+                next: Next<'a>,
+            ) -> BoxFuture<'a, Result<reqwest::Response, crate::error::Error>> {
+                Box::pin(async move {
+                    assert_eq!(next.extensions().get::<Tenant>(), Some(Tenant("acme")));
+                    let response = next.run(request).await?;
+                    next.extensions().insert(response.status().as_u16());
+                    Ok(response)
+                })
+            }
+        }
+
+        let middlewares: Vec<Box<dyn Middleware>> =
+            vec![Box::new(SetTenant), Box::new(CheckTenant)];
+        let handler = |_req: reqwest::Request| -> BoxFuture<'_, Result<reqwest::Response, crate::error::Error>> {
+            Box::pin(async {
+                Ok(reqwest::Response::from(
+                    http::Response::builder().status(201).body("").unwrap(),
+                ))
+            })
+        };
+
+        let extensions = Extensions::default();
+        let req =
+            reqwest::Request::new(reqwest::Method::GET, "https://example.com".parse().unwrap());
+        let resp = execute_middleware_chain_with_extensions(
+            &middlewares,
+            req,
+            extensions.clone(),
+            handler,
+        )
+        .await
+        .unwrap();
+        assert_eq!(resp.status(), 201);
+        assert_eq!(extensions.remove::<Tenant>(), Some(Tenant("acme")));
+        assert_eq!(extensions.get::<Tenant>(), None);
+    }
+
+    #[test]
+    fn test_operation_name() {
+        assert_eq!(operation_name("POST", "messages"), "messages.create");
+        assert_eq!(
+            operation_name("POST", "messages/count_tokens"),
+            "messages.count_tokens"
+        );
+        assert_eq!(
+            operation_name("GET", "messages/batches?limit=20"),
+            "messages.batches.list"
+        );
+        assert_eq!(
+            operation_name("POST", "messages/batches/msgbatch_1/cancel"),
+            "messages.batches.cancel"
+        );
+        assert_eq!(
+            operation_name("GET", "models/claude-opus-4-6"),
+            "models.retrieve"
+        );
+        assert_eq!(
+            operation_name("GET", "files/file_1/content"),
+            "files.content"
+        );
+        assert_eq!(
+            operation_name("DELETE", "skills/skill_1/versions/v1"),
+            "skills.versions.delete"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_operation_info_per_attempt() {
+        use std::sync::Mutex;
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        struct Record(Arc<Mutex<Vec<OperationInfo>>>);
+
+        impl Middleware for Record {
+            fn handle<'a>(
+                &'a self,
+                request: reqwest::Request,
+                next: Next<'a>,
+            ) -> BoxFuture<'a, Result<reqwest::Response, crate::error::Error>> {
+                Box::pin(async move {
+                    let operation = next.extensions().get::<OperationInfo>().unwrap();
+                    self.0.lock().unwrap().push(operation);
+                    next.run(request).await
+                })
+            }
+        }
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v1/models/m"))
+            .respond_with(ResponseTemplate::new(503))
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/v1/models/m"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({})))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/v1/messages"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(""))
+            .mount(&server)
+            .await;
+
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let client = crate::client::ClientBuilder::new()
+            .api_key("test")
+            .base_url(server.uri())
+            .middleware(Record(seen.clone()))
+            .build();
+        let _: serde_json::Value = client.get("models/m", None).await.unwrap();
+        client
+            .execute_streaming("messages", &serde_json::json!({}), None)
+            .await
+            .unwrap();
+
+        let seen = seen.lock().unwrap().clone();
+        let retrieve = |attempt| OperationInfo {
+            name: "models.retrieve".to_string(),
+            attempt,
+            streaming: false,
+        };
+        assert_eq!(
+            seen,
+            [
+                retrieve(0),
+                retrieve(1),
+                OperationInfo {
+                    name: "messages.create".to_string(),
+                    attempt: 0,
+                    streaming: true,
+                }
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_async_middleware() {
+        struct AsyncHeader;
+
+        impl AsyncMiddleware for AsyncHeader {
+            async fn handle(
+                &self,
+                mut request: reqwest::Request,
+                next: Next<'_>,
+            ) -> Result<reqwest::Response, crate::error::Error> {
+                request
+                    .headers_mut()
+                    .insert("x-async", "yes".parse().unwrap());
+                next.run(request).await
+            }
+        }
+
+        let middlewares: Vec<Box<dyn Middleware>> = vec![Box::new(AsyncHeader)];
+        let handler = |req: reqwest::Request| -> BoxFuture<'_, Result<reqwest::Response, crate::error::Error>> {
+            Box::pin(async move {
+                assert_eq!(req.headers().get("x-async").unwrap(), "yes");
+                Ok(reqwest::Response::from(
+                    http::Response::builder().status(200).body("").unwrap(),
+                ))
+            })
+        };
+
+        let req =
+            reqwest::Request::new(reqwest::Method::GET, "https://example.com".parse().unwrap());
+        let resp = execute_middleware_chain(&middlewares, req, handler)
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), 200);
+    }
 }
@@ -0,0 +1,503 @@
+//! A mock Anthropic API server for integration tests.
+//!
+//! Enabled with the `test-util` feature. [`MockAnthropic`] runs a local
+//! server with expectations for the common endpoints and canned responses,
+//! so tests can exercise code built on the client without a network.
+//!
+//! ```ignore
+//! use uno_anthropic::mock::{MockAnthropic, MockResponse};
+//!
+//! let mock = MockAnthropic::start().await;
+//! mock.expect_create()
+//!     .times(1)
+//!     .respond_with(MockResponse::text("Hello!"))
+//!     .await;
+//!
+//! let message = mock.client().messages().create(params).await?;
+//! assert_eq!(message.text(), "Hello!");
+//! ```
+
+use wiremock::matchers::{body_partial_json, method, path};
+use wiremock::{Match, Mock, MockBuilder, MockServer, Request, ResponseTemplate};
+
+use crate::client::{Client, ClientBuilder};
+use crate::types::content::ContentBlock;
+use crate::types::message::Message;
+
+/// Model reported by canned responses.
+const MOCK_MODEL: &str = "claude-opus-4-6";
+
+/// A local server that answers requests like the Anthropic API.
+///
+/// Requests without a matching expectation get a 404.
+pub struct MockAnthropic {
+    server: MockServer,
+}
+
+impl MockAnthropic {
+    /// Start a server on a random local port.
+    pub async fn start() -> Self {
+        Self {
+            server: MockServer::start().await,
+        }
+    }
+
+    /// The base URL of the server.
+    pub fn uri(&self) -> String {
+        self.server.uri()
+    }
+
+    /// The underlying wiremock server, for expectations the helpers don't cover.
+    pub fn server(&self) -> &MockServer {
+        &self.server
+    }
+
+    /// A client builder pointed at the server, with retries disabled so
+    /// failures surface immediately.
+    pub fn client_builder(&self) -> ClientBuilder {
+        ClientBuilder::new()
+            .api_key("sk-ant-mock")
+            .base_url(self.uri())
+            .max_retries(0)
+    }
+
+    /// A client pointed at the server.
+    pub fn client(&self) -> Client {
+        self.client_builder().build()
+    }
+
+    /// Expect non-streaming `POST /v1/messages` requests.
+    pub fn expect_create(&self) -> Expectation<'_> {
+        self.expect_messages(false)
+    }
+
+    /// Expect streaming `POST /v1/messages` requests.
+    pub fn expect_stream(&self) -> Expectation<'_> {
+        self.expect_messages(true)
+    }
+
+    /// Expect `POST /v1/messages/count_tokens` requests.
+    pub fn expect_count_tokens(&self) -> Expectation<'_> {
+        self.expect("POST", "/v1/messages/count_tokens")
+    }
+
+    /// Expect `POST /v1/messages/batches` requests.
+    pub fn expect_batch_create(&self) -> Expectation<'_> {
+        self.expect("POST", "/v1/messages/batches")
+    }
+
+    /// Expect `GET /v1/messages/batches/{batch_id}` requests.
+    pub fn expect_batch_get(&self, batch_id: &str) -> Expectation<'_> {
+        self.expect("GET", &format!("/v1/messages/batches/{batch_id}"))
+    }
+
+    /// Expect `GET /v1/messages/batches/{batch_id}/results` requests.
+    pub fn expect_batch_results(&self, batch_id: &str) -> Expectation<'_> {
+        self.expect("GET", &format!("/v1/messages/batches/{batch_id}/results"))
+    }
+
+    /// Expect requests with `http_method` to `url_path`, e.g. `("GET", "/v1/models")`.
+    pub fn expect(&self, http_method: &str, url_path: &str) -> Expectation<'_> {
+        Expectation::new(
+            &self.server,
+            Mock::given(method(http_method)).and(path(url_path)),
+        )
+    }
+
+    /// The JSON bodies of every request received so far, in order.
+    ///
+    /// Requests without a JSON body are reported as `null`.
+    pub async fn received_bodies(&self) -> Vec<serde_json::Value> {
+        self.server
+            .received_requests()
+            .await
+            .unwrap_or_default()
+            .iter()
+            .map(|request| request.body_json().unwrap_or_default())
+            .collect()
+    }
+
+    fn expect_messages(&self, streaming: bool) -> Expectation<'_> {
+        Expectation::new(
+            &self.server,
+            Mock::given(method("POST"))
+                .and(path("/v1/messages"))
+                .and(Streaming(streaming)),
+        )
+    }
+}
+
+/// An expectation being set up on a `MockAnthropic`, mounted by `respond_with`.
+pub struct Expectation<'a> {
+    server: &'a MockServer,
+    mock: MockBuilder,
+    times: Option<u64>,
+    up_to: Option<u64>,
+}
+
+impl<'a> Expectation<'a> {
+    fn new(server: &'a MockServer, mock: MockBuilder) -> Self {
+        Self {
+            server,
+            mock,
+            times: None,
+            up_to: None,
+        }
+    }
+
+    /// Only match requests whose JSON body contains `body`.
+    pub fn with_body(mut self, body: serde_json::Value) -> Self {
+        self.mock = self.mock.and(body_partial_json(body));
+        self
+    }
+
+    /// Fail the test when the server is dropped unless exactly `count`
+    /// requests matched.
+    pub fn times(mut self, count: u64) -> Self {
+        self.times = Some(count);
+        self
+    }
+
+    /// Stop matching after `count` requests, so a later expectation can
+    /// answer the ones after it.
+    pub fn up_to(mut self, count: u64) -> Self {
+        self.up_to = Some(count);
+        self
+    }
+
+    /// Answer matching requests with `response`.
+    pub async fn respond_with(self, response: MockResponse) {
+        let mut mock = self.mock.respond_with(response.0);
+        if let Some(count) = self.up_to {
+            mock = mock.up_to_n_times(count);
+        }
+        if let Some(count) = self.times {
+            mock = mock.expect(count);
+        }
+        mock.mount(self.server).await;
+    }
+}
+
+/// A canned API response.
+#[derive(Debug, Clone)]
+pub struct MockResponse(ResponseTemplate);
+
+impl MockResponse {
+    /// A 200 response with a JSON body.
+    pub fn json(body: serde_json::Value) -> Self {
+        Self(ResponseTemplate::new(200).set_body_json(body))
+    }
+
+    /// A complete `message`.
+    pub fn message(message: &Message) -> Self {
+        Self(ResponseTemplate::new(200).set_body_json(message))
+    }
+
+    /// A message with a single text block.
+    pub fn text(text: &str) -> Self {
+        Self::message(&text_message(text))
+    }
+
+    /// `message` as a stream of server-sent events.
+    ///
+    /// Text and thinking arrive as one delta each and tool inputs as one
+    /// partial JSON delta; other blocks arrive whole.
+    pub fn stream(message: &Message) -> Self {
+        Self(ResponseTemplate::new(200).set_body_raw(sse_body(message), "text/event-stream"))
+    }
+
+    /// A streamed message with a single text block.
+    pub fn text_stream(text: &str) -> Self {
+        Self::stream(&text_message(text))
+    }
+
+    /// A token count.
+    pub fn token_count(input_tokens: u32) -> Self {
+        Self::json(serde_json::json!({"input_tokens": input_tokens}))
+    }
+
+    /// A message batch with `processing_status` (e.g. `in_progress` or
+    /// `ended`).
+    pub fn batch(batch_id: &str, processing_status: &str) -> Self {
+        let ended = processing_status == "ended";
+        Self::json(serde_json::json!({
+            "id": batch_id,
+            "type": "message_batch",
+            "processing_status": processing_status,
+            "request_counts": {
+                "processing": if ended { 0 } else { 1 },
+                "succeeded": if ended { 1 } else { 0 },
+                "errored": 0,
+                "canceled": 0,
+                "expired": 0
+            },
+            "ended_at": ended.then_some("2025-01-01T01:00:00Z"),
+            "created_at": "2025-01-01T00:00:00Z",
+            "expires_at": "2025-01-02T00:00:00Z",
+            "cancel_initiated_at": null,
+            "results_url": ended.then(|| format!("/v1/messages/batches/{batch_id}/results"))
+        }))
+    }
+
+    /// Batch results as JSONL, one succeeded result per `(custom_id, message)`.
+    pub fn batch_results<'m>(results: impl IntoIterator<Item = (&'m str, &'m Message)>) -> Self {
+        let mut body = String::new();
+        for (custom_id, message) in results {
+            let line = serde_json::json!({
+                "custom_id": custom_id,
+                "result": {"type": "succeeded", "message": message}
+            });
+            body.push_str(&line.to_string());
+            body.push('\n');
+        }
+        Self(ResponseTemplate::new(200).set_body_raw(body, "application/x-jsonl"))
+    }
+
+    /// An API error, e.g. `error(429, "rate_limit_error", "Slow down")`.
+    pub fn error(status: u16, error_type: &str, message: &str) -> Self {
+        Self(
+            ResponseTemplate::new(status).set_body_json(serde_json::json!({
+                "type": "error",
+                "error": {"type": error_type, "message": message}
+            })),
+        )
+    }
+
+    /// A 529 `overloaded_error`.
+    pub fn overloaded() -> Self {
+        Self::error(529, "overloaded_error", "Overloaded")
+    }
+
+    /// Add a response header.
+    pub fn header(self, name: &str, value: &str) -> Self {
+        Self(self.0.insert_header(name, value))
+    }
+
+    /// Delay the response by `delay`, e.g. to test timeouts.
+    pub fn delay(self, delay: std::time::Duration) -> Self {
+        Self(self.0.set_delay(delay))
+    }
+}
+
+/// Matches `/v1/messages` requests by whether their body asks for a stream.
+struct Streaming(bool);
+
+impl Match for Streaming {
+    fn matches(&self, request: &Request) -> bool {
+        let stream = request
+            .body_json::<serde_json::Value>()
+            .ok()
+            .and_then(|body| body.get("stream").and_then(serde_json::Value::as_bool))
+            .unwrap_or(false);
+        stream == self.0
+    }
+}
+
+/// A finished assistant message with one text block.
+///
+/// Also the shared fixture for unit tests that need a response body.
+pub(crate) fn text_message(text: &str) -> Message {
+    serde_json::from_value(serde_json::json!({
+        "id": "msg_mock",
+        "type": "message",
+        "role": "assistant",
+        "content": [{"type": "text", "text": text}],
+        "model": MOCK_MODEL,
+        "stop_reason": "end_turn",
+        "stop_sequence": null,
+        "usage": {"input_tokens": 10, "output_tokens": text.split_whitespace().count().max(1)}
+    }))
+    .expect("canned message is valid")
+}
+
+/// The server-sent events that stream `message`.
+fn sse_body(message: &Message) -> String {
+    let mut body = String::new();
+    let mut event = |name: &str, mut data: serde_json::Value| {
+        data["type"] = name.into();
+        body.push_str(&format!("event: {name}\ndata: {data}\n\n"));
+    };
+
+    let mut start = serde_json::to_value(message).expect("messages serialize");
+    start["content"] = serde_json::json!([]);
+    start["stop_reason"] = serde_json::Value::Null;
+    start["stop_sequence"] = serde_json::Value::Null;
+    start["usage"]["output_tokens"] = 0.into();
+    event("message_start", serde_json::json!({"message": start}));
+
+    for (index, block) in message.content.iter().enumerate() {
+        let mut content_block = serde_json::to_value(block).expect("blocks serialize");
+        let delta = match block {
+            ContentBlock::Text(text) => {
+                content_block["text"] = "".into();
+                content_block["citations"] = serde_json::Value::Null;
+                Some(serde_json::json!({"type": "text_delta", "text": text.text}))
+            }
+            ContentBlock::Thinking(thinking) => {
+                content_block["thinking"] = "".into();
+                content_block["signature"] = "".into();
+                Some(serde_json::json!({"type": "thinking_delta", "thinking": thinking.thinking}))
+            }
+            ContentBlock::ToolUse(tool_use) => {
+                content_block["input"] = serde_json::json!({});
+                Some(serde_json::json!({
+                    "type": "input_json_delta",
+                    "partial_json": tool_use.input.to_string()
+                }))
+            }
+            _ => None,
+        };
+        event(
+            "content_block_start",
+            serde_json::json!({"index": index, "content_block": content_block}),
+        );
+        if let Some(delta) = delta {
+            event(
+                "content_block_delta",
+                serde_json::json!({"index": index, "delta": delta}),
+            );
+        }
+        if let ContentBlock::Thinking(thinking) = block {
+            event(
+                "content_block_delta",
+                serde_json::json!({
+                    "index": index,
+                    "delta": {"type": "signature_delta", "signature": thinking.signature}
+                }),
+            );
+        }
+        event("content_block_stop", serde_json::json!({"index": index}));
+    }
+
+    event(
+        "message_delta",
+        serde_json::json!({
+            "delta": {"stop_reason": message.stop_reason, "stop_sequence": message.stop_sequence},
+            "usage": {"output_tokens": message.usage.output_tokens}
+        }),
+    );
+    event("message_stop", serde_json::json!({}));
+    body
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::StreamExt;
+
+    use super::*;
+    use crate::messages::params::MessageCreateParams;
+    use crate::types::message::MessageParam;
+    use crate::types::model::Model;
+
+    fn params() -> MessageCreateParams {
+        MessageCreateParams::builder()
+            .model(Model::ClaudeOpus4_6)
+            .max_tokens(100)
+            .messages(vec![MessageParam::user("Hi")])
+            .build()
+    }
+
+    #[tokio::test]
+    async fn test_create_and_stream() {
+        let mock = MockAnthropic::start().await;
+        mock.expect_create()
+            .times(1)
+            .respond_with(MockResponse::text("Hello there"))
+            .await;
+        mock.expect_stream()
+            .times(1)
+            .respond_with(MockResponse::text_stream("Streamed hello"))
+            .await;
+
+        let client = mock.client();
+        let message = client.messages().create(params()).await.unwrap();
+        assert_eq!(message.text(), "Hello there");
+
+        let streamed = client
+            .messages()
+            .create_stream(params())
+            .await
+            .unwrap()
+            .accumulate()
+            .await
+            .unwrap();
+        assert_eq!(streamed.text(), "Streamed hello");
+        assert_eq!(streamed.usage.output_tokens, 2);
+
+        let bodies = mock.received_bodies().await;
+        assert_eq!(bodies.len(), 2);
+        assert_eq!(bodies[1]["stream"], true);
+    }
+
+    #[tokio::test]
+    async fn test_stream_tool_use_and_thinking() {
+        let message: Message = serde_json::from_value(serde_json::json!({
+            "id": "msg_1",
+            "type": "message",
+            "role": "assistant",
+            "content": [
+                {"type": "thinking", "thinking": "Weather needs a tool.", "signature": "sig"},
+                {"type": "tool_use", "id": "toolu_1", "name": "weather", "input": {"city": "Paris"}}
+            ],
+            "model": MOCK_MODEL,
+            "stop_reason": "tool_use",
+            "usage": {"input_tokens": 5, "output_tokens": 7}
+        }))
+        .unwrap();
+
+        let mock = MockAnthropic::start().await;
+        mock.expect_stream()
+            .respond_with(MockResponse::stream(&message))
+            .await;
+        let streamed = mock
+            .client()
+            .messages()
+            .create_stream(params())
+            .await
+            .unwrap()
+            .accumulate()
+            .await
+            .unwrap();
+        assert_eq!(streamed.content, message.content);
+        assert_eq!(streamed.stop_reason, message.stop_reason);
+    }
+
+    #[tokio::test]
+    async fn test_errors_and_batches() {
+        let mock = MockAnthropic::start().await;
+        mock.expect_create()
+            .up_to(1)
+            .respond_with(MockResponse::overloaded())
+            .await;
+        mock.expect_create()
+            .respond_with(MockResponse::text("ok"))
+            .await;
+        mock.expect_batch_get("msgbatch_1")
+            .respond_with(MockResponse::batch("msgbatch_1", "ended"))
+            .await;
+        let result = text_message("done");
+        mock.expect_batch_results("msgbatch_1")
+            .respond_with(MockResponse::batch_results([("req_1", &result)]))
+            .await;
+
+        let client = mock.client();
+        let err = client.messages().create(params()).await.unwrap_err();
+        assert!(matches!(err, crate::error::Error::Overloaded { .. }));
+        assert_eq!(
+            client.messages().create(params()).await.unwrap().text(),
+            "ok"
+        );
+
+        let batch = client.batches().get("msgbatch_1").await.unwrap();
+        assert!(batch.results_url.is_some());
+        let results: Vec<_> = client
+            .batches()
+            .results("msgbatch_1")
+            .await
+            .unwrap()
+            .collect()
+            .await;
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].as_ref().unwrap().custom_id, "req_1");
+    }
+}
@@ -1,27 +1,70 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use reqwest::header::HeaderMap;
 use serde::Serialize;
 
-use crate::client::Client;
+use crate::client::{Client, RequestOptions};
 use crate::error::Error;
+use crate::types::model::Model;
 use crate::types::{ModelInfo, Page};
 
+/// Page size used by `list_all`, the largest the API accepts.
+const LIST_ALL_PAGE_SIZE: u32 = 1000;
+
 /// Service for the Models API.
 ///
 /// Access via `client.models()`.
 pub struct ModelService<'a> {
     pub(crate) client: &'a Client,
+    pub(crate) extra_headers: Option<HeaderMap>,
 }
 
 impl<'a> ModelService<'a> {
-    pub(crate) fn new(client: &'a Client) -> Self {
-        Self { client }
+    /// Create a service bound to `client`. Equivalent to `client.models()`.
+    pub fn new(client: &'a Client) -> Self {
+        Self {
+            client,
+            extra_headers: None,
+        }
+    }
+
+    /// Create a service that sends `headers` with every request.
+    pub fn with_extra_headers(client: &'a Client, headers: HeaderMap) -> Self {
+        Self {
+            client,
+            extra_headers: Some(headers),
+        }
+    }
+
+    /// Send the headers in `options` with requests made through this service.
+    pub fn with_options(mut self, options: RequestOptions) -> Self {
+        self.extra_headers = options.merge_into(self.extra_headers.take());
+        self
     }
 
     /// Get information about a specific model.
     ///
     /// Calls `GET /v1/models/{model_id}`.
+    ///
+    /// With `ClientBuilder::model_cache_ttl`, a fresh cached model list is
+    /// searched first.
     pub async fn get(&self, model_id: &str) -> Result<ModelInfo, Error> {
+        let cached = self
+            .client
+            .inner
+            .model_cache
+            .as_ref()
+            .and_then(ModelCache::get);
+        if let Some((_, info)) = cached
+            .into_iter()
+            .flatten()
+            .find(|(_, info)| info.id == model_id)
+        {
+            return Ok(info);
+        }
         let path = format!("models/{}", model_id);
-        self.client.get(&path, None).await
+        self.client.get(&path, self.extra_headers.as_ref()).await
     }
 
     /// List available models.
@@ -35,7 +78,91 @@ impl<'a> ModelService<'a> {
         } else {
             format!("models?{}", query)
         };
-        self.client.get(&path, None).await
+        self.client.get(&path, self.extra_headers.as_ref()).await
+    }
+
+    /// Fetch the page after `page`, or `None` if it was the last one.
+    ///
+    /// The next page uses the API's default page size.
+    pub async fn next_page(
+        &self,
+        page: &Page<ModelInfo>,
+    ) -> Result<Option<Page<ModelInfo>>, Error> {
+        let Some(cursor) = page.next_cursor() else {
+            return Ok(None);
+        };
+        let params = ModelListParams {
+            after_id: Some(cursor.to_string()),
+            ..Default::default()
+        };
+        self.list(params).await.map(Some)
+    }
+
+    /// List every available model, following pagination to the end.
+    ///
+    /// Each id is also parsed with `Model::from_str_lossy`, so models this
+    /// crate doesn't know yet come back as `Model::Other`. With
+    /// `ClientBuilder::model_cache_ttl`, the result is cached and reused
+    /// until it expires.
+    pub async fn list_all(&self) -> Result<Vec<(Model, ModelInfo)>, Error> {
+        let cache = self.client.inner.model_cache.as_ref();
+        if let Some(models) = cache.and_then(ModelCache::get) {
+            return Ok(models);
+        }
+
+        let mut models = Vec::new();
+        let mut params = ModelListParams {
+            limit: Some(LIST_ALL_PAGE_SIZE),
+            ..Default::default()
+        };
+        loop {
+            let mut page = self.list(params.clone()).await?;
+            models.extend(
+                std::mem::take(&mut page.data)
+                    .into_iter()
+                    .map(|info| (Model::from_str_lossy(&info.id), info)),
+            );
+            match page.next_cursor() {
+                Some(cursor) => params.after_id = Some(cursor.to_string()),
+                None => break,
+            }
+        }
+
+        if let Some(cache) = cache {
+            cache.set(models.clone());
+        }
+        Ok(models)
+    }
+}
+
+/// Models paired with the information the API returned for them.
+type ModelList = Vec<(Model, ModelInfo)>;
+
+/// The client-wide model list cache enabled by `ClientBuilder::model_cache_ttl`.
+pub(crate) struct ModelCache {
+    ttl: Duration,
+    entry: Mutex<Option<(Instant, ModelList)>>,
+}
+
+impl ModelCache {
+    pub(crate) fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entry: Mutex::new(None),
+        }
+    }
+
+    /// The cached list, if it has not expired.
+    fn get(&self) -> Option<ModelList> {
+        let entry = self.entry.lock().unwrap();
+        entry
+            .as_ref()
+            .filter(|(stored, _)| stored.elapsed() < self.ttl)
+            .map(|(_, models)| models.clone())
+    }
+
+    fn set(&self, models: ModelList) {
+        *self.entry.lock().unwrap() = Some((Instant::now(), models));
     }
 }
 
@@ -100,4 +227,137 @@ mod tests {
         assert!(qs.contains("after_id=model_abc"));
         assert!(qs.contains("before_id=model_xyz"));
     }
+
+    #[tokio::test]
+    async fn test_with_extra_headers() {
+        use wiremock::matchers::{header, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v1/models/claude-opus-4-6"))
+            .and(header("x-tenant", "acme"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "id": "claude-opus-4-6",
+                "type": "model",
+                "display_name": "Claude Opus 4.6",
+                "created_at": "2026-02-01T00:00:00Z"
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = crate::Client::builder()
+            .api_key("test")
+            .base_url(server.uri())
+            .build();
+        let mut headers = HeaderMap::new();
+        headers.insert("x-tenant", "acme".parse().unwrap());
+        let info = ModelService::with_extra_headers(&client, headers)
+            .get("claude-opus-4-6")
+            .await
+            .unwrap();
+        assert_eq!(info.id, "claude-opus-4-6");
+    }
+
+    #[tokio::test]
+    async fn test_next_page() {
+        use wiremock::matchers::{method, path, query_param};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v1/models"))
+            .and(query_param("after_id", "claude-opus-4-6"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "data": [model_json("claude-haiku-4-5")],
+                "has_more": false,
+                "first_id": "claude-haiku-4-5",
+                "last_id": "claude-haiku-4-5"
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = crate::Client::builder()
+            .api_key("test")
+            .base_url(server.uri())
+            .build();
+        let first: Page<ModelInfo> = serde_json::from_value(serde_json::json!({
+            "data": [model_json("claude-opus-4-6")],
+            "has_more": true,
+            "first_id": "claude-opus-4-6",
+            "last_id": "claude-opus-4-6"
+        }))
+        .unwrap();
+        let second = client.models().next_page(&first).await.unwrap().unwrap();
+        assert_eq!(second.data[0].id, "claude-haiku-4-5");
+        assert!(client.models().next_page(&second).await.unwrap().is_none());
+    }
+
+    fn model_json(id: &str) -> serde_json::Value {
+        serde_json::json!({
+            "id": id,
+            "type": "model",
+            "display_name": id,
+            "created_at": "2026-02-01T00:00:00Z"
+        })
+    }
+
+    #[tokio::test]
+    async fn test_list_all_follows_pages_and_caches() {
+        use wiremock::matchers::{method, path, query_param};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v1/models"))
+            .and(query_param("after_id", "claude-opus-4-6"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "data": [model_json("claude-future-9")],
+                "has_more": false,
+                "first_id": "claude-future-9",
+                "last_id": "claude-future-9"
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/v1/models"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "data": [model_json("claude-opus-4-6")],
+                "has_more": true,
+                "first_id": "claude-opus-4-6",
+                "last_id": "claude-opus-4-6"
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = crate::Client::builder()
+            .api_key("test")
+            .base_url(server.uri())
+            .model_cache_ttl(Duration::from_secs(60))
+            .build();
+        let models = client.models().list_all().await.unwrap();
+        assert_eq!(models.len(), 2);
+        assert_eq!(models[0].0, Model::ClaudeOpus4_6);
+        assert_eq!(models[1].0, Model::Other("claude-future-9".to_string()));
+
+        // Both answered from the cache; the mocks expect a single call each.
+        assert_eq!(client.models().list_all().await.unwrap().len(), 2);
+        let info = client.models().get("claude-future-9").await.unwrap();
+        assert_eq!(info.display_name, "claude-future-9");
+    }
+
+    #[test]
+    fn test_model_cache_expires() {
+        let cache = ModelCache::new(Duration::ZERO);
+        cache.set(Vec::new());
+        assert!(cache.get().is_none());
+
+        let cache = ModelCache::new(Duration::from_secs(60));
+        cache.set(Vec::new());
+        assert_eq!(cache.get().map(|models| models.len()), Some(0));
+    }
 }
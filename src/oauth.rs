@@ -204,8 +204,9 @@ fn apply_oauth_headers(
     headers.remove("x-api-key");
 
     // Set Authorization: Bearer <token>
-    let bearer = HeaderValue::from_str(&format!("Bearer {token}"))
+    let mut bearer = HeaderValue::from_str(&format!("Bearer {token}"))
         .map_err(|_| Error::OAuth("invalid token value for Authorization header".to_string()))?;
+    bearer.set_sensitive(true);
     headers.insert(reqwest::header::AUTHORIZATION, bearer);
 
     // Set anthropic-dangerous-direct-browser-access: true
@@ -0,0 +1,495 @@
+//! Conversion between Anthropic messages and the OpenAI chat-completions format.
+//!
+//! Enabled with the `openai` feature. Useful for sharing conversation storage
+//! with code that speaks the OpenAI format. The mapping covers text, images,
+//! tool calls and tool results; other content (thinking, documents, server
+//! tool blocks) has no OpenAI equivalent and is dropped.
+//!
+//! ```ignore
+//! use uno_anthropic::openai::{from_chat_messages, to_chat_messages};
+//!
+//! let stored: Vec<ChatMessage> = serde_json::from_str(&json)?;
+//! let (system, messages) = from_chat_messages(&stored)?;
+//! let params = MessageCreateParams::builder()
+//!     .model(Model::ClaudeSonnet4_5)
+//!     .max_tokens(1024)
+//!     .maybe_system(system)
+//!     .messages(messages)
+//!     .build();
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::Error;
+use crate::types::common::Role;
+use crate::types::content::{
+    ContentBlockParam, ImageBlockParam, TextBlockParam, ToolResultBlockParam, ToolResultContent,
+    ToolResultContentBlock, ToolUseBlockParam,
+};
+use crate::types::image::{Base64ImageSource, ImageSource, MediaType};
+use crate::types::message::{Message, MessageContent, MessageParam, SystemBlock, SystemContent};
+
+/// A message in the OpenAI chat-completions format.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChatMessage {
+    pub role: ChatRole,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content: Option<ChatContent>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_calls: Option<Vec<ChatToolCall>>,
+    /// The tool call a `tool` message answers.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_call_id: Option<String>,
+}
+
+/// Role of a chat-completions message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChatRole {
+    System,
+    Developer,
+    User,
+    Assistant,
+    Tool,
+}
+
+/// Message content: a string or a list of parts.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ChatContent {
+    Text(String),
+    Parts(Vec<ChatContentPart>),
+}
+
+/// A part of multi-part message content.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ChatContentPart {
+    Text { text: String },
+    ImageUrl { image_url: ChatImageUrl },
+}
+
+/// An image referenced by URL, which may be a base64 `data:` URL.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChatImageUrl {
+    pub url: String,
+}
+
+/// A function call requested by the assistant.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChatToolCall {
+    pub id: String,
+    /// Always `"function"`.
+    #[serde(rename = "type")]
+    pub call_type: String,
+    pub function: ChatFunctionCall,
+}
+
+/// Name and JSON-encoded arguments of a function call.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChatFunctionCall {
+    pub name: String,
+    pub arguments: String,
+}
+
+impl ChatMessage {
+    fn new(role: ChatRole, content: Option<ChatContent>) -> Self {
+        Self {
+            role,
+            content,
+            tool_calls: None,
+            tool_call_id: None,
+        }
+    }
+}
+
+impl From<&Message> for ChatMessage {
+    /// Convert an assistant response into a chat-completions assistant message.
+    fn from(message: &Message) -> Self {
+        let mut converted = Vec::new();
+        push_param(&mut converted, &message.to_param());
+        converted
+            .pop()
+            .unwrap_or_else(|| ChatMessage::new(ChatRole::Assistant, None))
+    }
+}
+
+/// Convert a system prompt and history into chat-completions messages.
+///
+/// A user message holding tool results becomes one `tool` message per result,
+/// followed by a `user` message for any remaining content.
+pub fn to_chat_messages(
+    system: Option<&SystemContent>,
+    messages: &[MessageParam],
+) -> Vec<ChatMessage> {
+    let mut converted = Vec::new();
+    if let Some(system) = system {
+        let text = match system {
+            SystemContent::Text(text) => text.clone(),
+            SystemContent::Blocks(blocks) => blocks
+                .iter()
+                .map(|SystemBlock::Text(block)| block.text.as_str())
+                .collect::<Vec<_>>()
+                .join("\n\n"),
+        };
+        converted.push(ChatMessage::new(
+            ChatRole::System,
+            Some(ChatContent::Text(text)),
+        ));
+    }
+    for message in messages {
+        push_param(&mut converted, message);
+    }
+    converted
+}
+
+/// Convert chat-completions messages into a system prompt and history.
+///
+/// `system` and `developer` messages are joined into the system prompt.
+/// Consecutive `tool` and `user` messages are merged into a single user turn,
+/// as the Messages API requires tool results to directly follow the tool calls.
+///
+/// Returns `Error::Validation` if a tool call's arguments are not valid JSON or
+/// a `tool` message has no `tool_call_id`.
+pub fn from_chat_messages(
+    messages: &[ChatMessage],
+) -> Result<(Option<SystemContent>, Vec<MessageParam>), Error> {
+    let mut system: Vec<String> = Vec::new();
+    let mut converted: Vec<MessageParam> = Vec::new();
+    for message in messages {
+        match message.role {
+            ChatRole::System | ChatRole::Developer => {
+                system.push(content_text(message.content.as_ref()));
+            }
+            ChatRole::User => match &message.content {
+                Some(ChatContent::Parts(parts)) => {
+                    push_user_blocks(&mut converted, parts.iter().map(part_to_block).collect());
+                }
+                other if matches!(converted.last(), Some(m) if m.role == Role::User) => {
+                    let text = content_text(other.as_ref());
+                    push_user_blocks(
+                        &mut converted,
+                        vec![ContentBlockParam::Text(TextBlockParam::new(text))],
+                    );
+                }
+                other => converted.push(MessageParam::user(content_text(other.as_ref()))),
+            },
+            ChatRole::Tool => {
+                let tool_use_id = message.tool_call_id.clone().ok_or_else(|| {
+                    Error::Validation("tool message is missing tool_call_id".to_string())
+                })?;
+                let block = ContentBlockParam::ToolResult(ToolResultBlockParam {
+                    tool_use_id,
+                    content: Some(ToolResultContent::Text(content_text(
+                        message.content.as_ref(),
+                    ))),
+                    is_error: None,
+                    cache_control: None,
+                });
+                push_user_blocks(&mut converted, vec![block]);
+            }
+            ChatRole::Assistant => converted.push(assistant_param(message)?),
+        }
+    }
+    let system = (!system.is_empty()).then(|| SystemContent::Text(system.join("\n\n")));
+    Ok((system, converted))
+}
+
+fn push_param(converted: &mut Vec<ChatMessage>, message: &MessageParam) {
+    let blocks = match &message.content {
+        MessageContent::Text(text) => {
+            let role = match message.role {
+                Role::User => ChatRole::User,
+                Role::Assistant => ChatRole::Assistant,
+            };
+            converted.push(ChatMessage::new(
+                role,
+                Some(ChatContent::Text(text.clone())),
+            ));
+            return;
+        }
+        MessageContent::Blocks(blocks) => blocks,
+    };
+
+    let mut parts = Vec::new();
+    let mut tool_calls = Vec::new();
+    for block in blocks {
+        match block {
+            ContentBlockParam::Text(text) => parts.push(ChatContentPart::Text {
+                text: text.text.clone(),
+            }),
+            ContentBlockParam::Image(image) => {
+                if let Some(part) = image_part(image) {
+                    parts.push(part);
+                }
+            }
+            ContentBlockParam::ToolUse(tool_use) => tool_calls.push(ChatToolCall {
+                id: tool_use.id.clone(),
+                call_type: "function".to_string(),
+                function: ChatFunctionCall {
+                    name: tool_use.name.clone(),
+                    arguments: tool_use.input.to_string(),
+                },
+            }),
+            ContentBlockParam::ToolResult(result) => {
+                let mut tool = ChatMessage::new(
+                    ChatRole::Tool,
+                    Some(ChatContent::Text(tool_result_text(result))),
+                );
+                tool.tool_call_id = Some(result.tool_use_id.clone());
+                converted.push(tool);
+            }
+            _ => {}
+        }
+    }
+
+    match message.role {
+        Role::Assistant => {
+            let text = parts
+                .iter()
+                .filter_map(|part| match part {
+                    ChatContentPart::Text { text } => Some(text.as_str()),
+                    ChatContentPart::ImageUrl { .. } => None,
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            let mut assistant = ChatMessage::new(
+                ChatRole::Assistant,
+                (!text.is_empty()).then_some(ChatContent::Text(text)),
+            );
+            assistant.tool_calls = (!tool_calls.is_empty()).then_some(tool_calls);
+            converted.push(assistant);
+        }
+        Role::User if !parts.is_empty() => {
+            let content = match parts.as_slice() {
+                [ChatContentPart::Text { text }] => ChatContent::Text(text.clone()),
+                _ => ChatContent::Parts(parts),
+            };
+            converted.push(ChatMessage::new(ChatRole::User, Some(content)));
+        }
+        Role::User => {}
+    }
+}
+
+fn image_part(image: &ImageBlockParam) -> Option<ChatContentPart> {
+    let url = match &image.source {
+        ImageSource::Base64(source) => {
+            let media_type = serde_json::to_value(&source.media_type).ok()?;
+            format!("data:{};base64,{}", media_type.as_str()?, source.data)
+        }
+        ImageSource::Url(source) => source.url.clone(),
+        ImageSource::File(_) => return None,
+    };
+    Some(ChatContentPart::ImageUrl {
+        image_url: ChatImageUrl { url },
+    })
+}
+
+fn tool_result_text(result: &ToolResultBlockParam) -> String {
+    match &result.content {
+        Some(ToolResultContent::Text(text)) => text.clone(),
+        Some(ToolResultContent::Blocks(blocks)) => blocks
+            .iter()
+            .filter_map(|block| match block {
+                ToolResultContentBlock::Text(text) => Some(text.text.as_str()),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+        None => String::new(),
+    }
+}
+
+fn content_text(content: Option<&ChatContent>) -> String {
+    match content {
+        Some(ChatContent::Text(text)) => text.clone(),
+        Some(ChatContent::Parts(parts)) => parts
+            .iter()
+            .filter_map(|part| match part {
+                ChatContentPart::Text { text } => Some(text.as_str()),
+                ChatContentPart::ImageUrl { .. } => None,
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+        None => String::new(),
+    }
+}
+
+fn part_to_block(part: &ChatContentPart) -> ContentBlockParam {
+    match part {
+        ChatContentPart::Text { text } => ContentBlockParam::Text(TextBlockParam::new(text)),
+        ChatContentPart::ImageUrl { image_url } => {
+            ContentBlockParam::Image(ImageBlockParam::new(image_source(&image_url.url)))
+        }
+    }
+}
+
+/// Decode a `data:<media type>;base64,` URL, or reference the URL directly.
+fn image_source(url: &str) -> ImageSource {
+    let data_url = url.strip_prefix("data:").and_then(|rest| {
+        let (media_type, data) = rest.split_once(";base64,")?;
+        let media_type: MediaType = serde_json::from_value(media_type.into()).ok()?;
+        Some((media_type, data))
+    });
+    match data_url {
+        Some((media_type, data)) => ImageSource::Base64(Base64ImageSource {
+            media_type,
+            data: data.to_string(),
+        }),
+        None => ImageSource::url(url),
+    }
+}
+
+fn push_user_blocks(converted: &mut Vec<MessageParam>, mut blocks: Vec<ContentBlockParam>) {
+    if let Some(MessageParam {
+        role: Role::User,
+        content,
+    }) = converted.last_mut()
+    {
+        let existing = match std::mem::replace(content, MessageContent::Blocks(Vec::new())) {
+            MessageContent::Text(text) => vec![ContentBlockParam::Text(TextBlockParam::new(text))],
+            MessageContent::Blocks(existing) => existing,
+        };
+        blocks.splice(0..0, existing);
+        *content = MessageContent::Blocks(blocks);
+        return;
+    }
+    converted.push(MessageParam::user_blocks(blocks));
+}
+
+fn assistant_param(message: &ChatMessage) -> Result<MessageParam, Error> {
+    let text = content_text(message.content.as_ref());
+    let Some(tool_calls) = message.tool_calls.as_ref().filter(|c| !c.is_empty()) else {
+        return Ok(MessageParam::assistant(text));
+    };
+    let mut blocks = Vec::new();
+    if !text.is_empty() {
+        blocks.push(ContentBlockParam::Text(TextBlockParam::new(text)));
+    }
+    for call in tool_calls {
+        let input = serde_json::from_str(&call.function.arguments).map_err(|e| {
+            Error::Validation(format!("invalid arguments for tool call {}: {e}", call.id))
+        })?;
+        blocks.push(ContentBlockParam::ToolUse(ToolUseBlockParam {
+            id: call.id.clone(),
+            name: call.function.name.clone(),
+            input,
+            cache_control: None,
+            caller: None,
+        }));
+    }
+    Ok(MessageParam::assistant_blocks(blocks))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::text_message;
+
+    fn chat_history() -> serde_json::Value {
+        serde_json::json!([
+            {"role": "system", "content": "Be brief."},
+            {"role": "user", "content": "Weather in Paris?"},
+            {"role": "assistant", "content": "Checking.", "tool_calls": [{
+                "id": "call_1",
+                "type": "function",
+                "function": {"name": "get_weather", "arguments": "{\"city\":\"Paris\"}"}
+            }]},
+            {"role": "tool", "tool_call_id": "call_1", "content": "Sunny"},
+            {"role": "assistant", "content": "It is sunny."}
+        ])
+    }
+
+    #[test]
+    fn test_from_chat_messages() {
+        let chat: Vec<ChatMessage> = serde_json::from_value(chat_history()).unwrap();
+        let (system, messages) = from_chat_messages(&chat).unwrap();
+        assert!(matches!(system, Some(SystemContent::Text(ref s)) if s == "Be brief."));
+        assert_eq!(
+            serde_json::to_value(&messages).unwrap(),
+            serde_json::json!([
+                {"role": "user", "content": "Weather in Paris?"},
+                {"role": "assistant", "content": [
+                    {"type": "text", "text": "Checking."},
+                    {"type": "tool_use", "id": "call_1", "name": "get_weather", "input": {"city": "Paris"}}
+                ]},
+                {"role": "user", "content": [
+                    {"type": "tool_result", "tool_use_id": "call_1", "content": "Sunny"}
+                ]},
+                {"role": "assistant", "content": "It is sunny."}
+            ])
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let chat: Vec<ChatMessage> = serde_json::from_value(chat_history()).unwrap();
+        let (system, messages) = from_chat_messages(&chat).unwrap();
+        let back = to_chat_messages(system.as_ref(), &messages);
+        assert_eq!(serde_json::to_value(&back).unwrap(), chat_history());
+    }
+
+    #[test]
+    fn test_tool_results_merge_with_user_text() {
+        let chat: Vec<ChatMessage> = serde_json::from_value(serde_json::json!([
+            {"role": "tool", "tool_call_id": "call_1", "content": "42"},
+            {"role": "user", "content": [
+                {"type": "text", "text": "And this?"},
+                {"type": "image_url", "image_url": {"url": "data:image/png;base64,iVBORw=="}}
+            ]}
+        ]))
+        .unwrap();
+        let (_, messages) = from_chat_messages(&chat).unwrap();
+        assert_eq!(messages.len(), 1);
+        assert_eq!(
+            serde_json::to_value(&messages[0].content).unwrap(),
+            serde_json::json!([
+                {"type": "tool_result", "tool_use_id": "call_1", "content": "42"},
+                {"type": "text", "text": "And this?"},
+                {"type": "image", "source": {"type": "base64", "media_type": "image/png", "data": "iVBORw=="}}
+            ])
+        );
+
+        let back = to_chat_messages(None, &messages);
+        assert_eq!(back.len(), 2);
+        assert_eq!(back[0].role, ChatRole::Tool);
+        assert_eq!(back[1].content, chat[1].content);
+    }
+
+    #[test]
+    fn test_invalid_tool_arguments() {
+        let chat: Vec<ChatMessage> = serde_json::from_value(serde_json::json!([
+            {"role": "assistant", "tool_calls": [{
+                "id": "call_1",
+                "type": "function",
+                "function": {"name": "f", "arguments": "{not json"}
+            }]}
+        ]))
+        .unwrap();
+        assert!(matches!(
+            from_chat_messages(&chat),
+            Err(Error::Validation(_))
+        ));
+    }
+
+    #[test]
+    fn test_message_into_chat_message() {
+        let message = Message {
+            content: serde_json::from_value(serde_json::json!([
+                {"type": "thinking", "thinking": "hmm", "signature": "sig"},
+                {"type": "tool_use", "id": "toolu_1", "name": "lookup", "input": {"q": "x"}}
+            ]))
+            .unwrap(),
+            ..text_message("")
+        };
+        let chat = ChatMessage::from(&message);
+        assert_eq!(
+            serde_json::to_value(&chat).unwrap(),
+            serde_json::json!({"role": "assistant", "tool_calls": [{
+                "id": "toolu_1",
+                "type": "function",
+                "function": {"name": "lookup", "arguments": "{\"q\":\"x\"}"}
+            }]})
+        );
+    }
+}
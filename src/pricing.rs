@@ -0,0 +1,219 @@
+//! Estimated request costs from published per-token prices.
+//!
+//! [`CostHook`] turns the usage the client reports after each Messages API
+//! call into a cost, so billing pipelines can be fed without wrapping every
+//! call site.
+//!
+//! ```ignore
+//! use uno_anthropic::pricing::CostHook;
+//!
+//! let client = Client::builder()
+//!     .metrics_hook(CostHook::new(|event| {
+//!         billing.record(&event.model, event.cost_usd, event.request_id.as_deref());
+//!     }))
+//!     .build();
+//! ```
+
+use std::collections::HashMap;
+
+use crate::telemetry::{MetricsHook, UsageMetrics};
+use crate::types::model::Model;
+
+/// Price multiplier for tokens written to the prompt cache (5-minute TTL).
+const CACHE_WRITE_MULTIPLIER: f64 = 1.25;
+/// Price multiplier for tokens read from the prompt cache.
+const CACHE_READ_MULTIPLIER: f64 = 0.1;
+
+/// Prices for a model, in US dollars per million tokens.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ModelPricing {
+    pub input_per_mtok: f64,
+    pub output_per_mtok: f64,
+}
+
+impl ModelPricing {
+    pub const fn new(input_per_mtok: f64, output_per_mtok: f64) -> Self {
+        Self {
+            input_per_mtok,
+            output_per_mtok,
+        }
+    }
+
+    /// Standard list prices for `model`, or `None` for `Model::Other`.
+    ///
+    /// Batch discounts, 1-hour cache writes and long-context pricing are not
+    /// taken into account.
+    pub fn for_model(model: &Model) -> Option<Self> {
+        let pricing = match model {
+            Model::ClaudeOpus4_6 | Model::ClaudeOpus4_5_20251101 | Model::ClaudeOpus4_5 => {
+                Self::new(5.0, 25.0)
+            }
+            Model::ClaudeOpus4_1
+            | Model::ClaudeOpus4_1_20250805
+            | Model::ClaudeOpus4_0
+            | Model::ClaudeOpus4_20250514
+            | Model::Claude4Opus20250514
+            | Model::Claude3OpusLatest
+            | Model::Claude3Opus20240229 => Self::new(15.0, 75.0),
+            Model::ClaudeSonnet4_6
+            | Model::ClaudeSonnet4_5
+            | Model::ClaudeSonnet4_5_20250929
+            | Model::ClaudeSonnet4_0
+            | Model::ClaudeSonnet4_20250514
+            | Model::Claude4Sonnet20250514
+            | Model::Claude3_7SonnetLatest
+            | Model::Claude3_7Sonnet20250219 => Self::new(3.0, 15.0),
+            Model::ClaudeHaiku4_5 | Model::ClaudeHaiku4_5_20251001 => Self::new(1.0, 5.0),
+            Model::Claude3_5HaikuLatest | Model::Claude3_5Haiku20241022 => Self::new(0.8, 4.0),
+            Model::Claude3Haiku20240307 => Self::new(0.25, 1.25),
+            Model::Other(_) => return None,
+        };
+        Some(pricing)
+    }
+
+    /// The cost of `usage` in US dollars.
+    pub fn cost(&self, usage: &UsageMetrics) -> f64 {
+        let cache_write = usage.cache_creation_input_tokens.unwrap_or(0) as f64;
+        let cache_read = usage.cache_read_input_tokens.unwrap_or(0) as f64;
+        let input = usage.input_tokens as f64
+            + cache_write * CACHE_WRITE_MULTIPLIER
+            + cache_read * CACHE_READ_MULTIPLIER;
+        (input * self.input_per_mtok + usage.output_tokens as f64 * self.output_per_mtok)
+            / 1_000_000.0
+    }
+}
+
+/// The usage and cost of one Messages API call, as passed to a `CostHook`.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct CostEvent {
+    /// The model id reported by the response.
+    pub model: String,
+    pub usage: UsageMetrics,
+    /// Estimated cost in US dollars, or `None` if the model's prices are
+    /// unknown.
+    pub cost_usd: Option<f64>,
+    pub request_id: Option<String>,
+}
+
+/// A `MetricsHook` that calls a function with the cost of every Messages API
+/// call once its final usage is known.
+pub struct CostHook<F> {
+    callback: F,
+    prices: HashMap<String, ModelPricing>,
+}
+
+impl<F> CostHook<F>
+where
+    F: Fn(&CostEvent) + Send + Sync,
+{
+    pub fn new(callback: F) -> Self {
+        Self {
+            callback,
+            prices: HashMap::new(),
+        }
+    }
+
+    /// Price `model_id` with `pricing` instead of the built-in list prices,
+    /// e.g. for negotiated rates or models this crate doesn't know.
+    pub fn with_pricing(mut self, model_id: impl Into<String>, pricing: ModelPricing) -> Self {
+        self.prices.insert(model_id.into(), pricing);
+        self
+    }
+
+    fn pricing(&self, model_id: &str) -> Option<ModelPricing> {
+        self.prices
+            .get(model_id)
+            .copied()
+            .or_else(|| ModelPricing::for_model(&Model::from_str_lossy(model_id)))
+    }
+}
+
+impl<F> MetricsHook for CostHook<F>
+where
+    F: Fn(&CostEvent) + Send + Sync,
+{
+    fn on_usage(&self, usage: &UsageMetrics) {
+        let event = CostEvent {
+            model: usage.model.clone(),
+            usage: usage.clone(),
+            cost_usd: self.pricing(&usage.model).map(|p| p.cost(usage)),
+            request_id: usage.request_id.clone(),
+        };
+        (self.callback)(&event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use super::*;
+    use crate::client::ClientBuilder;
+    use crate::messages::params::MessageCreateParams;
+    use crate::mock::text_message;
+    use crate::types::message::MessageParam;
+
+    #[test]
+    fn test_cost_includes_cache_tokens() {
+        let usage = UsageMetrics {
+            model: "claude-sonnet-4-5".to_string(),
+            input_tokens: 1_000_000,
+            output_tokens: 100_000,
+            cache_creation_input_tokens: Some(1_000_000),
+            cache_read_input_tokens: Some(1_000_000),
+            stop_reason: None,
+            request_id: None,
+        };
+        let pricing = ModelPricing::for_model(&Model::ClaudeSonnet4_5).unwrap();
+        // 3.00 input + 3.75 cache write + 0.30 cache read + 1.50 output
+        assert!((pricing.cost(&usage) - 8.55).abs() < 1e-9);
+        assert_eq!(
+            ModelPricing::for_model(&Model::Other("x".to_string())),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn test_cost_hook_reports_each_call() {
+        let mut message = text_message("Hi");
+        message.model = "claude-custom".to_string();
+        message.usage.input_tokens = 1000;
+        message.usage.output_tokens = 100;
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/messages"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("request-id", "req_42")
+                    .set_body_json(&message),
+            )
+            .mount(&server)
+            .await;
+
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let sink = events.clone();
+        let hook = CostHook::new(move |event: &CostEvent| sink.lock().unwrap().push(event.clone()))
+            .with_pricing("claude-custom", ModelPricing::new(2.0, 10.0));
+        let client = ClientBuilder::new()
+            .api_key("test")
+            .base_url(server.uri())
+            .metrics_hook(hook)
+            .build();
+        let params = MessageCreateParams::builder()
+            .model(Model::ClaudeOpus4_6)
+            .max_tokens(10)
+            .message(MessageParam::user("Hi"))
+            .build();
+        client.messages().create(params).await.unwrap();
+
+        let events = events.lock().unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].model, "claude-custom");
+        assert_eq!(events[0].request_id.as_deref(), Some("req_42"));
+        assert!((events[0].cost_usd.unwrap() - 0.003).abs() < 1e-12);
+    }
+}
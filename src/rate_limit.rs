@@ -0,0 +1,252 @@
+//! Client-side request and token rate limiting.
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use reqwest::header::HeaderMap;
+use tokio::time::Instant;
+use tracing::debug;
+
+use crate::error::Error;
+use crate::middleware::{BoxFuture, Middleware, Next};
+
+/// Rough request body size per token, used to estimate a request's token cost.
+const BYTES_PER_TOKEN: usize = 4;
+
+const REQUESTS_LIMIT: &str = "anthropic-ratelimit-requests-limit";
+const REQUESTS_REMAINING: &str = "anthropic-ratelimit-requests-remaining";
+const TOKENS_LIMIT: &str = "anthropic-ratelimit-input-tokens-limit";
+const TOKENS_REMAINING: &str = "anthropic-ratelimit-input-tokens-remaining";
+
+/// Token-bucket rate limiter that delays requests instead of letting them fail
+/// with 429s.
+///
+/// Limits are per minute and refill continuously. A request's token cost is
+/// estimated from the size of its body. After each response the limiter adopts
+/// the limits and remaining budget reported in the `anthropic-ratelimit-*`
+/// headers, so an unconfigured limiter still starts throttling once the API
+/// reports how much is left.
+///
+/// Clones share their budget, so one limiter can throttle several clients that
+/// use the same API key.
+///
+/// ```ignore
+/// let client = Client::builder()
+///     .rate_limiter(RateLimiter::new().requests_per_minute(50).tokens_per_minute(40_000))
+///     .build();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct RateLimiter {
+    state: Arc<Mutex<State>>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    requests: Option<Bucket>,
+    tokens: Option<Bucket>,
+}
+
+impl RateLimiter {
+    /// A limiter with no limits until they are configured or learned from
+    /// response headers.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Allow at most `limit` requests per minute.
+    pub fn requests_per_minute(self, limit: u32) -> Self {
+        self.lock().requests = Some(Bucket::new(limit as f64));
+        self
+    }
+
+    /// Allow at most `limit` input tokens per minute.
+    pub fn tokens_per_minute(self, limit: u32) -> Self {
+        self.lock().tokens = Some(Bucket::new(limit as f64));
+        self
+    }
+
+    /// Wait until a request costing `tokens` fits within the limits, then
+    /// reserve it.
+    pub async fn acquire(&self, tokens: u32) {
+        loop {
+            let wait = {
+                let mut state = self.lock();
+                let now = Instant::now();
+                let wait = [
+                    state.requests.as_mut().map(|b| b.wait_for(1.0, now)),
+                    state
+                        .tokens
+                        .as_mut()
+                        .map(|b| b.wait_for(tokens as f64, now)),
+                ]
+                .into_iter()
+                .flatten()
+                .max()
+                .unwrap_or_default();
+                if wait.is_zero() {
+                    if let Some(bucket) = &mut state.requests {
+                        bucket.take(1.0);
+                    }
+                    if let Some(bucket) = &mut state.tokens {
+                        bucket.take(tokens as f64);
+                    }
+                    return;
+                }
+                wait
+            };
+            debug!(wait_ms = wait.as_millis() as u64, "rate limited");
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// Adopt the limits and remaining budget reported by the API.
+    pub fn update_from_headers(&self, headers: &HeaderMap) {
+        let mut state = self.lock();
+        let now = Instant::now();
+        learn(
+            &mut state.requests,
+            headers,
+            REQUESTS_LIMIT,
+            REQUESTS_REMAINING,
+            now,
+        );
+        learn(
+            &mut state.tokens,
+            headers,
+            TOKENS_LIMIT,
+            TOKENS_REMAINING,
+            now,
+        );
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Middleware for RateLimiter {
+    fn handle<'a>(
+        &'a self,
+        request: reqwest::Request,
+        next: Next<'a>,
+    ) -> BoxFuture<'a, Result<reqwest::Response, Error>> {
+        Box::pin(async move {
+            let body_len = request
+                .body()
+                .and_then(|b| b.as_bytes())
+                .map_or(0, <[u8]>::len);
+            let tokens = (body_len / BYTES_PER_TOKEN).min(u32::MAX as usize) as u32;
+            self.acquire(tokens).await;
+            let response = next.run(request).await?;
+            self.update_from_headers(response.headers());
+            Ok(response)
+        })
+    }
+}
+
+fn learn(
+    bucket: &mut Option<Bucket>,
+    headers: &HeaderMap,
+    limit_header: &str,
+    remaining_header: &str,
+    now: Instant,
+) {
+    let parse = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse::<f64>().ok())
+    };
+    if let Some(limit) = parse(limit_header) {
+        match bucket {
+            Some(b) => b.capacity = limit,
+            None => *bucket = Some(Bucket::new(limit)),
+        }
+    }
+    if let (Some(b), Some(remaining)) = (bucket.as_mut(), parse(remaining_header)) {
+        b.refill(now);
+        b.available = b.available.min(remaining);
+    }
+}
+
+/// A bucket holding up to `capacity` units that refills to full over a minute.
+#[derive(Debug)]
+struct Bucket {
+    capacity: f64,
+    available: f64,
+    updated: Instant,
+}
+
+impl Bucket {
+    fn new(capacity: f64) -> Self {
+        Self {
+            capacity,
+            available: capacity,
+            updated: Instant::now(),
+        }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.available = (self.available + elapsed * self.capacity / 60.0).min(self.capacity);
+        self.updated = now;
+    }
+
+    /// Time until `amount` units are available. Amounts above the capacity
+    /// only wait for a full bucket so oversized requests still go through.
+    fn wait_for(&mut self, amount: f64, now: Instant) -> Duration {
+        self.refill(now);
+        let amount = amount.min(self.capacity);
+        if self.available >= amount || self.capacity <= 0.0 {
+            return Duration::ZERO;
+        }
+        Duration::from_secs_f64((amount - self.available) * 60.0 / self.capacity)
+    }
+
+    fn take(&mut self, amount: f64) {
+        self.available -= amount.min(self.capacity);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(start_paused = true)]
+    async fn test_requests_per_minute_throttles() {
+        let limiter = RateLimiter::new().requests_per_minute(2);
+        let start = Instant::now();
+        limiter.acquire(0).await;
+        limiter.acquire(0).await;
+        assert_eq!(start.elapsed(), Duration::ZERO);
+
+        limiter.acquire(0).await;
+        let waited = start.elapsed();
+        assert!(waited >= Duration::from_secs(29) && waited <= Duration::from_secs(31));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_tokens_per_minute_throttles() {
+        let limiter = RateLimiter::new().tokens_per_minute(1000);
+        let start = Instant::now();
+        limiter.acquire(800).await;
+        limiter.acquire(400).await;
+        let waited = start.elapsed();
+        assert!(waited >= Duration::from_secs(11) && waited <= Duration::from_secs(13));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_learns_from_headers() {
+        let limiter = RateLimiter::new();
+        let mut headers = HeaderMap::new();
+        headers.insert(REQUESTS_LIMIT, "60".parse().unwrap());
+        headers.insert(REQUESTS_REMAINING, "0".parse().unwrap());
+        limiter.update_from_headers(&headers);
+
+        let start = Instant::now();
+        limiter.acquire(0).await;
+        let waited = start.elapsed();
+        assert!(waited >= Duration::from_millis(900) && waited <= Duration::from_millis(1100));
+        assert!(limiter.lock().tokens.is_none());
+    }
+}
@@ -0,0 +1,177 @@
+//! Redaction of credentials and other sensitive strings in logged output.
+
+use std::borrow::Cow;
+use std::fmt;
+
+use reqwest::header::HeaderMap;
+
+/// Replacement text for redacted values.
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Prefix of Anthropic API keys and OAuth tokens.
+const ANTHROPIC_KEY_PREFIX: &str = "sk-ant-";
+
+/// Prefix of bearer credentials in `Authorization` headers.
+const BEARER_PREFIX: &str = "Bearer ";
+
+/// Controls which parts of logged text are replaced with [`REDACTED`].
+///
+/// The default policy redacts built-in secret patterns (Anthropic API keys and
+/// bearer tokens). Additional literal values, such as customer identifiers, can
+/// be added with `redact_literal`.
+#[derive(Debug, Clone)]
+pub struct RedactionPolicy {
+    secrets: bool,
+    literals: Vec<String>,
+}
+
+impl Default for RedactionPolicy {
+    fn default() -> Self {
+        Self {
+            secrets: true,
+            literals: Vec::new(),
+        }
+    }
+}
+
+impl RedactionPolicy {
+    /// A policy that leaves text unchanged.
+    pub fn none() -> Self {
+        Self {
+            secrets: false,
+            literals: Vec::new(),
+        }
+    }
+
+    /// Also redact every occurrence of `value`.
+    pub fn redact_literal(mut self, value: impl Into<String>) -> Self {
+        let value = value.into();
+        if !value.is_empty() {
+            self.literals.push(value);
+        }
+        self
+    }
+
+    /// Apply the policy to `text`, borrowing it when nothing needs redacting.
+    pub fn apply<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        let mut result = if self.secrets {
+            redact_secrets(text)
+        } else {
+            Cow::Borrowed(text)
+        };
+        for literal in &self.literals {
+            if result.contains(literal.as_str()) {
+                result = Cow::Owned(result.replace(literal.as_str(), REDACTED));
+            }
+        }
+        result
+    }
+}
+
+/// Redact Anthropic API keys (`sk-ant-...`) and bearer tokens in `text`.
+pub fn redact_secrets(text: &str) -> Cow<'_, str> {
+    if !text.contains(ANTHROPIC_KEY_PREFIX) && !text.contains(BEARER_PREFIX) {
+        return Cow::Borrowed(text);
+    }
+
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    loop {
+        let next = [ANTHROPIC_KEY_PREFIX, BEARER_PREFIX]
+            .iter()
+            .filter_map(|prefix| rest.find(prefix).map(|i| (i, *prefix)))
+            .min_by_key(|(i, _)| *i);
+        let Some((start, prefix)) = next else {
+            out.push_str(rest);
+            break;
+        };
+
+        // Keep the scheme for bearer tokens so the output stays readable.
+        let secret_start = if prefix == BEARER_PREFIX {
+            start + prefix.len()
+        } else {
+            start
+        };
+        out.push_str(&rest[..secret_start]);
+
+        let token_len = rest[secret_start..]
+            .find(|c: char| !is_token_char(c))
+            .unwrap_or(rest.len() - secret_start);
+        if token_len > 0 {
+            out.push_str(REDACTED);
+        }
+        rest = &rest[secret_start + token_len..];
+    }
+    Cow::Owned(out)
+}
+
+/// Headers whose values are credentials and must never be logged.
+const SENSITIVE_HEADERS: &[&str] = &["authorization", "proxy-authorization", "x-api-key"];
+
+/// Whether the header `name` carries a credential.
+pub fn is_sensitive_header(name: &str) -> bool {
+    SENSITIVE_HEADERS
+        .iter()
+        .any(|h| name.eq_ignore_ascii_case(h))
+}
+
+/// Formats a `HeaderMap` for `Debug` output with credential values replaced by
+/// [`REDACTED`].
+pub struct RedactedHeaders<'a>(pub &'a HeaderMap);
+
+impl fmt::Debug for RedactedHeaders<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut map = f.debug_map();
+        for (name, value) in self.0 {
+            if value.is_sensitive() || is_sensitive_header(name.as_str()) {
+                map.entry(&name.as_str(), &REDACTED);
+            } else {
+                map.entry(&name.as_str(), value);
+            }
+        }
+        map.finish()
+    }
+}
+
+fn is_token_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | '~' | '+' | '/' | '=')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redact_secrets() {
+        assert_eq!(
+            redact_secrets("key=sk-ant-api03-abc_DEF-123 end"),
+            "key=[REDACTED] end"
+        );
+        assert_eq!(
+            redact_secrets("Authorization: Bearer eyJhbGciOi.x-y"),
+            "Authorization: Bearer [REDACTED]"
+        );
+        assert!(matches!(redact_secrets("nothing here"), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn test_policy_literals() {
+        let policy = RedactionPolicy::default().redact_literal("acct-42");
+        assert_eq!(
+            policy.apply("account acct-42 used sk-ant-xyz"),
+            "account [REDACTED] used [REDACTED]"
+        );
+        assert_eq!(RedactionPolicy::none().apply("sk-ant-xyz"), "sk-ant-xyz");
+    }
+
+    #[test]
+    fn test_redacted_headers() {
+        let mut headers = HeaderMap::new();
+        headers.insert("X-Api-Key", "sk-ant-xyz".parse().unwrap());
+        headers.insert("x-custom", "visible".parse().unwrap());
+        let debug = format!("{:?}", RedactedHeaders(&headers));
+        assert!(debug.contains("\"x-api-key\": \"[REDACTED]\""));
+        assert!(debug.contains("visible"));
+        assert!(!debug.contains("sk-ant-xyz"));
+    }
+}
@@ -0,0 +1,72 @@
+//! Metadata about the HTTP response behind an API call.
+
+use std::time::Duration;
+
+use reqwest::header::HeaderMap;
+
+use crate::timing::RequestTiming;
+
+/// Status, headers and timing of the response a result was read from.
+///
+/// Returned alongside the parsed result by the `*_with_response` methods, e.g.
+/// `MessageService::create_with_response`, for auditing and debugging.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct ApiResponse {
+    pub status: u16,
+    pub headers: HeaderMap,
+    /// The `request-id` header, which Anthropic support asks for when
+    /// investigating a request.
+    pub request_id: Option<String>,
+    /// Timing of the attempt that produced this response. `timing.attempt` is
+    /// greater than zero if the call was retried, and `timing.total` covers
+    /// reading the body for non-streaming calls.
+    pub timing: Option<RequestTiming>,
+    /// Wall time of the whole call, including retries. For streaming calls this
+    /// ends when the response headers arrive.
+    pub elapsed: Duration,
+}
+
+impl ApiResponse {
+    pub(crate) fn new(response: &reqwest::Response, elapsed: Duration) -> Self {
+        let headers = response.headers().clone();
+        Self {
+            status: response.status().as_u16(),
+            request_id: headers
+                .get("request-id")
+                .and_then(|v| v.to_str().ok())
+                .map(String::from),
+            timing: response.extensions().get::<RequestTiming>().copied(),
+            headers,
+            elapsed,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new_reads_request_id_and_timing() {
+        let mut response: reqwest::Response = http::Response::builder()
+            .status(201)
+            .header("request-id", "req_123")
+            .body(Vec::<u8>::new())
+            .unwrap()
+            .into();
+        response.extensions_mut().insert(RequestTiming {
+            attempt: 1,
+            dns: None,
+            connect: None,
+            time_to_headers: Duration::from_millis(40),
+            total: None,
+        });
+
+        let meta = ApiResponse::new(&response, Duration::from_millis(900));
+        assert_eq!(meta.status, 201);
+        assert_eq!(meta.request_id.as_deref(), Some("req_123"));
+        assert_eq!(meta.timing.map(|t| t.attempt), Some(1));
+        assert_eq!(meta.elapsed, Duration::from_millis(900));
+    }
+}
@@ -0,0 +1,441 @@
+//! Caching of Messages API responses for deterministic requests.
+//!
+//! Test suites and evaluation runs often send the same prompt many times.
+//! [`ResponseCache`] answers a repeated request from a store instead of the
+//! API, so it is only billed once.
+//!
+//! ```ignore
+//! use uno_anthropic::response_cache::ResponseCache;
+//!
+//! let client = Client::builder()
+//!     .middleware(ResponseCache::on_disk("target/anthropic-cache"))
+//!     .build();
+//! ```
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::error::Error;
+use crate::middleware::{AsyncMiddleware, Next, OperationInfo};
+
+/// Response headers that describe the wire encoding rather than the stored
+/// body, so they are not cached.
+const TRANSPORT_HEADERS: &[&str] = &["content-encoding", "content-length", "transfer-encoding"];
+
+/// Request headers that change the response, so they are part of the key.
+const KEYED_HEADERS: &[&str] = &["anthropic-version", "anthropic-beta"];
+
+/// A successful response as stored in a cache.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CachedResponse {
+    pub status: u16,
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    pub body: String,
+}
+
+/// Storage for cached responses, keyed by a hash of the request.
+///
+/// Stores are best-effort: a failed write should be logged, not returned, so
+/// the response still reaches the caller.
+pub trait CacheStore: Send + Sync {
+    fn get(&self, key: &str) -> Option<CachedResponse>;
+    fn put(&self, key: &str, response: CachedResponse);
+}
+
+/// An in-memory store that evicts the least recently used response once it
+/// holds `capacity` of them.
+pub struct MemoryStore {
+    capacity: usize,
+    state: Mutex<MemoryState>,
+}
+
+/// Each entry records when it was last used, and `order` indexes keys by
+/// that tick, so lookups, inserts and evictions are O(log n).
+#[derive(Default)]
+struct MemoryState {
+    entries: HashMap<String, (CachedResponse, u64)>,
+    /// Keys by last use, least recent first.
+    order: BTreeMap<u64, String>,
+    tick: u64,
+}
+
+impl MemoryStore {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            state: Mutex::new(MemoryState::default()),
+        }
+    }
+
+    /// Number of cached responses.
+    pub fn len(&self) -> usize {
+        self.state.lock().unwrap().entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl MemoryState {
+    /// Mark `key` as the most recently used entry.
+    fn touch(&mut self, key: &str) {
+        self.tick += 1;
+        if let Some((_, used)) = self.entries.get_mut(key) {
+            let key = self.order.remove(used).expect("entries are ordered");
+            *used = self.tick;
+            self.order.insert(self.tick, key);
+        }
+    }
+}
+
+impl CacheStore for MemoryStore {
+    fn get(&self, key: &str) -> Option<CachedResponse> {
+        let mut state = self.state.lock().unwrap();
+        let (response, _) = state.entries.get(key)?;
+        let response = response.clone();
+        state.touch(key);
+        Some(response)
+    }
+
+    fn put(&self, key: &str, response: CachedResponse) {
+        if self.capacity == 0 {
+            return;
+        }
+        let mut state = self.state.lock().unwrap();
+        if let Some((cached, _)) = state.entries.get_mut(key) {
+            *cached = response;
+            state.touch(key);
+            return;
+        }
+        state.tick += 1;
+        let tick = state.tick;
+        state.entries.insert(key.to_string(), (response, tick));
+        state.order.insert(tick, key.to_string());
+        while state.order.len() > self.capacity {
+            if let Some((_, evicted)) = state.order.pop_first() {
+                state.entries.remove(&evicted);
+            }
+        }
+    }
+}
+
+/// A store that keeps each response as a JSON file in a directory, so the
+/// cache survives between runs.
+pub struct DiskStore {
+    dir: PathBuf,
+}
+
+impl DiskStore {
+    /// Store responses in `dir`, which is created on the first write.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{key}.json"))
+    }
+}
+
+impl CacheStore for DiskStore {
+    fn get(&self, key: &str) -> Option<CachedResponse> {
+        let data = std::fs::read(self.path(key)).ok()?;
+        serde_json::from_slice(&data).ok()
+    }
+
+    fn put(&self, key: &str, response: CachedResponse) {
+        let result = std::fs::create_dir_all(&self.dir).and_then(|()| {
+            let data = serde_json::to_vec_pretty(&response).map_err(std::io::Error::other)?;
+            std::fs::write(self.path(key), data)
+        });
+        if let Err(e) = result {
+            warn!(error = %e, dir = %self.dir.display(), "failed to write cached response");
+        }
+    }
+}
+
+/// Middleware that answers repeated `messages.create` requests from a cache.
+///
+/// Only requests with `temperature` set to 0 are cached unless `always` is
+/// enabled, since other requests are expected to vary. Streaming requests and
+/// failed responses are never cached. The key covers the URL, the body and
+/// the `anthropic-version` and `anthropic-beta` headers, so clients with
+/// different base URLs can share a store.
+///
+/// Add it before other middleware so the cache sees the request as the client
+/// built it.
+pub struct ResponseCache {
+    store: Box<dyn CacheStore>,
+    always: bool,
+}
+
+impl ResponseCache {
+    pub fn new(store: impl CacheStore + 'static) -> Self {
+        Self {
+            store: Box::new(store),
+            always: false,
+        }
+    }
+
+    /// Cache up to `capacity` responses in memory.
+    pub fn in_memory(capacity: usize) -> Self {
+        Self::new(MemoryStore::new(capacity))
+    }
+
+    /// Cache responses as files in `dir`.
+    pub fn on_disk(dir: impl Into<PathBuf>) -> Self {
+        Self::new(DiskStore::new(dir))
+    }
+
+    /// Cache every `messages.create` request, whatever its temperature.
+    pub fn always(mut self, always: bool) -> Self {
+        self.always = always;
+        self
+    }
+
+    /// The cache key for `request`, or `None` if it should not be cached.
+    fn key(&self, request: &reqwest::Request, operation: Option<&OperationInfo>) -> Option<String> {
+        let operation = operation?;
+        if operation.name != "messages.create" || operation.streaming {
+            return None;
+        }
+        let body = request.body()?.as_bytes()?;
+        if !self.always {
+            let value: serde_json::Value = serde_json::from_slice(body).ok()?;
+            if value.get("temperature")?.as_f64()? != 0.0 {
+                return None;
+            }
+        }
+
+        let mut hash = Fnv128::default();
+        hash.write(request.method().as_str().as_bytes());
+        let url = request.url();
+        hash.write(url.scheme().as_bytes());
+        hash.write(url.host_str().unwrap_or_default().as_bytes());
+        hash.write(
+            &url.port_or_known_default()
+                .unwrap_or_default()
+                .to_le_bytes(),
+        );
+        hash.write(url.path().as_bytes());
+        for name in KEYED_HEADERS {
+            for value in request.headers().get_all(*name) {
+                hash.write(name.as_bytes());
+                hash.write(value.as_bytes());
+            }
+        }
+        hash.write(body);
+        Some(format!("{:032x}", hash.finish()))
+    }
+}
+
+impl AsyncMiddleware for ResponseCache {
+    async fn handle(
+        &self,
+        request: reqwest::Request,
+        next: Next<'_>,
+    ) -> Result<reqwest::Response, Error> {
+        let operation = next.extensions().get::<OperationInfo>();
+        let Some(key) = self.key(&request, operation.as_ref()) else {
+            return next.run(request).await;
+        };
+        if let Some(cached) = self.store.get(&key) {
+            return to_response(cached);
+        }
+
+        let response = next.run(request).await?;
+        if !response.status().is_success() {
+            return Ok(response);
+        }
+        let status = response.status().as_u16();
+        let mut headers = BTreeMap::new();
+        for (name, value) in response.headers() {
+            if TRANSPORT_HEADERS.contains(&name.as_str()) {
+                continue;
+            }
+            if let Ok(value) = value.to_str() {
+                headers.insert(name.to_string(), value.to_string());
+            }
+        }
+        let bytes = response.bytes().await?;
+        let Ok(body) = String::from_utf8(bytes.to_vec()) else {
+            return to_response_bytes(status, &headers, bytes);
+        };
+        let cached = CachedResponse {
+            status,
+            headers,
+            body,
+        };
+        self.store.put(&key, cached.clone());
+        to_response(cached)
+    }
+}
+
+fn to_response(cached: CachedResponse) -> Result<reqwest::Response, Error> {
+    to_response_bytes(cached.status, &cached.headers, cached.body)
+}
+
+fn to_response_bytes(
+    status: u16,
+    headers: &BTreeMap<String, String>,
+    body: impl Into<reqwest::Body>,
+) -> Result<reqwest::Response, Error> {
+    let mut builder = http::Response::builder().status(status);
+    for (name, value) in headers {
+        builder = builder.header(name, value);
+    }
+    let response = builder
+        .body(body.into())
+        .map_err(|e| Error::Validation(format!("invalid cached response: {e}")))?;
+    Ok(reqwest::Response::from(response))
+}
+
+/// 128-bit FNV-1a, which unlike `DefaultHasher` is stable across Rust
+/// versions, so keys stay valid for an on-disk cache.
+struct Fnv128(u128);
+
+impl Default for Fnv128 {
+    fn default() -> Self {
+        Self(0x6c62272e07bb014262b821756295c58d)
+    }
+}
+
+impl Fnv128 {
+    const PRIME: u128 = 0x0000000001000000000000000000013b;
+
+    fn write(&mut self, bytes: &[u8]) {
+        // Length-prefix each part so adjacent parts can't run together.
+        for byte in (bytes.len() as u64).to_le_bytes().iter().chain(bytes) {
+            self.0 ^= u128::from(*byte);
+            self.0 = self.0.wrapping_mul(Self::PRIME);
+        }
+    }
+
+    fn finish(&self) -> u128 {
+        self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use super::*;
+    use crate::client::ClientBuilder;
+    use crate::messages::params::MessageCreateParams;
+    use crate::mock::text_message;
+    use crate::types::message::MessageParam;
+    use crate::types::model::Model;
+
+    /// Shares a memory store with the test so it can be inspected.
+    struct Shared(Arc<MemoryStore>);
+
+    impl CacheStore for Shared {
+        fn get(&self, key: &str) -> Option<CachedResponse> {
+            self.0.get(key)
+        }
+
+        fn put(&self, key: &str, response: CachedResponse) {
+            self.0.put(key, response)
+        }
+    }
+
+    fn params(prompt: &str, temperature: f64) -> MessageCreateParams {
+        MessageCreateParams::builder()
+            .model(Model::ClaudeOpus4_6)
+            .max_tokens(10)
+            .temperature(temperature)
+            .messages(vec![MessageParam::user(prompt)])
+            .build()
+    }
+
+    #[tokio::test]
+    async fn test_caches_deterministic_requests() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/messages"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(text_message("Hi")))
+            .expect(4)
+            .mount(&server)
+            .await;
+
+        let store = Arc::new(MemoryStore::new(8));
+        let client = ClientBuilder::new()
+            .api_key("test")
+            .base_url(server.uri())
+            .middleware(ResponseCache::new(Shared(store.clone())))
+            .build();
+
+        // Sent once, then answered from the cache.
+        for _ in 0..3 {
+            let message = client.messages().create(params("hi", 0.0)).await.unwrap();
+            assert_eq!(message.id, "msg_mock");
+        }
+        // A different prompt is a different key.
+        client.messages().create(params("yo", 0.0)).await.unwrap();
+        // Non-zero temperatures are never cached.
+        client.messages().create(params("hi", 0.7)).await.unwrap();
+        client.messages().create(params("hi", 0.7)).await.unwrap();
+        assert_eq!(store.len(), 2);
+    }
+
+    #[test]
+    fn test_key_covers_scheme_and_host() {
+        let cache = ResponseCache::in_memory(8).always(true);
+        let operation = OperationInfo {
+            name: "messages.create".to_string(),
+            attempt: 0,
+            streaming: false,
+        };
+        let key = |url: &str| {
+            let mut request = reqwest::Request::new(reqwest::Method::POST, url.parse().unwrap());
+            *request.body_mut() = Some("{}".into());
+            cache.key(&request, Some(&operation)).unwrap()
+        };
+        let base = key("https://api.anthropic.com/v1/messages");
+        assert_eq!(base, key("https://api.anthropic.com:443/v1/messages"));
+        assert_ne!(base, key("http://api.anthropic.com/v1/messages"));
+        assert_ne!(base, key("https://proxy.example.com/v1/messages"));
+        assert_ne!(base, key("https://api.anthropic.com:8443/v1/messages"));
+    }
+
+    #[test]
+    fn test_memory_store_evicts_least_recently_used() {
+        let store = MemoryStore::new(2);
+        let response = |body: &str| CachedResponse {
+            status: 200,
+            headers: BTreeMap::new(),
+            body: body.to_string(),
+        };
+        store.put("a", response("a"));
+        store.put("b", response("b"));
+        assert!(store.get("a").is_some());
+        store.put("c", response("c"));
+        assert!(store.get("b").is_none());
+        assert_eq!(store.get("a"), Some(response("a")));
+        assert_eq!(store.get("c"), Some(response("c")));
+    }
+
+    #[test]
+    fn test_disk_store_round_trip() {
+        let dir = std::env::temp_dir().join(format!("uno-cache-{}", std::process::id()));
+        let store = DiskStore::new(&dir);
+        let response = CachedResponse {
+            status: 200,
+            headers: BTreeMap::from([("request-id".to_string(), "req_1".to_string())]),
+            body: "{}".to_string(),
+        };
+        store.put("k", response.clone());
+        assert_eq!(store.get("k"), Some(response));
+        assert_eq!(store.get("missing"), None);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -1,6 +1,19 @@
-use std::time::Duration;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
 
 use rand::Rng;
+use tokio::time::Instant;
+use tracing::debug;
+
+use crate::error::Error;
+use crate::time::parse_rfc3339;
+
+/// Longest proactive delay applied before a request, matching the cap on
+/// honored `retry-after` values.
+const MAX_QUOTA_DELAY: Duration = Duration::from_secs(60);
+
+/// The `anthropic-ratelimit-<kind>-*` header families.
+const RATE_LIMIT_KINDS: &[&str] = &["requests", "tokens", "input-tokens", "output-tokens"];
 
 /// Configuration for retry behavior.
 #[derive(Debug, Clone)]
@@ -11,6 +24,27 @@ pub struct RetryPolicy {
     pub initial_delay: Duration,
     /// Maximum delay between retries (default: 8s).
     pub max_delay: Duration,
+    /// Backoff for 529 `overloaded_error` responses, which clear more slowly
+    /// than other failures (default: 2s initial, 30s maximum).
+    pub overloaded_backoff: BackoffProfile,
+    /// How backoff delays are randomized.
+    pub jitter: JitterStrategy,
+    /// Which failures of streaming requests are retried.
+    pub stream: StreamRetryPolicy,
+    /// When set, a response whose `anthropic-ratelimit-*` headers show this
+    /// fraction or less of a quota remaining (e.g. `0.05`) delays the client's
+    /// upcoming requests until that quota resets. Disabled by default.
+    pub proactive_backoff_threshold: Option<f64>,
+    /// Total time budget for a request, covering every attempt and the backoff
+    /// between them. A retry whose delay would overrun the budget is not made,
+    /// and an attempt still running when it expires fails with
+    /// `Error::Timeout`. Unlimited by default.
+    pub max_elapsed: Option<Duration>,
+    /// Overrides the built-in decision of which failures are retried.
+    pub classifier: Option<RetryClassifier>,
+    /// Fail fast after repeated server failures instead of retrying each
+    /// request in full. Disabled by default.
+    pub circuit_breaker: Option<CircuitBreakerConfig>,
 }
 
 impl Default for RetryPolicy {
@@ -19,19 +53,138 @@ impl Default for RetryPolicy {
             max_retries: 2,
             initial_delay: Duration::from_millis(500),
             max_delay: Duration::from_secs(8),
+            overloaded_backoff: BackoffProfile {
+                initial_delay: Duration::from_secs(2),
+                max_delay: Duration::from_secs(30),
+            },
+            jitter: JitterStrategy::default(),
+            stream: StreamRetryPolicy::default(),
+            proactive_backoff_threshold: None,
+            max_elapsed: None,
+            classifier: None,
+            circuit_breaker: None,
         }
     }
 }
 
+/// Exponential backoff settings for one class of retried failure.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BackoffProfile {
+    /// Base delay for exponential backoff.
+    pub initial_delay: Duration,
+    /// Maximum delay between retries.
+    pub max_delay: Duration,
+}
+
+/// Which backoff profile a retried failure uses.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RetryClass {
+    /// `initial_delay` and `max_delay`.
+    #[default]
+    Standard,
+    /// `overloaded_backoff`, for 529 `overloaded_error` responses.
+    Overloaded,
+}
+
+/// Randomization applied to backoff delays, so that clients failing together
+/// do not retry in lockstep.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum JitterStrategy {
+    /// Exactly `initial_delay * 2^attempt`, capped at `max_delay`.
+    None,
+    /// Subtract up to 25% from the capped exponential delay.
+    #[default]
+    Subtractive,
+    /// Uniformly random between zero and the capped exponential delay.
+    Full,
+    /// Uniformly random between `initial_delay` and three times the
+    /// exponential delay of the previous attempt, capped at `max_delay`.
+    /// Spreads retries the most, at the cost of occasionally longer waits.
+    Decorrelated,
+}
+
+/// What a [`RetryClassifier`] wants done with a failed attempt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RetryDecision {
+    /// Use the built-in rules (`x-should-retry`, then the status code).
+    Default,
+    /// Retry, subject to `max_retries` and `max_elapsed`.
+    Retry,
+    /// Return the error without retrying.
+    DoNotRetry,
+}
+
+/// A caller-supplied function deciding whether a failed attempt is retried.
+///
+/// It receives the error the attempt failed with and the zero-based attempt
+/// number.
+///
+/// ```ignore
+/// let policy = RetryPolicy {
+///     classifier: Some(RetryClassifier::new(|err, _attempt| match err {
+///         Error::Overloaded { .. } => RetryDecision::Retry,
+///         _ => RetryDecision::Default,
+///     })),
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Clone)]
+pub struct RetryClassifier(Arc<ClassifyFn>);
+
+type ClassifyFn = dyn Fn(&Error, u32) -> RetryDecision + Send + Sync;
+
+impl RetryClassifier {
+    pub fn new(f: impl Fn(&Error, u32) -> RetryDecision + Send + Sync + 'static) -> Self {
+        Self(Arc::new(f))
+    }
+
+    pub fn classify(&self, error: &Error, attempt: u32) -> RetryDecision {
+        (self.0)(error, attempt)
+    }
+}
+
+impl std::fmt::Debug for RetryClassifier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("RetryClassifier(..)")
+    }
+}
+
+/// Retry behavior for streaming requests.
+///
+/// Once a `message_start` event has been received the model is generating, and
+/// retrying would produce (and bill) a second, duplicate generation. Streams are
+/// therefore never retried silently after that point; the error is returned to
+/// the caller instead.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StreamRetryPolicy {
+    /// Retry connection failures, retryable status codes and retryable SSE
+    /// `error` events that arrive before `message_start`.
+    #[default]
+    BeforeMessageStart,
+    /// Never retry streaming requests, including connection failures.
+    Never,
+}
+
 impl RetryPolicy {
     /// Calculate the delay for a given retry attempt.
     ///
     /// Uses exponential backoff: `initial_delay * 2^attempt`, capped at `max_delay`,
-    /// with jitter subtracted (up to 25% of the computed delay).
+    /// randomized according to `jitter` (by default, up to 25% is subtracted).
     ///
     /// If a `retry_after` duration is provided (from Retry-After header), it takes
     /// precedence as long as it is reasonable (< 60 seconds).
     pub fn delay_for_attempt(&self, attempt: u32, retry_after: Option<Duration>) -> Duration {
+        self.delay_for_class(RetryClass::Standard, attempt, retry_after)
+    }
+
+    /// Like [`delay_for_attempt`](Self::delay_for_attempt), but backing off
+    /// with the profile for `class`.
+    pub fn delay_for_class(
+        &self,
+        class: RetryClass,
+        attempt: u32,
+        retry_after: Option<Duration>,
+    ) -> Duration {
         // If the server told us to wait and it's reasonable, use that
         if let Some(ra) = retry_after
             && ra < Duration::from_secs(60)
@@ -39,19 +192,258 @@ impl RetryPolicy {
             return ra;
         }
 
+        let (initial_delay, max_delay) = match class {
+            RetryClass::Standard => (self.initial_delay, self.max_delay),
+            RetryClass::Overloaded => (
+                self.overloaded_backoff.initial_delay,
+                self.overloaded_backoff.max_delay,
+            ),
+        };
+
         // Exponential backoff: initial_delay * 2^attempt
-        let delay_ms = self.initial_delay.as_millis() as u64 * 2u64.saturating_pow(attempt);
-        let max_ms = self.max_delay.as_millis() as u64;
+        let initial_ms = initial_delay.as_millis() as u64;
+        let delay_ms = initial_ms.saturating_mul(2u64.saturating_pow(attempt));
+        let max_ms = max_delay.as_millis() as u64;
         let capped_ms = delay_ms.min(max_ms);
 
-        // Subtract jitter: up to 25% of the delay
-        let jitter = if capped_ms > 0 {
-            rand::rng().random_range(0..=(capped_ms / 4))
-        } else {
-            0
+        let mut rng = rand::rng();
+        let ms = match self.jitter {
+            JitterStrategy::None => capped_ms,
+            // Subtract jitter: up to 25% of the delay
+            JitterStrategy::Subtractive => capped_ms - rng.random_range(0..=(capped_ms / 4)),
+            JitterStrategy::Full => rng.random_range(0..=capped_ms),
+            JitterStrategy::Decorrelated => {
+                let previous_ms = if attempt == 0 {
+                    initial_ms
+                } else {
+                    initial_ms.saturating_mul(2u64.saturating_pow(attempt - 1))
+                };
+                let upper_ms = previous_ms.saturating_mul(3).min(max_ms);
+                rng.random_range(initial_ms.min(upper_ms)..=upper_ms)
+            }
+        };
+
+        Duration::from_millis(ms)
+    }
+
+    /// The backoff before retrying attempt number `attempt`, which failed
+    /// with `error`, honoring its `retry-after`.
+    pub fn delay_for_error(&self, error: &Error, attempt: u32) -> Duration {
+        self.delay_for_class(error.retry_class(), attempt, error.retry_after())
+    }
+
+    /// How long to hold upcoming requests given a response's rate limit headers.
+    ///
+    /// Returns `None` unless `proactive_backoff_threshold` is set and some quota
+    /// is at or below it with a reset time in the future.
+    pub fn quota_delay(&self, headers: &reqwest::header::HeaderMap) -> Option<Duration> {
+        let threshold = self.proactive_backoff_threshold?;
+        let now = SystemTime::now();
+        RATE_LIMIT_KINDS
+            .iter()
+            .filter_map(|kind| parse_rate_limit(headers, kind))
+            .filter(|window| window.fraction_remaining() <= threshold)
+            .filter_map(|window| window.reset?.duration_since(now).ok())
+            .max()
+            .map(|delay| delay.min(MAX_QUOTA_DELAY))
+    }
+
+    /// Whether `error` from attempt number `attempt` should be retried, where
+    /// `default` is the built-in answer. Does not check the attempt limits.
+    pub(crate) fn should_retry(&self, error: &Error, attempt: u32, default: bool) -> bool {
+        match self.classifier.as_ref().map(|c| c.classify(error, attempt)) {
+            Some(RetryDecision::Retry) => true,
+            Some(RetryDecision::DoNotRetry) => false,
+            Some(RetryDecision::Default) | None => default,
+        }
+    }
+
+    /// When a request started at `started` runs out of `max_elapsed`.
+    pub(crate) fn deadline(&self, started: Instant) -> Option<Instant> {
+        self.max_elapsed.map(|budget| started + budget)
+    }
+
+    /// Whether sleeping for `delay` before another attempt stays within
+    /// `max_elapsed` of `started`.
+    pub(crate) fn allows_retry(&self, started: Instant, delay: Duration) -> bool {
+        self.max_elapsed
+            .is_none_or(|budget| started.elapsed() + delay < budget)
+    }
+}
+
+/// Settings for a client's circuit breaker.
+///
+/// After `failure_threshold` consecutive failed attempts (5xx responses,
+/// connection errors and timeouts) the circuit opens and requests fail with
+/// `Error::CircuitOpen` without being sent. Once `cool_down` has passed a
+/// single probe request is let through: if it succeeds the circuit closes,
+/// otherwise it opens for another `cool_down`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CircuitBreakerConfig {
+    pub failure_threshold: u32,
+    pub cool_down: Duration,
+}
+
+impl Default for CircuitBreakerConfig {
+    fn default() -> Self {
+        Self {
+            failure_threshold: 5,
+            cool_down: Duration::from_secs(30),
+        }
+    }
+}
+
+/// Circuit breaker state shared by all clones of a client.
+#[derive(Debug)]
+pub(crate) struct CircuitBreaker {
+    config: CircuitBreakerConfig,
+    state: Mutex<CircuitState>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CircuitState {
+    Closed {
+        failures: u32,
+    },
+    Open {
+        until: Instant,
+    },
+    /// A probe was admitted at `since`. If it never reports back (e.g. the
+    /// request was cancelled), another probe is admitted after `cool_down`.
+    HalfOpen {
+        since: Instant,
+    },
+}
+
+impl CircuitBreaker {
+    pub(crate) fn new(config: CircuitBreakerConfig) -> Self {
+        Self {
+            config,
+            state: Mutex::new(CircuitState::Closed { failures: 0 }),
+        }
+    }
+
+    /// Allow an attempt to be sent, or fail with `Error::CircuitOpen`.
+    pub(crate) fn admit(&self) -> Result<(), Error> {
+        let mut state = self.lock();
+        let now = Instant::now();
+        match *state {
+            CircuitState::Closed { .. } => Ok(()),
+            CircuitState::Open { until } if now < until => Err(Error::CircuitOpen {
+                retry_after: Some(until - now),
+            }),
+            CircuitState::HalfOpen { since } if now < since + self.config.cool_down => {
+                Err(Error::CircuitOpen { retry_after: None })
+            }
+            CircuitState::Open { .. } | CircuitState::HalfOpen { .. } => {
+                debug!("circuit breaker half-open, sending probe");
+                *state = CircuitState::HalfOpen { since: now };
+                Ok(())
+            }
+        }
+    }
+
+    /// Record the result of an admitted attempt.
+    pub(crate) fn record(&self, result: &Result<reqwest::Response, Error>) {
+        let failed = match result {
+            Ok(response) => response.status().is_server_error(),
+            Err(Error::Api { status, .. }) => *status >= 500,
+            Err(e) => e.is_retryable(),
         };
+        let mut state = self.lock();
+        let open = CircuitState::Open {
+            until: Instant::now() + self.config.cool_down,
+        };
+        *state = match (*state, failed) {
+            (_, false) => CircuitState::Closed { failures: 0 },
+            (CircuitState::Closed { failures }, true) => {
+                if failures + 1 < self.config.failure_threshold {
+                    CircuitState::Closed {
+                        failures: failures + 1,
+                    }
+                } else {
+                    tracing::warn!(failures = failures + 1, "circuit breaker opened");
+                    open
+                }
+            }
+            (CircuitState::HalfOpen { .. }, true) => {
+                tracing::warn!("circuit breaker probe failed");
+                open
+            }
+            (CircuitState::Open { until }, true) => CircuitState::Open { until },
+        };
+    }
 
-        Duration::from_millis(capped_ms - jitter)
+    fn lock(&self) -> std::sync::MutexGuard<'_, CircuitState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// One quota window reported by the `anthropic-ratelimit-*` headers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimitWindow {
+    pub limit: u64,
+    pub remaining: u64,
+    /// When the quota is fully replenished.
+    pub reset: Option<SystemTime>,
+}
+
+impl RateLimitWindow {
+    /// Fraction of the limit still available, from 0.0 to 1.0.
+    pub fn fraction_remaining(&self) -> f64 {
+        if self.limit == 0 {
+            return 0.0;
+        }
+        self.remaining as f64 / self.limit as f64
+    }
+}
+
+/// Parse the `anthropic-ratelimit-<kind>-{limit,remaining,reset}` headers, where
+/// `kind` is `requests`, `tokens`, `input-tokens` or `output-tokens`.
+pub fn parse_rate_limit(
+    headers: &reqwest::header::HeaderMap,
+    kind: &str,
+) -> Option<RateLimitWindow> {
+    let get = |suffix: &str| {
+        headers
+            .get(format!("anthropic-ratelimit-{kind}-{suffix}"))
+            .and_then(|v| v.to_str().ok())
+    };
+    Some(RateLimitWindow {
+        limit: get("limit")?.parse().ok()?,
+        remaining: get("remaining")?.parse().ok()?,
+        reset: get("reset").and_then(parse_rfc3339),
+    })
+}
+
+/// Holds a client's requests until a depleted quota resets.
+#[derive(Debug, Default)]
+pub(crate) struct QuotaGate {
+    until: Mutex<Option<Instant>>,
+}
+
+impl QuotaGate {
+    /// Hold requests for at least `delay` from now.
+    pub(crate) fn hold(&self, delay: Duration) {
+        let until = Instant::now() + delay;
+        let mut current = self.until.lock().unwrap_or_else(|e| e.into_inner());
+        if current.is_none_or(|c| c < until) {
+            *current = Some(until);
+        }
+    }
+
+    /// Wait until any hold has expired.
+    pub(crate) async fn wait(&self) {
+        let until = *self.until.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(until) = until
+            && until > Instant::now()
+        {
+            debug!(
+                delay_ms = (until - Instant::now()).as_millis() as u64,
+                "delaying request until rate limit quota resets"
+            );
+            tokio::time::sleep_until(until).await;
+        }
     }
 }
 
@@ -107,6 +499,7 @@ mod tests {
         assert_eq!(policy.max_retries, 2);
         assert_eq!(policy.initial_delay, Duration::from_millis(500));
         assert_eq!(policy.max_delay, Duration::from_secs(8));
+        assert_eq!(policy.stream, StreamRetryPolicy::BeforeMessageStart);
     }
 
     #[test]
@@ -138,6 +531,49 @@ mod tests {
         assert!(d10 <= Duration::from_millis(8000));
     }
 
+    #[test]
+    fn test_delay_for_overloaded() {
+        let policy = RetryPolicy {
+            jitter: JitterStrategy::None,
+            ..Default::default()
+        };
+        let overloaded = Error::Overloaded {
+            body: crate::error::ApiErrorBody {
+                error_type: "overloaded_error".to_string(),
+                message: "Overloaded".to_string(),
+            },
+            retry_after: None,
+        };
+        assert_eq!(
+            policy.delay_for_error(&overloaded, 0),
+            Duration::from_secs(2)
+        );
+        assert_eq!(
+            policy.delay_for_error(&overloaded, 2),
+            Duration::from_secs(8)
+        );
+        assert_eq!(
+            policy.delay_for_error(&overloaded, 10),
+            Duration::from_secs(30)
+        );
+        assert_eq!(
+            policy.delay_for_error(&Error::Timeout, 0),
+            Duration::from_millis(500)
+        );
+
+        let overloaded = Error::Overloaded {
+            body: crate::error::ApiErrorBody {
+                error_type: "overloaded_error".to_string(),
+                message: "Overloaded".to_string(),
+            },
+            retry_after: Some(Duration::from_secs(1)),
+        };
+        assert_eq!(
+            policy.delay_for_error(&overloaded, 3),
+            Duration::from_secs(1)
+        );
+    }
+
     #[test]
     fn test_delay_with_retry_after() {
         let policy = RetryPolicy::default();
@@ -208,4 +644,185 @@ mod tests {
         headers.insert("x-should-retry", HeaderValue::from_static("True"));
         assert_eq!(check_should_retry_header(&headers), Some(true));
     }
+
+    #[test]
+    fn test_quota_delay() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "anthropic-ratelimit-requests-limit",
+            HeaderValue::from_static("100"),
+        );
+        headers.insert(
+            "anthropic-ratelimit-requests-remaining",
+            HeaderValue::from_static("2"),
+        );
+        headers.insert(
+            "anthropic-ratelimit-requests-reset",
+            HeaderValue::from_static("2999-01-01T00:00:00Z"),
+        );
+
+        let window = parse_rate_limit(&headers, "requests").unwrap();
+        assert_eq!(window.limit, 100);
+        assert_eq!(window.remaining, 2);
+
+        assert!(RetryPolicy::default().quota_delay(&headers).is_none());
+
+        let policy = RetryPolicy {
+            proactive_backoff_threshold: Some(0.05),
+            ..RetryPolicy::default()
+        };
+        // Far-off resets are capped.
+        assert_eq!(policy.quota_delay(&headers), Some(MAX_QUOTA_DELAY));
+
+        let relaxed = RetryPolicy {
+            proactive_backoff_threshold: Some(0.01),
+            ..RetryPolicy::default()
+        };
+        assert!(relaxed.quota_delay(&headers).is_none());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_quota_gate_waits() {
+        let gate = QuotaGate::default();
+        let start = Instant::now();
+        gate.wait().await;
+        assert_eq!(start.elapsed(), Duration::ZERO);
+
+        gate.hold(Duration::from_secs(5));
+        gate.hold(Duration::from_secs(1));
+        gate.wait().await;
+        assert!(start.elapsed() >= Duration::from_secs(5));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_allows_retry_within_max_elapsed() {
+        let started = Instant::now();
+        assert!(RetryPolicy::default().allows_retry(started, Duration::from_secs(3600)));
+
+        let policy = RetryPolicy {
+            max_elapsed: Some(Duration::from_secs(10)),
+            ..Default::default()
+        };
+        assert!(policy.allows_retry(started, Duration::from_secs(4)));
+        tokio::time::advance(Duration::from_secs(7)).await;
+        assert!(!policy.allows_retry(started, Duration::from_secs(4)));
+        assert_eq!(
+            policy.deadline(started),
+            Some(started + Duration::from_secs(10))
+        );
+    }
+
+    #[test]
+    fn test_classifier_overrides_default() {
+        let policy = RetryPolicy {
+            classifier: Some(RetryClassifier::new(|err, attempt| match err {
+                Error::Api { status: 400, .. } => RetryDecision::Retry,
+                Error::Timeout if attempt > 0 => RetryDecision::DoNotRetry,
+                _ => RetryDecision::Default,
+            })),
+            ..Default::default()
+        };
+        let bad_request = Error::Api {
+            status: 400,
+            body: crate::error::ApiErrorBody {
+                error_type: "invalid_request_error".to_string(),
+                message: "Try again".to_string(),
+            },
+            retry_after: None,
+        };
+        assert!(policy.should_retry(&bad_request, 0, false));
+        assert!(policy.should_retry(&Error::Timeout, 0, true));
+        assert!(!policy.should_retry(&Error::Timeout, 1, true));
+        assert!(!RetryPolicy::default().should_retry(&bad_request, 0, false));
+    }
+
+    fn response(status: u16) -> Result<reqwest::Response, Error> {
+        Ok(http::Response::builder()
+            .status(status)
+            .body(Vec::<u8>::new())
+            .unwrap()
+            .into())
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_circuit_breaker_opens_and_probes() {
+        let breaker = CircuitBreaker::new(CircuitBreakerConfig {
+            failure_threshold: 2,
+            cool_down: Duration::from_secs(10),
+        });
+        breaker.admit().unwrap();
+        breaker.record(&response(529));
+        breaker.admit().unwrap();
+        breaker.record(&response(400));
+        breaker.admit().unwrap();
+        breaker.record(&response(500));
+        breaker.admit().unwrap();
+        breaker.record(&Err(Error::Timeout));
+
+        let err = breaker.admit().unwrap_err();
+        assert_eq!(err.retry_after(), Some(Duration::from_secs(10)));
+
+        // One probe after the cool-down; others fail fast while it is in flight.
+        tokio::time::advance(Duration::from_secs(10)).await;
+        breaker.admit().unwrap();
+        assert!(matches!(
+            breaker.admit(),
+            Err(Error::CircuitOpen { retry_after: None })
+        ));
+        breaker.record(&response(529));
+        assert!(breaker.admit().is_err());
+
+        tokio::time::advance(Duration::from_secs(10)).await;
+        breaker.admit().unwrap();
+        breaker.record(&response(200));
+        breaker.admit().unwrap();
+        breaker.admit().unwrap();
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_circuit_breaker_abandoned_probe() {
+        let breaker = CircuitBreaker::new(CircuitBreakerConfig {
+            failure_threshold: 1,
+            cool_down: Duration::from_secs(5),
+        });
+        breaker.record(&Err(Error::Timeout));
+        tokio::time::advance(Duration::from_secs(5)).await;
+        breaker.admit().unwrap();
+        assert!(breaker.admit().is_err());
+        tokio::time::advance(Duration::from_secs(5)).await;
+        breaker.admit().unwrap();
+    }
+
+    #[test]
+    fn test_jitter_strategies() {
+        let policy = |jitter| RetryPolicy {
+            jitter,
+            ..Default::default()
+        };
+
+        let none = policy(JitterStrategy::None);
+        assert_eq!(none.delay_for_attempt(0, None), Duration::from_millis(500));
+        assert_eq!(none.delay_for_attempt(2, None), Duration::from_millis(2000));
+        assert_eq!(none.delay_for_attempt(10, None), Duration::from_secs(8));
+
+        let full = policy(JitterStrategy::Full);
+        for _ in 0..20 {
+            assert!(full.delay_for_attempt(2, None) <= Duration::from_millis(2000));
+        }
+
+        let decorrelated = policy(JitterStrategy::Decorrelated);
+        for _ in 0..20 {
+            let d0 = decorrelated.delay_for_attempt(0, None);
+            assert!(d0 >= Duration::from_millis(500) && d0 <= Duration::from_millis(1500));
+            let d2 = decorrelated.delay_for_attempt(2, None);
+            assert!(d2 >= Duration::from_millis(500) && d2 <= Duration::from_millis(3000));
+            assert!(decorrelated.delay_for_attempt(10, None) <= Duration::from_secs(8));
+        }
+
+        // retry-after still takes precedence
+        assert_eq!(
+            full.delay_for_attempt(3, Some(Duration::from_secs(2))),
+            Duration::from_secs(2)
+        );
+    }
 }
@@ -1,8 +1,9 @@
 pub mod types;
 
 use reqwest::header::{HeaderMap, HeaderValue};
+use reqwest::multipart;
 
-use crate::client::Client;
+use crate::client::{Client, RequestOptions};
 use crate::error::Error;
 use crate::types::Page;
 
@@ -15,17 +16,39 @@ pub use self::types::*;
 /// This API requires the `skills-2025-10-02` beta header.
 pub struct SkillService<'a> {
     pub(crate) client: &'a Client,
+    pub(crate) extra_headers: Option<HeaderMap>,
 }
 
 impl<'a> SkillService<'a> {
-    pub(crate) fn new(client: &'a Client) -> Self {
-        Self { client }
+    /// Create a service bound to `client`. Equivalent to `client.skills()`.
+    pub fn new(client: &'a Client) -> Self {
+        Self {
+            client,
+            extra_headers: None,
+        }
+    }
+
+    /// Create a service that sends `headers` with every request.
+    ///
+    /// The required beta flag is sent alongside any `anthropic-beta` value in `headers`.
+    pub fn with_extra_headers(client: &'a Client, headers: HeaderMap) -> Self {
+        Self {
+            client,
+            extra_headers: Some(headers),
+        }
+    }
+
+    /// Send the headers in `options` with requests made through this service
+    /// and its `versions()`.
+    pub fn with_options(mut self, options: RequestOptions) -> Self {
+        self.extra_headers = options.merge_into(self.extra_headers.take());
+        self
     }
 
-    /// Build the beta header for skills API requests.
-    fn beta_headers() -> HeaderMap {
-        let mut headers = HeaderMap::new();
-        headers.insert(
+    /// Build the headers for skills API requests, including the beta header.
+    fn beta_headers(&self) -> HeaderMap {
+        let mut headers = self.extra_headers.clone().unwrap_or_default();
+        headers.append(
             "anthropic-beta",
             HeaderValue::from_static("skills-2025-10-02"),
         );
@@ -36,16 +59,39 @@ impl<'a> SkillService<'a> {
     ///
     /// Calls `POST /v1/skills`.
     pub async fn create(&self, params: SkillCreateParams) -> Result<Skill, Error> {
-        let headers = Self::beta_headers();
+        let headers = self.beta_headers();
         self.client.post("skills", &params, Some(&headers)).await
     }
 
+    /// Create a skill from a bundle of files.
+    ///
+    /// Calls `POST /v1/skills` with multipart form data.
+    ///
+    /// ```ignore
+    /// let files = SkillFile::read_dir("skills/report-writer")?;
+    /// let skill = client.skills().upload(Some("Report writer"), files).await?;
+    /// ```
+    pub async fn upload(
+        &self,
+        display_title: Option<&str>,
+        files: Vec<SkillFile>,
+    ) -> Result<Skill, Error> {
+        let mut form = bundle_form(files);
+        if let Some(title) = display_title {
+            form = form.text("display_title", title.to_string());
+        }
+        let headers = self.beta_headers();
+        self.client
+            .post_multipart("skills", form, Some(&headers))
+            .await
+    }
+
     /// Get a skill by ID.
     ///
     /// Calls `GET /v1/skills/{skill_id}`.
     pub async fn get(&self, skill_id: &str) -> Result<Skill, Error> {
         let path = format!("skills/{}", skill_id);
-        let headers = Self::beta_headers();
+        let headers = self.beta_headers();
         self.client.get(&path, Some(&headers)).await
     }
 
@@ -59,7 +105,7 @@ impl<'a> SkillService<'a> {
         } else {
             format!("skills?{}", query)
         };
-        let headers = Self::beta_headers();
+        let headers = self.beta_headers();
         self.client.get(&path, Some(&headers)).await
     }
 
@@ -68,7 +114,7 @@ impl<'a> SkillService<'a> {
     /// Calls `DELETE /v1/skills/{skill_id}`.
     pub async fn delete(&self, skill_id: &str) -> Result<DeletedSkill, Error> {
         let path = format!("skills/{}", skill_id);
-        let headers = Self::beta_headers();
+        let headers = self.beta_headers();
         self.client.delete(&path, Some(&headers)).await
     }
 
@@ -76,6 +122,7 @@ impl<'a> SkillService<'a> {
     pub fn versions(&self) -> SkillVersionService<'a> {
         SkillVersionService {
             client: self.client,
+            extra_headers: self.extra_headers.clone(),
         }
     }
 }
@@ -83,13 +130,14 @@ impl<'a> SkillService<'a> {
 /// Service for skill version operations.
 pub struct SkillVersionService<'a> {
     pub(crate) client: &'a Client,
+    pub(crate) extra_headers: Option<HeaderMap>,
 }
 
 impl<'a> SkillVersionService<'a> {
-    /// Build the beta header for skills API requests.
-    fn beta_headers() -> HeaderMap {
-        let mut headers = HeaderMap::new();
-        headers.insert(
+    /// Build the headers for skills API requests, including the beta header.
+    fn beta_headers(&self) -> HeaderMap {
+        let mut headers = self.extra_headers.clone().unwrap_or_default();
+        headers.append(
             "anthropic-beta",
             HeaderValue::from_static("skills-2025-10-02"),
         );
@@ -105,16 +153,31 @@ impl<'a> SkillVersionService<'a> {
         params: SkillVersionCreateParams,
     ) -> Result<SkillVersion, Error> {
         let path = format!("skills/{}/versions", skill_id);
-        let headers = Self::beta_headers();
+        let headers = self.beta_headers();
         self.client.post(&path, &params, Some(&headers)).await
     }
 
+    /// Create a new version of a skill from a bundle of files.
+    ///
+    /// Calls `POST /v1/skills/{skill_id}/versions` with multipart form data.
+    pub async fn upload(
+        &self,
+        skill_id: &str,
+        files: Vec<SkillFile>,
+    ) -> Result<SkillVersion, Error> {
+        let path = format!("skills/{}/versions", skill_id);
+        let headers = self.beta_headers();
+        self.client
+            .post_multipart(&path, bundle_form(files), Some(&headers))
+            .await
+    }
+
     /// Get a skill version by ID.
     ///
     /// Calls `GET /v1/skills/{skill_id}/versions/{version_id}`.
     pub async fn get(&self, skill_id: &str, version_id: &str) -> Result<SkillVersion, Error> {
         let path = format!("skills/{}/versions/{}", skill_id, version_id);
-        let headers = Self::beta_headers();
+        let headers = self.beta_headers();
         self.client.get(&path, Some(&headers)).await
     }
 
@@ -132,7 +195,7 @@ impl<'a> SkillVersionService<'a> {
         } else {
             format!("skills/{}/versions?{}", skill_id, query)
         };
-        let headers = Self::beta_headers();
+        let headers = self.beta_headers();
         self.client.get(&path, Some(&headers)).await
     }
 
@@ -145,11 +208,23 @@ impl<'a> SkillVersionService<'a> {
         version_id: &str,
     ) -> Result<DeletedSkillVersion, Error> {
         let path = format!("skills/{}/versions/{}", skill_id, version_id);
-        let headers = Self::beta_headers();
+        let headers = self.beta_headers();
         self.client.delete(&path, Some(&headers)).await
     }
 }
 
+/// Build the multipart form for a skill bundle.
+fn bundle_form(files: Vec<SkillFile>) -> multipart::Form {
+    files
+        .into_iter()
+        .fold(multipart::Form::new(), |form, file| {
+            form.part(
+                "files[]",
+                multipart::Part::bytes(file.data).file_name(file.path),
+            )
+        })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -160,6 +235,61 @@ mod tests {
         assert_eq!(params.to_query_string(), "");
     }
 
+    #[tokio::test]
+    async fn test_upload_sends_bundle() {
+        use wiremock::matchers::{body_string_contains, header, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/skills"))
+            .and(header("anthropic-beta", "skills-2025-10-02"))
+            .and(body_string_contains("filename=\"report/SKILL.md\""))
+            .and(body_string_contains("Report writer"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "id": "skill_01",
+                "type": "skill",
+                "created_at": "2025-10-02T00:00:00Z",
+                "updated_at": "2025-10-02T00:00:00Z",
+                "display_title": "Report writer",
+                "source": "custom",
+                "latest_version": "1"
+            })))
+            .mount(&server)
+            .await;
+
+        let client = Client::builder()
+            .api_key("test")
+            .base_url(server.uri())
+            .build();
+        let skill = client
+            .skills()
+            .upload(
+                Some("Report writer"),
+                vec![SkillFile::new(
+                    "report/SKILL.md",
+                    "---\nname: report\n---\n",
+                )],
+            )
+            .await
+            .unwrap();
+        assert_eq!(skill.id, "skill_01");
+    }
+
+    #[test]
+    fn test_skill_file_read_dir() {
+        let root = std::env::temp_dir().join(format!("skill-test-{}", std::process::id()));
+        let dir = root.join("report");
+        std::fs::create_dir_all(dir.join("scripts")).unwrap();
+        std::fs::write(dir.join("SKILL.md"), "skill").unwrap();
+        std::fs::write(dir.join("scripts/run.py"), "print()").unwrap();
+
+        let files = SkillFile::read_dir(&dir).unwrap();
+        std::fs::remove_dir_all(&root).unwrap();
+        let paths: Vec<_> = files.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(paths, vec!["report/SKILL.md", "report/scripts/run.py"]);
+    }
+
     #[test]
     fn test_skill_version_list_params_default() {
         let params = SkillVersionListParams::default();
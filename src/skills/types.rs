@@ -2,6 +2,7 @@ use serde::{Deserialize, Serialize};
 
 /// A skill resource.
 #[derive(Debug, Clone, Deserialize)]
+#[cfg_attr(feature = "strict-serde", serde(deny_unknown_fields))]
 pub struct Skill {
     pub id: String,
     #[serde(rename = "type")]
@@ -16,6 +17,7 @@ pub struct Skill {
 
 /// A skill version resource.
 #[derive(Debug, Clone, Deserialize)]
+#[cfg_attr(feature = "strict-serde", serde(deny_unknown_fields))]
 pub struct SkillVersion {
     pub id: String,
     #[serde(rename = "type")]
@@ -26,6 +28,7 @@ pub struct SkillVersion {
 
 /// Response when a skill is deleted.
 #[derive(Debug, Clone, Deserialize)]
+#[cfg_attr(feature = "strict-serde", serde(deny_unknown_fields))]
 pub struct DeletedSkill {
     pub id: String,
     #[serde(rename = "type")]
@@ -34,6 +37,7 @@ pub struct DeletedSkill {
 
 /// Response when a skill version is deleted.
 #[derive(Debug, Clone, Deserialize)]
+#[cfg_attr(feature = "strict-serde", serde(deny_unknown_fields))]
 pub struct DeletedSkillVersion {
     pub id: String,
     #[serde(rename = "type")]
@@ -52,6 +56,51 @@ pub struct SkillCreateParams {
     pub content: Option<String>,
 }
 
+/// A file in a skill bundle, for `SkillService::upload`.
+#[derive(Debug, Clone)]
+pub struct SkillFile {
+    /// Path within the bundle, e.g. `my-skill/SKILL.md`. Every bundle needs a
+    /// `SKILL.md` at the top of its directory.
+    pub path: String,
+    pub data: Vec<u8>,
+}
+
+impl SkillFile {
+    pub fn new(path: impl Into<String>, data: impl Into<Vec<u8>>) -> Self {
+        Self {
+            path: path.into(),
+            data: data.into(),
+        }
+    }
+
+    /// Read every file under `dir`, with paths relative to `dir`'s parent so
+    /// the bundle keeps the directory as its top level.
+    pub fn read_dir(dir: impl AsRef<std::path::Path>) -> std::io::Result<Vec<SkillFile>> {
+        let dir = dir.as_ref();
+        let root = dir.parent().unwrap_or(dir);
+        let mut files = Vec::new();
+        let mut pending = vec![dir.to_path_buf()];
+        while let Some(current) = pending.pop() {
+            for entry in std::fs::read_dir(&current)? {
+                let path = entry?.path();
+                if path.is_dir() {
+                    pending.push(path);
+                    continue;
+                }
+                let relative = path.strip_prefix(root).unwrap_or(&path);
+                let relative = relative
+                    .components()
+                    .map(|c| c.as_os_str().to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join("/");
+                files.push(SkillFile::new(relative, std::fs::read(&path)?));
+            }
+        }
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok(files)
+    }
+}
+
 /// Parameters for listing skills.
 #[derive(Debug, Clone, Default, Serialize)]
 pub struct SkillListParams {
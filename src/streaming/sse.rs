@@ -1,7 +1,7 @@
+use bytes::{Buf, Bytes, BytesMut};
 use futures::stream::Stream;
-use tokio::io::AsyncBufReadExt;
+use tokio::io::{AsyncRead, AsyncReadExt};
 use tokio_stream::StreamExt;
-use tokio_stream::wrappers::LinesStream;
 
 use crate::error::Error;
 
@@ -21,86 +21,83 @@ pub struct RawSseEvent {
 /// Parse an SSE byte stream into a stream of `RawSseEvent`.
 ///
 /// Follows the SSE spec:
+/// - Lines end with `\n`, `\r\n` or a lone `\r`.
+/// - A leading UTF-8 byte order mark is ignored.
 /// - Lines starting with `:` are comments (skipped).
 /// - Empty lines dispatch the current event.
 /// - `event:`, `data:`, `id:`, `retry:` fields are parsed.
 /// - Multiple `data:` lines are concatenated with `\n`.
+///
+/// Lines are split on bytes before being decoded, so a multi-byte character
+/// split across network chunks is decoded intact.
 pub fn parse_sse_stream(
     response: reqwest::Response,
 ) -> impl Stream<Item = Result<RawSseEvent, Error>> {
-    let byte_stream = response.bytes_stream();
-
-    // Convert the byte stream into an AsyncRead, then split into lines
-    let reader = tokio_util::io::StreamReader::new(
-        byte_stream.map(|result| result.map_err(std::io::Error::other)),
-    );
-    let buf_reader = tokio::io::BufReader::new(reader);
-    let lines = LinesStream::new(buf_reader.lines());
+    parse_sse_bytes(response.bytes_stream())
+}
 
-    // State machine: accumulate fields until an empty line dispatches the event
+/// Parse a stream of byte chunks into a stream of `RawSseEvent`, following
+/// the same rules as `parse_sse_stream`.
+pub fn parse_sse_bytes<S, E>(stream: S) -> impl Stream<Item = Result<RawSseEvent, Error>>
+where
+    S: Stream<Item = Result<Bytes, E>>,
+    E: std::fmt::Display,
+{
     futures::stream::unfold(
-        (lines, RawSseEvent::default()),
-        |(mut lines, mut current)| async move {
+        (Box::pin(stream), SseDecoder::new(), false),
+        |(mut stream, mut decoder, mut done)| async move {
             loop {
-                match lines.next().await {
-                    Some(Ok(line)) => {
-                        if line.is_empty() {
-                            // Empty line = dispatch event
-                            if current.event.is_some() || current.data.is_some() {
-                                let event = std::mem::take(&mut current);
-                                return Some((Ok(event), (lines, current)));
-                            }
-                            // No accumulated data, skip
-                            continue;
-                        }
-
-                        // Comment line: starts with ':'
-                        if line.starts_with(':') {
-                            continue;
-                        }
-
-                        // Parse field: name:value
-                        if let Some((field, value)) = parse_field(&line) {
-                            match field {
-                                "event" => {
-                                    current.event = Some(value.to_string());
-                                }
-                                "data" => match &mut current.data {
-                                    Some(existing) => {
-                                        existing.push('\n');
-                                        existing.push_str(value);
-                                    }
-                                    None => {
-                                        current.data = Some(value.to_string());
-                                    }
-                                },
-                                "id" => {
-                                    current.id = Some(value.to_string());
-                                }
-                                "retry" => {
-                                    if let Ok(ms) = value.trim().parse::<u64>() {
-                                        current.retry = Some(ms);
-                                    }
-                                }
-                                _ => {
-                                    // Unknown field, ignore per spec
-                                }
-                            }
-                        }
-                    }
+                if let Some(event) = decoder.next_event() {
+                    return Some((Ok(event), (stream, decoder, done)));
+                }
+                if done {
+                    return decoder
+                        .finish()
+                        .map(|event| (Ok(event), (stream, decoder, done)));
+                }
+                match stream.next().await {
+                    Some(Ok(chunk)) => decoder.push(&chunk),
                     Some(Err(e)) => {
                         return Some((
                             Err(Error::StreamError(format!("SSE read error: {e}"))),
-                            (lines, current),
+                            (stream, decoder, done),
                         ));
                     }
-                    None => {
-                        // Stream ended. Dispatch any remaining event.
-                        if current.event.is_some() || current.data.is_some() {
-                            let event = std::mem::take(&mut current);
-                            return Some((Ok(event), (lines, current)));
-                        }
-                        return None;
+                    None => done = true,
+                }
+            }
+        },
+    )
+}
+
+/// Parse SSE text read from `reader` into a stream of `RawSseEvent`, following
+/// the same rules as `parse_sse_stream`.
+pub fn parse_sse_reader<R>(reader: R) -> impl Stream<Item = Result<RawSseEvent, Error>>
+where
+    R: AsyncRead + Unpin,
+{
+    futures::stream::unfold(
+        (reader, SseDecoder::new(), false),
+        |(mut reader, mut decoder, mut done)| async move {
+            loop {
+                if let Some(event) = decoder.next_event() {
+                    return Some((Ok(event), (reader, decoder, done)));
+                }
+                if done {
+                    return decoder
+                        .finish()
+                        .map(|event| (Ok(event), (reader, decoder, done)));
+                }
+                // Read straight into the decoder's buffer to avoid a copy.
+                decoder.buf.reserve(READ_CHUNK_SIZE);
+                match reader.read_buf(&mut decoder.buf).await {
+                    Ok(0) => done = true,
+                    Ok(_) => {}
+                    Err(e) => {
+                        return Some((
+                            Err(Error::StreamError(format!("SSE read error: {e}"))),
+                            (reader, decoder, done),
+                        ));
                     }
                 }
             }
@@ -108,6 +105,135 @@ pub fn parse_sse_stream(
     )
 }
 
+const READ_CHUNK_SIZE: usize = 8 * 1024;
+
+const BOM: &[u8] = b"\xEF\xBB\xBF";
+
+/// Incremental SSE decoder that accepts bytes in arbitrarily sized chunks.
+///
+/// Lines are parsed in place in the decoder's buffer; the only allocations
+/// are the field values stored in each `RawSseEvent`. The `parse_sse_*`
+/// functions drive one of these, and it is public for transports that deliver
+/// bytes some other way.
+#[derive(Debug, Default)]
+pub struct SseDecoder {
+    buf: BytesMut,
+    bom_checked: bool,
+    eof: bool,
+    current: RawSseEvent,
+}
+
+impl SseDecoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append bytes read from the stream.
+    pub fn push(&mut self, bytes: &[u8]) {
+        self.buf.extend_from_slice(bytes);
+    }
+
+    /// Return the next complete event in the buffered bytes, if any.
+    pub fn next_event(&mut self) -> Option<RawSseEvent> {
+        while let Some((len, consumed)) = self.next_line() {
+            let line = String::from_utf8_lossy(&self.buf[..len]);
+            let blank = apply_line(&mut self.current, &line);
+            self.buf.advance(consumed);
+            if blank && let Some(event) = self.dispatch() {
+                return Some(event);
+            }
+        }
+        None
+    }
+
+    /// Signal the end of the stream and return the final event, if any.
+    ///
+    /// A trailing line without a terminator is still processed, and an event
+    /// that was not followed by a blank line is dispatched. Call repeatedly
+    /// until it returns `None`.
+    pub fn finish(&mut self) -> Option<RawSseEvent> {
+        self.eof = true;
+        self.next_event().or_else(|| self.dispatch())
+    }
+
+    /// Find the next complete line at the front of the buffer, returning its
+    /// length without the terminator and its length with it.
+    fn next_line(&mut self) -> Option<(usize, usize)> {
+        if !self.bom_checked {
+            if self.buf.len() < BOM.len() && BOM.starts_with(&self.buf) && !self.eof {
+                return None;
+            }
+            if self.buf.starts_with(BOM) {
+                self.buf.advance(BOM.len());
+            }
+            self.bom_checked = true;
+        }
+
+        let buf = &self.buf[..];
+        match buf.iter().position(|&b| b == b'\n' || b == b'\r') {
+            Some(i) if buf[i] == b'\n' => Some((i, i + 1)),
+            // A `\r` at the end of the buffer may be the first half of a
+            // `\r\n` split across chunks, so wait for the next byte.
+            Some(i) if i + 1 == buf.len() && !self.eof => None,
+            Some(i) if buf.get(i + 1) == Some(&b'\n') => Some((i, i + 2)),
+            Some(i) => Some((i, i + 1)),
+            None if self.eof && !buf.is_empty() => Some((buf.len(), buf.len())),
+            None => None,
+        }
+    }
+
+    /// Take the event being built, unless it has neither a name nor data.
+    fn dispatch(&mut self) -> Option<RawSseEvent> {
+        if self.current.event.is_some() || self.current.data.is_some() {
+            Some(std::mem::take(&mut self.current))
+        } else {
+            None
+        }
+    }
+}
+
+/// Apply one line to the event being built. Returns `true` for a blank line,
+/// which dispatches the event.
+fn apply_line(current: &mut RawSseEvent, line: &str) -> bool {
+    if line.is_empty() {
+        return true;
+    }
+
+    // Comment line: starts with ':'
+    if line.starts_with(':') {
+        return false;
+    }
+
+    if let Some((field, value)) = parse_field(line) {
+        match field {
+            "event" => {
+                current.event = Some(value.to_string());
+            }
+            "data" => match &mut current.data {
+                Some(existing) => {
+                    existing.push('\n');
+                    existing.push_str(value);
+                }
+                None => {
+                    current.data = Some(value.to_string());
+                }
+            },
+            "id" => {
+                current.id = Some(value.to_string());
+            }
+            "retry" => {
+                if let Ok(ms) = value.trim().parse::<u64>() {
+                    current.retry = Some(ms);
+                }
+            }
+            _ => {
+                // Unknown field, ignore per spec
+            }
+        }
+    }
+    false
+}
+
 /// Parse an SSE field line into (field_name, value).
 ///
 /// Format: `field: value` or `field:value` (space after colon is optional but trimmed).
@@ -230,4 +356,105 @@ mod tests {
         assert_eq!(events[0].event.as_deref(), Some("a"));
         assert_eq!(events[1].event.as_deref(), Some("b"));
     }
+
+    /// Feed `input` to a decoder in pieces of `size` bytes and collect the
+    /// events.
+    fn decode_in_chunks(input: &[u8], size: usize) -> Vec<RawSseEvent> {
+        let mut decoder = SseDecoder::new();
+        let mut events = Vec::new();
+        for chunk in input.chunks(size) {
+            decoder.push(chunk);
+            while let Some(event) = decoder.next_event() {
+                events.push(event);
+            }
+        }
+        while let Some(event) = decoder.finish() {
+            events.push(event);
+        }
+        events
+    }
+
+    #[test]
+    fn test_decoder_adversarial_chunking() {
+        let input = concat!(
+            "\u{FEFF}event: a\r\ndata: héllo 😀\r\n\r\n",
+            "event: b\rdata: 日本\rdata: two\r\r",
+            ": comment\nevent: c\ndata: 3\n\n",
+        );
+        for size in 1..=input.len() {
+            let events = decode_in_chunks(input.as_bytes(), size);
+            assert_eq!(events.len(), 3, "chunk size {size}");
+            assert_eq!(events[0].event.as_deref(), Some("a"));
+            assert_eq!(events[0].data.as_deref(), Some("héllo 😀"));
+            assert_eq!(events[1].event.as_deref(), Some("b"));
+            assert_eq!(events[1].data.as_deref(), Some("日本\ntwo"));
+            assert_eq!(events[2].event.as_deref(), Some("c"));
+            assert_eq!(events[2].data.as_deref(), Some("3"));
+        }
+    }
+
+    #[test]
+    fn test_decoder_crlf_split_between_chunks() {
+        let mut decoder = SseDecoder::new();
+        decoder.push(b"data: 1\r");
+        assert!(decoder.next_event().is_none());
+        decoder.push(b"\n\r");
+        assert!(decoder.next_event().is_none());
+        // The `\n` completing the second CRLF must not count as another line.
+        decoder.push(b"\ndata: 2\n\n");
+        assert_eq!(decoder.next_event().unwrap().data.as_deref(), Some("1"));
+        assert_eq!(decoder.next_event().unwrap().data.as_deref(), Some("2"));
+        assert!(decoder.next_event().is_none());
+        assert!(decoder.finish().is_none());
+    }
+
+    #[test]
+    fn test_decoder_finish_dispatches_unterminated_event() {
+        let mut decoder = SseDecoder::new();
+        decoder.push(b"event: ping\ndata: {}");
+        assert!(decoder.next_event().is_none());
+        let event = decoder.finish().unwrap();
+        assert_eq!(event.event.as_deref(), Some("ping"));
+        assert_eq!(event.data.as_deref(), Some("{}"));
+        assert!(decoder.finish().is_none());
+    }
+
+    #[test]
+    fn test_decoder_bom_only_stripped_at_start() {
+        let events = decode_in_chunks("\u{FEFF}data: \u{FEFF}x\n\n".as_bytes(), 1);
+        assert_eq!(events[0].data.as_deref(), Some("\u{FEFF}x"));
+    }
+
+    #[tokio::test]
+    async fn test_parse_sse_reader_split_utf8() {
+        let body = "event: t\r\ndata: 😀\r\n\r\n".as_bytes();
+        let chunks: Vec<std::io::Result<bytes::Bytes>> = body
+            .chunks(3)
+            .map(|c| Ok(bytes::Bytes::copy_from_slice(c)))
+            .collect();
+        let reader = tokio_util::io::StreamReader::new(futures::stream::iter(chunks));
+        let events: Vec<_> = futures::StreamExt::collect::<Vec<_>>(parse_sse_reader(reader))
+            .await
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].data.as_deref(), Some("😀"));
+    }
+
+    #[tokio::test]
+    async fn test_parse_sse_bytes() {
+        let body = "event: a\ndata: 1\n\nevent: b\ndata: 😀\n\n".as_bytes();
+        let chunks: Vec<Result<Bytes, std::io::Error>> =
+            body.chunks(2).map(Bytes::copy_from_slice).map(Ok).collect();
+        let events: Vec<_> =
+            futures::StreamExt::collect::<Vec<_>>(parse_sse_bytes(futures::stream::iter(chunks)))
+                .await
+                .into_iter()
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[1].event.as_deref(), Some("b"));
+        assert_eq!(events[1].data.as_deref(), Some("😀"));
+    }
 }
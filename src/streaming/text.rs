@@ -0,0 +1,211 @@
+//! Segment streamed text at natural boundaries.
+//!
+//! `MessageStream::text_stream` yields text deltas as the API sends them,
+//! which split words and sentences at arbitrary points. The adapters here
+//! re-buffer those deltas so text-to-speech engines and renderers receive
+//! whole sentences, whole words or fixed-size chunks.
+//!
+//! ```ignore
+//! let mut sentences = client.messages().create_stream(params).await?.text_stream().sentences();
+//! while let Some(sentence) = sentences.next().await {
+//!     speak(&sentence?);
+//! }
+//! ```
+
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use futures::StreamExt;
+use futures::stream::Stream;
+use pin_project_lite::pin_project;
+
+use crate::error::Error;
+
+pin_project! {
+    /// A stream of text pieces from a streaming response.
+    ///
+    /// Segments are exact slices of the generated text: concatenating every
+    /// item reproduces the full text, including whitespace.
+    pub struct TextStream {
+        #[pin]
+        inner: Pin<Box<dyn Stream<Item = Result<String, Error>> + Send>>,
+    }
+}
+
+impl TextStream {
+    /// Wrap any stream of text results.
+    pub fn from_stream<S>(stream: S) -> Self
+    where
+        S: Stream<Item = Result<String, Error>> + Send + 'static,
+    {
+        Self {
+            inner: Box::pin(stream),
+        }
+    }
+
+    /// Yield whole sentences.
+    ///
+    /// A sentence ends after `.`, `!` or `?` (and any closing quotes or
+    /// brackets) followed by whitespace, or at a newline; the whitespace
+    /// character is included in the sentence. This is a simple heuristic, so
+    /// abbreviations such as "e.g. " also end a sentence.
+    pub fn sentences(self) -> Self {
+        self.segment(sentence_end)
+    }
+
+    /// Yield whole words, each with the whitespace character that ended it.
+    pub fn words(self) -> Self {
+        self.segment(word_end)
+    }
+
+    /// Yield chunks of `size` characters; the last chunk may be shorter.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is zero.
+    pub fn chunks(self, size: usize) -> Self {
+        assert!(size > 0, "chunk size must be greater than zero");
+        self.segment(move |text| text.char_indices().nth(size).map(|(i, _)| i))
+    }
+
+    /// Consume the stream and concatenate every piece.
+    pub async fn collect_text(mut self) -> Result<String, Error> {
+        let mut text = String::new();
+        while let Some(piece) = self.next().await {
+            text.push_str(&piece?);
+        }
+        Ok(text)
+    }
+
+    /// Re-buffer the stream, cutting the buffer wherever `split` finds the end
+    /// of a segment. Whatever remains when the stream ends is yielded as-is.
+    fn segment<F>(self, split: F) -> Self
+    where
+        F: Fn(&str) -> Option<usize> + Send + Sync + 'static,
+    {
+        let split = Arc::new(split);
+        let state = (self, String::new(), false);
+        let stream = futures::stream::unfold(state, move |(mut inner, mut buf, mut done)| {
+            let split = split.clone();
+            async move {
+                loop {
+                    if let Some(end) = split(&buf) {
+                        let rest = buf.split_off(end);
+                        return Some((Ok(std::mem::replace(&mut buf, rest)), (inner, buf, done)));
+                    }
+                    if done {
+                        if buf.is_empty() {
+                            return None;
+                        }
+                        return Some((Ok(std::mem::take(&mut buf)), (inner, buf, done)));
+                    }
+                    match inner.next().await {
+                        Some(Ok(text)) => buf.push_str(&text),
+                        Some(Err(err)) => return Some((Err(err), (inner, buf, done))),
+                        None => done = true,
+                    }
+                }
+            }
+        });
+        Self::from_stream(stream)
+    }
+}
+
+impl Stream for TextStream {
+    type Item = Result<String, Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.project().inner.as_mut().poll_next(cx)
+    }
+}
+
+/// Byte offset just past the first sentence in `text`, if it is complete.
+fn sentence_end(text: &str) -> Option<usize> {
+    let mut after_terminator = false;
+    for (i, c) in text.char_indices() {
+        if c == '\n' || (after_terminator && c.is_whitespace()) {
+            return Some(i + c.len_utf8());
+        }
+        after_terminator = match c {
+            '.' | '!' | '?' | '…' | '。' | '！' | '？' => true,
+            '"' | '\'' | ')' | ']' | '”' | '’' => after_terminator,
+            _ => false,
+        };
+    }
+    None
+}
+
+/// Byte offset just past the first word and the whitespace that ends it.
+fn word_end(text: &str) -> Option<usize> {
+    let start = text.find(|c: char| !c.is_whitespace())?;
+    let (i, c) = text[start..]
+        .char_indices()
+        .find(|(_, c)| c.is_whitespace())?;
+    Some(start + i + c.len_utf8())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stream(pieces: &[&str]) -> TextStream {
+        let pieces: Vec<_> = pieces.iter().map(|p| Ok(p.to_string())).collect();
+        TextStream::from_stream(futures::stream::iter(pieces))
+    }
+
+    async fn collect(stream: TextStream) -> Vec<String> {
+        stream.map(Result::unwrap).collect().await
+    }
+
+    #[tokio::test]
+    async fn test_sentences() {
+        let pieces = [
+            "Hel",
+            "lo there. How",
+            " are you?",
+            " I said \"hi!\" ",
+            "then\nleft",
+        ];
+        let sentences = collect(stream(&pieces).sentences()).await;
+        assert_eq!(
+            sentences,
+            [
+                "Hello there. ",
+                "How are you? ",
+                "I said \"hi!\" ",
+                "then\n",
+                "left"
+            ]
+        );
+        assert_eq!(sentences.concat(), pieces.concat());
+    }
+
+    #[tokio::test]
+    async fn test_words() {
+        let words = collect(stream(&["  Hel", "lo wo", "rld", "\n\nbye"]).words()).await;
+        assert_eq!(words, ["  Hello ", "world\n", "\nbye"]);
+    }
+
+    #[tokio::test]
+    async fn test_chunks_count_characters() {
+        let chunks = collect(stream(&["héll", "ó wörld"]).chunks(4)).await;
+        assert_eq!(chunks, ["héll", "ó wö", "rld"]);
+    }
+
+    #[tokio::test]
+    async fn test_error_is_passed_through() {
+        let pieces = vec![
+            Ok("One. Tw".to_string()),
+            Err(Error::StreamError("dropped".to_string())),
+        ];
+        let mut sentences = TextStream::from_stream(futures::stream::iter(pieces)).sentences();
+        assert_eq!(sentences.next().await.unwrap().unwrap(), "One. ");
+        assert!(matches!(
+            sentences.next().await,
+            Some(Err(Error::StreamError(_)))
+        ));
+        assert_eq!(sentences.next().await.unwrap().unwrap(), "Tw");
+        assert!(sentences.next().await.is_none());
+    }
+}
@@ -0,0 +1,436 @@
+//! Tracing spans and metrics hooks.
+//!
+//! Every HTTP call runs inside an `anthropic.http` span, and Messages API calls
+//! are wrapped in a `chat` span carrying the OpenTelemetry `gen_ai` semantic
+//! convention attributes (`gen_ai.request.model`, `gen_ai.usage.input_tokens`,
+//! `gen_ai.response.finish_reasons`, ...). With `tracing-opentelemetry` installed
+//! these export as OTel spans named `chat <model>`.
+//!
+//! For counters and histograms, register a [`MetricsHook`] with
+//! `ClientBuilder::metrics_hook`.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures::StreamExt;
+use tracing::Span;
+use tracing::field::Empty;
+
+use crate::budget::TokenBudget;
+use crate::error::Error;
+use crate::messages::streaming::{MessageStream, StreamEvent};
+use crate::timing::RequestTiming;
+use crate::types::common::StopReason;
+use crate::types::message::Message;
+use crate::types::usage::MessageDeltaUsage;
+
+/// Receives latency and token usage measurements from the client.
+///
+/// Both methods default to doing nothing, so implementations only override what
+/// they record. They are called inline on the request path and should not block.
+///
+/// ```ignore
+/// struct Prometheus { latency: Histogram, output_tokens: Counter }
+///
+/// impl MetricsHook for Prometheus {
+///     fn on_request(&self, m: &RequestMetrics) {
+///         self.latency.observe(m.latency.as_secs_f64());
+///     }
+///     fn on_usage(&self, u: &UsageMetrics) {
+///         self.output_tokens.inc_by(u.output_tokens as u64);
+///     }
+/// }
+/// ```
+pub trait MetricsHook: Send + Sync {
+    /// Called once per API call, after any retries, whether it succeeded or not.
+    fn on_request(&self, _metrics: &RequestMetrics) {}
+
+    /// Called when a Messages API response reports its final token usage.
+    fn on_usage(&self, _usage: &UsageMetrics) {}
+}
+
+/// Measurements for a single API call.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct RequestMetrics {
+    pub method: String,
+    /// Path below `/v1/`, e.g. `messages`.
+    pub path: String,
+    /// Status of the last response received, if any.
+    pub status: Option<u16>,
+    /// Number of HTTP attempts made, including retries.
+    pub attempts: u32,
+    /// Wall time including retries. For streaming requests this ends when the
+    /// response headers arrive.
+    pub latency: Duration,
+    pub streaming: bool,
+    /// `Error::code` of the failure, if the call failed.
+    pub error: Option<&'static str>,
+    /// Timing of the last attempt that received a response. Its `total`
+    /// includes reading the body for successful non-streaming calls.
+    pub timing: Option<RequestTiming>,
+}
+
+/// Token usage reported by a Messages API response.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct UsageMetrics {
+    pub model: String,
+    pub input_tokens: u32,
+    pub output_tokens: u32,
+    pub cache_creation_input_tokens: Option<u32>,
+    pub cache_read_input_tokens: Option<u32>,
+    pub stop_reason: Option<StopReason>,
+    /// The `request-id` header of the response.
+    pub request_id: Option<String>,
+}
+
+/// Status and attempt count tracked by the retry loops.
+#[derive(Debug, Default)]
+pub(crate) struct RequestOutcome {
+    pub(crate) status: Option<u16>,
+    pub(crate) attempts: u32,
+    pub(crate) timing: Option<RequestTiming>,
+}
+
+pub(crate) fn http_span(method: &str, path: &str) -> Span {
+    tracing::info_span!(
+        "anthropic.http",
+        "http.request.method" = method,
+        "url.path" = path,
+        "http.response.status_code" = Empty,
+        "anthropic.attempts" = Empty,
+        "error.type" = Empty,
+    )
+}
+
+/// Record the outcome of a call on its span and report it to the hook.
+pub(crate) fn finish_request(
+    span: &Span,
+    hook: Option<&Arc<dyn MetricsHook>>,
+    metrics: RequestMetrics,
+) {
+    if let Some(status) = metrics.status {
+        span.record("http.response.status_code", status);
+    }
+    span.record("anthropic.attempts", metrics.attempts);
+    if let Some(code) = metrics.error {
+        span.record("error.type", code);
+    }
+    if let Some(hook) = hook {
+        hook.on_request(&metrics);
+    }
+}
+
+pub(crate) fn chat_span(model: &str) -> Span {
+    tracing::info_span!(
+        "chat",
+        "otel.name" = format!("chat {model}"),
+        "gen_ai.operation.name" = "chat",
+        "gen_ai.system" = "anthropic",
+        "gen_ai.request.model" = model,
+        "gen_ai.response.id" = Empty,
+        "gen_ai.response.model" = Empty,
+        "gen_ai.response.finish_reasons" = Empty,
+        "gen_ai.usage.input_tokens" = Empty,
+        "gen_ai.usage.output_tokens" = Empty,
+    )
+}
+
+/// Collects response attributes for a `chat` span, from either a complete
+/// `Message` or the events of a stream.
+pub(crate) struct UsageRecorder {
+    span: Span,
+    hook: Option<Arc<dyn MetricsHook>>,
+    budget: Option<TokenBudget>,
+    request_id: Option<String>,
+    usage: Option<UsageMetrics>,
+}
+
+impl UsageRecorder {
+    pub(crate) fn new(span: Span, hook: Option<Arc<dyn MetricsHook>>) -> Self {
+        Self {
+            span,
+            hook,
+            budget: None,
+            request_id: None,
+            usage: None,
+        }
+    }
+
+    /// Also count the recorded usage against `budget`.
+    pub(crate) fn with_budget(mut self, budget: Option<TokenBudget>) -> Self {
+        self.budget = budget;
+        self
+    }
+
+    /// Report `request_id` with the usage.
+    pub(crate) fn with_request_id(mut self, request_id: Option<String>) -> Self {
+        self.request_id = request_id;
+        self
+    }
+
+    pub(crate) fn span(&self) -> &Span {
+        &self.span
+    }
+
+    pub(crate) fn record_message(mut self, message: &Message) {
+        self.start(message);
+        self.finish(message.usage.output_tokens, message.stop_reason.clone());
+    }
+
+    /// Record usage as `stream` is consumed. The span stays open until the
+    /// returned stream is dropped.
+    pub(crate) fn wrap(mut self, stream: MessageStream) -> MessageStream {
+        stream.rewrap(|stream| {
+            stream.inspect(move |event| {
+                if let Ok(event) = event {
+                    self.observe(event);
+                }
+            })
+        })
+    }
+
+    fn observe(&mut self, event: &StreamEvent) {
+        match event {
+            StreamEvent::MessageStart { message } => self.start(message),
+            StreamEvent::MessageDelta { delta, usage } => {
+                self.apply_delta(usage);
+                self.finish(usage.output_tokens, delta.stop_reason.clone())
+            }
+            _ => {}
+        }
+    }
+
+    fn start(&mut self, message: &Message) {
+        self.span.record("gen_ai.response.id", message.id.as_str());
+        self.span
+            .record("gen_ai.response.model", message.model.as_str());
+        self.span
+            .record("gen_ai.usage.input_tokens", message.usage.input_tokens);
+        self.usage = Some(UsageMetrics {
+            model: message.model.clone(),
+            input_tokens: message.usage.input_tokens,
+            output_tokens: message.usage.output_tokens,
+            cache_creation_input_tokens: message.usage.cache_creation_input_tokens,
+            cache_read_input_tokens: message.usage.cache_read_input_tokens,
+            stop_reason: None,
+            request_id: self.request_id.clone(),
+        });
+    }
+
+    /// Take the cumulative counts a `message_delta` reports, as
+    /// `Usage::apply_delta` does.
+    fn apply_delta(&mut self, delta: &MessageDeltaUsage) {
+        let Some(usage) = &mut self.usage else {
+            return;
+        };
+        if let Some(input_tokens) = delta.input_tokens {
+            usage.input_tokens = input_tokens;
+            self.span.record("gen_ai.usage.input_tokens", input_tokens);
+        }
+        if delta.cache_creation_input_tokens.is_some() {
+            usage.cache_creation_input_tokens = delta.cache_creation_input_tokens;
+        }
+        if delta.cache_read_input_tokens.is_some() {
+            usage.cache_read_input_tokens = delta.cache_read_input_tokens;
+        }
+    }
+
+    fn finish(&mut self, output_tokens: u32, stop_reason: Option<StopReason>) {
+        let Some(mut usage) = self.usage.take() else {
+            return;
+        };
+        self.span
+            .record("gen_ai.usage.output_tokens", output_tokens);
+        if let Some(reason) = stop_reason
+            .as_ref()
+            .and_then(|r| serde_json::to_value(r).ok())
+            .and_then(|v| v.as_str().map(String::from))
+        {
+            self.span
+                .record("gen_ai.response.finish_reasons", reason.as_str());
+        }
+        usage.output_tokens = output_tokens;
+        usage.stop_reason = stop_reason;
+        if let Some(budget) = &self.budget {
+            budget.record_metrics(&usage);
+        }
+        if let Some(hook) = &self.hook {
+            hook.on_usage(&usage);
+        }
+    }
+}
+
+impl RequestMetrics {
+    pub(crate) fn new(
+        method: &str,
+        path: &str,
+        streaming: bool,
+        outcome: &RequestOutcome,
+        latency: Duration,
+        error: Option<&Error>,
+    ) -> Self {
+        Self {
+            method: method.to_string(),
+            path: path.to_string(),
+            status: outcome.status,
+            attempts: outcome.attempts,
+            latency,
+            streaming,
+            error: error.map(Error::code),
+            timing: outcome.timing,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+    use crate::client::ClientBuilder;
+    use crate::messages::params::MessageCreateParams;
+    use crate::types::message::MessageParam;
+    use crate::types::model::Model;
+
+    #[derive(Default)]
+    struct Recorded {
+        requests: Mutex<Vec<RequestMetrics>>,
+        usage: Mutex<Vec<UsageMetrics>>,
+    }
+
+    impl MetricsHook for Arc<Recorded> {
+        fn on_request(&self, metrics: &RequestMetrics) {
+            self.requests.lock().unwrap().push(metrics.clone());
+        }
+
+        fn on_usage(&self, usage: &UsageMetrics) {
+            self.usage.lock().unwrap().push(usage.clone());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_metrics_hook_reports_request_and_usage() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let mut message = crate::mock::text_message("Hi");
+        message.usage.input_tokens = 12;
+        message.usage.output_tokens = 3;
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/messages"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(&message))
+            .mount(&server)
+            .await;
+
+        let recorded = Arc::new(Recorded::default());
+        let client = ClientBuilder::new()
+            .api_key("test")
+            .base_url(server.uri())
+            .metrics_hook(recorded.clone())
+            .build();
+        let params = MessageCreateParams::builder()
+            .model(Model::ClaudeOpus4_6)
+            .max_tokens(10)
+            .messages(vec![MessageParam::user("hi")])
+            .build();
+        client.messages().create(params).await.unwrap();
+
+        let requests = recorded.requests.lock().unwrap();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].path, "messages");
+        assert_eq!(requests[0].status, Some(200));
+        assert_eq!(requests[0].attempts, 1);
+        assert!(requests[0].error.is_none());
+
+        let usage = recorded.usage.lock().unwrap();
+        assert_eq!(usage.len(), 1);
+        assert_eq!(usage[0].model, "claude-opus-4-6");
+        assert_eq!(usage[0].input_tokens, 12);
+        assert_eq!(usage[0].output_tokens, 3);
+        assert_eq!(usage[0].stop_reason, Some(StopReason::EndTurn));
+    }
+
+    #[tokio::test]
+    async fn test_metrics_hook_reports_total_time() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v1/models/m"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({})))
+            .mount(&server)
+            .await;
+
+        let recorded = Arc::new(Recorded::default());
+        let client = ClientBuilder::new()
+            .api_key("test")
+            .base_url(server.uri())
+            .metrics_hook(recorded.clone())
+            .build();
+        let (_, response) = client
+            .execute_raw_with_response("GET", "models/m", None::<&()>, None)
+            .await
+            .unwrap();
+        let timing = response.timing.unwrap();
+        let total = timing.total.unwrap();
+        assert!(total >= timing.time_to_headers);
+
+        let requests = recorded.requests.lock().unwrap();
+        assert_eq!(requests[0].timing, Some(timing));
+    }
+
+    #[tokio::test]
+    async fn test_usage_recorder_observes_stream() {
+        let events: Vec<StreamEvent> = serde_json::from_value(serde_json::json!([
+            {"type": "message_start", "message": {"id": "msg_1", "type": "message", "role": "assistant", "content": [], "model": "claude-opus-4-6", "stop_reason": null, "usage": {"input_tokens": 7, "output_tokens": 1}}},
+            {"type": "message_delta", "delta": {"stop_reason": "max_tokens", "stop_sequence": null}, "usage": {"output_tokens": 10}},
+            {"type": "message_stop"}
+        ]))
+        .unwrap();
+
+        let recorded = Arc::new(Recorded::default());
+        let hook: Arc<dyn MetricsHook> = Arc::new(recorded.clone());
+        let recorder = UsageRecorder::new(chat_span("claude-opus-4-6"), Some(hook));
+        recorder
+            .wrap(MessageStream::from_events(events))
+            .accumulate()
+            .await
+            .unwrap();
+
+        let usage = recorded.usage.lock().unwrap();
+        assert_eq!(usage.len(), 1);
+        assert_eq!(usage[0].input_tokens, 7);
+        assert_eq!(usage[0].output_tokens, 10);
+        assert_eq!(usage[0].stop_reason, Some(StopReason::MaxTokens));
+    }
+
+    #[tokio::test]
+    async fn test_usage_recorder_applies_delta_counts() {
+        let events: Vec<StreamEvent> = serde_json::from_value(serde_json::json!([
+            {"type": "message_start", "message": {"id": "msg_1", "type": "message", "role": "assistant", "content": [], "model": "claude-opus-4-6", "stop_reason": null, "usage": {"input_tokens": 7, "output_tokens": 1, "cache_read_input_tokens": 0}}},
+            {"type": "message_delta", "delta": {"stop_reason": "end_turn", "stop_sequence": null}, "usage": {"output_tokens": 10, "input_tokens": 2107, "cache_creation_input_tokens": 40, "cache_read_input_tokens": 300, "server_tool_use": {"web_search_requests": 1}}},
+            {"type": "message_stop"}
+        ]))
+        .unwrap();
+
+        let recorded = Arc::new(Recorded::default());
+        let hook: Arc<dyn MetricsHook> = Arc::new(recorded.clone());
+        let recorder = UsageRecorder::new(chat_span("claude-opus-4-6"), Some(hook));
+        recorder
+            .wrap(MessageStream::from_events(events))
+            .accumulate()
+            .await
+            .unwrap();
+
+        let usage = recorded.usage.lock().unwrap();
+        assert_eq!(usage.len(), 1);
+        assert_eq!(usage[0].input_tokens, 2107);
+        assert_eq!(usage[0].output_tokens, 10);
+        assert_eq!(usage[0].cache_creation_input_tokens, Some(40));
+        assert_eq!(usage[0].cache_read_input_tokens, Some(300));
+    }
+}
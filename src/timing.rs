@@ -0,0 +1,188 @@
+use std::cell::Cell;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use tower_layer::Layer;
+use tower_service::Service;
+
+/// Timing information for a single HTTP attempt.
+///
+/// Attached to every `reqwest::Response` produced by the client as a response
+/// extension, so middleware and callers holding the raw response can read it with
+/// `response.extensions().get::<RequestTiming>()`.
+///
+/// `dns` and `connect` are only set when the attempt opened a new connection
+/// on the client's own HTTP client; a reused connection, or a custom
+/// `http_client`, leaves them `None`. Both are included in `time_to_headers`,
+/// so slow setup phases point at the network while a large `time_to_headers`
+/// on a warm connection points at API-side latency.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RequestTiming {
+    /// Zero-based retry attempt this timing belongs to.
+    pub attempt: u32,
+    /// Time spent resolving the API host name.
+    pub dns: Option<Duration>,
+    /// Time spent opening the TCP connection and completing the TLS
+    /// handshake, after DNS resolution. `reqwest` performs the handshake
+    /// inside its connector, so the two are measured together.
+    pub connect: Option<Duration>,
+    /// Time from sending the request until the response headers were
+    /// received, which is the time to first byte.
+    pub time_to_headers: Duration,
+    /// Time from sending the request until the whole body was read. `None`
+    /// until the client reads the body itself, so always `None` on the raw
+    /// response seen by middleware and for streaming responses.
+    pub total: Option<Duration>,
+}
+
+impl RequestTiming {
+    pub(crate) fn since(start: Instant, attempt: u32) -> Self {
+        Self {
+            attempt,
+            dns: None,
+            connect: None,
+            time_to_headers: start.elapsed(),
+            total: None,
+        }
+    }
+
+    /// This timing with the body finished reading `body_read` after the
+    /// headers arrived.
+    pub(crate) fn with_body_read(self, body_read: Duration) -> Self {
+        Self {
+            total: Some(self.time_to_headers + body_read),
+            ..self
+        }
+    }
+}
+
+/// Connection setup phases measured while sending one request.
+#[derive(Debug, Clone, Copy, Default)]
+struct ConnectPhases {
+    dns: Option<Duration>,
+    connect: Option<Duration>,
+}
+
+tokio::task_local! {
+    /// Phases of the request `send_timed` is driving. `reqwest` opens a new
+    /// connection inside the request's own future, so the resolver and
+    /// connector can find it here.
+    static PHASES: Cell<ConnectPhases>;
+}
+
+/// Update the phases of the request being sent, if any.
+fn record_phase(update: impl FnOnce(&mut ConnectPhases)) {
+    let _ = PHASES.try_with(|phases| {
+        let mut current = phases.get();
+        update(&mut current);
+        phases.set(current);
+    });
+}
+
+/// Send a request and attach a `RequestTiming` extension to the response.
+pub(crate) async fn send_timed(
+    http: &reqwest::Client,
+    request: reqwest::Request,
+    attempt: u32,
+) -> Result<reqwest::Response, crate::error::Error> {
+    let start = Instant::now();
+    let (result, phases) = PHASES
+        .scope(Cell::new(ConnectPhases::default()), async {
+            let result = http.execute(request).await;
+            (result, PHASES.with(Cell::get))
+        })
+        .await;
+    let mut response = result.map_err(crate::error::Error::Http)?;
+    response.extensions_mut().insert(RequestTiming {
+        dns: phases.dns,
+        connect: phases.connect,
+        ..RequestTiming::since(start, attempt)
+    });
+    Ok(response)
+}
+
+/// The system resolver, timing each lookup for `RequestTiming::dns`.
+pub(crate) struct TimedResolver;
+
+impl Resolve for TimedResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        Box::pin(async move {
+            let start = Instant::now();
+            let addrs: Vec<_> = tokio::net::lookup_host((name.as_str(), 0)).await?.collect();
+            record_phase(|phases| phases.dns = Some(start.elapsed()));
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+/// Connector layer timing new connections for `RequestTiming::connect`.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct TimedConnectLayer;
+
+impl<S> Layer<S> for TimedConnectLayer {
+    type Service = TimedConnect<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        TimedConnect(inner)
+    }
+}
+
+/// A connector that records how long each connection took to open.
+#[derive(Debug, Clone)]
+pub(crate) struct TimedConnect<S>(S);
+
+impl<S, R> Service<R> for TimedConnect<S>
+where
+    S: Service<R>,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<S::Response, S::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.0.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: R) -> Self::Future {
+        let connecting = self.0.call(request);
+        Box::pin(async move {
+            let start = Instant::now();
+            let result = connecting.await;
+            if result.is_ok() {
+                // The connector resolves the host first; report only the rest.
+                record_phase(|phases| {
+                    phases.connect = Some(
+                        start
+                            .elapsed()
+                            .saturating_sub(phases.dns.unwrap_or_default()),
+                    );
+                });
+            }
+            result
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_since_records_attempt() {
+        let timing = RequestTiming::since(Instant::now(), 2);
+        assert_eq!(timing.attempt, 2);
+        assert_eq!(timing.dns, None);
+        assert!(timing.time_to_headers < Duration::from_secs(1));
+        assert_eq!(timing.total, None);
+
+        let timing = timing.with_body_read(Duration::from_millis(30));
+        assert_eq!(
+            timing.total,
+            Some(timing.time_to_headers + Duration::from_millis(30))
+        );
+    }
+}
@@ -0,0 +1,201 @@
+//! Offline, approximate token estimation.
+//!
+//! Enabled with the `tokenizer` feature. Estimates are computed locally without a
+//! network round-trip, so they are suitable for hot paths such as client-side
+//! budgeting and context-window trimming. They are approximations of Claude's
+//! tokenizer, not exact counts; use `messages().count_tokens()` when precision
+//! matters.
+//!
+//! ```ignore
+//! use uno_anthropic::tokenizer::estimate_tokens;
+//!
+//! let estimate = estimate_tokens(&params);
+//! if estimate + params.max_tokens > 200_000 {
+//!     // trim history before sending
+//! }
+//! ```
+
+use serde_json::Value;
+
+use crate::messages::params::MessageCreateParams;
+use crate::types::message::MessageParam;
+
+/// Approximate number of characters per token for Latin-script words.
+const CHARS_PER_TOKEN: usize = 4;
+
+/// Fixed overhead per message for role and turn delimiters.
+const MESSAGE_OVERHEAD: u32 = 4;
+
+/// Fixed overhead per tool definition for the tool-use preamble.
+const TOOL_OVERHEAD: u32 = 8;
+
+/// Estimate for an image whose dimensions are unknown (roughly a 1.15 megapixel image).
+const IMAGE_TOKENS: u32 = 1_600;
+
+/// Estimate for a base64 or URL document whose page count is unknown.
+const DOCUMENT_TOKENS: u32 = 3_000;
+
+/// String fields that carry opaque data rather than tokenized text.
+const OPAQUE_KEYS: &[&str] = &[
+    "data",
+    "signature",
+    "type",
+    "media_type",
+    "id",
+    "tool_use_id",
+    "file_id",
+    "url",
+];
+
+/// Estimate the number of input tokens for a request.
+///
+/// Covers the system prompt, every message and the tool definitions.
+pub fn estimate_tokens(params: &MessageCreateParams) -> u32 {
+    let mut total = 0;
+
+    if let Some(system) = &params.system {
+        total += estimate_value(&serde_json::to_value(system).unwrap_or(Value::Null));
+    }
+
+    total += params
+        .messages
+        .iter()
+        .map(estimate_message_tokens)
+        .sum::<u32>();
+
+    if let Some(tools) = &params.tools {
+        for tool in tools {
+            let json = serde_json::to_string(tool).unwrap_or_default();
+            total += TOOL_OVERHEAD + estimate_text_tokens(&json);
+        }
+    }
+
+    total
+}
+
+/// Estimate the number of tokens a single message contributes to a request.
+pub fn estimate_message_tokens(message: &MessageParam) -> u32 {
+    let content = serde_json::to_value(&message.content).unwrap_or(Value::Null);
+    MESSAGE_OVERHEAD + estimate_value(&content)
+}
+
+/// Estimate the number of tokens in a piece of text.
+///
+/// Runs of ASCII letters and digits count as one token per four characters
+/// (rounded up), punctuation counts as one token per character, whitespace is
+/// folded into the following token, and every other character (CJK, emoji, ...)
+/// counts as one token.
+pub fn estimate_text_tokens(text: &str) -> u32 {
+    let mut tokens = 0usize;
+    let mut word_len = 0usize;
+
+    for c in text.chars() {
+        if c.is_ascii_alphanumeric() {
+            word_len += 1;
+            continue;
+        }
+
+        tokens += word_len.div_ceil(CHARS_PER_TOKEN);
+        word_len = 0;
+
+        if c.is_whitespace() {
+            continue;
+        }
+        tokens += 1;
+    }
+    tokens += word_len.div_ceil(CHARS_PER_TOKEN);
+
+    tokens as u32
+}
+
+/// Walk serialized content, estimating text fields and assigning fixed costs to media.
+fn estimate_value(value: &Value) -> u32 {
+    match value {
+        Value::String(s) => estimate_text_tokens(s),
+        Value::Array(items) => items.iter().map(estimate_value).sum(),
+        Value::Object(map) => match map.get("type").and_then(Value::as_str) {
+            Some("image") => IMAGE_TOKENS,
+            Some("document") if !is_text_document(map.get("source")) => DOCUMENT_TOKENS,
+            _ => map
+                .iter()
+                .filter(|(key, _)| !OPAQUE_KEYS.contains(&key.as_str()))
+                .map(|(_, v)| estimate_value(v))
+                .sum(),
+        },
+        _ => 0,
+    }
+}
+
+/// Plain-text and content-block documents are tokenized like regular text.
+fn is_text_document(source: Option<&Value>) -> bool {
+    matches!(
+        source.and_then(|s| s.get("type")).and_then(Value::as_str),
+        Some("text") | Some("content")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::message::SystemContent;
+    use crate::types::model::Model;
+    use crate::types::tool::{Tool, ToolDefinition, ToolInputSchema};
+
+    #[test]
+    fn test_estimate_text_tokens() {
+        assert_eq!(estimate_text_tokens(""), 0);
+        assert_eq!(estimate_text_tokens("Hello"), 2);
+        assert_eq!(estimate_text_tokens("Hello, world!"), 6);
+        assert_eq!(estimate_text_tokens("你好"), 2);
+    }
+
+    #[test]
+    fn test_estimate_tokens_grows_with_content() {
+        let short = MessageCreateParams::builder()
+            .model(Model::ClaudeOpus4_6)
+            .max_tokens(1024)
+            .messages(vec![MessageParam::user("Hi")])
+            .build();
+        let long = MessageCreateParams::builder()
+            .model(Model::ClaudeOpus4_6)
+            .max_tokens(1024)
+            .messages(vec![MessageParam::user("Hi ".repeat(100))])
+            .system(SystemContent::from("You are helpful."))
+            .build();
+        assert!(estimate_tokens(&short) > 0);
+        assert!(estimate_tokens(&long) > estimate_tokens(&short) + 100);
+    }
+
+    #[test]
+    fn test_estimate_tokens_includes_tools() {
+        let without = MessageCreateParams::builder()
+            .model(Model::ClaudeOpus4_6)
+            .max_tokens(1024)
+            .messages(vec![MessageParam::user("Weather?")])
+            .build();
+        let mut with = without.clone();
+        with.tools = Some(vec![ToolDefinition::Custom(Tool {
+            name: "get_weather".to_string(),
+            description: Some("Get the weather for a city".to_string()),
+            input_schema: ToolInputSchema::default(),
+            ..Default::default()
+        })]);
+        assert!(estimate_tokens(&with) > estimate_tokens(&without) + TOOL_OVERHEAD);
+    }
+
+    #[test]
+    fn test_estimate_image_uses_fixed_cost() {
+        let message: MessageParam = serde_json::from_value(serde_json::json!({
+            "role": "user",
+            "content": [{
+                "type": "image",
+                "source": {"type": "base64", "media_type": "image/png", "data": "iVBORw0KGgo".repeat(1000)}
+            }]
+        }))
+        .unwrap();
+        assert_eq!(
+            estimate_message_tokens(&message),
+            MESSAGE_OVERHEAD + IMAGE_TOKENS
+        );
+    }
+}
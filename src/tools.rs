@@ -0,0 +1,375 @@
+//! Strongly typed client tools and dispatch of the model's tool calls.
+//!
+//! Implement [`TypedTool`] for each tool, collect them in a [`ToolRegistry`],
+//! send `registry.definitions()` with the request and answer the response's
+//! `tool_use` blocks with `registry.call_all`.
+//!
+//! ```ignore
+//! use uno_anthropic::tools::{ToolOutput, ToolRegistry, TypedTool};
+//!
+//! #[derive(Deserialize)]
+//! struct WeatherInput {
+//!     location: String,
+//! }
+//!
+//! struct Weather;
+//!
+//! impl TypedTool for Weather {
+//!     type Input = WeatherInput;
+//!     const NAME: &'static str = "get_weather";
+//!
+//!     fn input_schema(&self) -> ToolInputSchema {
+//!         ToolInputSchema::object()
+//!             .property("location", json!({"type": "string"}))
+//!             .required("location")
+//!     }
+//!
+//!     async fn call(&self, input: WeatherInput) -> Result<ToolOutput, Error> {
+//!         Ok(ToolOutput::text(format!("Sunny in {}", input.location)))
+//!     }
+//! }
+//!
+//! let registry = ToolRegistry::new().register(Weather);
+//! let message = client.messages().create(
+//!     MessageCreateParams::builder()
+//!         .model(Model::ClaudeSonnet4_5)
+//!         .messages(messages.clone())
+//!         .tools(registry.definitions())
+//!         .build(),
+//! ).await?;
+//! let results = registry.call_all(&message).await;
+//! ```
+
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::sync::Arc;
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::error::Error;
+use crate::middleware::BoxFuture;
+use crate::types::content::{
+    ContentBlock, ToolResultBlockParam, ToolResultContent, ToolResultContentBlock, ToolUseBlock,
+};
+use crate::types::message::Message;
+use crate::types::tool::{Tool, ToolDefinition, ToolInputSchema};
+
+/// A client tool whose input is deserialized into a Rust type.
+///
+/// `call` may be written as an `async fn`; the returned future must be `Send`.
+pub trait TypedTool: Send + Sync + 'static {
+    /// The tool's input, deserialized from the `tool_use` block.
+    type Input: DeserializeOwned + Send;
+
+    /// The name the model uses to call the tool.
+    const NAME: &'static str;
+
+    /// A description telling the model what the tool does and when to use it.
+    fn description(&self) -> Option<String> {
+        None
+    }
+
+    /// The JSON Schema the model's input must follow.
+    fn input_schema(&self) -> ToolInputSchema;
+
+    /// Run the tool. An `Err` is reported to the model as an error result.
+    fn call(&self, input: Self::Input) -> impl Future<Output = Result<ToolOutput, Error>> + Send;
+
+    /// The definition sent with requests.
+    fn definition(&self) -> ToolDefinition {
+        ToolDefinition::Custom(Tool {
+            name: Self::NAME.to_string(),
+            description: self.description(),
+            input_schema: self.input_schema(),
+            ..Default::default()
+        })
+    }
+}
+
+/// The result of a tool call.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ToolOutput {
+    pub content: ToolResultContent,
+    pub is_error: bool,
+}
+
+impl ToolOutput {
+    /// A plain text result.
+    pub fn text(text: impl Into<String>) -> Self {
+        Self {
+            content: ToolResultContent::Text(text.into()),
+            is_error: false,
+        }
+    }
+
+    /// A result serialized as JSON text.
+    pub fn json<T: Serialize + ?Sized>(value: &T) -> Result<Self, Error> {
+        Ok(Self::text(serde_json::to_string(value)?))
+    }
+
+    /// A result made of content blocks, such as images or documents.
+    pub fn blocks(blocks: Vec<ToolResultContentBlock>) -> Self {
+        Self {
+            content: ToolResultContent::Blocks(blocks),
+            is_error: false,
+        }
+    }
+
+    /// An error result, which tells the model the call failed.
+    pub fn error(message: impl Into<String>) -> Self {
+        Self {
+            is_error: true,
+            ..Self::text(message)
+        }
+    }
+
+    /// The `tool_result` block answering the call with id `tool_use_id`.
+    pub fn into_result(self, tool_use_id: impl Into<String>) -> ToolResultBlockParam {
+        ToolResultBlockParam {
+            tool_use_id: tool_use_id.into(),
+            content: Some(self.content),
+            is_error: self.is_error.then_some(true),
+            cache_control: None,
+        }
+    }
+}
+
+impl From<String> for ToolOutput {
+    fn from(text: String) -> Self {
+        Self::text(text)
+    }
+}
+
+impl From<&str> for ToolOutput {
+    fn from(text: &str) -> Self {
+        Self::text(text)
+    }
+}
+
+/// Object-safe form of `TypedTool`, taking raw JSON input.
+trait DynTool: Send + Sync {
+    fn definition(&self) -> ToolDefinition;
+    fn call<'a>(&'a self, input: &serde_json::Value) -> BoxFuture<'a, Result<ToolOutput, Error>>;
+}
+
+impl<T: TypedTool> DynTool for T {
+    fn definition(&self) -> ToolDefinition {
+        TypedTool::definition(self)
+    }
+
+    fn call<'a>(&'a self, input: &serde_json::Value) -> BoxFuture<'a, Result<ToolOutput, Error>> {
+        let input = validate_input(self, input).and_then(|()| {
+            T::Input::deserialize(input)
+                .map_err(|e| Error::Validation(format!("invalid input for tool {}: {e}", T::NAME)))
+        });
+        Box::pin(async move { TypedTool::call(self, input?).await })
+    }
+}
+
+/// Check `input` against the tool's schema before it reaches the tool.
+#[cfg(feature = "jsonschema")]
+fn validate_input<T: TypedTool>(tool: &T, input: &serde_json::Value) -> Result<(), Error> {
+    TypedTool::definition(tool).validate_input(input)
+}
+
+#[cfg(not(feature = "jsonschema"))]
+fn validate_input<T: TypedTool>(_tool: &T, _input: &serde_json::Value) -> Result<(), Error> {
+    Ok(())
+}
+
+/// A set of tools, keyed by name, that answers the model's tool calls.
+///
+/// Cloning is cheap; clones share the registered tools.
+#[derive(Clone, Default)]
+pub struct ToolRegistry {
+    tools: BTreeMap<&'static str, Arc<dyn DynTool>>,
+}
+
+impl ToolRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a tool, replacing any tool registered under the same name.
+    pub fn register<T: TypedTool>(mut self, tool: T) -> Self {
+        self.tools.insert(T::NAME, Arc::new(tool));
+        self
+    }
+
+    /// The registered tool names, in sorted order.
+    pub fn names(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.tools.keys().copied()
+    }
+
+    /// Definitions of every registered tool, for `MessageCreateParams::tools`.
+    pub fn definitions(&self) -> Vec<ToolDefinition> {
+        self.tools.values().map(|tool| tool.definition()).collect()
+    }
+
+    /// Answer one tool call.
+    ///
+    /// Unknown tools, input that doesn't deserialize and errors returned by
+    /// the tool all become error results, so the model can correct itself.
+    /// With the `jsonschema` feature, input is also checked against the
+    /// tool's input schema first.
+    pub async fn call(&self, tool_use: &ToolUseBlock) -> ToolResultBlockParam {
+        let output = match self.tools.get(tool_use.name.as_str()) {
+            Some(tool) => tool
+                .call(&tool_use.input)
+                .await
+                .unwrap_or_else(|e| ToolOutput::error(e.to_string())),
+            None => ToolOutput::error(format!("Unknown tool: {}", tool_use.name)),
+        };
+        output.into_result(tool_use.id.clone())
+    }
+
+    /// Answer every `tool_use` block in `message` concurrently, returning the
+    /// results in the order of the calls.
+    pub async fn call_all(&self, message: &Message) -> Vec<ToolResultBlockParam> {
+        let calls = message.content.iter().filter_map(|block| match block {
+            ContentBlock::ToolUse(tool_use) => Some(self.call(tool_use)),
+            _ => None,
+        });
+        futures::future::join_all(calls).await
+    }
+}
+
+impl std::fmt::Debug for ToolRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ToolRegistry")
+            .field("tools", &self.tools.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Deserialize)]
+    struct AddInput {
+        a: i64,
+        b: i64,
+    }
+
+    struct Add;
+
+    impl TypedTool for Add {
+        type Input = AddInput;
+        const NAME: &'static str = "add";
+
+        fn description(&self) -> Option<String> {
+            Some("Add two integers.".to_string())
+        }
+
+        fn input_schema(&self) -> ToolInputSchema {
+            ToolInputSchema::object()
+                .property("a", serde_json::json!({"type": "integer"}))
+                .property("b", serde_json::json!({"type": "integer"}))
+                .required("a")
+                .required("b")
+        }
+
+        async fn call(&self, input: AddInput) -> Result<ToolOutput, Error> {
+            input
+                .a
+                .checked_add(input.b)
+                .map(|sum| ToolOutput::text(sum.to_string()))
+                .ok_or_else(|| Error::Validation("overflow".to_string()))
+        }
+    }
+
+    fn tool_use(id: &str, name: &str, input: serde_json::Value) -> ContentBlock {
+        serde_json::from_value(serde_json::json!({
+            "type": "tool_use",
+            "id": id,
+            "name": name,
+            "input": input
+        }))
+        .unwrap()
+    }
+
+    #[cfg(feature = "jsonschema")]
+    #[tokio::test]
+    async fn test_input_is_checked_against_schema() {
+        struct Echo;
+
+        impl TypedTool for Echo {
+            type Input = serde_json::Value;
+            const NAME: &'static str = "echo";
+
+            fn input_schema(&self) -> ToolInputSchema {
+                ToolInputSchema::object()
+                    .property("text", serde_json::json!({"type": "string"}))
+                    .required("text")
+            }
+
+            async fn call(&self, input: serde_json::Value) -> Result<ToolOutput, Error> {
+                Ok(ToolOutput::text(input.to_string()))
+            }
+        }
+
+        let registry = ToolRegistry::new().register(Echo);
+        let ContentBlock::ToolUse(call) = tool_use("t1", "echo", serde_json::json!({"text": 1}))
+        else {
+            unreachable!()
+        };
+        let result = registry.call(&call).await;
+        assert_eq!(result.is_error, Some(true));
+        let Some(ToolResultContent::Text(text)) = result.content else {
+            panic!("expected a text result");
+        };
+        assert!(text.contains("does not match its schema"), "{text}");
+    }
+
+    #[test]
+    fn test_definitions() {
+        let registry = ToolRegistry::new().register(Add);
+        let definitions = registry.definitions();
+        assert_eq!(definitions.len(), 1);
+        let ToolDefinition::Custom(tool) = &definitions[0] else {
+            panic!("expected a custom tool");
+        };
+        assert_eq!(tool.name, "add");
+        assert_eq!(
+            tool.input_schema.required,
+            Some(vec!["a".to_string(), "b".to_string()])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_call_all_dispatches_and_reports_errors() {
+        let registry = ToolRegistry::new().register(Add);
+        let message = crate::mock::text_message("Let me add.");
+        let message = Message {
+            content: [
+                message.content,
+                vec![
+                    tool_use("t1", "add", serde_json::json!({"a": 2, "b": 3})),
+                    tool_use("t2", "add", serde_json::json!({"a": "two"})),
+                    tool_use("t3", "add", serde_json::json!({"a": i64::MAX, "b": 1})),
+                    tool_use("t4", "subtract", serde_json::json!({})),
+                ],
+            ]
+            .concat(),
+            ..message
+        };
+
+        let results = registry.call_all(&message).await;
+        let ids: Vec<_> = results.iter().map(|r| r.tool_use_id.as_str()).collect();
+        assert_eq!(ids, ["t1", "t2", "t3", "t4"]);
+        assert_eq!(
+            results[0].content,
+            Some(ToolResultContent::Text("5".to_string()))
+        );
+        assert_eq!(results[0].is_error, None);
+        assert!(results[1..].iter().all(|r| r.is_error == Some(true)));
+        assert_eq!(
+            results[3].content,
+            Some(ToolResultContent::Text(
+                "Unknown tool: subtract".to_string()
+            ))
+        );
+    }
+}
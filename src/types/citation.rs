@@ -1,14 +1,26 @@
 use serde::{Deserialize, Serialize};
 
 /// Configuration for enabling citations on a content block.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct CitationsConfig {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub enabled: Option<bool>,
 }
 
+impl CitationsConfig {
+    /// Turn citations on.
+    pub fn enabled() -> Self {
+        Self {
+            enabled: Some(true),
+        }
+    }
+}
+
 /// A citation within a text response.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+///
+/// Unrecognized citation types deserialize to `TextCitation::Other` with the
+/// raw JSON preserved.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[non_exhaustive]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum TextCitation {
@@ -17,10 +29,13 @@ pub enum TextCitation {
     ContentBlockLocation(ContentBlockLocationCitation),
     WebSearchResultLocation(WebSearchResultLocationCitation),
     SearchResultLocation(SearchResultLocationCitation),
+    /// Any citation type not in the known variants.
+    #[serde(untagged)]
+    Other(serde_json::Value),
 }
 
 /// A citation referencing a character range in a document.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct CharLocationCitation {
     pub cited_text: String,
     pub document_index: u32,
@@ -30,7 +45,7 @@ pub struct CharLocationCitation {
 }
 
 /// A citation referencing a page range in a document.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct PageLocationCitation {
     pub cited_text: String,
     pub document_index: u32,
@@ -40,7 +55,7 @@ pub struct PageLocationCitation {
 }
 
 /// A citation referencing content block indices.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ContentBlockLocationCitation {
     pub cited_text: String,
     pub document_index: u32,
@@ -50,7 +65,7 @@ pub struct ContentBlockLocationCitation {
 }
 
 /// A citation referencing a web search result.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct WebSearchResultLocationCitation {
     pub cited_text: String,
     pub encrypted_index: String,
@@ -61,7 +76,7 @@ pub struct WebSearchResultLocationCitation {
 }
 
 /// A citation referencing a search result block.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct SearchResultLocationCitation {
     pub cited_text: String,
     pub search_result_index: u32,
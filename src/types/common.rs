@@ -1,6 +1,9 @@
 use serde::{Deserialize, Serialize};
 
 /// The reason a message stopped generating.
+///
+/// Unrecognized stop reasons deserialize to `StopReason::Other(String)` so that
+/// new values returned by the API never break response parsing.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[non_exhaustive]
 #[serde(rename_all = "snake_case")]
@@ -10,6 +13,40 @@ pub enum StopReason {
     StopSequence,
     ToolUse,
     Refusal,
+    /// A long-running server tool turn was paused; resend the conversation to continue.
+    PauseTurn,
+    /// The model's context window was exhausted before generation finished.
+    ModelContextWindowExceeded,
+    /// Any stop reason not in the known variants.
+    #[serde(untagged)]
+    Other(String),
+}
+
+/// Response fields this version of the SDK does not model.
+///
+/// Response types keep any unrecognized JSON fields here instead of dropping
+/// them, so new API fields can be read before the SDK adds them. They are
+/// written back out when the value is serialized.
+///
+/// With the `strict-serde` feature, unrecognized fields are rejected instead.
+pub type ExtraFields = serde_json::Map<String, serde_json::Value>;
+
+/// Reject any unrecognized fields, for `extra` under `strict-serde`.
+///
+/// `deny_unknown_fields` cannot be combined with a flattened field, so types
+/// that collect extras fail here instead.
+#[cfg(feature = "strict-serde")]
+pub(crate) fn deny_extra_fields<'de, D>(deserializer: D) -> Result<ExtraFields, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let extra = ExtraFields::deserialize(deserializer)?;
+    match extra.keys().next() {
+        Some(field) => Err(serde::de::Error::custom(format_args!(
+            "unknown field `{field}`"
+        ))),
+        None => Ok(extra),
+    }
 }
 
 /// The role of a message participant.
@@ -55,6 +92,23 @@ mod tests {
 
         let reason: StopReason = serde_json::from_str(r#""tool_use""#).unwrap();
         assert_eq!(reason, StopReason::ToolUse);
+
+        let reason: StopReason = serde_json::from_str(r#""pause_turn""#).unwrap();
+        assert_eq!(reason, StopReason::PauseTurn);
+
+        let reason: StopReason =
+            serde_json::from_str(r#""model_context_window_exceeded""#).unwrap();
+        assert_eq!(reason, StopReason::ModelContextWindowExceeded);
+    }
+
+    #[test]
+    fn test_stop_reason_unknown_falls_back_to_other() {
+        let reason: StopReason = serde_json::from_str(r#""brand_new_reason""#).unwrap();
+        assert_eq!(reason, StopReason::Other("brand_new_reason".to_string()));
+        assert_eq!(
+            serde_json::to_string(&reason).unwrap(),
+            r#""brand_new_reason""#
+        );
     }
 
     #[test]
@@ -0,0 +1,110 @@
+use serde::{Deserialize, Serialize};
+
+/// The `container` request parameter.
+///
+/// Either the id of a container to reuse, or a configuration that can also
+/// load skills into the container. Strings convert into `ContainerParam::Id`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ContainerParam {
+    Id(String),
+    Config(ContainerConfig),
+}
+
+impl From<String> for ContainerParam {
+    fn from(id: String) -> Self {
+        ContainerParam::Id(id)
+    }
+}
+
+impl From<&str> for ContainerParam {
+    fn from(id: &str) -> Self {
+        ContainerParam::Id(id.to_string())
+    }
+}
+
+impl From<ContainerConfig> for ContainerParam {
+    fn from(config: ContainerConfig) -> Self {
+        ContainerParam::Config(config)
+    }
+}
+
+/// Container configuration with an optional id to reuse and skills to load.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContainerConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub skills: Option<Vec<SkillReference>>,
+}
+
+/// A skill to load into the container for a request.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SkillReference {
+    #[serde(rename = "type")]
+    pub source: SkillSource,
+    pub skill_id: String,
+    /// Version to load. Defaults to the latest version.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+}
+
+impl SkillReference {
+    /// Reference a prebuilt Anthropic skill, such as `pptx` or `xlsx`.
+    pub fn anthropic(skill_id: impl Into<String>) -> Self {
+        Self {
+            source: SkillSource::Anthropic,
+            skill_id: skill_id.into(),
+            version: None,
+        }
+    }
+
+    /// Reference a skill uploaded through the Skills API.
+    pub fn custom(skill_id: impl Into<String>) -> Self {
+        Self {
+            source: SkillSource::Custom,
+            skill_id: skill_id.into(),
+            version: None,
+        }
+    }
+
+    /// Pin a specific version instead of the latest.
+    pub fn version(mut self, version: impl Into<String>) -> Self {
+        self.version = Some(version.into());
+        self
+    }
+}
+
+/// Who publishes a skill.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SkillSource {
+    Anthropic,
+    Custom,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_container_param_serialize() {
+        let id: ContainerParam = "container_1".into();
+        assert_eq!(serde_json::to_value(&id).unwrap(), "container_1");
+
+        let config = ContainerParam::Config(ContainerConfig {
+            id: None,
+            skills: Some(vec![
+                SkillReference::anthropic("xlsx"),
+                SkillReference::custom("skill_01").version("3"),
+            ]),
+        });
+        assert_eq!(
+            serde_json::to_value(&config).unwrap(),
+            serde_json::json!({"skills": [
+                {"type": "anthropic", "skill_id": "xlsx"},
+                {"type": "custom", "skill_id": "skill_01", "version": "3"}
+            ]})
+        );
+    }
+}
@@ -6,7 +6,7 @@ use super::metadata::CacheControl;
 // ── Response content blocks ──────────────────────────────────────────
 
 /// A content block in a message response.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[non_exhaustive]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum ContentBlock {
@@ -27,29 +27,80 @@ pub enum ContentBlock {
     Compaction(CompactionBlock),
 }
 
+impl ContentBlock {
+    /// The Files API ids of files written by a code execution tool result.
+    ///
+    /// Feed them back with `ToolResultContentBlock::image_file` or
+    /// `document_file`, or download them with the Files API.
+    pub fn output_file_ids(&self) -> Vec<&str> {
+        match self {
+            ContentBlock::CodeExecutionToolResult(result) => result
+                .content
+                .iter()
+                .filter_map(|content| match content {
+                    CodeExecutionContent::CodeExecutionOutput(output) => output.file_id.as_deref(),
+                    _ => None,
+                })
+                .collect(),
+            ContentBlock::BashCodeExecutionToolResult(result) => result
+                .content
+                .iter()
+                .filter_map(|content| match content {
+                    BashCodeExecutionContent::BashCodeExecutionOutput(output) => {
+                        output.file_id.as_deref()
+                    }
+                    _ => None,
+                })
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+}
+
 /// A text content block in a response.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct TextBlock {
     pub text: String,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub citations: Option<Vec<super::citation::TextCitation>>,
+    /// Unrecognized fields from the API.
+    #[serde(flatten)]
+    #[cfg_attr(
+        feature = "strict-serde",
+        serde(deserialize_with = "super::common::deny_extra_fields")
+    )]
+    pub extra: super::common::ExtraFields,
 }
 
 /// A thinking content block in a response.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ThinkingBlock {
     pub thinking: String,
     pub signature: String,
+    /// Unrecognized fields from the API.
+    #[serde(flatten)]
+    #[cfg_attr(
+        feature = "strict-serde",
+        serde(deserialize_with = "super::common::deny_extra_fields")
+    )]
+    pub extra: super::common::ExtraFields,
 }
 
 /// A redacted thinking content block in a response.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct RedactedThinkingBlock {
     pub data: String,
+    /// Unrecognized fields from the API.
+    #[serde(flatten)]
+    #[cfg_attr(
+        feature = "strict-serde",
+        serde(deserialize_with = "super::common::deny_extra_fields")
+    )]
+    pub extra: super::common::ExtraFields,
 }
 
 /// A tool use content block in a response.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ToolUseBlock {
     pub id: String,
     pub name: String,
@@ -57,27 +108,48 @@ pub struct ToolUseBlock {
     /// Accumulated partial JSON during streaming. Not part of the API response.
     #[serde(skip)]
     pub partial_json: Option<String>,
+    /// Unrecognized fields from the API.
+    #[serde(flatten)]
+    #[cfg_attr(
+        feature = "strict-serde",
+        serde(deserialize_with = "super::common::deny_extra_fields")
+    )]
+    pub extra: super::common::ExtraFields,
 }
 
 /// A server tool use content block in a response.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ServerToolUseBlock {
     pub id: String,
     pub name: String,
     pub input: serde_json::Value,
+    /// Unrecognized fields from the API.
+    #[serde(flatten)]
+    #[cfg_attr(
+        feature = "strict-serde",
+        serde(deserialize_with = "super::common::deny_extra_fields")
+    )]
+    pub extra: super::common::ExtraFields,
 }
 
 /// A web search tool result content block in a response.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct WebSearchToolResultBlock {
     pub tool_use_id: String,
     pub content: WebSearchToolResultContent,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub caller: Option<serde_json::Value>,
+    /// Unrecognized fields from the API.
+    #[serde(flatten)]
+    #[cfg_attr(
+        feature = "strict-serde",
+        serde(deserialize_with = "super::common::deny_extra_fields")
+    )]
+    pub extra: super::common::ExtraFields,
 }
 
 /// Content of a web search tool result: either search results or an error.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum WebSearchToolResultContent {
     Results(Vec<WebSearchResultBlock>),
@@ -85,7 +157,8 @@ pub enum WebSearchToolResultContent {
 }
 
 /// A single web search result block.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "strict-serde", serde(deny_unknown_fields))]
 pub struct WebSearchResultBlock {
     #[serde(rename = "type")]
     pub result_type: String,
@@ -98,7 +171,8 @@ pub struct WebSearchResultBlock {
 }
 
 /// An error from a web search tool request.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "strict-serde", serde(deny_unknown_fields))]
 pub struct WebSearchToolRequestError {
     #[serde(rename = "type")]
     pub error_type: String,
@@ -108,7 +182,10 @@ pub struct WebSearchToolRequestError {
 }
 
 /// Typed error codes for web search tool results.
+///
+/// Unrecognized codes deserialize to `WebSearchToolResultErrorCode::Other(String)`.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[non_exhaustive]
 #[serde(rename_all = "snake_case")]
 pub enum WebSearchToolResultErrorCode {
     InvalidToolInput,
@@ -117,10 +194,16 @@ pub enum WebSearchToolResultErrorCode {
     TooManyRequests,
     QueryTooLong,
     RequestTooLarge,
+    /// Any error code not in the known variants.
+    #[serde(untagged)]
+    Other(String),
 }
 
 /// Typed error codes for web fetch tool results.
+///
+/// Unrecognized codes deserialize to `WebFetchToolResultErrorCode::Other(String)`.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[non_exhaustive]
 #[serde(rename_all = "snake_case")]
 pub enum WebFetchToolResultErrorCode {
     InvalidToolInput,
@@ -131,25 +214,80 @@ pub enum WebFetchToolResultErrorCode {
     TooManyRequests,
     MaxUsesExceeded,
     Unavailable,
+    /// Any error code not in the known variants.
+    #[serde(untagged)]
+    Other(String),
 }
 
 /// A container upload content block in a response.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ContainerUploadBlock {
     pub file_id: String,
+    /// Unrecognized fields from the API.
+    #[serde(flatten)]
+    #[cfg_attr(
+        feature = "strict-serde",
+        serde(deserialize_with = "super::common::deny_extra_fields")
+    )]
+    pub extra: super::common::ExtraFields,
 }
 
 /// A web fetch tool result content block in a response.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct WebFetchToolResultBlock {
     pub tool_use_id: String,
     pub content: WebFetchToolResultContent,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub caller: Option<serde_json::Value>,
+    pub caller: Option<ToolCaller>,
+    /// Unrecognized fields from the API.
+    #[serde(flatten)]
+    #[cfg_attr(
+        feature = "strict-serde",
+        serde(deserialize_with = "super::common::deny_extra_fields")
+    )]
+    pub extra: super::common::ExtraFields,
+}
+
+/// What invoked a server tool: the model directly, or a code execution tool
+/// calling it programmatically (see `allowed_callers` on tool definitions).
+///
+/// Unrecognized caller types deserialize to `ToolCaller::Other` with the raw
+/// JSON preserved.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+#[serde(tag = "type")]
+pub enum ToolCaller {
+    #[serde(rename = "direct")]
+    Direct,
+    #[serde(rename = "code_execution_20250825")]
+    CodeExecution20250825 { tool_id: String },
+    #[serde(rename = "code_execution_20260120")]
+    CodeExecution20260120 { tool_id: String },
+    /// Any caller type not in the known variants.
+    #[serde(untagged)]
+    Other(serde_json::Value),
+}
+
+impl ToolCaller {
+    /// Whether the model called the tool directly.
+    pub fn is_direct(&self) -> bool {
+        matches!(self, Self::Direct)
+    }
+
+    /// The id of the code execution tool use that made the call, if any.
+    pub fn tool_id(&self) -> Option<&str> {
+        match self {
+            Self::CodeExecution20250825 { tool_id } | Self::CodeExecution20260120 { tool_id } => {
+                Some(tool_id)
+            }
+            Self::Other(value) => value.get("tool_id").and_then(|id| id.as_str()),
+            Self::Direct => None,
+        }
+    }
 }
 
 /// Content of a web fetch tool result: either a fetched page or an error.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum WebFetchToolResultContent {
     WebFetchResult(Box<WebFetchBlock>),
@@ -157,7 +295,8 @@ pub enum WebFetchToolResultContent {
 }
 
 /// A successful web fetch result.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "strict-serde", serde(deny_unknown_fields))]
 pub struct WebFetchBlock {
     pub url: String,
     pub content: WebFetchDocument,
@@ -166,7 +305,8 @@ pub struct WebFetchBlock {
 }
 
 /// A document returned from a web fetch result.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "strict-serde", serde(deny_unknown_fields))]
 pub struct WebFetchDocument {
     #[serde(rename = "type", default = "WebFetchDocument::default_type")]
     pub document_type: String,
@@ -184,22 +324,30 @@ impl WebFetchDocument {
 }
 
 /// An error from a web fetch tool request.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "strict-serde", serde(deny_unknown_fields))]
 pub struct WebFetchToolResultErrorBlock {
     pub error_code: WebFetchToolResultErrorCode,
 }
 
 /// A tool search tool result content block in a response.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ToolSearchToolResultBlock {
     pub tool_use_id: String,
     pub content: ToolSearchToolResultContent,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub caller: Option<serde_json::Value>,
+    /// Unrecognized fields from the API.
+    #[serde(flatten)]
+    #[cfg_attr(
+        feature = "strict-serde",
+        serde(deserialize_with = "super::common::deny_extra_fields")
+    )]
+    pub extra: super::common::ExtraFields,
 }
 
 /// Content of a tool search tool result: either search results or an error.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum ToolSearchToolResultContent {
     ToolSearchToolSearchResult(ToolSearchToolSearchResultBlock),
@@ -207,13 +355,15 @@ pub enum ToolSearchToolResultContent {
 }
 
 /// A successful tool search result block.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "strict-serde", serde(deny_unknown_fields))]
 pub struct ToolSearchToolSearchResultBlock {
     pub tool_references: Vec<ToolReferenceBlock>,
 }
 
 /// A tool reference within a tool search result.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "strict-serde", serde(deny_unknown_fields))]
 pub struct ToolReferenceBlock {
     pub tool_name: String,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -221,43 +371,64 @@ pub struct ToolReferenceBlock {
 }
 
 /// An error from a tool search tool request.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "strict-serde", serde(deny_unknown_fields))]
 pub struct ToolSearchToolResultError {
     pub error_code: ToolSearchToolResultErrorCode,
     pub error_message: String,
 }
 
 /// Typed error codes for tool search tool results.
+///
+/// Unrecognized codes deserialize to `ToolSearchToolResultErrorCode::Other(String)`.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[non_exhaustive]
 #[serde(rename_all = "snake_case")]
 pub enum ToolSearchToolResultErrorCode {
     InvalidToolInput,
     Unavailable,
     TooManyRequests,
     ExecutionTimeExceeded,
+    /// Any error code not in the known variants.
+    #[serde(untagged)]
+    Other(String),
 }
 
 /// An MCP tool use content block in a response.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct McpToolUseBlock {
     pub id: String,
     pub server_label: String,
     pub name: String,
     pub input: serde_json::Value,
+    /// Unrecognized fields from the API.
+    #[serde(flatten)]
+    #[cfg_attr(
+        feature = "strict-serde",
+        serde(deserialize_with = "super::common::deny_extra_fields")
+    )]
+    pub extra: super::common::ExtraFields,
 }
 
 /// An MCP tool result content block in a response.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct McpToolResultBlock {
     pub tool_use_id: String,
     pub server_label: String,
     pub content: McpToolResultContent,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub is_error: Option<bool>,
+    /// Unrecognized fields from the API.
+    #[serde(flatten)]
+    #[cfg_attr(
+        feature = "strict-serde",
+        serde(deserialize_with = "super::common::deny_extra_fields")
+    )]
+    pub extra: super::common::ExtraFields,
 }
 
 /// Content of an MCP tool result.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum McpToolResultContent {
     Blocks(Vec<McpToolResultContentBlock>),
@@ -265,29 +436,37 @@ pub enum McpToolResultContent {
 }
 
 /// A content block within an MCP tool result.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum McpToolResultContentBlock {
     Text(McpToolResultTextBlock),
 }
 
 /// A text block within an MCP tool result.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "strict-serde", serde(deny_unknown_fields))]
 pub struct McpToolResultTextBlock {
     pub text: String,
 }
 
 /// A code execution tool result content block in a response.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct CodeExecutionToolResultBlock {
     pub tool_use_id: String,
     pub content: Vec<CodeExecutionContent>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub caller: Option<serde_json::Value>,
+    /// Unrecognized fields from the API.
+    #[serde(flatten)]
+    #[cfg_attr(
+        feature = "strict-serde",
+        serde(deserialize_with = "super::common::deny_extra_fields")
+    )]
+    pub extra: super::common::ExtraFields,
 }
 
 /// Content types within a code execution result.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum CodeExecutionContent {
     CodeExecutionOutput(CodeExecutionOutput),
@@ -295,14 +474,20 @@ pub enum CodeExecutionContent {
     CodeExecutionError(CodeExecutionError),
 }
 
-/// Standard output from code execution.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Output from code execution: printed text, or a file the code wrote.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "strict-serde", serde(deny_unknown_fields))]
 pub struct CodeExecutionOutput {
+    #[serde(default)]
     pub output: String,
+    /// A file written by the code, readable through the Files API.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file_id: Option<String>,
 }
 
 /// Return value from code execution.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "strict-serde", serde(deny_unknown_fields))]
 pub struct CodeExecutionResult {
     pub return_value: String,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -310,57 +495,78 @@ pub struct CodeExecutionResult {
 }
 
 /// Error output from code execution.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "strict-serde", serde(deny_unknown_fields))]
 pub struct CodeExecutionError {
     pub error_message: String,
 }
 
 /// A bash code execution tool result content block in a response.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct BashCodeExecutionToolResultBlock {
     pub tool_use_id: String,
     pub content: Vec<BashCodeExecutionContent>,
+    /// Unrecognized fields from the API.
+    #[serde(flatten)]
+    #[cfg_attr(
+        feature = "strict-serde",
+        serde(deserialize_with = "super::common::deny_extra_fields")
+    )]
+    pub extra: super::common::ExtraFields,
 }
 
 /// Content types within a bash code execution result.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum BashCodeExecutionContent {
     BashCodeExecutionOutput(BashCodeExecutionOutput),
     BashCodeExecutionResult(BashCodeExecutionResultContent),
 }
 
-/// Standard output from bash code execution.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Output from bash code execution: printed text, or a file the command wrote.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "strict-serde", serde(deny_unknown_fields))]
 pub struct BashCodeExecutionOutput {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub stdout: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub stderr: Option<String>,
+    /// A file written by the command, readable through the Files API.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file_id: Option<String>,
 }
 
 /// Return value from bash code execution.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "strict-serde", serde(deny_unknown_fields))]
 pub struct BashCodeExecutionResultContent {
     pub return_code: i32,
 }
 
 /// A text editor code execution tool result content block in a response.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct TextEditorCodeExecutionToolResultBlock {
     pub tool_use_id: String,
     pub content: Vec<TextEditorCodeExecutionContent>,
+    /// Unrecognized fields from the API.
+    #[serde(flatten)]
+    #[cfg_attr(
+        feature = "strict-serde",
+        serde(deserialize_with = "super::common::deny_extra_fields")
+    )]
+    pub extra: super::common::ExtraFields,
 }
 
 /// Content types within a text editor code execution result.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum TextEditorCodeExecutionContent {
     TextEditorCodeExecutionOutput(TextEditorCodeExecutionOutput),
 }
 
 /// Output from text editor code execution.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "strict-serde", serde(deny_unknown_fields))]
 pub struct TextEditorCodeExecutionOutput {
     pub output: String,
 }
@@ -368,15 +574,22 @@ pub struct TextEditorCodeExecutionOutput {
 /// A compaction content block in a response.
 ///
 /// Represents a summary produced by context management compaction.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct CompactionBlock {
     pub compacted: String,
+    /// Unrecognized fields from the API.
+    #[serde(flatten)]
+    #[cfg_attr(
+        feature = "strict-serde",
+        serde(deserialize_with = "super::common::deny_extra_fields")
+    )]
+    pub extra: super::common::ExtraFields,
 }
 
 // ── Request content blocks ───────────────────────────────────────────
 
 /// A content block in a message request.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[non_exhaustive]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum ContentBlockParam {
@@ -401,9 +614,29 @@ pub enum ContentBlockParam {
     Compaction(CompactionBlockParam),
 }
 
+impl ContentBlockParam {
+    /// Whether this block, or a tool result inside it, points at an uploaded file.
+    pub(crate) fn references_file(&self) -> bool {
+        match self {
+            ContentBlockParam::Image(image) => image.references_file(),
+            ContentBlockParam::Document(document) => document.references_file(),
+            ContentBlockParam::ToolResult(ToolResultBlockParam {
+                content: Some(ToolResultContent::Blocks(blocks)),
+                ..
+            }) => blocks.iter().any(|block| match block {
+                ToolResultContentBlock::Image(image) => image.references_file(),
+                ToolResultContentBlock::Document(document) => document.references_file(),
+                _ => false,
+            }),
+            _ => false,
+        }
+    }
+}
+
 /// A text block in a request.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, bon::Builder)]
 pub struct TextBlockParam {
+    #[builder(into)]
     pub text: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub cache_control: Option<CacheControl>,
@@ -423,20 +656,36 @@ impl TextBlockParam {
 }
 
 /// An image block in a request.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ImageBlockParam {
     pub source: super::image::ImageSource,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub cache_control: Option<CacheControl>,
 }
 
+impl ImageBlockParam {
+    /// Create an image block from a source.
+    pub fn new(source: super::image::ImageSource) -> Self {
+        Self {
+            source,
+            cache_control: None,
+        }
+    }
+
+    fn references_file(&self) -> bool {
+        matches!(self.source, super::image::ImageSource::File(_))
+    }
+}
+
 /// A document block in a request.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, bon::Builder)]
 pub struct DocumentBlockParam {
     pub source: super::document::DocumentSource,
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(into)]
     pub title: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(into)]
     pub context: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub citations: Option<super::citation::CitationsConfig>,
@@ -444,8 +693,25 @@ pub struct DocumentBlockParam {
     pub cache_control: Option<CacheControl>,
 }
 
+impl DocumentBlockParam {
+    /// Create a document block from a source.
+    pub fn new(source: super::document::DocumentSource) -> Self {
+        Self {
+            source,
+            title: None,
+            context: None,
+            citations: None,
+            cache_control: None,
+        }
+    }
+
+    fn references_file(&self) -> bool {
+        matches!(self.source, super::document::DocumentSource::File(_))
+    }
+}
+
 /// A tool use block in a request (for multi-turn conversations).
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ToolUseBlockParam {
     pub id: String,
     pub name: String,
@@ -457,10 +723,12 @@ pub struct ToolUseBlockParam {
 }
 
 /// A tool result block in a request.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, bon::Builder)]
 pub struct ToolResultBlockParam {
+    #[builder(into)]
     pub tool_use_id: String,
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(into)]
     pub content: Option<ToolResultContent>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub is_error: Option<bool>,
@@ -469,7 +737,7 @@ pub struct ToolResultBlockParam {
 }
 
 /// Content of a tool result: either a plain string or content blocks.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum ToolResultContent {
     Text(String),
@@ -477,7 +745,7 @@ pub enum ToolResultContent {
 }
 
 /// A content block allowed inside a tool result.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[non_exhaustive]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum ToolResultContentBlock {
@@ -485,10 +753,47 @@ pub enum ToolResultContentBlock {
     Image(ImageBlockParam),
     Document(DocumentBlockParam),
     SearchResult(SearchResultBlockParam),
+    ToolReference(ToolReferenceBlockParam),
+}
+
+impl ToolResultContentBlock {
+    /// An image uploaded through the Files API, such as a chart written by
+    /// code execution.
+    pub fn image_file(file_id: impl Into<String>) -> Self {
+        ToolResultContentBlock::Image(ImageBlockParam::new(super::image::ImageSource::file(
+            file_id,
+        )))
+    }
+
+    /// A document uploaded through the Files API.
+    pub fn document_file(file_id: impl Into<String>) -> Self {
+        ToolResultContentBlock::Document(DocumentBlockParam::new(
+            super::document::DocumentSource::file(file_id),
+        ))
+    }
+}
+
+/// A reference to a deferred tool, returned from a client-side tool search
+/// so the model can load the tool's definition.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ToolReferenceBlockParam {
+    pub tool_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cache_control: Option<CacheControl>,
+}
+
+impl ToolReferenceBlockParam {
+    /// Reference the tool named `tool_name`.
+    pub fn new(tool_name: impl Into<String>) -> Self {
+        Self {
+            tool_name: tool_name.into(),
+            cache_control: None,
+        }
+    }
 }
 
 /// A thinking block in a request (for multi-turn conversations).
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ThinkingBlockParam {
     pub thinking: String,
     pub signature: String,
@@ -497,7 +802,7 @@ pub struct ThinkingBlockParam {
 }
 
 /// A redacted thinking block in a request (for multi-turn conversations).
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct RedactedThinkingBlockParam {
     pub data: String,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -505,7 +810,7 @@ pub struct RedactedThinkingBlockParam {
 }
 
 /// A server tool use block in a request (for multi-turn conversations).
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ServerToolUseBlockParam {
     pub id: String,
     pub name: String,
@@ -517,7 +822,7 @@ pub struct ServerToolUseBlockParam {
 }
 
 /// A web search tool result block in a request (for multi-turn conversations).
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct WebSearchToolResultBlockParam {
     pub tool_use_id: String,
     pub content: WebSearchToolResultContent,
@@ -526,9 +831,11 @@ pub struct WebSearchToolResultBlockParam {
 }
 
 /// A search result block in a request.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, bon::Builder)]
 pub struct SearchResultBlockParam {
+    #[builder(into)]
     pub source: String,
+    #[builder(into)]
     pub title: String,
     pub content: Vec<SearchResultTextContent>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -537,16 +844,53 @@ pub struct SearchResultBlockParam {
     pub cache_control: Option<CacheControl>,
 }
 
+impl SearchResultBlockParam {
+    /// Create a search result from retrieved passages, one text entry per
+    /// passage, with citations enabled so answers can point back at them.
+    pub fn new(
+        source: impl Into<String>,
+        title: impl Into<String>,
+        passages: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        Self {
+            source: source.into(),
+            title: title.into(),
+            content: passages
+                .into_iter()
+                .map(SearchResultTextContent::new)
+                .collect(),
+            citations: Some(super::citation::CitationsConfig::enabled()),
+            cache_control: None,
+        }
+    }
+
+    /// Set the cache breakpoint on this block.
+    pub fn with_cache_control(mut self, cache_control: CacheControl) -> Self {
+        self.cache_control = Some(cache_control);
+        self
+    }
+}
+
 /// Text content within a search result block.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct SearchResultTextContent {
     #[serde(rename = "type")]
     pub content_type: String,
     pub text: String,
 }
 
+impl SearchResultTextContent {
+    /// Create a text content entry.
+    pub fn new(text: impl Into<String>) -> Self {
+        Self {
+            content_type: "text".to_string(),
+            text: text.into(),
+        }
+    }
+}
+
 /// A container upload block in a request.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ContainerUploadBlockParam {
     pub file_id: String,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -554,7 +898,7 @@ pub struct ContainerUploadBlockParam {
 }
 
 /// A web fetch tool result block in a request.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct WebFetchToolResultBlockParam {
     pub tool_use_id: String,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -565,11 +909,11 @@ pub struct WebFetchToolResultBlockParam {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub cache_control: Option<CacheControl>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub caller: Option<serde_json::Value>,
+    pub caller: Option<ToolCaller>,
 }
 
 /// A tool search tool result block in a request.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ToolSearchToolResultBlockParam {
     pub tool_use_id: String,
     pub content: ToolSearchToolResultContent,
@@ -578,7 +922,7 @@ pub struct ToolSearchToolResultBlockParam {
 }
 
 /// An MCP tool use block in a request (for multi-turn conversations).
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct McpToolUseBlockParam {
     pub id: String,
     pub server_label: String,
@@ -589,7 +933,7 @@ pub struct McpToolUseBlockParam {
 }
 
 /// An MCP tool result block in a request (for multi-turn conversations).
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct McpToolResultBlockParam {
     pub tool_use_id: String,
     pub server_label: String,
@@ -601,7 +945,7 @@ pub struct McpToolResultBlockParam {
 }
 
 /// A code execution tool result block in a request.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct CodeExecutionToolResultBlockParam {
     pub tool_use_id: String,
     pub content: Vec<CodeExecutionContent>,
@@ -610,7 +954,7 @@ pub struct CodeExecutionToolResultBlockParam {
 }
 
 /// A bash code execution tool result block in a request.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct BashCodeExecutionToolResultBlockParam {
     pub tool_use_id: String,
     pub content: Vec<BashCodeExecutionContent>,
@@ -619,7 +963,7 @@ pub struct BashCodeExecutionToolResultBlockParam {
 }
 
 /// A text editor code execution tool result block in a request.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct TextEditorCodeExecutionToolResultBlockParam {
     pub tool_use_id: String,
     pub content: Vec<TextEditorCodeExecutionContent>,
@@ -628,7 +972,7 @@ pub struct TextEditorCodeExecutionToolResultBlockParam {
 }
 
 /// A compaction block in a request (for multi-turn round-tripping).
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct CompactionBlockParam {
     pub compacted: String,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -653,10 +997,74 @@ impl From<Vec<ToolResultContentBlock>> for ToolResultContent {
     }
 }
 
+impl From<Vec<SearchResultBlockParam>> for ToolResultContent {
+    fn from(results: Vec<SearchResultBlockParam>) -> Self {
+        ToolResultContent::Blocks(
+            results
+                .into_iter()
+                .map(ToolResultContentBlock::SearchResult)
+                .collect(),
+        )
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_block_param_builders() {
+        let document = DocumentBlockParam::builder()
+            .source(DocumentSource::url("https://example.com/a.pdf"))
+            .title("Report")
+            .build();
+        assert_eq!(document.title.as_deref(), Some("Report"));
+        assert_eq!(document.context, None);
+
+        let search = SearchResultBlockParam::builder()
+            .source("https://example.com")
+            .title("Example")
+            .content(vec![SearchResultTextContent::new("Some text")])
+            .build();
+        assert_eq!(
+            serde_json::to_value(&search).unwrap(),
+            serde_json::json!({
+                "source": "https://example.com",
+                "title": "Example",
+                "content": [{"type": "text", "text": "Some text"}]
+            })
+        );
+    }
+
+    #[test]
+    fn test_search_result_from_passages() {
+        let result = SearchResultBlockParam::new(
+            "https://example.com/kb/1",
+            "Refund policy",
+            ["Refunds take 5 days.", "Contact support first."],
+        )
+        .with_cache_control(CacheControl::ephemeral());
+        assert_eq!(
+            serde_json::to_value(&result).unwrap(),
+            serde_json::json!({
+                "source": "https://example.com/kb/1",
+                "title": "Refund policy",
+                "content": [
+                    {"type": "text", "text": "Refunds take 5 days."},
+                    {"type": "text", "text": "Contact support first."}
+                ],
+                "citations": {"enabled": true},
+                "cache_control": {"type": "ephemeral"}
+            })
+        );
+
+        let content = ToolResultContent::from(vec![result]);
+        let ToolResultContent::Blocks(blocks) = content else {
+            panic!("expected blocks");
+        };
+        assert!(matches!(blocks[0], ToolResultContentBlock::SearchResult(_)));
+    }
+
     #[test]
     fn test_content_block_text() {
         let json = r#"{"type":"text","text":"Hello, world!"}"#;
@@ -768,6 +1176,33 @@ mod tests {
         assert!(json.contains(r#""text":"result""#));
     }
 
+    #[test]
+    fn test_tool_result_file_outputs() {
+        let result: ContentBlock = serde_json::from_value(serde_json::json!({
+            "type": "bash_code_execution_tool_result",
+            "tool_use_id": "srvtoolu_1",
+            "content": [
+                {"type": "bash_code_execution_output", "file_id": "file_chart"},
+                {"type": "bash_code_execution_result", "return_code": 0}
+            ]
+        }))
+        .unwrap();
+        let file_ids = result.output_file_ids();
+        assert_eq!(file_ids, ["file_chart"]);
+
+        let content = ToolResultContent::Blocks(vec![
+            ToolResultContentBlock::image_file(file_ids[0]),
+            ToolResultContentBlock::ToolReference(ToolReferenceBlockParam::new("get_weather")),
+        ]);
+        assert_eq!(
+            serde_json::to_value(&content).unwrap(),
+            serde_json::json!([
+                {"type": "image", "source": {"type": "file", "file_id": "file_chart"}},
+                {"type": "tool_reference", "tool_name": "get_weather"}
+            ])
+        );
+    }
+
     #[test]
     fn test_tool_result_content_from_str() {
         let content: ToolResultContent = "hello".into();
@@ -938,4 +1373,37 @@ mod tests {
         let roundtrip = serde_json::to_string(&block).unwrap();
         let _: ContentBlock = serde_json::from_str(&roundtrip).unwrap();
     }
+
+    #[test]
+    fn test_web_fetch_tool_result_caller() {
+        let json = r#"{"type":"web_fetch_tool_result","tool_use_id":"srvtoolu_1","content":{"type":"web_fetch_tool_result_error","error_code":"url_not_accessible"},"caller":{"type":"code_execution_20250825","tool_id":"srvtoolu_0"}}"#;
+        let block: ContentBlock = serde_json::from_str(json).unwrap();
+        let ContentBlock::WebFetchToolResult(result) = block else {
+            panic!("expected WebFetchToolResult");
+        };
+        let caller = result.caller.unwrap();
+        assert_eq!(
+            caller,
+            ToolCaller::CodeExecution20250825 {
+                tool_id: "srvtoolu_0".to_string()
+            }
+        );
+        assert_eq!(caller.tool_id(), Some("srvtoolu_0"));
+
+        let direct: ToolCaller = serde_json::from_str(r#"{"type":"direct"}"#).unwrap();
+        assert!(direct.is_direct());
+        assert_eq!(
+            serde_json::to_string(&direct).unwrap(),
+            r#"{"type":"direct"}"#
+        );
+    }
+
+    #[test]
+    fn test_tool_caller_other() {
+        let json = r#"{"type":"code_execution_20990101","tool_id":"srvtoolu_9"}"#;
+        let caller: ToolCaller = serde_json::from_str(json).unwrap();
+        assert!(matches!(caller, ToolCaller::Other(_)));
+        assert_eq!(caller.tool_id(), Some("srvtoolu_9"));
+        assert_eq!(serde_json::to_string(&caller).unwrap(), json);
+    }
 }
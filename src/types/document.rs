@@ -3,7 +3,7 @@ use serde::{Deserialize, Serialize};
 use super::content::TextBlockParam;
 
 /// The source of a document in a request.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum DocumentSource {
     Base64(Base64DocumentSource),
@@ -13,34 +13,57 @@ pub enum DocumentSource {
     File(FileDocumentSource),
 }
 
+impl DocumentSource {
+    /// Base64-encode the bytes of a PDF.
+    pub fn pdf(data: impl AsRef<[u8]>) -> Self {
+        use base64::Engine;
+        DocumentSource::Base64(Base64DocumentSource {
+            media_type: "application/pdf".to_string(),
+            data: base64::engine::general_purpose::STANDARD.encode(data),
+        })
+    }
+
+    /// Reference a PDF by URL.
+    pub fn url(url: impl Into<String>) -> Self {
+        DocumentSource::Url(UrlDocumentSource { url: url.into() })
+    }
+
+    /// Reference a document uploaded through the Files API.
+    pub fn file(file_id: impl Into<String>) -> Self {
+        DocumentSource::File(FileDocumentSource {
+            file_id: file_id.into(),
+        })
+    }
+}
+
 /// A file-based document source (references a previously uploaded file).
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct FileDocumentSource {
     pub file_id: String,
 }
 
 /// A base64-encoded document source (PDF).
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Base64DocumentSource {
     pub media_type: String,
     pub data: String,
 }
 
 /// A plain text document source.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct PlainTextSource {
     pub media_type: String,
     pub data: String,
 }
 
 /// A content block document source.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ContentBlockSource {
     pub content: Vec<TextBlockParam>,
 }
 
 /// A URL document source.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct UrlDocumentSource {
     pub url: String,
 }
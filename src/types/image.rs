@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
 
 /// The source of an image in a request.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum ImageSource {
     Base64(Base64ImageSource),
@@ -9,21 +9,44 @@ pub enum ImageSource {
     File(FileImageSource),
 }
 
+impl ImageSource {
+    /// Base64-encode raw image bytes.
+    pub fn base64(data: impl AsRef<[u8]>, media_type: MediaType) -> Self {
+        use base64::Engine;
+        ImageSource::Base64(Base64ImageSource {
+            media_type,
+            data: base64::engine::general_purpose::STANDARD.encode(data),
+        })
+    }
+
+    /// Reference an image by URL.
+    pub fn url(url: impl Into<String>) -> Self {
+        ImageSource::Url(UrlImageSource { url: url.into() })
+    }
+
+    /// Reference an image uploaded through the Files API.
+    pub fn file(file_id: impl Into<String>) -> Self {
+        ImageSource::File(FileImageSource {
+            file_id: file_id.into(),
+        })
+    }
+}
+
 /// A file-based image source (references a previously uploaded file).
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct FileImageSource {
     pub file_id: String,
 }
 
 /// A base64-encoded image source.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Base64ImageSource {
     pub media_type: MediaType,
     pub data: String,
 }
 
 /// A URL image source.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct UrlImageSource {
     pub url: String,
 }
@@ -94,6 +117,14 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_image_source_base64_encodes() {
+        match ImageSource::base64(b"\x89PNG", MediaType::Png) {
+            ImageSource::Base64(b) => assert_eq!(b.data, "iVBORw=="),
+            _ => panic!("Expected Base64 variant"),
+        }
+    }
+
     #[test]
     fn test_media_type_serialize() {
         assert_eq!(
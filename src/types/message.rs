@@ -1,11 +1,18 @@
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 
 use super::common::{Role, StopReason};
-use super::content::{ContentBlock, ContentBlockParam, TextBlockParam};
+use super::content::{
+    ContentBlock, ContentBlockParam, DocumentBlockParam, ImageBlockParam, SearchResultBlockParam,
+    TextBlockParam,
+};
+use super::document::DocumentSource;
+use super::image::{ImageSource, MediaType};
 use super::usage::Usage;
+use crate::error::Error;
 
 /// A message response from the API.
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
 pub struct Message {
     pub id: String,
     #[serde(rename = "type")]
@@ -20,10 +27,18 @@ pub struct Message {
     /// Container information for code execution tool reuse.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub container: Option<ContainerInfo>,
+    /// Unrecognized fields from the API.
+    #[serde(flatten)]
+    #[cfg_attr(
+        feature = "strict-serde",
+        serde(deserialize_with = "super::common::deny_extra_fields")
+    )]
+    pub extra: super::common::ExtraFields,
 }
 
 /// Information about the container used in a request.
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "strict-serde", serde(deny_unknown_fields))]
 pub struct ContainerInfo {
     pub id: String,
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -33,144 +48,373 @@ pub struct ContainerInfo {
 impl Message {
     /// Convert this response message into a `MessageParam` for multi-turn conversations.
     pub fn to_param(&self) -> MessageParam {
+        self.to_param_with(&ParamOptions::default())
+    }
+
+    /// Convert this response message into a `MessageParam`, leaving out the
+    /// blocks `options` strips.
+    pub fn to_param_with(&self, options: &ParamOptions) -> MessageParam {
         MessageParam {
             role: self.role.clone(),
             content: MessageContent::Blocks(
                 self.content
                     .iter()
-                    .map(|block| match block {
-                        ContentBlock::Text(t) => ContentBlockParam::Text(TextBlockParam {
-                            text: t.text.clone(),
-                            cache_control: None,
-                            citations: None,
-                        }),
-                        ContentBlock::ToolUse(t) => {
-                            ContentBlockParam::ToolUse(super::content::ToolUseBlockParam {
-                                id: t.id.clone(),
-                                name: t.name.clone(),
-                                input: t.input.clone(),
-                                cache_control: None,
-                                caller: None,
-                            })
-                        }
-                        ContentBlock::Thinking(t) => {
-                            ContentBlockParam::Thinking(super::content::ThinkingBlockParam {
-                                thinking: t.thinking.clone(),
-                                signature: t.signature.clone(),
-                                cache_control: None,
-                            })
-                        }
-                        ContentBlock::RedactedThinking(r) => ContentBlockParam::RedactedThinking(
-                            super::content::RedactedThinkingBlockParam {
-                                data: r.data.clone(),
-                                cache_control: None,
-                            },
-                        ),
-                        ContentBlock::ServerToolUse(s) => ContentBlockParam::ServerToolUse(
-                            super::content::ServerToolUseBlockParam {
-                                id: s.id.clone(),
-                                name: s.name.clone(),
-                                input: s.input.clone(),
-                                cache_control: None,
-                                caller: None,
-                            },
-                        ),
-                        ContentBlock::WebSearchToolResult(w) => {
-                            ContentBlockParam::WebSearchToolResult(
-                                super::content::WebSearchToolResultBlockParam {
-                                    tool_use_id: w.tool_use_id.clone(),
-                                    content: w.content.clone(),
-                                    cache_control: None,
-                                },
-                            )
-                        }
-                        ContentBlock::ContainerUpload(c) => ContentBlockParam::ContainerUpload(
-                            super::content::ContainerUploadBlockParam {
-                                file_id: c.file_id.clone(),
-                                cache_control: None,
-                            },
-                        ),
-                        ContentBlock::WebFetchToolResult(w) => {
-                            ContentBlockParam::WebFetchToolResult(
-                                super::content::WebFetchToolResultBlockParam {
-                                    tool_use_id: w.tool_use_id.clone(),
-                                    url: None,
-                                    retrieved_at: None,
-                                    content: w.content.clone(),
-                                    cache_control: None,
-                                    caller: w.caller.clone(),
-                                },
-                            )
-                        }
-                        ContentBlock::ToolSearchToolResult(t) => {
-                            ContentBlockParam::ToolSearchToolResult(
-                                super::content::ToolSearchToolResultBlockParam {
-                                    tool_use_id: t.tool_use_id.clone(),
-                                    content: t.content.clone(),
-                                    cache_control: None,
-                                },
-                            )
-                        }
-                        ContentBlock::McpToolUse(m) => {
-                            ContentBlockParam::McpToolUse(super::content::McpToolUseBlockParam {
-                                id: m.id.clone(),
-                                server_label: m.server_label.clone(),
-                                name: m.name.clone(),
-                                input: m.input.clone(),
-                                cache_control: None,
-                            })
-                        }
-                        ContentBlock::McpToolResult(m) => ContentBlockParam::McpToolResult(
-                            super::content::McpToolResultBlockParam {
-                                tool_use_id: m.tool_use_id.clone(),
-                                server_label: m.server_label.clone(),
-                                content: m.content.clone(),
-                                is_error: m.is_error,
-                                cache_control: None,
-                            },
-                        ),
-                        ContentBlock::CodeExecutionToolResult(c) => {
-                            ContentBlockParam::CodeExecutionToolResult(
-                                super::content::CodeExecutionToolResultBlockParam {
-                                    tool_use_id: c.tool_use_id.clone(),
-                                    content: c.content.clone(),
-                                    cache_control: None,
-                                },
-                            )
-                        }
-                        ContentBlock::BashCodeExecutionToolResult(b) => {
-                            ContentBlockParam::BashCodeExecutionToolResult(
-                                super::content::BashCodeExecutionToolResultBlockParam {
-                                    tool_use_id: b.tool_use_id.clone(),
-                                    content: b.content.clone(),
-                                    cache_control: None,
-                                },
-                            )
-                        }
-                        ContentBlock::TextEditorCodeExecutionToolResult(t) => {
-                            ContentBlockParam::TextEditorCodeExecutionToolResult(
-                                super::content::TextEditorCodeExecutionToolResultBlockParam {
-                                    tool_use_id: t.tool_use_id.clone(),
-                                    content: t.content.clone(),
-                                    cache_control: None,
-                                },
-                            )
-                        }
-                        ContentBlock::Compaction(c) => {
-                            ContentBlockParam::Compaction(super::content::CompactionBlockParam {
-                                compacted: c.compacted.clone(),
-                                cache_control: None,
-                            })
-                        }
-                    })
+                    .filter(|block| options.keeps(block))
+                    .map(block_to_param)
                     .collect(),
             ),
         }
     }
+
+    /// Strip Markdown code fences wrapping each text block, in place.
+    ///
+    /// See [`strip_code_fences`].
+    pub fn strip_code_fences(&mut self) {
+        for block in &mut self.content {
+            if let ContentBlock::Text(text_block) = block {
+                let stripped = strip_code_fences(&text_block.text);
+                if stripped.len() != text_block.text.len() {
+                    text_block.text = stripped.to_string();
+                }
+            }
+        }
+    }
+
+    /// The concatenated text of every text block.
+    pub fn text(&self) -> String {
+        self.content
+            .iter()
+            .filter_map(|block| match block {
+                ContentBlock::Text(text_block) => Some(text_block.text.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Render the message as Markdown: text with citation footnotes, plus
+    /// tool calls, tool results and thinking as selected by `options`.
+    pub fn to_markdown(&self, options: &crate::markdown::MarkdownOptions) -> String {
+        crate::markdown::render(self, options)
+    }
+
+    /// Parse the JSON value in the message's text into `T`.
+    ///
+    /// The value is located with [`extract_json`], so code fences and prose
+    /// around it are ignored. If it doesn't parse as-is, [`repair_json`] is
+    /// tried, which recovers trailing commas and output cut off by
+    /// `max_tokens`. Fails with `Error::Validation` quoting the start of the
+    /// JSON when neither parses.
+    pub fn parse_json<T: DeserializeOwned>(&self) -> Result<T, Error> {
+        let text = self.text();
+        let json = extract_json(&text);
+        serde_json::from_str(json)
+            .or_else(|err| serde_json::from_str(&repair_json(json)).map_err(|_| err))
+            .map_err(|err| {
+                let snippet: String = json.chars().take(JSON_ERROR_SNIPPET_CHARS).collect();
+                Error::Validation(format!(
+                    "could not parse JSON from message: {err} in `{snippet}`"
+                ))
+            })
+    }
+}
+
+/// Which blocks `Message::to_param_with` carries into the conversation.
+///
+/// By default every block is kept, as with `Message::to_param`. Stripping is
+/// meant for older turns: a turn whose tool use is still awaiting its
+/// `tool_result` must keep the tool use and the thinking before it. A turn
+/// stripped of every block has empty content, which the API rejects.
+#[derive(Debug, Clone, Default)]
+pub struct ParamOptions {
+    strip_thinking: bool,
+    strip_server_tools: bool,
+    text_only: bool,
+}
+
+impl ParamOptions {
+    /// Drop thinking and redacted thinking blocks.
+    pub fn strip_thinking(mut self, strip: bool) -> Self {
+        self.strip_thinking = strip;
+        self
+    }
+
+    /// Drop server tool calls and their results, such as web search hits,
+    /// fetched pages, code execution output and MCP tool calls.
+    pub fn strip_server_tools(mut self, strip: bool) -> Self {
+        self.strip_server_tools = strip;
+        self
+    }
+
+    /// Keep only text blocks.
+    pub fn text_only(mut self, text_only: bool) -> Self {
+        self.text_only = text_only;
+        self
+    }
+
+    fn keeps(&self, block: &ContentBlock) -> bool {
+        match block {
+            ContentBlock::Text(_) => true,
+            _ if self.text_only => false,
+            ContentBlock::Thinking(_) | ContentBlock::RedactedThinking(_) => !self.strip_thinking,
+            ContentBlock::ServerToolUse(_)
+            | ContentBlock::WebSearchToolResult(_)
+            | ContentBlock::WebFetchToolResult(_)
+            | ContentBlock::ToolSearchToolResult(_)
+            | ContentBlock::McpToolUse(_)
+            | ContentBlock::McpToolResult(_)
+            | ContentBlock::CodeExecutionToolResult(_)
+            | ContentBlock::BashCodeExecutionToolResult(_)
+            | ContentBlock::TextEditorCodeExecutionToolResult(_) => !self.strip_server_tools,
+            _ => true,
+        }
+    }
+}
+
+/// Convert a response block into the matching request block.
+fn block_to_param(block: &ContentBlock) -> ContentBlockParam {
+    match block {
+        ContentBlock::Text(t) => ContentBlockParam::Text(TextBlockParam {
+            text: t.text.clone(),
+            cache_control: None,
+            citations: None,
+        }),
+        ContentBlock::ToolUse(t) => ContentBlockParam::ToolUse(super::content::ToolUseBlockParam {
+            id: t.id.clone(),
+            name: t.name.clone(),
+            input: t.input.clone(),
+            cache_control: None,
+            caller: None,
+        }),
+        ContentBlock::Thinking(t) => {
+            ContentBlockParam::Thinking(super::content::ThinkingBlockParam {
+                thinking: t.thinking.clone(),
+                signature: t.signature.clone(),
+                cache_control: None,
+            })
+        }
+        ContentBlock::RedactedThinking(r) => {
+            ContentBlockParam::RedactedThinking(super::content::RedactedThinkingBlockParam {
+                data: r.data.clone(),
+                cache_control: None,
+            })
+        }
+        ContentBlock::ServerToolUse(s) => {
+            ContentBlockParam::ServerToolUse(super::content::ServerToolUseBlockParam {
+                id: s.id.clone(),
+                name: s.name.clone(),
+                input: s.input.clone(),
+                cache_control: None,
+                caller: None,
+            })
+        }
+        ContentBlock::WebSearchToolResult(w) => {
+            ContentBlockParam::WebSearchToolResult(super::content::WebSearchToolResultBlockParam {
+                tool_use_id: w.tool_use_id.clone(),
+                content: w.content.clone(),
+                cache_control: None,
+            })
+        }
+        ContentBlock::ContainerUpload(c) => {
+            ContentBlockParam::ContainerUpload(super::content::ContainerUploadBlockParam {
+                file_id: c.file_id.clone(),
+                cache_control: None,
+            })
+        }
+        ContentBlock::WebFetchToolResult(w) => {
+            ContentBlockParam::WebFetchToolResult(super::content::WebFetchToolResultBlockParam {
+                tool_use_id: w.tool_use_id.clone(),
+                url: None,
+                retrieved_at: None,
+                content: w.content.clone(),
+                cache_control: None,
+                caller: w.caller.clone(),
+            })
+        }
+        ContentBlock::ToolSearchToolResult(t) => ContentBlockParam::ToolSearchToolResult(
+            super::content::ToolSearchToolResultBlockParam {
+                tool_use_id: t.tool_use_id.clone(),
+                content: t.content.clone(),
+                cache_control: None,
+            },
+        ),
+        ContentBlock::McpToolUse(m) => {
+            ContentBlockParam::McpToolUse(super::content::McpToolUseBlockParam {
+                id: m.id.clone(),
+                server_label: m.server_label.clone(),
+                name: m.name.clone(),
+                input: m.input.clone(),
+                cache_control: None,
+            })
+        }
+        ContentBlock::McpToolResult(m) => {
+            ContentBlockParam::McpToolResult(super::content::McpToolResultBlockParam {
+                tool_use_id: m.tool_use_id.clone(),
+                server_label: m.server_label.clone(),
+                content: m.content.clone(),
+                is_error: m.is_error,
+                cache_control: None,
+            })
+        }
+        ContentBlock::CodeExecutionToolResult(c) => ContentBlockParam::CodeExecutionToolResult(
+            super::content::CodeExecutionToolResultBlockParam {
+                tool_use_id: c.tool_use_id.clone(),
+                content: c.content.clone(),
+                cache_control: None,
+            },
+        ),
+        ContentBlock::BashCodeExecutionToolResult(b) => {
+            ContentBlockParam::BashCodeExecutionToolResult(
+                super::content::BashCodeExecutionToolResultBlockParam {
+                    tool_use_id: b.tool_use_id.clone(),
+                    content: b.content.clone(),
+                    cache_control: None,
+                },
+            )
+        }
+        ContentBlock::TextEditorCodeExecutionToolResult(t) => {
+            ContentBlockParam::TextEditorCodeExecutionToolResult(
+                super::content::TextEditorCodeExecutionToolResultBlockParam {
+                    tool_use_id: t.tool_use_id.clone(),
+                    content: t.content.clone(),
+                    cache_control: None,
+                },
+            )
+        }
+        ContentBlock::Compaction(c) => {
+            ContentBlockParam::Compaction(super::content::CompactionBlockParam {
+                compacted: c.compacted.clone(),
+                cache_control: None,
+            })
+        }
+    }
+}
+
+/// Remove a leading Markdown code fence (with its language tag) and the
+/// matching closing fence from `text`.
+///
+/// Text that does not both start and end with a fence is returned unchanged;
+/// fences in the middle of the text are left alone.
+pub fn strip_code_fences(text: &str) -> &str {
+    let Some(inner) = text
+        .trim()
+        .strip_prefix("```")
+        .and_then(|rest| rest.strip_suffix("```"))
+    else {
+        return text;
+    };
+    // Everything up to the first newline is the language tag.
+    let inner = match inner.find('\n') {
+        Some(newline) => &inner[newline + 1..],
+        None => inner,
+    };
+    inner.trim()
+}
+
+/// Characters of the offending JSON quoted by `Message::parse_json` errors.
+const JSON_ERROR_SNIPPET_CHARS: usize = 200;
+
+/// Locate the JSON value in model output.
+///
+/// Returns the contents of the first code fence if there is one, and within
+/// that everything from the first `{` or `[` to the bracket that closes it, or
+/// to the end if it never closes. Text without brackets is returned trimmed.
+pub fn extract_json(text: &str) -> &str {
+    let mut text = text.trim();
+    if let Some(start) = text.find("```") {
+        let rest = &text[start + 3..];
+        // Skip the language tag.
+        let rest = rest.find('\n').map_or(rest, |newline| &rest[newline + 1..]);
+        text = rest.find("```").map_or(rest, |end| &rest[..end]).trim();
+    }
+    let Some(start) = text.find(['{', '[']) else {
+        return text;
+    };
+    let text = &text[start..];
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut escaped = false;
+    for (i, c) in text.char_indices() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match c {
+            '"' => in_string = true,
+            '{' | '[' => depth += 1,
+            '}' | ']' => {
+                depth = depth.saturating_sub(1);
+                if depth == 0 {
+                    return &text[..=i];
+                }
+            }
+            _ => {}
+        }
+    }
+    text
+}
+
+/// Fix the mistakes most often found in model-written JSON: trailing commas,
+/// and a value cut off part way, whose open string, brackets and braces are
+/// closed. A dangling key gets a `null` value.
+///
+/// The result is not guaranteed to be valid JSON, e.g. if output stopped in
+/// the middle of a number or keyword.
+pub fn repair_json(json: &str) -> String {
+    let mut out = String::with_capacity(json.len() + 8);
+    let mut closers = Vec::new();
+    let mut in_string = false;
+    let mut escaped = false;
+    for c in json.chars() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+        } else {
+            match c {
+                '"' => in_string = true,
+                '{' => closers.push('}'),
+                '[' => closers.push(']'),
+                '}' | ']' => {
+                    trim_trailing_comma(&mut out);
+                    closers.pop();
+                }
+                _ => {}
+            }
+        }
+        out.push(c);
+    }
+    if in_string {
+        if escaped {
+            out.pop();
+        }
+        out.push('"');
+    }
+    trim_trailing_comma(&mut out);
+    if out.ends_with(':') {
+        out.push_str("null");
+    }
+    out.extend(closers.into_iter().rev());
+    out
+}
+
+/// Remove trailing whitespace and a trailing comma from `out`.
+fn trim_trailing_comma(out: &mut String) {
+    out.truncate(out.trim_end().len());
+    if out.ends_with(',') {
+        out.pop();
+    }
 }
 
 /// A message parameter for a request.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct MessageParam {
     pub role: Role,
     pub content: MessageContent,
@@ -201,6 +445,63 @@ impl MessageParam {
         }
     }
 
+    /// Create a user message holding an image, base64-encoding `data`.
+    pub fn user_image(data: impl AsRef<[u8]>, media_type: MediaType) -> Self {
+        Self::user_blocks(vec![ContentBlockParam::Image(ImageBlockParam::new(
+            ImageSource::base64(data, media_type),
+        ))])
+    }
+
+    /// Create a user message holding an image fetched from `url`.
+    pub fn user_image_url(url: impl Into<String>) -> Self {
+        Self::user_blocks(vec![ContentBlockParam::Image(ImageBlockParam::new(
+            ImageSource::url(url),
+        ))])
+    }
+
+    /// Create a user message holding a PDF, base64-encoding `data`.
+    pub fn user_document_pdf(data: impl AsRef<[u8]>) -> Self {
+        Self::user_blocks(vec![ContentBlockParam::Document(DocumentBlockParam::new(
+            DocumentSource::pdf(data),
+        ))])
+    }
+
+    /// Create a user message holding an image uploaded through the Files API.
+    pub fn user_image_file(file_id: impl Into<String>) -> Self {
+        Self::user_blocks(vec![ContentBlockParam::Image(ImageBlockParam::new(
+            ImageSource::file(file_id),
+        ))])
+    }
+
+    /// Create a user message holding a document uploaded through the Files API.
+    pub fn user_document_file(file_id: impl Into<String>) -> Self {
+        Self::user_blocks(vec![ContentBlockParam::Document(DocumentBlockParam::new(
+            DocumentSource::file(file_id),
+        ))])
+    }
+
+    /// Create a user message that puts retrieved search results ahead of
+    /// `question`, so the answer can cite them.
+    pub fn user_search_results(
+        results: Vec<SearchResultBlockParam>,
+        question: impl Into<String>,
+    ) -> Self {
+        let mut blocks: Vec<ContentBlockParam> = results
+            .into_iter()
+            .map(ContentBlockParam::SearchResult)
+            .collect();
+        blocks.push(ContentBlockParam::Text(TextBlockParam::new(question)));
+        Self::user_blocks(blocks)
+    }
+
+    /// Whether any content block points at a file uploaded through the Files API.
+    pub(crate) fn references_file(&self) -> bool {
+        match &self.content {
+            MessageContent::Blocks(blocks) => blocks.iter().any(|b| b.references_file()),
+            MessageContent::Text(_) => false,
+        }
+    }
+
     /// Create an assistant message from content blocks.
     pub fn assistant_blocks(blocks: Vec<ContentBlockParam>) -> Self {
         Self {
@@ -208,10 +509,61 @@ impl MessageParam {
             content: MessageContent::Blocks(blocks),
         }
     }
+
+    /// Clean up machine-assembled content before sending.
+    ///
+    /// Removes text blocks that are empty or whitespace-only (which the API
+    /// rejects) and merges adjacent text blocks into one, separated by a newline.
+    /// Blocks carrying citations are left as-is, and a text block is only merged
+    /// into its predecessor when the predecessor has no `cache_control`, so cache
+    /// breakpoints are preserved.
+    pub fn normalize(&mut self) {
+        let MessageContent::Blocks(blocks) = &mut self.content else {
+            return;
+        };
+
+        let mut normalized: Vec<ContentBlockParam> = Vec::with_capacity(blocks.len());
+        for block in blocks.drain(..) {
+            if let ContentBlockParam::Text(text) = &block {
+                if text.text.trim().is_empty() {
+                    continue;
+                }
+                if let Some(ContentBlockParam::Text(prev)) = normalized.last_mut()
+                    && prev.cache_control.is_none()
+                    && prev.citations.is_none()
+                    && text.citations.is_none()
+                {
+                    prev.text.push('\n');
+                    prev.text.push_str(&text.text);
+                    prev.cache_control = text.cache_control.clone();
+                    continue;
+                }
+            }
+            normalized.push(block);
+        }
+        *blocks = normalized;
+    }
+
+    /// Normalize the content and check that it has at most `max_blocks` blocks.
+    ///
+    /// Returns `Error::Validation` if the message still exceeds the limit after
+    /// normalization.
+    pub fn normalize_with_limit(&mut self, max_blocks: usize) -> Result<(), crate::error::Error> {
+        self.normalize();
+        if let MessageContent::Blocks(blocks) = &self.content
+            && blocks.len() > max_blocks
+        {
+            return Err(crate::error::Error::Validation(format!(
+                "message has {} content blocks, limit is {max_blocks}",
+                blocks.len()
+            )));
+        }
+        Ok(())
+    }
 }
 
 /// Message content: either a plain string or structured content blocks.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum MessageContent {
     Text(String),
@@ -237,14 +589,14 @@ impl From<Vec<ContentBlockParam>> for MessageContent {
 }
 
 /// A tagged text block for use in system content arrays.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum SystemBlock {
     Text(TextBlockParam),
 }
 
 /// System content: either a plain string or text blocks.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum SystemContent {
     Text(String),
@@ -270,7 +622,7 @@ impl From<Vec<TextBlockParam>> for SystemContent {
 }
 
 /// Response from the count_tokens endpoint.
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
 pub struct TokenCount {
     pub input_tokens: u32,
 }
@@ -278,6 +630,144 @@ pub struct TokenCount {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::mock::text_message;
+
+    #[test]
+    #[cfg(not(feature = "strict-serde"))]
+    fn test_unknown_fields_preserved() {
+        let json = serde_json::json!({
+            "id": "msg_1",
+            "type": "message",
+            "role": "assistant",
+            "content": [{"type": "text", "text": "Hi", "confidence": 0.9}],
+            "model": "claude-opus-4-6",
+            "stop_reason": "end_turn",
+            "usage": {"input_tokens": 1, "output_tokens": 2, "reasoning_tokens": 7},
+            "trace": {"region": "us"}
+        });
+        let message: Message = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(message.extra["trace"]["region"], "us");
+        assert_eq!(message.usage.extra["reasoning_tokens"], 7);
+        match &message.content[0] {
+            ContentBlock::Text(block) => {
+                assert_eq!(block.extra.len(), 1);
+                assert_eq!(block.extra["confidence"], 0.9);
+            }
+            _ => panic!("Expected Text block"),
+        }
+
+        let round_trip = serde_json::to_value(&message).unwrap();
+        assert_eq!(round_trip["trace"], json["trace"]);
+        assert_eq!(round_trip["usage"]["reasoning_tokens"], 7);
+        assert_eq!(round_trip["content"][0], json["content"][0]);
+        let again: Message = serde_json::from_value(round_trip).unwrap();
+        assert_eq!(again, message);
+    }
+
+    #[test]
+    #[cfg(feature = "strict-serde")]
+    fn test_unknown_fields_rejected() {
+        let json = br#"{
+            "id": "msg_1",
+            "type": "message",
+            "role": "assistant",
+            "content": [{"type": "text", "text": "Hi"}],
+            "model": "claude-opus-4-6",
+            "stop_reason": "end_turn",
+            "usage": {"input_tokens": 1, "output_tokens": 2, "reasoning_tokens": 7}
+        }"#;
+        let err = crate::client::decode::<Message>(json).unwrap_err();
+        match err {
+            Error::Decode { path, source, .. } => {
+                assert_eq!(path, "usage");
+                assert!(
+                    source
+                        .to_string()
+                        .contains("unknown field `reasoning_tokens`")
+                );
+            }
+            other => panic!("Expected Decode error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_user_search_results() {
+        let message = MessageParam::user_search_results(
+            vec![SearchResultBlockParam::new("kb/1", "Policy", ["Text."])],
+            "What is the policy?",
+        );
+        let MessageContent::Blocks(blocks) = &message.content else {
+            panic!("expected blocks");
+        };
+        assert!(matches!(blocks[0], ContentBlockParam::SearchResult(_)));
+        assert!(
+            matches!(&blocks[1], ContentBlockParam::Text(t) if t.text == "What is the policy?")
+        );
+    }
+
+    #[test]
+    fn test_parse_json() {
+        #[derive(Debug, PartialEq, Deserialize)]
+        struct Answer {
+            label: String,
+            tags: Vec<String>,
+        }
+        let expected = Answer {
+            label: "ok".to_string(),
+            tags: vec!["a".to_string()],
+        };
+
+        let fenced = "Sure! Here it is:\n```json\n{\"label\": \"ok\", \"tags\": [\"a\"]}\n```\nAnything else?";
+        assert_eq!(
+            text_message(fenced).parse_json::<Answer>().unwrap(),
+            expected
+        );
+
+        let prose = "The answer is {\"label\": \"ok\", \"tags\": [\"a\",],} as requested.";
+        assert_eq!(
+            text_message(prose).parse_json::<Answer>().unwrap(),
+            expected
+        );
+
+        let truncated = "{\"label\": \"ok\", \"tags\": [\"a\", \"b";
+        let answer: Answer = text_message(truncated).parse_json().unwrap();
+        assert_eq!(answer.tags, ["a", "b"]);
+
+        let err = text_message("no json here")
+            .parse_json::<Answer>()
+            .unwrap_err();
+        let Error::Validation(message) = err else {
+            panic!("expected a validation error");
+        };
+        assert!(message.contains("`no json here`"), "{message}");
+    }
+
+    #[test]
+    fn test_repair_json() {
+        assert_eq!(repair_json("[1, 2, ]"), "[1, 2]");
+        assert_eq!(repair_json(r#"{"a": {"b": "x\"#), r#"{"a": {"b": "x"}}"#);
+        assert_eq!(
+            repair_json(r#"{"a": "1,]", "b":"#),
+            r#"{"a": "1,]", "b":null}"#
+        );
+        assert_eq!(extract_json("x {\"a\": \"}\"} y }"), "{\"a\": \"}\"}");
+    }
+
+    #[test]
+    fn test_strip_code_fences() {
+        assert_eq!(strip_code_fences("```json\n{\"a\": 1}\n```"), "{\"a\": 1}");
+        assert_eq!(
+            strip_code_fences("  ```\nfn main() {}\n```\n"),
+            "fn main() {}"
+        );
+        assert_eq!(strip_code_fences("```{\"a\": 1}```"), "{\"a\": 1}");
+        let unopened = "{\"a\": 1}\n```";
+        assert_eq!(strip_code_fences(unopened), unopened);
+        let unclosed = "```json\n{\"a\": 1}";
+        assert_eq!(strip_code_fences(unclosed), unclosed);
+        let plain = "Use ```rust``` fences.\n";
+        assert_eq!(strip_code_fences(plain), plain);
+    }
 
     #[test]
     fn test_message_param_user() {
@@ -288,6 +778,14 @@ mod tests {
         assert!(json.contains(r#""content":"Hello""#));
     }
 
+    #[test]
+    fn test_message_param_eq() {
+        let param = MessageParam::user("Hello");
+        assert_eq!(param, MessageParam::user("Hello"));
+        assert_ne!(param, MessageParam::user("Goodbye"));
+        assert_ne!(param, MessageParam::assistant("Hello"));
+    }
+
     #[test]
     fn test_message_param_assistant() {
         let param = MessageParam::assistant("Hi there");
@@ -296,6 +794,39 @@ mod tests {
         assert!(json.contains(r#""role":"assistant""#));
     }
 
+    #[test]
+    fn test_message_param_media_helpers() {
+        assert_eq!(
+            serde_json::to_value(MessageParam::user_image(b"GIF89a", MediaType::Gif)).unwrap(),
+            serde_json::json!({"role": "user", "content": [{
+                "type": "image",
+                "source": {"type": "base64", "media_type": "image/gif", "data": "R0lGODlh"}
+            }]})
+        );
+        assert_eq!(
+            serde_json::to_value(MessageParam::user_image_url("https://example.com/a.png"))
+                .unwrap(),
+            serde_json::json!({"role": "user", "content": [{
+                "type": "image",
+                "source": {"type": "url", "url": "https://example.com/a.png"}
+            }]})
+        );
+        assert_eq!(
+            serde_json::to_value(MessageParam::user_image_file("file_1")).unwrap(),
+            serde_json::json!({"role": "user", "content": [{
+                "type": "image",
+                "source": {"type": "file", "file_id": "file_1"}
+            }]})
+        );
+        assert_eq!(
+            serde_json::to_value(MessageParam::user_document_pdf(b"%PDF")).unwrap(),
+            serde_json::json!({"role": "user", "content": [{
+                "type": "document",
+                "source": {"type": "base64", "media_type": "application/pdf", "data": "JVBERg=="}
+            }]})
+        );
+    }
+
     #[test]
     fn test_message_content_text() {
         let content = MessageContent::Text("hello".to_string());
@@ -362,4 +893,102 @@ mod tests {
         let param_json = serde_json::to_string(&param).unwrap();
         assert!(param_json.contains(r#""text":"Hello!""#));
     }
+
+    #[test]
+    fn test_message_to_param_with() {
+        let msg = Message {
+            content: serde_json::from_value(serde_json::json!([
+                {"type": "thinking", "thinking": "Search first.", "signature": "sig"},
+                {"type": "server_tool_use", "id": "srvtoolu_1", "name": "web_search", "input": {"query": "q"}},
+                {"type": "web_search_tool_result", "tool_use_id": "srvtoolu_1", "content": []},
+                {"type": "text", "text": "Found it."},
+                {"type": "tool_use", "id": "toolu_1", "name": "save", "input": {}}
+            ]))
+            .unwrap(),
+            ..text_message("")
+        };
+        let block_types = |param: MessageParam| -> Vec<String> {
+            let value = serde_json::to_value(param).unwrap();
+            value["content"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|block| block["type"].as_str().unwrap().to_string())
+                .collect()
+        };
+
+        assert_eq!(block_types(msg.to_param()).len(), 5);
+        assert_eq!(
+            block_types(msg.to_param_with(&ParamOptions::default().strip_server_tools(true))),
+            ["thinking", "text", "tool_use"]
+        );
+        assert_eq!(
+            block_types(msg.to_param_with(&ParamOptions::default().strip_thinking(true))),
+            [
+                "server_tool_use",
+                "web_search_tool_result",
+                "text",
+                "tool_use"
+            ]
+        );
+        assert_eq!(
+            block_types(msg.to_param_with(&ParamOptions::default().text_only(true))),
+            ["text"]
+        );
+    }
+
+    #[test]
+    fn test_normalize_merges_and_strips_text() {
+        let mut param = MessageParam::user_blocks(vec![
+            ContentBlockParam::Text(TextBlockParam::new("Hello")),
+            ContentBlockParam::Text(TextBlockParam::new("  ")),
+            ContentBlockParam::Text(TextBlockParam::new("world")),
+        ]);
+        param.normalize();
+        match &param.content {
+            MessageContent::Blocks(blocks) => {
+                assert_eq!(blocks.len(), 1);
+                match &blocks[0] {
+                    ContentBlockParam::Text(t) => assert_eq!(t.text, "Hello\nworld"),
+                    _ => panic!("Expected text block"),
+                }
+            }
+            _ => panic!("Expected blocks"),
+        }
+    }
+
+    #[test]
+    fn test_normalize_preserves_cache_breakpoints() {
+        let mut cached = TextBlockParam::new("cached prefix");
+        cached.cache_control = Some(crate::types::metadata::CacheControl::ephemeral());
+        let mut param = MessageParam::user_blocks(vec![
+            ContentBlockParam::Text(cached),
+            ContentBlockParam::Text(TextBlockParam::new("suffix")),
+        ]);
+        param.normalize();
+        match &param.content {
+            MessageContent::Blocks(blocks) => assert_eq!(blocks.len(), 2),
+            _ => panic!("Expected blocks"),
+        }
+    }
+
+    #[test]
+    fn test_normalize_with_limit() {
+        let mut param = MessageParam::user_blocks(vec![
+            ContentBlockParam::Text(TextBlockParam::new("a")),
+            ContentBlockParam::Text(TextBlockParam::new("b")),
+        ]);
+        assert!(param.normalize_with_limit(1).is_ok());
+
+        let mut cached = TextBlockParam::new("a");
+        cached.cache_control = Some(crate::types::metadata::CacheControl::ephemeral());
+        let mut param = MessageParam::user_blocks(vec![
+            ContentBlockParam::Text(cached),
+            ContentBlockParam::Text(TextBlockParam::new("b")),
+        ]);
+        assert!(matches!(
+            param.normalize_with_limit(1),
+            Err(crate::error::Error::Validation(_))
+        ));
+    }
 }
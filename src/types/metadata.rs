@@ -1,14 +1,14 @@
 use serde::{Deserialize, Serialize};
 
 /// Metadata attached to a message request.
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
 pub struct Metadata {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub user_id: Option<String>,
 }
 
 /// Cache control directive for content blocks.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct CacheControl {
     #[serde(rename = "type")]
     pub cache_type: String,
@@ -34,26 +34,44 @@ impl CacheControl {
     }
 }
 
-/// Service tier for request routing.
+/// Service tier for request routing, and the tier a response was served on.
+///
+/// Requests take `Auto` or `StandardOnly`; `Usage::service_tier` reports
+/// `Standard`, `Priority` or `Batch`. Unrecognized tiers deserialize to
+/// `ServiceTier::Other` with the raw value preserved.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[non_exhaustive]
 #[serde(rename_all = "snake_case")]
 pub enum ServiceTier {
+    /// Use Priority Tier capacity when available, falling back to standard.
     Auto,
+    /// Only use standard capacity.
     StandardOnly,
+    /// Served on standard capacity.
+    Standard,
+    /// Served on Priority Tier capacity.
+    Priority,
+    /// Served through the Message Batches API.
+    Batch,
+    #[serde(untagged)]
+    Other(String),
 }
 
 /// Output configuration for message responses.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
 pub struct OutputConfig {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub format: Option<OutputFormat>,
+    /// How much effort the model puts into its response. Requires the
+    /// `effort-2025-11-24` beta, which `MessageService` adds automatically.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub effort: Option<ReasoningEffort>,
 }
 
 /// Output format configuration.
 ///
 /// Supports plain JSON output or structured JSON schema output.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum OutputFormat {
     /// Plain JSON output: `{"type": "json"}`.
@@ -95,6 +113,47 @@ impl OutputFormat {
     pub fn text() -> Self {
         OutputFormat::Text
     }
+
+    /// Create a strict JSON schema output format from the schema of `T`.
+    ///
+    /// Every object in the schema is closed with `"additionalProperties": false`,
+    /// which strict mode requires.
+    #[cfg(feature = "schemars")]
+    pub fn from_schema<T: schemars::JsonSchema>() -> Self {
+        let mut schema = schemars::schema_for!(T).to_value();
+        if let Some(obj) = schema.as_object_mut() {
+            obj.remove("$schema");
+        }
+        close_objects(&mut schema);
+        let name: String = T::schema_name()
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '_' || c == '-' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        OutputFormat::json_schema(name, schema, Some(true))
+    }
+}
+
+/// Set `"additionalProperties": false` on every object schema that does not
+/// specify it.
+#[cfg(feature = "schemars")]
+fn close_objects(schema: &mut serde_json::Value) {
+    match schema {
+        serde_json::Value::Object(obj) => {
+            if obj.get("type").and_then(|t| t.as_str()) == Some("object") {
+                obj.entry("additionalProperties")
+                    .or_insert(serde_json::Value::Bool(false));
+            }
+            obj.values_mut().for_each(close_objects);
+        }
+        serde_json::Value::Array(items) => items.iter_mut().for_each(close_objects),
+        _ => {}
+    }
 }
 
 /// Reasoning effort level for controlling how much effort the model
@@ -109,8 +168,11 @@ pub enum ReasoningEffort {
     Max,
 }
 
+/// The API's name for [`ReasoningEffort`].
+pub type Effort = ReasoningEffort;
+
 /// Geographic region configuration for inference routing.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct InferenceGeo {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub region: Option<String>,
@@ -126,13 +188,13 @@ impl InferenceGeo {
 }
 
 /// Context management configuration.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ContextManagementConfig {
     pub strategies: Vec<ContextManagementStrategy>,
 }
 
 /// A context management strategy.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[non_exhaustive]
 #[serde(tag = "type")]
 pub enum ContextManagementStrategy {
@@ -193,6 +255,17 @@ mod tests {
         assert_eq!(standard_json, r#""standard_only""#);
     }
 
+    #[test]
+    fn test_service_tier_response_values() {
+        let priority: ServiceTier = serde_json::from_str(r#""priority""#).unwrap();
+        assert_eq!(priority, ServiceTier::Priority);
+        let batch: ServiceTier = serde_json::from_str(r#""batch""#).unwrap();
+        assert_eq!(batch, ServiceTier::Batch);
+        let other: ServiceTier = serde_json::from_str(r#""flex""#).unwrap();
+        assert_eq!(other, ServiceTier::Other("flex".to_string()));
+        assert_eq!(serde_json::to_string(&other).unwrap(), r#""flex""#);
+    }
+
     #[test]
     fn test_json_output_format() {
         let fmt = OutputFormat::json();
@@ -221,10 +294,43 @@ mod tests {
         }
     }
 
+    #[cfg(feature = "schemars")]
+    #[test]
+    fn test_output_format_from_schema() {
+        #[derive(schemars::JsonSchema)]
+        #[allow(dead_code)]
+        struct Answer {
+            value: u32,
+            sources: Vec<Source>,
+        }
+        #[derive(schemars::JsonSchema)]
+        #[allow(dead_code)]
+        struct Source {
+            url: String,
+        }
+
+        match OutputFormat::from_schema::<Answer>() {
+            OutputFormat::JsonSchema {
+                name,
+                schema,
+                strict,
+            } => {
+                assert_eq!(name, "Answer");
+                assert_eq!(strict, Some(true));
+                assert!(schema.get("$schema").is_none());
+                assert_eq!(schema["additionalProperties"], false);
+                assert_eq!(schema["$defs"]["Source"]["additionalProperties"], false);
+                assert_eq!(schema["required"], serde_json::json!(["value", "sources"]));
+            }
+            _ => panic!("Expected JsonSchema variant"),
+        }
+    }
+
     #[test]
     fn test_output_config_serialize() {
         let config = OutputConfig {
             format: Some(OutputFormat::json()),
+            ..Default::default()
         };
         let json = serde_json::to_string(&config).unwrap();
         assert_eq!(json, r#"{"format":{"type":"json"}}"#);
@@ -232,11 +338,21 @@ mod tests {
 
     #[test]
     fn test_output_config_serialize_none() {
-        let config = OutputConfig { format: None };
+        let config = OutputConfig::default();
         let json = serde_json::to_string(&config).unwrap();
         assert_eq!(json, r#"{}"#);
     }
 
+    #[test]
+    fn test_output_config_effort() {
+        let config = OutputConfig {
+            effort: Some(Effort::Medium),
+            ..Default::default()
+        };
+        let json = serde_json::to_string(&config).unwrap();
+        assert_eq!(json, r#"{"effort":"medium"}"#);
+    }
+
     #[test]
     fn test_reasoning_effort_roundtrip() {
         let efforts = vec![
@@ -1,5 +1,6 @@
 pub mod citation;
 pub mod common;
+pub mod container;
 pub mod content;
 pub mod document;
 pub mod image;
@@ -14,6 +15,7 @@ pub mod usage;
 
 pub use citation::*;
 pub use common::*;
+pub use container::*;
 pub use content::*;
 pub use document::*;
 pub use image::*;
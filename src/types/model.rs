@@ -70,9 +70,6 @@ impl<S: Into<String>> From<S> for Model {
 }
 
 impl Model {
-    /// Parse a model from a string, falling back to `Model::Other(s.to_string())`
-    /// for unknown model IDs.
-    ///
     /// Returns whether this model supports extended thinking.
     ///
     /// Models that don't support thinking will have thinking config stripped
@@ -110,6 +107,118 @@ impl Model {
         }
     }
 
+    /// The standard context window size in tokens.
+    ///
+    /// Returns `None` for `Model::Other`. See `supports_1m_context()` for models
+    /// that can opt into a 1M-token window.
+    pub fn context_window(&self) -> Option<u32> {
+        match self {
+            Model::Other(_) => None,
+            _ => Some(200_000),
+        }
+    }
+
+    /// Returns whether this model can use the 1M-token extended context window
+    /// (enabled with the `BETA_CONTEXT_1M_2025_08_07` beta).
+    pub fn supports_1m_context(&self) -> bool {
+        matches!(
+            self,
+            Model::ClaudeOpus4_6
+                | Model::ClaudeSonnet4_6
+                | Model::ClaudeSonnet4_5
+                | Model::ClaudeSonnet4_5_20250929
+                | Model::ClaudeSonnet4_0
+                | Model::ClaudeSonnet4_20250514
+                | Model::Claude4Sonnet20250514
+        )
+    }
+
+    /// The maximum value accepted for `max_tokens`.
+    ///
+    /// Returns `None` for `Model::Other`.
+    pub fn max_output_tokens(&self) -> Option<u32> {
+        match self {
+            Model::ClaudeOpus4_6 => Some(128_000),
+            Model::ClaudeSonnet4_6
+            | Model::ClaudeOpus4_5_20251101
+            | Model::ClaudeOpus4_5
+            | Model::ClaudeSonnet4_5
+            | Model::ClaudeSonnet4_5_20250929
+            | Model::ClaudeSonnet4_0
+            | Model::ClaudeSonnet4_20250514
+            | Model::Claude4Sonnet20250514
+            | Model::ClaudeHaiku4_5
+            | Model::ClaudeHaiku4_5_20251001
+            | Model::Claude3_7SonnetLatest
+            | Model::Claude3_7Sonnet20250219 => Some(64_000),
+            Model::ClaudeOpus4_1
+            | Model::ClaudeOpus4_1_20250805
+            | Model::ClaudeOpus4_0
+            | Model::ClaudeOpus4_20250514
+            | Model::Claude4Opus20250514 => Some(32_000),
+            Model::Claude3_5HaikuLatest | Model::Claude3_5Haiku20241022 => Some(8_192),
+            Model::Claude3OpusLatest | Model::Claude3Opus20240229 | Model::Claude3Haiku20240307 => {
+                Some(4_096)
+            }
+            Model::Other(_) => None,
+        }
+    }
+
+    /// Returns whether this model accepts image input.
+    ///
+    /// All known models do; unknown models are allowed optimistically.
+    pub fn supports_vision(&self) -> bool {
+        true
+    }
+
+    /// The model family (Opus, Sonnet or Haiku), derived from the model ID.
+    ///
+    /// Also works for `Model::Other` IDs that follow the usual naming scheme.
+    pub fn family(&self) -> Option<ModelFamily> {
+        let id = self.to_string();
+        if id.contains("opus") {
+            Some(ModelFamily::Opus)
+        } else if id.contains("sonnet") {
+            Some(ModelFamily::Sonnet)
+        } else if id.contains("haiku") {
+            Some(ModelFamily::Haiku)
+        } else {
+            None
+        }
+    }
+
+    /// The model generation, e.g. `4.5` for `claude-sonnet-4-5-20250929`.
+    ///
+    /// Parsed from the model ID, so it also works for `Model::Other` IDs that
+    /// follow the usual naming scheme. Date suffixes are ignored.
+    pub fn generation(&self) -> Option<ModelVersion> {
+        let id = self.to_string();
+        let mut numbers = id
+            .split('-')
+            .filter(|part| part.len() <= 2)
+            .filter_map(|part| part.parse::<u32>().ok());
+        let major = numbers.next()?;
+        let minor = numbers.next().unwrap_or(0);
+        Some(ModelVersion { major, minor })
+    }
+
+    /// Returns whether this model's generation is at least `version` (e.g. `"4"` or `"4.5"`).
+    ///
+    /// Returns `false` if either version cannot be determined.
+    ///
+    /// ```ignore
+    /// let ok = model.family() == Some(ModelFamily::Sonnet) && model.is_at_least("4");
+    /// ```
+    pub fn is_at_least(&self, version: &str) -> bool {
+        match (self.generation(), ModelVersion::parse(version)) {
+            (Some(current), Some(required)) => current >= required,
+            _ => false,
+        }
+    }
+
+    /// Parse a model from a string, falling back to `Model::Other(s.to_string())`
+    /// for unknown model IDs.
+    ///
     /// Short aliases are resolved before parsing:
     /// - `"sonnet"` → `"claude-sonnet-4-6"`
     /// - `"opus"`   → `"claude-opus-4-6"`
@@ -145,8 +254,44 @@ impl std::fmt::Display for Model {
     }
 }
 
+/// A model family.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ModelFamily {
+    Opus,
+    Sonnet,
+    Haiku,
+}
+
+/// A model generation such as `4.5`, ordered by major then minor version.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ModelVersion {
+    pub major: u32,
+    pub minor: u32,
+}
+
+impl ModelVersion {
+    /// Parse a version like `"4"` or `"4.5"`.
+    pub fn parse(s: &str) -> Option<Self> {
+        let (major, minor) = match s.split_once('.') {
+            Some((major, minor)) => (major, minor),
+            None => (s, "0"),
+        };
+        Some(ModelVersion {
+            major: major.trim().parse().ok()?,
+            minor: minor.trim().parse().ok()?,
+        })
+    }
+}
+
+impl std::fmt::Display for ModelVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}", self.major, self.minor)
+    }
+}
+
 /// Information about a model returned by the Models API.
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[cfg_attr(feature = "strict-serde", serde(deny_unknown_fields))]
 pub struct ModelInfo {
     pub id: String,
     #[serde(rename = "type")]
@@ -163,7 +308,8 @@ pub struct ModelInfo {
 }
 
 /// Model capability information.
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[cfg_attr(feature = "strict-serde", serde(deny_unknown_fields))]
 pub struct ModelCapabilities {
     #[serde(default)]
     pub batch: Option<CapabilitySupport>,
@@ -186,13 +332,15 @@ pub struct ModelCapabilities {
 }
 
 /// Whether a capability is supported.
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[cfg_attr(feature = "strict-serde", serde(deny_unknown_fields))]
 pub struct CapabilitySupport {
     pub supported: bool,
 }
 
 /// Thinking capability details.
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[cfg_attr(feature = "strict-serde", serde(deny_unknown_fields))]
 pub struct ThinkingCapability {
     pub supported: bool,
     #[serde(default)]
@@ -200,7 +348,8 @@ pub struct ThinkingCapability {
 }
 
 /// Context management capability details.
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[cfg_attr(feature = "strict-serde", serde(deny_unknown_fields))]
 pub struct ContextManagementCapability {
     #[serde(default)]
     pub clear_thinking_20251015: Option<bool>,
@@ -211,7 +360,8 @@ pub struct ContextManagementCapability {
 }
 
 /// Effort capability details.
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[cfg_attr(feature = "strict-serde", serde(deny_unknown_fields))]
 pub struct EffortCapability {
     #[serde(default)]
     pub supported: Vec<String>,
@@ -220,7 +370,8 @@ pub struct EffortCapability {
 /// A parsed model specification that may include option flags.
 ///
 /// Parses strings like `"sonnet"`, `"claude-sonnet-4-6"`, `"sonnet[1m]"`, `"opus[1m]"`.
-/// The `[1m]` suffix enables the 1M-token extended context window.
+/// The `[1m]` suffix requests the 1M-token extended context window, which
+/// `MessageCreateParamsBuilder::model_spec` turns into the matching beta.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ModelSpec {
     pub model: Model,
@@ -460,4 +611,85 @@ mod tests {
         assert_eq!(spec.model, Model::ClaudeOpus4_6);
         assert!(spec.extended_context);
     }
+
+    #[test]
+    fn test_context_window() {
+        assert_eq!(Model::ClaudeOpus4_6.context_window(), Some(200_000));
+        assert_eq!(Model::Claude3Haiku20240307.context_window(), Some(200_000));
+        assert_eq!(Model::Other("custom".to_string()).context_window(), None);
+    }
+
+    #[test]
+    fn test_supports_1m_context() {
+        assert!(Model::ClaudeSonnet4_5.supports_1m_context());
+        assert!(Model::ClaudeOpus4_6.supports_1m_context());
+        assert!(!Model::ClaudeHaiku4_5.supports_1m_context());
+        assert!(!Model::Other("custom".to_string()).supports_1m_context());
+    }
+
+    #[test]
+    fn test_max_output_tokens() {
+        assert_eq!(Model::ClaudeOpus4_6.max_output_tokens(), Some(128_000));
+        assert_eq!(Model::ClaudeSonnet4_5.max_output_tokens(), Some(64_000));
+        assert_eq!(Model::ClaudeOpus4_1.max_output_tokens(), Some(32_000));
+        assert_eq!(
+            Model::Claude3_5Haiku20241022.max_output_tokens(),
+            Some(8_192)
+        );
+        assert_eq!(Model::Claude3Opus20240229.max_output_tokens(), Some(4_096));
+        assert_eq!(Model::Other("custom".to_string()).max_output_tokens(), None);
+    }
+
+    #[test]
+    fn test_model_family() {
+        assert_eq!(Model::ClaudeOpus4_6.family(), Some(ModelFamily::Opus));
+        assert_eq!(
+            Model::Claude4Sonnet20250514.family(),
+            Some(ModelFamily::Sonnet)
+        );
+        assert_eq!(
+            Model::Claude3_5HaikuLatest.family(),
+            Some(ModelFamily::Haiku)
+        );
+        assert_eq!(
+            Model::Other("claude-sonnet-5".to_string()).family(),
+            Some(ModelFamily::Sonnet)
+        );
+        assert_eq!(Model::Other("custom".to_string()).family(), None);
+    }
+
+    #[test]
+    fn test_model_generation() {
+        let v = |major, minor| Some(ModelVersion { major, minor });
+        assert_eq!(Model::ClaudeOpus4_6.generation(), v(4, 6));
+        assert_eq!(Model::ClaudeSonnet4_5_20250929.generation(), v(4, 5));
+        assert_eq!(Model::ClaudeOpus4_20250514.generation(), v(4, 0));
+        assert_eq!(Model::Claude4Opus20250514.generation(), v(4, 0));
+        assert_eq!(Model::Claude3_7SonnetLatest.generation(), v(3, 7));
+        assert_eq!(Model::Claude3Haiku20240307.generation(), v(3, 0));
+        assert_eq!(Model::Other("custom".to_string()).generation(), None);
+    }
+
+    #[test]
+    fn test_model_is_at_least() {
+        assert!(Model::ClaudeSonnet4_5.is_at_least("4"));
+        assert!(Model::ClaudeSonnet4_5.is_at_least("4.5"));
+        assert!(!Model::ClaudeSonnet4_5.is_at_least("4.6"));
+        assert!(!Model::Claude3_7Sonnet20250219.is_at_least("4"));
+        assert!(!Model::ClaudeOpus4_6.is_at_least("not a version"));
+    }
+
+    #[test]
+    fn test_model_version_parse() {
+        assert_eq!(
+            ModelVersion::parse("4.5"),
+            Some(ModelVersion { major: 4, minor: 5 })
+        );
+        assert_eq!(
+            ModelVersion::parse("4"),
+            Some(ModelVersion { major: 4, minor: 0 })
+        );
+        assert_eq!(ModelVersion::parse("x"), None);
+        assert_eq!(ModelVersion { major: 4, minor: 5 }.to_string(), "4.5");
+    }
 }
@@ -1,7 +1,8 @@
 use serde::Deserialize;
 
 /// A paginated response from list endpoints.
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[cfg_attr(feature = "strict-serde", serde(deny_unknown_fields))]
 pub struct Page<T> {
     pub data: Vec<T>,
     pub has_more: bool,
@@ -11,6 +12,28 @@ pub struct Page<T> {
     pub last_id: Option<String>,
 }
 
+impl<T> Page<T> {
+    /// Whether more items follow this page.
+    pub fn has_more(&self) -> bool {
+        self.has_more
+    }
+
+    /// The id of the first item on this page, usable as a `before_id` cursor.
+    pub fn first_id(&self) -> Option<&str> {
+        self.first_id.as_deref()
+    }
+
+    /// The id of the last item on this page, usable as an `after_id` cursor.
+    pub fn last_id(&self) -> Option<&str> {
+        self.last_id.as_deref()
+    }
+
+    /// The `after_id` cursor for the next page, or `None` on the last page.
+    pub fn next_cursor(&self) -> Option<&str> {
+        self.last_id().filter(|_| self.has_more)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -30,6 +53,25 @@ mod tests {
         assert_eq!(page.last_id.as_deref(), Some("item_003"));
     }
 
+    #[test]
+    fn test_cursor_accessors() {
+        let json = r#"{"data": [1, 2], "has_more": true, "first_id": "a", "last_id": "b"}"#;
+        let page: Page<u32> = serde_json::from_str(json).unwrap();
+        assert!(page.has_more());
+        assert_eq!(page.first_id(), Some("a"));
+        assert_eq!(page.last_id(), Some("b"));
+        assert_eq!(page.next_cursor(), Some("b"));
+    }
+
+    #[test]
+    fn test_next_cursor_on_last_page() {
+        let json = r#"{"data": [1], "has_more": false, "first_id": "a", "last_id": "a"}"#;
+        let page: Page<u32> = serde_json::from_str(json).unwrap();
+        assert!(!page.has_more());
+        assert_eq!(page.last_id(), Some("a"));
+        assert_eq!(page.next_cursor(), None);
+    }
+
     #[test]
     fn test_deserialize_page_minimal() {
         let json = r#"{
@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
 
 /// User location for web search queries.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct UserLocation {
     #[serde(rename = "type")]
     pub location_type: String,
@@ -29,6 +29,30 @@ impl UserLocation {
             timezone: None,
         }
     }
+
+    /// Set the city.
+    pub fn city(mut self, city: impl Into<String>) -> Self {
+        self.city = Some(city.into());
+        self
+    }
+
+    /// Set the region, such as a state or province.
+    pub fn region(mut self, region: impl Into<String>) -> Self {
+        self.region = Some(region.into());
+        self
+    }
+
+    /// Set the ISO 3166-1 alpha-2 country code.
+    pub fn country(mut self, country: impl Into<String>) -> Self {
+        self.country = Some(country.into());
+        self
+    }
+
+    /// Set the IANA time zone, e.g. `America/Los_Angeles`.
+    pub fn timezone(mut self, timezone: impl Into<String>) -> Self {
+        self.timezone = Some(timezone.into());
+        self
+    }
 }
 
 #[cfg(test)]
@@ -57,6 +81,16 @@ mod tests {
         assert_eq!(json, r#"{"type":"approximate"}"#);
     }
 
+    #[test]
+    fn test_user_location_setters() {
+        let loc = UserLocation::approximate().city("Paris").country("FR");
+        let json = serde_json::to_string(&loc).unwrap();
+        assert_eq!(
+            json,
+            r#"{"type":"approximate","city":"Paris","country":"FR"}"#
+        );
+    }
+
     #[test]
     fn test_web_search_user_location_deserialize() {
         let json = r#"{"type":"approximate","city":"NYC","country":"US"}"#;
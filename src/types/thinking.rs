@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
 
 /// Configuration for extended thinking.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum ThinkingConfig {
     /// Enable extended thinking with a token budget.
@@ -19,6 +19,26 @@ pub enum ThinkingConfig {
     },
 }
 
+impl ThinkingConfig {
+    /// Extended thinking with a budget of `budget_tokens`.
+    ///
+    /// To let the model think between tool calls as well, use
+    /// `MessageCreateParamsBuilder::interleaved_thinking`, which also enables
+    /// the beta that requires.
+    pub fn enabled(budget_tokens: u32) -> Self {
+        Self::Enabled {
+            budget_tokens,
+            display: None,
+        }
+    }
+
+    /// Adaptive thinking, where the model decides how much to think. Pair it
+    /// with an `effort` on the request to steer the thinking depth.
+    pub fn adaptive() -> Self {
+        Self::Adaptive { display: None }
+    }
+}
+
 /// How thinking blocks should be displayed in the response.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[non_exhaustive]
@@ -44,6 +64,14 @@ mod tests {
         assert_eq!(json, r#"{"type":"enabled","budget_tokens":10000}"#);
     }
 
+    #[test]
+    fn test_constructors() {
+        let json = serde_json::to_string(&ThinkingConfig::enabled(2048)).unwrap();
+        assert_eq!(json, r#"{"type":"enabled","budget_tokens":2048}"#);
+        let json = serde_json::to_string(&ThinkingConfig::adaptive()).unwrap();
+        assert_eq!(json, r#"{"type":"adaptive"}"#);
+    }
+
     #[test]
     fn test_serialize_disabled() {
         let config = ThinkingConfig::Disabled;
@@ -9,7 +9,7 @@ use super::search::UserLocation;
 ///
 /// Serialization uses `#[serde(untagged)]`. Deserialization uses a custom
 /// implementation that inspects the `type` field to dispatch to the correct variant.
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 #[non_exhaustive]
 #[serde(untagged)]
 pub enum ToolDefinition {
@@ -37,6 +37,47 @@ pub enum ToolDefinition {
     Custom(Tool),
 }
 
+impl ToolDefinition {
+    /// Reject settings the API refuses, before sending them: a web search or
+    /// web fetch tool may restrict domains with an allow list or a block list,
+    /// but not both.
+    pub(crate) fn validate(&self) -> Result<(), crate::error::Error> {
+        let (name, allowed, blocked) = match self {
+            ToolDefinition::WebSearch(t) => (&t.name, &t.allowed_domains, &t.blocked_domains),
+            ToolDefinition::WebSearch20260209(t) => {
+                (&t.name, &t.allowed_domains, &t.blocked_domains)
+            }
+            ToolDefinition::WebFetch20250910(t) => {
+                (&t.name, &t.allowed_domains, &t.blocked_domains)
+            }
+            ToolDefinition::WebFetch20260209(t) => {
+                (&t.name, &t.allowed_domains, &t.blocked_domains)
+            }
+            ToolDefinition::WebFetch20260309(t) => {
+                (&t.name, &t.allowed_domains, &t.blocked_domains)
+            }
+            _ => return Ok(()),
+        };
+        if allowed.is_some() && blocked.is_some() {
+            return Err(crate::error::Error::Validation(format!(
+                "tool {name} sets both allowed_domains and blocked_domains"
+            )));
+        }
+        Ok(())
+    }
+
+    /// Check a `tool_use` input against this tool's input schema.
+    ///
+    /// Only custom tools carry a schema; input for any other tool is accepted.
+    #[cfg(feature = "jsonschema")]
+    pub fn validate_input(&self, input: &serde_json::Value) -> Result<(), crate::error::Error> {
+        match self {
+            ToolDefinition::Custom(tool) => tool.input_schema.validate(input),
+            _ => Ok(()),
+        }
+    }
+}
+
 impl<'de> Deserialize<'de> for ToolDefinition {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
@@ -136,12 +177,13 @@ impl<'de> Deserialize<'de> for ToolDefinition {
                     serde_json::from_value(value).map_err(serde::de::Error::custom)?;
                 Ok(ToolDefinition::Memory(tool))
             }
-            Some("tool_search_bm25_20251119") => {
+            // The unprefixed names were emitted by earlier versions of this crate.
+            Some("tool_search_tool_bm25_20251119" | "tool_search_bm25_20251119") => {
                 let tool: ToolSearchBm25Tool =
                     serde_json::from_value(value).map_err(serde::de::Error::custom)?;
                 Ok(ToolDefinition::ToolSearchBm25(tool))
             }
-            Some("tool_search_regex_20251119") => {
+            Some("tool_search_tool_regex_20251119" | "tool_search_regex_20251119") => {
                 let tool: ToolSearchRegexTool =
                     serde_json::from_value(value).map_err(serde::de::Error::custom)?;
                 Ok(ToolDefinition::ToolSearchRegex(tool))
@@ -160,11 +202,19 @@ impl<'de> Deserialize<'de> for ToolDefinition {
     }
 }
 
+impl From<Tool> for ToolDefinition {
+    fn from(tool: Tool) -> Self {
+        ToolDefinition::Custom(tool)
+    }
+}
+
 /// A custom tool definition.
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize, bon::Builder)]
 pub struct Tool {
+    #[builder(into)]
     pub name: String,
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(into)]
     pub description: Option<String>,
     pub input_schema: ToolInputSchema,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -173,12 +223,17 @@ pub struct Tool {
     pub strict: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub eager_input_streaming: Option<bool>,
+    /// Keep the tool out of the context until a tool search tool returns a
+    /// reference to it. Requires a tool search tool in the same request.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub defer_loading: Option<bool>,
     #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    #[builder(into)]
     pub tool_type: Option<String>,
 }
 
 /// The JSON Schema for a tool's input.
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
 pub struct ToolInputSchema {
     #[serde(rename = "$schema", skip_serializing_if = "Option::is_none")]
     pub schema: Option<String>,
@@ -195,8 +250,90 @@ pub struct ToolInputSchema {
     pub additional_properties: Option<serde_json::Value>,
 }
 
+impl ToolInputSchema {
+    /// An object schema with no properties, to be extended with `property`
+    /// and `required`.
+    ///
+    /// ```ignore
+    /// let schema = ToolInputSchema::object()
+    ///     .property("location", json!({"type": "string"}))
+    ///     .required("location");
+    /// ```
+    pub fn object() -> Self {
+        Self {
+            schema_type: "object".to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Add a property described by `schema`, replacing any property with the
+    /// same name.
+    pub fn property(mut self, name: impl Into<String>, schema: serde_json::Value) -> Self {
+        if !matches!(self.properties, Some(serde_json::Value::Object(_))) {
+            self.properties = Some(serde_json::Value::Object(Default::default()));
+        }
+        if let Some(serde_json::Value::Object(properties)) = &mut self.properties {
+            properties.insert(name.into(), schema);
+        }
+        self
+    }
+
+    /// Mark a property as required.
+    pub fn required(mut self, name: impl Into<String>) -> Self {
+        let name = name.into();
+        let required = self.required.get_or_insert_with(Vec::new);
+        if !required.contains(&name) {
+            required.push(name);
+        }
+        self
+    }
+}
+
+#[cfg(feature = "jsonschema")]
+impl ToolInputSchema {
+    /// Check `input` against the schema, failing with `Error::Validation`
+    /// listing every violation.
+    pub fn validate(&self, input: &serde_json::Value) -> Result<(), crate::error::Error> {
+        let schema = serde_json::to_value(self)?;
+        let validator = jsonschema::validator_for(&schema).map_err(|e| {
+            crate::error::Error::Validation(format!("invalid tool input schema: {e}"))
+        })?;
+        let violations: Vec<String> = validator
+            .iter_errors(input)
+            .map(|e| match e.instance_path().to_string() {
+                path if path.is_empty() => e.to_string(),
+                path => format!("{path}: {e}"),
+            })
+            .collect();
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(crate::error::Error::Validation(format!(
+                "tool input does not match its schema: {}",
+                violations.join("; ")
+            )))
+        }
+    }
+}
+
+impl TryFrom<serde_json::Value> for ToolInputSchema {
+    type Error = crate::error::Error;
+
+    /// Parse a JSON Schema document, which must describe an object.
+    fn try_from(value: serde_json::Value) -> Result<Self, Self::Error> {
+        let schema: Self = serde_json::from_value(value)?;
+        if schema.schema_type != "object" {
+            return Err(crate::error::Error::Validation(format!(
+                "tool input schema must have type \"object\", got \"{}\"",
+                schema.schema_type
+            )));
+        }
+        Ok(schema)
+    }
+}
+
 /// A Bash server tool.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct BashTool {
     #[serde(rename = "type")]
     pub tool_type: String,
@@ -223,7 +360,7 @@ impl Default for BashTool {
 }
 
 /// A text editor server tool (2025-01-24 version).
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct TextEditorTool {
     #[serde(rename = "type")]
     pub tool_type: String,
@@ -250,7 +387,7 @@ impl Default for TextEditorTool {
 }
 
 /// A text editor server tool (2025-04-29 version).
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct TextEditorTool429 {
     #[serde(rename = "type")]
     pub tool_type: String,
@@ -277,7 +414,7 @@ impl Default for TextEditorTool429 {
 }
 
 /// A text editor server tool (2025-07-28 version).
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct TextEditorTool728 {
     #[serde(rename = "type")]
     pub tool_type: String,
@@ -304,10 +441,17 @@ impl Default for TextEditorTool728 {
 }
 
 /// A web search server tool.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, bon::Builder)]
 pub struct WebSearchTool {
+    /// Set in the builder with `.user_location(...)` or field by field with
+    /// `.city(...)`, `.region(...)`, `.country(...)` and `.timezone(...)`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(field)]
+    pub user_location: Option<UserLocation>,
     #[serde(rename = "type")]
+    #[builder(default = "web_search_20250305".to_string(), into)]
     pub tool_type: String,
+    #[builder(default = "web_search".to_string(), into)]
     pub name: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub max_uses: Option<u32>,
@@ -316,8 +460,6 @@ pub struct WebSearchTool {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub blocked_domains: Option<Vec<String>>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub user_location: Option<UserLocation>,
-    #[serde(skip_serializing_if = "Option::is_none")]
     pub cache_control: Option<CacheControl>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub strict: Option<bool>,
@@ -326,16 +468,7 @@ pub struct WebSearchTool {
 impl WebSearchTool {
     /// Create a new web search tool with defaults.
     pub fn new() -> Self {
-        Self {
-            tool_type: "web_search_20250305".to_string(),
-            name: "web_search".to_string(),
-            max_uses: None,
-            allowed_domains: None,
-            blocked_domains: None,
-            user_location: None,
-            cache_control: None,
-            strict: None,
-        }
+        Self::builder().build()
     }
 }
 
@@ -345,11 +478,55 @@ impl Default for WebSearchTool {
     }
 }
 
+impl<S: web_search_tool_builder::State> WebSearchToolBuilder<S> {
+    /// Set the approximate user location, replacing any location fields set so far.
+    pub fn user_location(mut self, location: UserLocation) -> Self {
+        self.user_location = Some(location);
+        self
+    }
+
+    /// Set the city of the user location.
+    pub fn city(mut self, city: impl Into<String>) -> Self {
+        self.location().city = Some(city.into());
+        self
+    }
+
+    /// Set the region, such as a state or province, of the user location.
+    pub fn region(mut self, region: impl Into<String>) -> Self {
+        self.location().region = Some(region.into());
+        self
+    }
+
+    /// Set the ISO 3166-1 alpha-2 country code of the user location.
+    pub fn country(mut self, country: impl Into<String>) -> Self {
+        self.location().country = Some(country.into());
+        self
+    }
+
+    /// Set the IANA time zone of the user location, e.g. `America/Los_Angeles`.
+    pub fn timezone(mut self, timezone: impl Into<String>) -> Self {
+        self.location().timezone = Some(timezone.into());
+        self
+    }
+
+    fn location(&mut self) -> &mut UserLocation {
+        self.user_location
+            .get_or_insert_with(UserLocation::approximate)
+    }
+}
+
 /// A web search server tool (2026-02-09 version).
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, bon::Builder)]
 pub struct WebSearchTool20260209 {
+    /// Set in the builder with `.user_location(...)` or field by field with
+    /// `.city(...)`, `.region(...)`, `.country(...)` and `.timezone(...)`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(field)]
+    pub user_location: Option<UserLocation>,
     #[serde(rename = "type")]
+    #[builder(default = "web_search_20260209".to_string(), into)]
     pub tool_type: String,
+    #[builder(default = "web_search".to_string(), into)]
     pub name: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub max_uses: Option<u32>,
@@ -358,8 +535,6 @@ pub struct WebSearchTool20260209 {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub blocked_domains: Option<Vec<String>>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub user_location: Option<UserLocation>,
-    #[serde(skip_serializing_if = "Option::is_none")]
     pub cache_control: Option<CacheControl>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub strict: Option<bool>,
@@ -372,18 +547,7 @@ pub struct WebSearchTool20260209 {
 impl WebSearchTool20260209 {
     /// Create a new web search tool (2026-02-09) with defaults.
     pub fn new() -> Self {
-        Self {
-            tool_type: "web_search_20260209".to_string(),
-            name: "web_search".to_string(),
-            max_uses: None,
-            allowed_domains: None,
-            blocked_domains: None,
-            user_location: None,
-            cache_control: None,
-            strict: None,
-            allowed_callers: None,
-            defer_loading: None,
-        }
+        Self::builder().build()
     }
 }
 
@@ -393,11 +557,50 @@ impl Default for WebSearchTool20260209 {
     }
 }
 
+impl<S: web_search_tool20260209_builder::State> WebSearchTool20260209Builder<S> {
+    /// Set the approximate user location, replacing any location fields set so far.
+    pub fn user_location(mut self, location: UserLocation) -> Self {
+        self.user_location = Some(location);
+        self
+    }
+
+    /// Set the city of the user location.
+    pub fn city(mut self, city: impl Into<String>) -> Self {
+        self.location().city = Some(city.into());
+        self
+    }
+
+    /// Set the region, such as a state or province, of the user location.
+    pub fn region(mut self, region: impl Into<String>) -> Self {
+        self.location().region = Some(region.into());
+        self
+    }
+
+    /// Set the ISO 3166-1 alpha-2 country code of the user location.
+    pub fn country(mut self, country: impl Into<String>) -> Self {
+        self.location().country = Some(country.into());
+        self
+    }
+
+    /// Set the IANA time zone of the user location, e.g. `America/Los_Angeles`.
+    pub fn timezone(mut self, timezone: impl Into<String>) -> Self {
+        self.location().timezone = Some(timezone.into());
+        self
+    }
+
+    fn location(&mut self) -> &mut UserLocation {
+        self.user_location
+            .get_or_insert_with(UserLocation::approximate)
+    }
+}
+
 /// A web fetch server tool (2025-09-10 version).
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, bon::Builder)]
 pub struct WebFetchTool20250910 {
     #[serde(rename = "type")]
+    #[builder(default = "web_fetch_20250910".to_string(), into)]
     pub tool_type: String,
+    #[builder(default = "web_fetch".to_string(), into)]
     pub name: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub max_content_tokens: Option<u64>,
@@ -422,19 +625,7 @@ pub struct WebFetchTool20250910 {
 impl WebFetchTool20250910 {
     /// Create a new web fetch tool (2025-09-10) with defaults.
     pub fn new() -> Self {
-        Self {
-            tool_type: "web_fetch_20250910".to_string(),
-            name: "web_fetch".to_string(),
-            max_content_tokens: None,
-            max_uses: None,
-            defer_loading: None,
-            strict: None,
-            allowed_domains: None,
-            blocked_domains: None,
-            allowed_callers: None,
-            citations: None,
-            cache_control: None,
-        }
+        Self::builder().build()
     }
 }
 
@@ -445,10 +636,12 @@ impl Default for WebFetchTool20250910 {
 }
 
 /// A web fetch server tool (2026-02-09 version).
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, bon::Builder)]
 pub struct WebFetchTool20260209 {
     #[serde(rename = "type")]
+    #[builder(default = "web_fetch_20260209".to_string(), into)]
     pub tool_type: String,
+    #[builder(default = "web_fetch".to_string(), into)]
     pub name: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub max_content_tokens: Option<u64>,
@@ -473,19 +666,7 @@ pub struct WebFetchTool20260209 {
 impl WebFetchTool20260209 {
     /// Create a new web fetch tool (2026-02-09) with defaults.
     pub fn new() -> Self {
-        Self {
-            tool_type: "web_fetch_20260209".to_string(),
-            name: "web_fetch".to_string(),
-            max_content_tokens: None,
-            max_uses: None,
-            defer_loading: None,
-            strict: None,
-            allowed_domains: None,
-            blocked_domains: None,
-            allowed_callers: None,
-            citations: None,
-            cache_control: None,
-        }
+        Self::builder().build()
     }
 }
 
@@ -496,7 +677,7 @@ impl Default for WebFetchTool20260209 {
 }
 
 /// A code execution server tool.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct CodeExecutionTool {
     #[serde(rename = "type")]
     pub tool_type: String,
@@ -526,7 +707,7 @@ impl Default for CodeExecutionTool {
 }
 
 /// A Bash server tool (2024-10-22 version).
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct BashTool20241022 {
     #[serde(rename = "type")]
     pub tool_type: String,
@@ -553,7 +734,7 @@ impl Default for BashTool20241022 {
 }
 
 /// A text editor server tool (2024-10-22 version).
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct TextEditorTool20241022 {
     #[serde(rename = "type")]
     pub tool_type: String,
@@ -580,10 +761,12 @@ impl Default for TextEditorTool20241022 {
 }
 
 /// A web fetch server tool (2026-03-09 version).
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, bon::Builder)]
 pub struct WebFetchTool20260309 {
     #[serde(rename = "type")]
+    #[builder(default = "web_fetch_20260309".to_string(), into)]
     pub tool_type: String,
+    #[builder(default = "web_fetch".to_string(), into)]
     pub name: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub max_content_tokens: Option<u64>,
@@ -608,19 +791,7 @@ pub struct WebFetchTool20260309 {
 impl WebFetchTool20260309 {
     /// Create a new web fetch tool (2026-03-09) with defaults.
     pub fn new() -> Self {
-        Self {
-            tool_type: "web_fetch_20260309".to_string(),
-            name: "web_fetch".to_string(),
-            max_content_tokens: None,
-            max_uses: None,
-            defer_loading: None,
-            strict: None,
-            allowed_domains: None,
-            blocked_domains: None,
-            allowed_callers: None,
-            citations: None,
-            cache_control: None,
-        }
+        Self::builder().build()
     }
 }
 
@@ -631,7 +802,7 @@ impl Default for WebFetchTool20260309 {
 }
 
 /// A code execution server tool (2025-05-22 version).
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct CodeExecutionTool20250522 {
     #[serde(rename = "type")]
     pub tool_type: String,
@@ -661,7 +832,7 @@ impl Default for CodeExecutionTool20250522 {
 }
 
 /// A code execution server tool (2026-01-20 version).
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct CodeExecutionTool20260120 {
     #[serde(rename = "type")]
     pub tool_type: String,
@@ -691,7 +862,7 @@ impl Default for CodeExecutionTool20260120 {
 }
 
 /// A computer use tool (2024-10-22 version).
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ComputerTool20241022 {
     #[serde(rename = "type")]
     pub tool_type: String,
@@ -719,7 +890,7 @@ impl ComputerTool20241022 {
 }
 
 /// A computer use tool (2025-01-24 version).
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ComputerTool20250124 {
     #[serde(rename = "type")]
     pub tool_type: String,
@@ -747,7 +918,7 @@ impl ComputerTool20250124 {
 }
 
 /// A computer use tool (2025-11-24 version).
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ComputerTool20251124 {
     #[serde(rename = "type")]
     pub tool_type: String,
@@ -775,7 +946,7 @@ impl ComputerTool20251124 {
 }
 
 /// A memory server tool.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct MemoryTool {
     #[serde(rename = "type")]
     pub tool_type: String,
@@ -802,7 +973,10 @@ impl Default for MemoryTool {
 }
 
 /// A tool search tool definition (BM25 variant).
-#[derive(Debug, Clone, Serialize, Deserialize)]
+///
+/// Lets the model search tools marked `defer_loading` with natural language
+/// queries; matching tools are loaded into context as they are found.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ToolSearchBm25Tool {
     #[serde(rename = "type")]
     pub tool_type: String,
@@ -817,8 +991,8 @@ impl ToolSearchBm25Tool {
     /// Create a new BM25 tool search tool definition.
     pub fn new() -> Self {
         Self {
-            tool_type: "tool_search_bm25_20251119".to_string(),
-            name: "tool_search".to_string(),
+            tool_type: "tool_search_tool_bm25_20251119".to_string(),
+            name: "tool_search_tool_bm25".to_string(),
             max_results: None,
             cache_control: None,
         }
@@ -832,7 +1006,10 @@ impl Default for ToolSearchBm25Tool {
 }
 
 /// A tool search tool definition (Regex variant).
-#[derive(Debug, Clone, Serialize, Deserialize)]
+///
+/// Like [`ToolSearchBm25Tool`], but the model searches deferred tools with
+/// regular expressions.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ToolSearchRegexTool {
     #[serde(rename = "type")]
     pub tool_type: String,
@@ -847,8 +1024,8 @@ impl ToolSearchRegexTool {
     /// Create a new Regex tool search tool definition.
     pub fn new() -> Self {
         Self {
-            tool_type: "tool_search_regex_20251119".to_string(),
-            name: "tool_search".to_string(),
+            tool_type: "tool_search_tool_regex_20251119".to_string(),
+            name: "tool_search_tool_regex".to_string(),
             max_results: None,
             cache_control: None,
         }
@@ -862,7 +1039,7 @@ impl Default for ToolSearchRegexTool {
 }
 
 /// Tool configuration for MCP toolsets.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct McpToolConfiguration {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub allowed_tools: Option<Vec<String>>,
@@ -871,7 +1048,7 @@ pub struct McpToolConfiguration {
 }
 
 /// An MCP (Model Context Protocol) toolset definition.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct McpToolset {
     #[serde(rename = "type")]
     pub tool_type: String,
@@ -900,7 +1077,7 @@ impl McpToolset {
 }
 
 /// How the model should choose which tool to use.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[non_exhaustive]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum ToolChoice {
@@ -924,6 +1101,92 @@ pub enum ToolChoice {
 mod tests {
     use super::*;
 
+    #[cfg(feature = "jsonschema")]
+    #[test]
+    fn test_validate_input() {
+        let tool = ToolDefinition::Custom(Tool {
+            name: "get_weather".to_string(),
+            input_schema: ToolInputSchema::object()
+                .property("location", serde_json::json!({"type": "string"}))
+                .property("days", serde_json::json!({"type": "integer", "minimum": 1}))
+                .required("location"),
+            ..Default::default()
+        });
+        assert!(
+            tool.validate_input(&serde_json::json!({"location": "Paris", "days": 2}))
+                .is_ok()
+        );
+
+        let err = tool
+            .validate_input(&serde_json::json!({"days": 0}))
+            .unwrap_err();
+        let crate::error::Error::Validation(message) = err else {
+            panic!("expected a validation error");
+        };
+        assert!(
+            message.contains("\"location\" is a required property"),
+            "{message}"
+        );
+        assert!(message.contains("/days: "), "{message}");
+
+        let server_tool = ToolDefinition::WebSearch(WebSearchTool::new());
+        assert!(server_tool.validate_input(&serde_json::json!(null)).is_ok());
+    }
+
+    #[test]
+    fn test_input_schema_constructors() {
+        let schema = ToolInputSchema::object()
+            .property("location", serde_json::json!({"type": "string"}))
+            .property("unit", serde_json::json!({"enum": ["c", "f"]}))
+            .required("location")
+            .required("location");
+        assert_eq!(
+            serde_json::to_value(&schema).unwrap(),
+            serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "location": {"type": "string"},
+                    "unit": {"enum": ["c", "f"]}
+                },
+                "required": ["location"]
+            })
+        );
+
+        let parsed = ToolInputSchema::try_from(serde_json::to_value(&schema).unwrap()).unwrap();
+        assert_eq!(parsed, schema);
+        let err = ToolInputSchema::try_from(serde_json::json!({"type": "string"})).unwrap_err();
+        assert!(matches!(err, crate::error::Error::Validation(_)));
+        assert!(ToolInputSchema::try_from(serde_json::json!([])).is_err());
+    }
+
+    #[test]
+    fn test_server_tool_builders() {
+        let search = WebSearchTool::builder()
+            .max_uses(3)
+            .allowed_domains(vec!["docs.rs".to_string()])
+            .build();
+        assert_eq!(
+            serde_json::to_value(&search).unwrap(),
+            serde_json::json!({
+                "type": "web_search_20250305",
+                "name": "web_search",
+                "max_uses": 3,
+                "allowed_domains": ["docs.rs"]
+            })
+        );
+        assert_eq!(
+            WebFetchTool20260209::builder().build(),
+            WebFetchTool20260209::new()
+        );
+        assert_eq!(
+            WebFetchTool20260209::builder()
+                .name("fetch")
+                .build()
+                .tool_type,
+            "web_fetch_20260209"
+        );
+    }
+
     #[test]
     fn test_tool_choice_auto() {
         let choice = ToolChoice::Auto {
@@ -1089,6 +1352,38 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_web_search_location_builder() {
+        let search = WebSearchTool::builder()
+            .city("San Francisco")
+            .timezone("America/Los_Angeles")
+            .max_uses(5)
+            .build();
+        assert_eq!(
+            serde_json::to_value(&search).unwrap(),
+            serde_json::json!({
+                "type": "web_search_20250305",
+                "name": "web_search",
+                "max_uses": 5,
+                "user_location": {
+                    "type": "approximate",
+                    "city": "San Francisco",
+                    "timezone": "America/Los_Angeles"
+                }
+            })
+        );
+        assert!(ToolDefinition::WebSearch(search).validate().is_ok());
+
+        let both = WebSearchTool20260209::builder()
+            .allowed_domains(vec!["docs.rs".to_string()])
+            .blocked_domains(vec!["example.com".to_string()])
+            .build();
+        assert!(matches!(
+            ToolDefinition::WebSearch20260209(both).validate(),
+            Err(crate::error::Error::Validation(_))
+        ));
+    }
+
     #[test]
     fn test_web_search_tool_serialize() {
         let tool = ToolDefinition::WebSearch(WebSearchTool {
@@ -1248,12 +1543,37 @@ mod tests {
             ..ToolSearchBm25Tool::new()
         });
         let json = serde_json::to_string(&tool).unwrap();
-        assert!(json.contains(r#""type":"tool_search_bm25_20251119""#));
+        assert!(json.contains(r#""type":"tool_search_tool_bm25_20251119""#));
         assert!(json.contains(r#""max_results":10"#));
         let roundtrip: ToolDefinition = serde_json::from_str(&json).unwrap();
         assert!(matches!(roundtrip, ToolDefinition::ToolSearchBm25(_)));
     }
 
+    #[test]
+    fn test_deferred_tool_catalog() {
+        let tools = vec![
+            ToolDefinition::ToolSearchRegex(ToolSearchRegexTool::new()),
+            ToolDefinition::Custom(Tool {
+                name: "get_invoice".to_string(),
+                input_schema: ToolInputSchema {
+                    schema_type: "object".to_string(),
+                    ..Default::default()
+                },
+                defer_loading: Some(true),
+                ..Default::default()
+            }),
+        ];
+        let json = serde_json::to_value(&tools).unwrap();
+        assert_eq!(json[0]["type"], "tool_search_tool_regex_20251119");
+        assert_eq!(json[0]["name"], "tool_search_tool_regex");
+        assert_eq!(json[1]["defer_loading"], true);
+
+        let legacy: ToolDefinition =
+            serde_json::from_str(r#"{"type":"tool_search_regex_20251119","name":"tool_search"}"#)
+                .unwrap();
+        assert!(matches!(legacy, ToolDefinition::ToolSearchRegex(_)));
+    }
+
     #[test]
     fn test_mcp_toolset_roundtrip() {
         let tool =
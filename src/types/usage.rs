@@ -1,7 +1,9 @@
 use serde::{Deserialize, Serialize};
 
+use super::metadata::ServiceTier;
+
 /// Token usage information returned with a message response.
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
 pub struct Usage {
     pub input_tokens: u32,
     pub output_tokens: u32,
@@ -15,25 +17,135 @@ pub struct Usage {
     pub ephemeral_5m_input_tokens: Option<u32>,
     #[serde(default)]
     pub ephemeral_1h_input_tokens: Option<u32>,
+    /// The tier the request was served on.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub service_tier: Option<ServiceTier>,
+    /// Unrecognized fields from the API.
+    #[serde(flatten)]
+    #[cfg_attr(
+        feature = "strict-serde",
+        serde(deserialize_with = "super::common::deny_extra_fields")
+    )]
+    pub extra: super::common::ExtraFields,
 }
 
 /// Usage information specific to server tool use.
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "strict-serde", serde(deny_unknown_fields))]
 pub struct ServerToolUsage {
     #[serde(default)]
     pub web_search_requests: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub web_fetch_requests: Option<u32>,
 }
 
 /// Usage information in a `message_delta` streaming event.
-#[derive(Debug, Clone, Deserialize, Serialize)]
+///
+/// Counts are cumulative for the message. Input and server tool counts are
+/// only present when they changed since `message_start`, as they do when
+/// server tools run during the response.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "strict-serde", serde(deny_unknown_fields))]
 pub struct MessageDeltaUsage {
     pub output_tokens: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input_tokens: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_creation_input_tokens: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_read_input_tokens: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub server_tool_use: Option<ServerToolUsage>,
+}
+
+impl Usage {
+    /// Overwrite these counts with the ones reported by a `message_delta` event.
+    pub fn apply_delta(&mut self, delta: &MessageDeltaUsage) {
+        self.output_tokens = delta.output_tokens;
+        if let Some(input_tokens) = delta.input_tokens {
+            self.input_tokens = input_tokens;
+        }
+        if delta.cache_creation_input_tokens.is_some() {
+            self.cache_creation_input_tokens = delta.cache_creation_input_tokens;
+        }
+        if delta.cache_read_input_tokens.is_some() {
+            self.cache_read_input_tokens = delta.cache_read_input_tokens;
+        }
+        if delta.server_tool_use.is_some() {
+            self.server_tool_use = delta.server_tool_use.clone();
+        }
+    }
+
+    /// Add the counts from `other`, as for one turn split across requests.
+    pub fn add(&mut self, other: &Usage) {
+        self.input_tokens += other.input_tokens;
+        self.output_tokens += other.output_tokens;
+        add_count(
+            &mut self.cache_creation_input_tokens,
+            other.cache_creation_input_tokens,
+        );
+        add_count(
+            &mut self.cache_read_input_tokens,
+            other.cache_read_input_tokens,
+        );
+        add_count(
+            &mut self.ephemeral_5m_input_tokens,
+            other.ephemeral_5m_input_tokens,
+        );
+        add_count(
+            &mut self.ephemeral_1h_input_tokens,
+            other.ephemeral_1h_input_tokens,
+        );
+        if let Some(other) = &other.server_tool_use {
+            let server = self.server_tool_use.get_or_insert(ServerToolUsage {
+                web_search_requests: None,
+                web_fetch_requests: None,
+            });
+            add_count(&mut server.web_search_requests, other.web_search_requests);
+            add_count(&mut server.web_fetch_requests, other.web_fetch_requests);
+        }
+    }
+}
+
+/// Add an optional count, leaving it `None` only if both sides are.
+fn add_count(total: &mut Option<u32>, count: Option<u32>) {
+    if let Some(count) = count {
+        *total = Some(total.unwrap_or(0) + count);
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_add_sums_every_count() {
+        let mut usage: Usage = serde_json::from_value(serde_json::json!({
+            "input_tokens": 10,
+            "output_tokens": 5,
+            "cache_read_input_tokens": 100,
+            "server_tool_use": {"web_search_requests": 1}
+        }))
+        .unwrap();
+        let next: Usage = serde_json::from_value(serde_json::json!({
+            "input_tokens": 20,
+            "output_tokens": 7,
+            "cache_creation_input_tokens": 30,
+            "cache_read_input_tokens": 50,
+            "server_tool_use": {"web_search_requests": 2, "web_fetch_requests": 1}
+        }))
+        .unwrap();
+        usage.add(&next);
+        assert_eq!(usage.input_tokens, 30);
+        assert_eq!(usage.output_tokens, 12);
+        assert_eq!(usage.cache_creation_input_tokens, Some(30));
+        assert_eq!(usage.cache_read_input_tokens, Some(150));
+        assert_eq!(usage.ephemeral_5m_input_tokens, None);
+        let server = usage.server_tool_use.unwrap();
+        assert_eq!(server.web_search_requests, Some(3));
+        assert_eq!(server.web_fetch_requests, Some(1));
+    }
+
     #[test]
     fn test_deserialize_usage() {
         let json = r#"{
@@ -90,6 +202,14 @@ mod tests {
         assert_eq!(usage.ephemeral_1h_input_tokens, Some(20));
     }
 
+    #[test]
+    fn test_deserialize_usage_with_service_tier() {
+        let json = r#"{"input_tokens": 1, "output_tokens": 2, "service_tier": "priority"}"#;
+        let usage: Usage = serde_json::from_str(json).unwrap();
+        assert_eq!(usage.service_tier, Some(ServiceTier::Priority));
+        assert!(!usage.extra.contains_key("service_tier"));
+    }
+
     #[test]
     fn test_deserialize_message_delta_usage() {
         let json = r#"{"output_tokens": 42}"#;
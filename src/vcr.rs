@@ -0,0 +1,340 @@
+//! Record and replay API traffic for tests.
+//!
+//! Enabled with the `test-util` feature. [`VcrMiddleware`] saves each
+//! request/response pair to a JSON cassette file, with credentials and other
+//! secrets redacted, and can later answer the same requests from the file
+//! without touching the network.
+//!
+//! ```ignore
+//! use uno_anthropic::vcr::VcrMiddleware;
+//!
+//! // Records against the live API on the first run, replays afterwards.
+//! let client = Client::builder()
+//!     .middleware(VcrMiddleware::auto("tests/fixtures/hello.json")?)
+//!     .build();
+//! ```
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::Error;
+use crate::middleware::{BoxFuture, Middleware, Next};
+use crate::redact::{RedactionPolicy, is_sensitive_header};
+
+/// Response headers that describe the wire encoding rather than the body as
+/// recorded, so they are not saved.
+const TRANSPORT_HEADERS: &[&str] = &["content-encoding", "content-length", "transfer-encoding"];
+
+/// A recorded sequence of interactions.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Cassette {
+    pub interactions: Vec<Interaction>,
+}
+
+/// One request and the response it received.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Interaction {
+    pub request: RecordedRequest,
+    pub response: RecordedResponse,
+}
+
+/// The parts of a request used to match it on replay.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedRequest {
+    pub method: String,
+    /// Path and query, without the scheme and host, so a cassette can be
+    /// replayed against any base URL.
+    pub path: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
+}
+
+/// A recorded response.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedResponse {
+    pub status: u16,
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    pub body: String,
+}
+
+/// Whether the middleware records live traffic or replays a cassette.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VcrMode {
+    /// Send requests to the API and append them to the cassette.
+    Record,
+    /// Answer requests from the cassette; unmatched requests fail.
+    Replay,
+}
+
+/// Middleware that records requests to, or replays them from, a cassette file.
+///
+/// On replay each recorded interaction answers at most one request, matched by
+/// method, path and body, so repeated identical requests are answered in the
+/// order they were recorded. Request headers are never saved.
+pub struct VcrMiddleware {
+    path: PathBuf,
+    mode: VcrMode,
+    redaction: RedactionPolicy,
+    state: Mutex<State>,
+}
+
+struct State {
+    cassette: Cassette,
+    used: Vec<bool>,
+}
+
+impl VcrMiddleware {
+    /// Record into a new cassette at `path`, replacing any existing file when
+    /// the first interaction is saved.
+    pub fn record(path: impl Into<PathBuf>) -> Self {
+        Self::with_cassette(path.into(), VcrMode::Record, Cassette::default())
+    }
+
+    /// Replay the cassette at `path`.
+    pub fn replay(path: impl Into<PathBuf>) -> std::io::Result<Self> {
+        let path = path.into();
+        let cassette = load(&path)?;
+        Ok(Self::with_cassette(path, VcrMode::Replay, cassette))
+    }
+
+    /// Replay the cassette at `path` if it exists, otherwise record it.
+    pub fn auto(path: impl Into<PathBuf>) -> std::io::Result<Self> {
+        let path = path.into();
+        if path.exists() {
+            Self::replay(path)
+        } else {
+            Ok(Self::record(path))
+        }
+    }
+
+    /// Set the policy applied to recorded bodies and header values.
+    ///
+    /// The default policy redacts API keys and bearer tokens.
+    pub fn redaction(mut self, policy: RedactionPolicy) -> Self {
+        self.redaction = policy;
+        self
+    }
+
+    /// Whether this middleware is recording or replaying.
+    pub fn mode(&self) -> VcrMode {
+        self.mode
+    }
+
+    fn with_cassette(path: PathBuf, mode: VcrMode, cassette: Cassette) -> Self {
+        let used = vec![false; cassette.interactions.len()];
+        Self {
+            path,
+            mode,
+            redaction: RedactionPolicy::default(),
+            state: Mutex::new(State { cassette, used }),
+        }
+    }
+
+    fn recorded_request(&self, request: &reqwest::Request) -> RecordedRequest {
+        let url = request.url();
+        let path = match url.query() {
+            Some(query) => format!("{}?{query}", url.path()),
+            None => url.path().to_string(),
+        };
+        let body = request
+            .body()
+            .and_then(|body| body.as_bytes())
+            .map(|bytes| {
+                self.redaction
+                    .apply(&String::from_utf8_lossy(bytes))
+                    .into_owned()
+            });
+        RecordedRequest {
+            method: request.method().to_string(),
+            path,
+            body,
+        }
+    }
+
+    fn replay_request(&self, request: &RecordedRequest) -> Result<reqwest::Response, Error> {
+        let mut state = self.state.lock().unwrap();
+        let State { cassette, used } = &mut *state;
+        let index = cassette
+            .interactions
+            .iter()
+            .enumerate()
+            .position(|(i, interaction)| !used[i] && interaction.request == *request)
+            .ok_or_else(|| {
+                Error::Validation(format!(
+                    "no recorded interaction for {} {} in {}",
+                    request.method,
+                    request.path,
+                    self.path.display()
+                ))
+            })?;
+        used[index] = true;
+
+        let recorded = &cassette.interactions[index].response;
+        let mut builder = http::Response::builder().status(recorded.status);
+        for (name, value) in &recorded.headers {
+            builder = builder.header(name, value);
+        }
+        let response = builder
+            .body(recorded.body.clone())
+            .map_err(|e| Error::Validation(format!("invalid recorded response: {e}")))?;
+        Ok(reqwest::Response::from(response))
+    }
+
+    async fn record_request(
+        &self,
+        recorded: RecordedRequest,
+        request: reqwest::Request,
+        next: Next<'_>,
+    ) -> Result<reqwest::Response, Error> {
+        let response = next.run(request).await?;
+        let status = response.status();
+        let headers = response.headers().clone();
+        let body = response.bytes().await?;
+
+        let mut saved_headers = BTreeMap::new();
+        for (name, value) in &headers {
+            let name = name.as_str();
+            if is_sensitive_header(name) || TRANSPORT_HEADERS.contains(&name) {
+                continue;
+            }
+            if let Ok(value) = value.to_str() {
+                saved_headers.insert(name.to_string(), self.redaction.apply(value).into_owned());
+            }
+        }
+        let interaction = Interaction {
+            request: recorded,
+            response: RecordedResponse {
+                status: status.as_u16(),
+                headers: saved_headers,
+                body: self
+                    .redaction
+                    .apply(&String::from_utf8_lossy(&body))
+                    .into_owned(),
+            },
+        };
+        {
+            let mut state = self.state.lock().unwrap();
+            state.cassette.interactions.push(interaction);
+            state.used.push(true);
+            save(&self.path, &state.cassette)?;
+        }
+
+        let mut builder = http::Response::builder().status(status);
+        for (name, value) in &headers {
+            if !TRANSPORT_HEADERS.contains(&name.as_str()) {
+                builder = builder.header(name, value);
+            }
+        }
+        let response = builder
+            .body(body)
+            .map_err(|e| Error::Validation(format!("invalid response: {e}")))?;
+        Ok(reqwest::Response::from(response))
+    }
+}
+
+impl Middleware for VcrMiddleware {
+    fn handle<'a>(
+        &'a self,
+        request: reqwest::Request,
+        next: Next<'a>,
+    ) -> BoxFuture<'a, Result<reqwest::Response, Error>> {
+        Box::pin(async move {
+            let recorded = self.recorded_request(&request);
+            match self.mode {
+                VcrMode::Replay => self.replay_request(&recorded),
+                VcrMode::Record => self.record_request(recorded, request, next).await,
+            }
+        })
+    }
+}
+
+fn load(path: &Path) -> std::io::Result<Cassette> {
+    let data = std::fs::read(path)?;
+    serde_json::from_slice(&data).map_err(std::io::Error::other)
+}
+
+fn save(path: &Path, cassette: &Cassette) -> Result<(), Error> {
+    let data = serde_json::to_vec_pretty(cassette)?;
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| Error::Validation(format!("failed to save cassette: {e}")))?;
+    }
+    std::fs::write(path, data)
+        .map_err(|e| Error::Validation(format!("failed to save cassette: {e}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use super::*;
+    use crate::client::ClientBuilder;
+    use crate::messages::params::MessageCreateParams;
+    use crate::mock::text_message;
+    use crate::types::message::MessageParam;
+    use crate::types::model::Model;
+
+    fn params() -> MessageCreateParams {
+        MessageCreateParams::builder()
+            .model(Model::ClaudeOpus4_6)
+            .max_tokens(10)
+            .messages(vec![MessageParam::user("my key is sk-ant-secret123")])
+            .build()
+    }
+
+    #[tokio::test]
+    async fn test_record_then_replay() {
+        let dir = std::env::temp_dir().join(format!("uno-vcr-{}", std::process::id()));
+        let cassette = dir.join("hello.json");
+        let _ = std::fs::remove_file(&cassette);
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/messages"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("request-id", "req_1")
+                    .set_body_json(text_message("Hi")),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let recorder = VcrMiddleware::auto(&cassette).unwrap();
+        assert_eq!(recorder.mode(), VcrMode::Record);
+        let client = ClientBuilder::new()
+            .api_key("sk-ant-api-key")
+            .base_url(server.uri())
+            .middleware(recorder)
+            .build();
+        let message = client.messages().create(params()).await.unwrap();
+        assert_eq!(message.id, "msg_mock");
+
+        let saved = std::fs::read_to_string(&cassette).unwrap();
+        assert!(!saved.contains("sk-ant-"));
+        assert!(saved.contains("[REDACTED]"));
+        assert!(saved.contains("req_1"));
+
+        // Replays without the server, which would fail `expect(1)` if hit.
+        let player = VcrMiddleware::auto(&cassette).unwrap();
+        assert_eq!(player.mode(), VcrMode::Replay);
+        let client = ClientBuilder::new()
+            .api_key("sk-ant-api-key")
+            .base_url("http://127.0.0.1:9")
+            .middleware(player)
+            .build();
+        let message = client.messages().create(params()).await.unwrap();
+        assert_eq!(message.id, "msg_mock");
+
+        // Each interaction answers one request.
+        let err = client.messages().create(params()).await.unwrap_err();
+        assert!(matches!(err, Error::Validation(_)));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,72 @@
+//! Golden serde tests against recorded API response bodies in `tests/golden/`.
+//!
+//! Each `golden!` entry decodes one fixture into its response type, reporting
+//! the path to the first value that fails. Types that serialize must also
+//! write the fixture back out unchanged. Run with `UPDATE_GOLDEN=1` to
+//! regenerate those fixtures from the current serialization.
+//!
+//! With the `strict-serde` feature every field in a fixture must be modeled,
+//! so refreshing a fixture from a live response catches schema drift.
+
+use std::path::PathBuf;
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use uno_anthropic::batches::MessageBatch;
+use uno_anthropic::files::FileMetadata;
+use uno_anthropic::types::message::Message;
+use uno_anthropic::types::model::ModelInfo;
+
+fn fixture_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/golden")
+        .join(format!("{name}.json"))
+}
+
+/// Decode fixture `name` into `T`, panicking with the failing path.
+fn decode<T: DeserializeOwned>(name: &str) -> (T, serde_json::Value) {
+    let text = std::fs::read_to_string(fixture_path(name)).expect("read fixture");
+    let mut deserializer = serde_json::Deserializer::from_str(&text);
+    let value = serde_path_to_error::deserialize(&mut deserializer)
+        .unwrap_or_else(|err| panic!("{name}: decode failed at `{}`: {}", err.path(), err.inner()));
+    (value, serde_json::from_str(&text).expect("fixture is JSON"))
+}
+
+/// Decode fixture `name` and check that `T` writes it back out unchanged.
+fn roundtrip<T: Serialize + DeserializeOwned>(name: &str) {
+    let (value, fixture) = decode::<T>(name);
+    let serialized = serde_json::to_value(&value).expect("serialize");
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        let mut text = serde_json::to_string_pretty(&serialized).expect("serialize");
+        text.push('\n');
+        std::fs::write(fixture_path(name), text).expect("write fixture");
+        return;
+    }
+    assert_eq!(serialized, fixture, "{name}: round trip changed the body");
+}
+
+macro_rules! golden {
+    ($($test:ident: $ty:ty => $mode:ident($name:literal);)*) => {
+        $(
+            #[test]
+            fn $test() {
+                golden!(@$mode $ty, $name);
+            }
+        )*
+    };
+    (@roundtrip $ty:ty, $name:literal) => {
+        roundtrip::<$ty>($name)
+    };
+    (@decode $ty:ty, $name:literal) => {
+        decode::<$ty>($name);
+    };
+}
+
+golden! {
+    golden_message_text: Message => roundtrip("message_text");
+    golden_message_tool_use: Message => roundtrip("message_tool_use");
+    golden_message_web_search: Message => roundtrip("message_web_search");
+    golden_model_info: ModelInfo => decode("model_info");
+    golden_message_batch: MessageBatch => decode("message_batch");
+    golden_file_metadata: FileMetadata => decode("file_metadata");
+}
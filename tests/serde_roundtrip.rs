@@ -57,6 +57,9 @@ fn roundtrip_stop_reason() {
         StopReason::StopSequence,
         StopReason::ToolUse,
         StopReason::Refusal,
+        StopReason::PauseTurn,
+        StopReason::ModelContextWindowExceeded,
+        StopReason::Other("future_reason".to_string()),
     ];
     for reason in &reasons {
         roundtrip_json(reason);
@@ -476,6 +479,8 @@ fn roundtrip_image_source_all_variants() {
 fn roundtrip_service_tier() {
     roundtrip_json(&ServiceTier::Auto);
     roundtrip_json(&ServiceTier::StandardOnly);
+    roundtrip_json(&ServiceTier::Priority);
+    roundtrip_json(&ServiceTier::Other("flex".to_string()));
 }
 
 // ── MessageParam ─────────────────────────────────────────────────────
@@ -776,6 +781,27 @@ fn roundtrip_web_search_tool_result_error_code_all_variants() {
     }
 }
 
+#[test]
+fn unknown_tool_result_error_codes_are_preserved() {
+    let code: WebSearchToolResultErrorCode = serde_json::from_str("\"region_blocked\"").unwrap();
+    assert_eq!(
+        code,
+        WebSearchToolResultErrorCode::Other("region_blocked".to_string())
+    );
+    assert_eq!(serde_json::to_string(&code).unwrap(), "\"region_blocked\"");
+
+    let code: WebFetchToolResultErrorCode = serde_json::from_str("\"robots_disallowed\"").unwrap();
+    assert_eq!(
+        code,
+        WebFetchToolResultErrorCode::Other("robots_disallowed".to_string())
+    );
+    let code: ToolSearchToolResultErrorCode = serde_json::from_str("\"index_missing\"").unwrap();
+    assert_eq!(
+        code,
+        ToolSearchToolResultErrorCode::Other("index_missing".to_string())
+    );
+}
+
 // ── WebFetchToolResultErrorCode ───────────────────────────────────────
 
 #[test]